@@ -214,6 +214,11 @@ mod json {
         pub config: String,
     }
 
+    #[derive(Clone, Deserialize)]
+    pub struct PackageVersion {
+        pub version: String,
+    }
+
     #[derive(Clone, Deserialize)]
     pub struct PackageIdent {
         pub origin: String,
@@ -861,6 +866,38 @@ impl Client {
         Ok(package.ident.into())
     }
 
+    /// Returns every version of a package which has been uploaded to Builder, in no particular
+    /// order.
+    ///
+    /// # Failures
+    ///
+    /// * Package cannot be found
+    /// * Remote Builder is not available
+    pub fn package_versions(
+        &self,
+        package: &PackageIdent,
+        token: Option<&str>,
+    ) -> Result<Vec<String>> {
+        let path = package_versions_path(package);
+        debug!("Retrieving versions for {}", package);
+
+        let mut res = self.maybe_add_authz(self.0.get(&path), token).send()?;
+
+        if res.status != StatusCode::Ok {
+            return Err(err_from_response(res));
+        }
+
+        let mut encoded = String::new();
+        res.read_to_string(&mut encoded)
+            .map_err(Error::BadResponseBody)?;
+        debug!("Response body: {:?}", encoded);
+        let versions = serde_json::from_str::<Vec<json::PackageVersion>>(&encoded)?
+            .into_iter()
+            .map(|v| v.version)
+            .collect();
+        Ok(versions)
+    }
+
     /// Upload a package to a remote Builder.
     ///
     /// # Failures
@@ -1277,6 +1314,14 @@ fn channel_package_path(channel: &str, package: &PackageIdent) -> String {
     path
 }
 
+fn package_versions_path(package: &PackageIdent) -> String {
+    format!(
+        "depot/pkgs/{}/{}/versions",
+        package.origin(),
+        package.name()
+    )
+}
+
 fn package_channels_path(package: &PackageIdent) -> String {
     format!(
         "depot/pkgs/{}/{}/{}/{}/channels",
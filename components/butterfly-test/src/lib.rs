@@ -37,6 +37,7 @@ use habitat_butterfly::rumor::election::ElectionStatus;
 use habitat_butterfly::rumor::service::{Service, SysInfo};
 use habitat_butterfly::rumor::service_config::ServiceConfig;
 use habitat_butterfly::rumor::service_file::ServiceFile;
+use habitat_butterfly::rumor::tombstone::Tombstone;
 use habitat_butterfly::server::timing::Timing;
 use habitat_butterfly::server::{Server, Suitability};
 use habitat_butterfly::trace::Trace;
@@ -517,6 +518,14 @@ impl SwimNet {
         self[member].insert_departure(d);
     }
 
+    pub fn add_tombstone(&mut self, member: usize, service: &str) {
+        let t = Tombstone::new(
+            self[member].member_id(),
+            ServiceGroup::new(None, service, "prod", None).unwrap(),
+        );
+        self[member].insert_tombstone(t);
+    }
+
     pub fn add_election(&mut self, member: usize, service: &str) {
         self[member].start_election(&ServiceGroup::new(None, service, "prod", None).unwrap(), 0);
     }
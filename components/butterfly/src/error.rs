@@ -32,6 +32,7 @@ pub enum Error {
     BadDataPath(PathBuf, io::Error),
     BadDatFile(PathBuf, io::Error),
     CannotBind(io::Error),
+    Compression(io::Error),
     DatFileIO(PathBuf, io::Error),
     DecodeError(prost::DecodeError),
     EncodeError(prost::EncodeError),
@@ -64,6 +65,9 @@ impl fmt::Display for Error {
                 err
             ),
             Error::CannotBind(ref err) => format!("Cannot bind to port: {:?}", err),
+            Error::Compression(ref err) => {
+                format!("Cannot compress or decompress wire payload: {}", err)
+            }
             Error::DatFileIO(ref path, ref err) => format!(
                 "Error reading or writing to DatFile, {}, {}",
                 path.display(),
@@ -121,6 +125,7 @@ impl error::Error for Error {
             Error::BadDataPath(_, _) => "Unable to read or write to data directory",
             Error::BadDatFile(_, _) => "Unable to decode contents of DatFile",
             Error::CannotBind(_) => "Cannot bind to port",
+            Error::Compression(_) => "Cannot compress or decompress wire payload",
             Error::DatFileIO(_, _) => "Error reading or writing to DatFile",
             Error::DecodeError(ref err) => err.description(),
             Error::EncodeError(ref err) => err.description(),
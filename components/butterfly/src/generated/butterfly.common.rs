@@ -7,4 +7,23 @@ pub struct Wire {
     pub nonce: ::std::option::Option<Vec<u8>>,
     #[prost(bytes, optional, tag="3")]
     pub payload: ::std::option::Option<Vec<u8>>,
+    /// The payload was deflated before it was encrypted.
+    #[prost(bool, optional, tag="4", default="false")]
+    pub compressed: ::std::option::Option<bool>,
+    /// The payload is a Batch of messages rather than a single one.
+    #[prost(bool, optional, tag="5", default="false")]
+    pub batched: ::std::option::Option<bool>,
+    /// The sender can receive compressed and batched payloads. Members which don't set this are
+    /// only ever sent single, uncompressed messages.
+    #[prost(bool, optional, tag="6", default="false")]
+    pub accepts_compact: ::std::option::Option<bool>,
+    /// The revision of the ring key the payload was encrypted with. Older members leave this unset.
+    #[prost(string, optional, tag="7")]
+    pub key_rev: ::std::option::Option<String>,
+}
+#[derive(Clone, PartialEq, Message)]
+#[derive(Serialize, Deserialize)]
+pub struct Batch {
+    #[prost(bytes, repeated, tag="1")]
+    pub payloads: ::std::vec::Vec<Vec<u8>>,
 }
@@ -40,6 +40,12 @@ pub struct Service {
     pub cfg: ::std::option::Option<Vec<u8>>,
     #[prost(message, optional, tag="12")]
     pub sys: ::std::option::Option<SysInfo>,
+    /// Package whose smoke test failed on this member, causing it to roll back.
+    #[prost(string, optional, tag="13")]
+    pub failed_pkg: ::std::option::Option<String>,
+    /// Most recent health check status of the service on this member.
+    #[prost(string, optional, tag="14")]
+    pub health_check: ::std::option::Option<String>,
 }
 #[derive(Clone, PartialEq, Message)]
 #[derive(Serialize, Deserialize)]
@@ -93,6 +99,26 @@ pub struct Departure {
     #[prost(string, optional, tag="1")]
     pub member_id: ::std::option::Option<String>,
 }
+/// Declares that a service group has been forgotten, and that its rumors should be discarded.
+#[derive(Clone, PartialEq, Message)]
+#[derive(Serialize, Deserialize)]
+pub struct Tombstone {
+    #[prost(string, optional, tag="1")]
+    pub service_group: ::std::option::Option<String>,
+    /// Seconds since the epoch at which the group was forgotten.
+    #[prost(uint64, optional, tag="2")]
+    pub incarnation: ::std::option::Option<u64>,
+}
+/// A new revision of the ring key, for members to add to the revisions they accept.
+#[derive(Clone, PartialEq, Message)]
+#[derive(Serialize, Deserialize)]
+pub struct RingKey {
+    #[prost(string, optional, tag="1")]
+    pub name_with_rev: ::std::option::Option<String>,
+    /// The contents of the secret key file.
+    #[prost(string, optional, tag="2")]
+    pub content: ::std::option::Option<String>,
+}
 #[derive(Clone, PartialEq, Message)]
 #[derive(Serialize, Deserialize)]
 pub struct Rumor {
@@ -102,7 +128,7 @@ pub struct Rumor {
     pub tag: ::std::vec::Vec<String>,
     #[prost(string, optional, tag="3")]
     pub from_id: ::std::option::Option<String>,
-    #[prost(oneof="rumor::Payload", tags="4, 5, 6, 7, 8, 9")]
+    #[prost(oneof="rumor::Payload", tags="4, 5, 6, 7, 8, 9, 10, 11")]
     pub payload: ::std::option::Option<rumor::Payload>,
 }
 pub mod rumor {
@@ -118,6 +144,8 @@ pub mod rumor {
         Fake2 = 7,
         ElectionUpdate = 8,
         Departure = 9,
+        Tombstone = 10,
+        RingKey = 11,
     }
     #[derive(Clone, Oneof, PartialEq)]
     #[derive(Serialize, Deserialize)]
@@ -134,5 +162,9 @@ pub mod rumor {
         Election(super::Election),
         #[prost(message, tag="9")]
         Departure(super::Departure),
+        #[prost(message, tag="10")]
+        Tombstone(super::Tombstone),
+        #[prost(message, tag="11")]
+        RingKey(super::RingKey),
     }
 }
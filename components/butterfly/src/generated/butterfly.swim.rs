@@ -1,3 +1,12 @@
+/// A key/value pair an operator attaches to a Supervisor, such as `zone=us-east-1a`.
+#[derive(Clone, PartialEq, Message)]
+#[derive(Serialize, Deserialize)]
+pub struct Tag {
+    #[prost(string, optional, tag="1")]
+    pub key: ::std::option::Option<String>,
+    #[prost(string, optional, tag="2")]
+    pub value: ::std::option::Option<String>,
+}
 #[derive(Clone, PartialEq, Message)]
 #[derive(Serialize, Deserialize)]
 pub struct Member {
@@ -16,6 +25,11 @@ pub struct Member {
     pub persistent: ::std::option::Option<bool>,
     #[prost(bool, optional, tag="7", default="false")]
     pub departed: ::std::option::Option<bool>,
+    /// The newest revision of the ring key the member holds.
+    #[prost(string, optional, tag="8")]
+    pub ring_key_rev: ::std::option::Option<String>,
+    #[prost(message, repeated, tag="9")]
+    pub tags: ::std::vec::Vec<Tag>,
 }
 #[derive(Clone, PartialEq, Message)]
 #[derive(Serialize, Deserialize)]
@@ -45,6 +45,7 @@
 
 extern crate byteorder;
 extern crate bytes;
+extern crate flate2;
 extern crate habitat_core;
 #[macro_use]
 extern crate lazy_static;
@@ -77,6 +78,8 @@ pub mod protocol;
 pub mod rumor;
 pub mod server;
 pub mod swim;
+#[cfg(test)]
+pub mod test_helpers;
 
 use std::cell::UnsafeCell;
 
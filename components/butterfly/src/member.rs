@@ -15,7 +15,7 @@
 //! Tracks membership. Contains both the `Member` struct and the `MemberList`.
 
 use std::cmp;
-use std::collections::{hash_map, HashMap};
+use std::collections::{hash_map, BTreeMap, HashMap};
 use std::fmt;
 use std::iter::IntoIterator;
 use std::net::SocketAddr;
@@ -42,6 +42,11 @@ use rumor::{RumorKey, RumorPayload, RumorType};
 
 /// How many nodes do we target when we need to run PingReq.
 const PINGREQ_TARGETS: usize = 5;
+/// The most tags a member may carry.
+pub const MAX_TAGS: usize = 8;
+/// The most bytes a member's tags may take up, counting each key and value. Tags are carried in
+/// every SWIM message about the member, which must fit in a single packet.
+pub const MAX_TAGS_LEN: usize = 128;
 
 /// Wraps a `u64` to represent the "incarnation number" of a
 /// `Member`. Incarnation numbers can only ever be incremented.
@@ -141,6 +146,11 @@ pub struct Member {
     pub gossip_port: u16,
     pub persistent: bool,
     pub departed: bool,
+    /// The newest revision of the ring key the member holds.
+    pub ring_key_rev: Option<String>,
+    /// Key/value pairs describing the member, such as the zone or rack it runs in.
+    #[serde(default)]
+    pub tags: BTreeMap<String, String>,
 }
 
 impl Member {
@@ -161,6 +171,29 @@ impl Member {
     }
 }
 
+/// Returns an error describing the problem if there are too many tags, or they are too large, to
+/// carry in SWIM messages.
+pub fn check_tags(tags: &BTreeMap<String, String>) -> result::Result<(), String> {
+    let len: usize = tags
+        .iter()
+        .map(|(key, value)| key.len() + value.len())
+        .sum();
+    if tags.len() > MAX_TAGS {
+        Err(format!(
+            "at most {} tags may be given, not {}",
+            MAX_TAGS,
+            tags.len()
+        ))
+    } else if len > MAX_TAGS_LEN {
+        Err(format!(
+            "tags may total at most {} bytes, not {}",
+            MAX_TAGS_LEN, len
+        ))
+    } else {
+        Ok(())
+    }
+}
+
 impl Default for Member {
     fn default() -> Self {
         Member {
@@ -176,6 +209,8 @@ impl Default for Member {
             gossip_port: 0,
             persistent: false,
             departed: false,
+            ring_key_rev: None,
+            tags: BTreeMap::new(),
         }
     }
 }
@@ -208,6 +243,14 @@ impl From<Member> for proto::Member {
             gossip_port: Some(value.gossip_port.into()),
             persistent: Some(value.persistent),
             departed: Some(value.departed),
+            ring_key_rev: value.ring_key_rev,
+            tags: value
+                .tags
+                .into_iter()
+                .map(|(key, value)| proto::Tag {
+                    key: Some(key),
+                    value: Some(value),
+                }).collect(),
         }
     }
 }
@@ -298,6 +341,14 @@ impl FromProto<proto::Member> for Member {
                 .ok_or(Error::ProtocolMismatch("gossip-port"))?,
             persistent: proto.persistent.unwrap_or(false),
             departed: proto.departed.unwrap_or(false),
+            ring_key_rev: proto.ring_key_rev,
+            tags: proto
+                .tags
+                .into_iter()
+                .filter_map(|tag| match (tag.key, tag.value) {
+                    (Some(key), Some(value)) => Some((key, value)),
+                    _ => None,
+                }).collect(),
         })
     }
 }
@@ -725,6 +776,20 @@ impl MemberList {
             .len()
     }
 
+    /// Returns the number of members in each state of health.
+    pub fn health_counts(&self) -> HashMap<Health, usize> {
+        let mut counts = HashMap::new();
+        for health in self.health.read().expect("Health lock is poisoned").values() {
+            *counts.entry(*health).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Returns the health of every member, keyed by member ID.
+    pub fn health_by_id(&self) -> HashMap<UuidSimple, Health> {
+        self.health.read().expect("Health lock is poisoned").clone()
+    }
+
     /// A randomized list of members to check.
     pub fn check_list(&self, exclude_id: &str) -> Vec<Member> {
         let mut members: Vec<Member> = self
@@ -923,13 +988,14 @@ impl<'a> Serialize for MemberProxy<'a> {
     where
         S: Serializer,
     {
-        let mut strukt = serializer.serialize_struct("member", 6)?;
+        let mut strukt = serializer.serialize_struct("member", 7)?;
         strukt.serialize_field("address", &self.0.address)?;
         strukt.serialize_field("gossip_port", &self.0.gossip_port)?;
         strukt.serialize_field("incarnation", &self.0.incarnation)?;
         strukt.serialize_field("persistent", &self.0.persistent)?;
         strukt.serialize_field("swim_port", &self.0.swim_port)?;
         strukt.serialize_field("health", &self.1)?;
+        strukt.serialize_field("tags", &self.0.tags)?;
         strukt.end()
     }
 }
@@ -969,6 +1035,28 @@ mod tests {
             assert_eq!(&membership.member, &from_bytes.member);
             assert_eq!(&membership.health, &from_bytes.health);
         }
+
+        #[test]
+        fn tags_survive_the_roundtrip() {
+            let mut member = Member::default();
+            member
+                .tags
+                .insert("zone".to_string(), "us-east-1a".to_string());
+            member.tags.insert("rack".to_string(), "12".to_string());
+            let membership = Membership {
+                member: member,
+                health: Health::Alive,
+            };
+
+            let bytes = membership
+                .clone()
+                .write_to_bytes()
+                .expect("Could not write membership to bytes!");
+            let from_bytes =
+                Membership::from_bytes(&bytes).expect("Could not decode membership from bytes!");
+
+            assert_eq!(&membership.member.tags, &from_bytes.member.tags);
+        }
     }
 
     mod member_list {
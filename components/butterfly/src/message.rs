@@ -12,17 +12,100 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::io::{Read, Write};
+
 use bytes::BytesMut;
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
 use habitat_core::crypto::SymKey;
 use prost::Message;
 
 use error::{Error, Result};
-use protocol::Wire;
+use protocol::{Batch, Wire};
+
+/// Batches smaller than this aren't worth compressing.
+const COMPRESSION_THRESHOLD: usize = 512;
+/// The most a compressed payload may inflate to; anything larger is rejected.
+const MAX_INFLATED_LEN: u64 = 64 * 1024 * 1024;
+
+/// A message taken off the wire.
+#[derive(Debug)]
+pub struct WireMessage {
+    /// The decrypted and decompressed payload.
+    pub payload: Vec<u8>,
+    /// True if the payload is a `Batch` of messages rather than a single one.
+    pub batched: bool,
+    /// True if the sender can receive compressed and batched payloads itself.
+    pub accepts_compact: bool,
+}
+
+impl WireMessage {
+    /// The messages carried; more than one if the sender batched them.
+    pub fn payloads(self) -> Result<Vec<Vec<u8>>> {
+        if self.batched {
+            Ok(Batch::decode(&self.payload[..])?.payloads)
+        } else {
+            Ok(vec![self.payload])
+        }
+    }
+}
 
+/// Wraps a single message for any member.
 pub fn generate_wire(payload: Vec<u8>, ring_key: Option<&SymKey>) -> Result<Vec<u8>> {
+    seal(Wire::default(), payload, ring_key)
+}
+
+/// Wraps a batch of messages for a member which accepts compact payloads, compressing it when
+/// that makes it smaller.
+pub fn generate_batch_wire(payloads: Vec<Vec<u8>>, ring_key: Option<&SymKey>) -> Result<Vec<u8>> {
+    let batch = Batch { payloads: payloads };
+    let mut buf = BytesMut::with_capacity(batch.encoded_len());
+    batch.encode(&mut buf)?;
+    let mut payload = buf.to_vec();
     let mut wire = Wire::default();
+    wire.batched = Some(true);
+    if payload.len() >= COMPRESSION_THRESHOLD {
+        let deflated = deflate(&payload)?;
+        if deflated.len() < payload.len() {
+            wire.compressed = Some(true);
+            payload = deflated;
+        }
+    }
+    seal(wire, payload, ring_key)
+}
+
+/// Unwraps a message, decrypting it with any of the given revisions of the ring key.
+pub fn unwrap_wire(payload: &[u8], ring_keys: &[SymKey]) -> Result<WireMessage> {
+    let wire = Wire::decode(payload)?;
+    let payload = wire
+        .payload
+        .ok_or(Error::ProtocolMismatch("missing payload"))?;
+    let payload = if ring_keys.is_empty() {
+        payload
+    } else {
+        let nonce = wire.nonce.ok_or(Error::ProtocolMismatch("missing nonce"))?;
+        decrypt(&nonce, &payload, wire.key_rev.as_ref(), ring_keys)?
+    };
+    let payload = if wire.compressed.unwrap_or(false) {
+        inflate(&payload)?
+    } else {
+        payload
+    };
+    Ok(WireMessage {
+        payload: payload,
+        batched: wire.batched.unwrap_or(false),
+        accepts_compact: wire.accepts_compact.unwrap_or(false),
+    })
+}
+
+/// Encrypts the payload if there's a ring key, and encodes the wire message. Every message
+/// advertises that we accept compact payloads; older members ignore the flag.
+fn seal(mut wire: Wire, payload: Vec<u8>, ring_key: Option<&SymKey>) -> Result<Vec<u8>> {
+    wire.accepts_compact = Some(true);
     if let Some(ring_key) = ring_key {
         wire.encrypted = Some(true);
+        wire.key_rev = Some(ring_key.rev.clone());
         let (nonce, encrypted_payload) = ring_key.encrypt(&payload)?;
         wire.nonce = Some(nonce);
         wire.payload = Some(encrypted_payload);
@@ -34,15 +117,118 @@ pub fn generate_wire(payload: Vec<u8>, ring_key: Option<&SymKey>) -> Result<Vec<
     Ok(buf.to_vec())
 }
 
-pub fn unwrap_wire(payload: &[u8], ring_key: Option<&SymKey>) -> Result<Vec<u8>> {
-    let wire = Wire::decode(payload)?;
-    let payload = wire
-        .payload
-        .ok_or(Error::ProtocolMismatch("missing payload"))?;
-    if let Some(ring_key) = ring_key {
-        let nonce = wire.nonce.ok_or(Error::ProtocolMismatch("missing nonce"))?;
-        Ok(ring_key.decrypt(&nonce, &payload)?)
-    } else {
-        Ok(payload)
+/// Decrypts with the revision the sender named or, since older members don't name one, with each
+/// revision in turn.
+fn decrypt(
+    nonce: &[u8],
+    payload: &[u8],
+    key_rev: Option<&String>,
+    ring_keys: &[SymKey],
+) -> Result<Vec<u8>> {
+    if let Some(key) = key_rev.and_then(|rev| ring_keys.iter().find(|k| k.rev == *rev)) {
+        return Ok(key.decrypt(nonce, payload)?);
+    }
+    let mut result = Err(Error::ProtocolMismatch("ring key"));
+    for key in ring_keys {
+        match key.decrypt(nonce, payload) {
+            Ok(decrypted) => return Ok(decrypted),
+            Err(err) => result = Err(Error::from(err)),
+        }
+    }
+    result
+}
+
+fn deflate(payload: &[u8]) -> Result<Vec<u8>> {
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(payload).map_err(Error::Compression)?;
+    encoder.finish().map_err(Error::Compression)
+}
+
+fn inflate(payload: &[u8]) -> Result<Vec<u8>> {
+    let mut inflated = Vec::new();
+    DeflateDecoder::new(payload)
+        .take(MAX_INFLATED_LEN + 1)
+        .read_to_end(&mut inflated)
+        .map_err(Error::Compression)?;
+    if inflated.len() as u64 > MAX_INFLATED_LEN {
+        return Err(Error::ProtocolMismatch("payload too large"));
+    }
+    Ok(inflated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_helpers::ring_key;
+
+    #[test]
+    fn single_messages_round_trip() {
+        let wire = generate_wire(b"ping".to_vec(), None).unwrap();
+        let msg = unwrap_wire(&wire, &[]).unwrap();
+        assert!(msg.accepts_compact);
+        assert!(!msg.batched);
+        assert_eq!(msg.payloads().unwrap(), vec![b"ping".to_vec()]);
+    }
+
+    #[test]
+    fn batches_round_trip_compressed() {
+        let payloads = vec![vec![b'a'; 1024], vec![b'b'; 1024], b"small".to_vec()];
+        let wire = generate_batch_wire(payloads.clone(), None).unwrap();
+        assert!(Wire::decode(&wire[..]).unwrap().compressed.unwrap_or(false));
+        assert!(wire.len() < 1024);
+        let msg = unwrap_wire(&wire, &[]).unwrap();
+        assert!(msg.batched);
+        assert_eq!(msg.payloads().unwrap(), payloads);
+    }
+
+    #[test]
+    fn small_batches_are_not_compressed() {
+        let wire = generate_batch_wire(vec![b"small".to_vec()], None).unwrap();
+        assert!(!Wire::decode(&wire[..]).unwrap().compressed.unwrap_or(false));
+        let msg = unwrap_wire(&wire, &[]).unwrap();
+        assert_eq!(msg.payloads().unwrap(), vec![b"small".to_vec()]);
+    }
+
+    #[test]
+    fn messages_from_older_members_are_single_and_uncompressed() {
+        let mut wire = Wire::default();
+        wire.payload = Some(b"ping".to_vec());
+        let mut buf = BytesMut::with_capacity(wire.encoded_len());
+        wire.encode(&mut buf).unwrap();
+        let msg = unwrap_wire(&buf, &[]).unwrap();
+        assert!(!msg.accepts_compact);
+        assert_eq!(msg.payloads().unwrap(), vec![b"ping".to_vec()]);
+    }
+
+    #[test]
+    fn any_held_revision_of_the_ring_key_decrypts() {
+        let old = ring_key("20180101000000");
+        let new = ring_key("20180102000000");
+        let ring_keys = vec![new.clone(), old.clone()];
+
+        let wire = generate_wire(b"ping".to_vec(), Some(&old)).unwrap();
+        let msg = unwrap_wire(&wire, &ring_keys).unwrap();
+        assert_eq!(msg.payloads().unwrap(), vec![b"ping".to_vec()]);
+
+        let wire = generate_batch_wire(vec![b"ping".to_vec()], Some(&new)).unwrap();
+        let msg = unwrap_wire(&wire, &ring_keys).unwrap();
+        assert_eq!(msg.payloads().unwrap(), vec![b"ping".to_vec()]);
+
+        assert!(unwrap_wire(&wire, &[old]).is_err());
+    }
+
+    #[test]
+    fn messages_without_a_revision_are_tried_against_every_key() {
+        let old = ring_key("20180101000000");
+        let new = ring_key("20180102000000");
+        let (nonce, payload) = old.encrypt(b"ping").unwrap();
+        let mut wire = Wire::default();
+        wire.encrypted = Some(true);
+        wire.nonce = Some(nonce);
+        wire.payload = Some(payload);
+        let mut buf = BytesMut::with_capacity(wire.encoded_len());
+        wire.encode(&mut buf).unwrap();
+        let msg = unwrap_wire(&buf, &[new, old]).unwrap();
+        assert_eq!(msg.payloads().unwrap(), vec![b"ping".to_vec()]);
     }
 }
@@ -14,9 +14,11 @@
 
 use rumor::departure::Departure as CDeparture;
 use rumor::election::{Election as CElection, ElectionUpdate as CElectionUpdate};
+use rumor::ring_key::RingKey as CRingKey;
 use rumor::service::Service as CService;
 use rumor::service_config::ServiceConfig as CServiceConfig;
 use rumor::service_file::ServiceFile as CServiceFile;
+use rumor::tombstone::Tombstone as CTombstone;
 
 include!("../generated/butterfly.newscast.rs");
 
@@ -84,6 +86,8 @@ impl From<CService> for Rumor {
             pkg: Some(value.pkg),
             cfg: Some(value.cfg),
             sys: Some(value.sys.into()),
+            failed_pkg: value.failed_pkg,
+            health_check: value.health_check,
         };
         Rumor {
             type_: RumorType::Service as i32,
@@ -128,3 +132,33 @@ impl From<CServiceFile> for Rumor {
         }
     }
 }
+
+impl From<CRingKey> for Rumor {
+    fn from(value: CRingKey) -> Self {
+        let payload = RingKey {
+            name_with_rev: Some(value.name_with_rev),
+            content: Some(value.content),
+        };
+        Rumor {
+            type_: RumorType::RingKey as i32,
+            tag: Vec::default(),
+            from_id: Some(value.from_id),
+            payload: Some(RumorPayload::RingKey(payload)),
+        }
+    }
+}
+
+impl From<CTombstone> for Rumor {
+    fn from(value: CTombstone) -> Self {
+        let payload = Tombstone {
+            service_group: Some(value.service_group.to_string()),
+            incarnation: Some(value.incarnation),
+        };
+        Rumor {
+            type_: RumorType::Tombstone as i32,
+            tag: Vec::default(),
+            from_id: Some(value.from_id),
+            payload: Some(RumorPayload::Tombstone(payload)),
+        }
+    }
+}
@@ -26,10 +26,11 @@ use member::{MemberList, Membership};
 use protocol::{newscast, Message};
 use rumor::{
     Departure, Election, ElectionUpdate, Rumor, RumorStore, Service, ServiceConfig, ServiceFile,
+    Tombstone,
 };
 use server::Server;
 
-const HEADER_VERSION: u8 = 2;
+const HEADER_VERSION: u8 = 3;
 
 /// A versioned binary file containing rumors exchanged by the butterfly server which have
 /// been periodically persisted to disk.
@@ -230,6 +231,30 @@ impl DatFile {
             }
         }
 
+        if version[0] >= 3 {
+            debug!(
+                "Reading tombstone rumors list from {}",
+                self.path().display()
+            );
+            bytes_read = 0;
+            loop {
+                if bytes_read >= self.header.tombstone_len {
+                    break;
+                }
+                reader
+                    .read_exact(&mut size_buf)
+                    .map_err(|err| Error::DatFileIO(self.path.clone(), err))?;
+                let rumor_size = LittleEndian::read_u64(&size_buf);
+                rumor_buf.resize(rumor_size as usize, 0);
+                reader
+                    .read_exact(&mut rumor_buf)
+                    .map_err(|err| Error::DatFileIO(self.path.clone(), err))?;
+                let rumor = Tombstone::from_bytes(&rumor_buf)?;
+                server.restore_tombstone(rumor);
+                bytes_read += size_buf.len() as u64 + rumor_size;
+            }
+        }
+
         Ok(())
     }
 
@@ -259,6 +284,7 @@ impl DatFile {
             header.election_len = self.write_rumor_store(&mut writer, &server.election_store)?;
             header.update_len = self.write_rumor_store(&mut writer, &server.update_store)?;
             header.departure_len = self.write_rumor_store(&mut writer, &server.departure_store)?;
+            header.tombstone_len = self.write_rumor_store(&mut writer, &server.tombstone_store)?;
             writer
                 .seek(SeekFrom::Start(1))
                 .map_err(|err| Error::DatFileIO(self.path.clone(), err))?;
@@ -347,6 +373,11 @@ impl DatFile {
         self.update_offset() + self.header.update_len
     }
 
+    #[allow(dead_code)]
+    fn tombstone_offset(&self) -> u64 {
+        self.departure_offset() + self.header.departure_len
+    }
+
     fn write_header<W>(&self, writer: &mut W, header: &Header) -> Result<usize>
     where
         W: Write,
@@ -455,6 +486,7 @@ pub struct Header {
     pub election_len: u64,
     pub update_len: u64,
     pub departure_len: u64,
+    pub tombstone_len: u64,
 }
 
 impl Header {
@@ -463,7 +495,8 @@ impl Header {
         R: Read,
     {
         let mut bytes = match version {
-            1 => vec![0; mem::size_of::<Self>()],
+            1 => vec![0; 48],
+            2 => vec![0; 64],
             _ => vec![0; mem::size_of::<Self>() + 8],
         };
         reader.read_exact(&mut bytes)?;
@@ -487,9 +520,23 @@ impl Header {
                         election_len: LittleEndian::read_u64(&bytes[32..40]),
                         update_len: LittleEndian::read_u64(&bytes[40..48]),
                         departure_len: 0,
+                        tombstone_len: 0,
                     },
                 )
             }
+            2 => (
+                LittleEndian::read_u64(&bytes[0..8]),
+                Header {
+                    member_len: LittleEndian::read_u64(&bytes[8..16]),
+                    service_len: LittleEndian::read_u64(&bytes[16..24]),
+                    service_config_len: LittleEndian::read_u64(&bytes[24..32]),
+                    service_file_len: LittleEndian::read_u64(&bytes[32..40]),
+                    election_len: LittleEndian::read_u64(&bytes[40..48]),
+                    update_len: LittleEndian::read_u64(&bytes[48..56]),
+                    departure_len: LittleEndian::read_u64(&bytes[56..64]),
+                    tombstone_len: 0,
+                },
+            ),
             // This should be the latest version of the header. As we deprecate
             // header versions, just roll this code up, and match it, then add
             // your new structure.
@@ -508,6 +555,7 @@ impl Header {
                     election_len: LittleEndian::read_u64(&bytes[40..48]),
                     update_len: LittleEndian::read_u64(&bytes[48..56]),
                     departure_len: LittleEndian::read_u64(&bytes[56..64]),
+                    tombstone_len: LittleEndian::read_u64(&bytes[64..72]),
                 },
             ),
         }
@@ -525,6 +573,7 @@ impl Header {
         LittleEndian::write_u64(&mut bytes[40..48], self.election_len);
         LittleEndian::write_u64(&mut bytes[48..56], self.update_len);
         LittleEndian::write_u64(&mut bytes[56..64], self.departure_len);
+        LittleEndian::write_u64(&mut bytes[64..72], self.tombstone_len);
         Ok(bytes)
     }
 }
@@ -544,9 +593,23 @@ mod tests {
         original.service_file_len = rand::random::<u64>();
         original.election_len = rand::random::<u64>();
         original.update_len = rand::random::<u64>();
+        original.departure_len = rand::random::<u64>();
+        original.tombstone_len = rand::random::<u64>();
         let bytes = original.write_to_bytes().unwrap();
         let (_size_of_header, restored) = Header::from_bytes(&bytes, HEADER_VERSION);
         assert_eq!(bytes.len(), mem::size_of::<Header>() + 8);
         assert_eq!(original, restored);
     }
+    #[test]
+    fn read_version_2_header() {
+        let mut original = Header::default();
+        original.member_len = rand::random::<u64>();
+        original.departure_len = rand::random::<u64>();
+        let mut bytes = original.write_to_bytes().unwrap();
+        bytes.truncate(64);
+        LittleEndian::write_u64(&mut bytes[0..8], 64);
+        let (size_of_header, restored) = Header::from_bytes(&bytes, 2);
+        assert_eq!(size_of_header, 64);
+        assert_eq!(original, restored);
+    }
 }
@@ -126,6 +126,22 @@ impl RumorHeat {
         }
     }
 
+    /// The number of rumors being tracked; each is still "hot" for at
+    /// least those members it hasn't yet been shared with.
+    pub fn len(&self) -> usize {
+        self.0.read().expect("RumorHeat lock poisoned").len()
+    }
+
+    /// Stop tracking the rumors matching `predicate`, such as those which have been discarded;
+    /// they won't be gossiped again unless they are started up again.
+    pub fn stop_rumors<F>(&self, predicate: F)
+    where
+        F: Fn(&RumorKey) -> bool,
+    {
+        let mut heat_map = self.0.write().expect("RumorHeat lock poisoned");
+        heat_map.retain(|k, _| !predicate(k));
+    }
+
     /// When a member is considered "gone" (e.g., once it is
     /// considered Departed), we can get rid of all the "cooling"
     /// information, since we're not going to be sending anything
@@ -24,9 +24,11 @@ pub mod dat_file;
 pub mod departure;
 pub mod election;
 pub mod heat;
+pub mod ring_key;
 pub mod service;
 pub mod service_config;
 pub mod service_file;
+pub mod tombstone;
 
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
@@ -43,9 +45,11 @@ use serde::{Serialize, Serializer};
 
 pub use self::departure::Departure;
 pub use self::election::{Election, ElectionUpdate};
+pub use self::ring_key::RingKey;
 pub use self::service::Service;
 pub use self::service_config::ServiceConfig;
 pub use self::service_file::ServiceFile;
+pub use self::tombstone::Tombstone;
 use error::{Error, Result};
 use member::Membership;
 pub use protocol::newscast::{Rumor as ProtoRumor, RumorPayload, RumorType};
@@ -57,9 +61,11 @@ pub enum RumorKind {
     Election(Election),
     ElectionUpdate(ElectionUpdate),
     Membership(Membership),
+    RingKey(RingKey),
     Service(Service),
     ServiceConfig(ServiceConfig),
     ServiceFile(ServiceFile),
+    Tombstone(Tombstone),
 }
 
 impl From<RumorKind> for RumorPayload {
@@ -69,11 +75,13 @@ impl From<RumorKind> for RumorPayload {
             RumorKind::Election(election) => RumorPayload::Election(election.into()),
             RumorKind::ElectionUpdate(election) => RumorPayload::Election(election.into()),
             RumorKind::Membership(membership) => RumorPayload::Member(membership.into()),
+            RumorKind::RingKey(ring_key) => RumorPayload::RingKey(ring_key.into()),
             RumorKind::Service(service) => RumorPayload::Service(service.into()),
             RumorKind::ServiceConfig(service_config) => {
                 RumorPayload::ServiceConfig(service_config.into())
             }
             RumorKind::ServiceFile(service_file) => RumorPayload::ServiceFile(service_file.into()),
+            RumorKind::Tombstone(tombstone) => RumorPayload::Tombstone(tombstone.into()),
         }
     }
 }
@@ -314,6 +322,25 @@ impl<'a> Serialize for RumorStoreProxy<'a, ServiceFile> {
     }
 }
 
+impl<'a> Serialize for RumorStoreProxy<'a, Tombstone> {
+    fn serialize<S>(&self, serializer: S) -> result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let map = self.0.list.read().expect("Rumor store lock poisoned");
+        let inner_map = map.get("tombstone");
+        let mut s = serializer.serialize_seq(Some(inner_map.map_or(0, |m| m.len())))?;
+
+        if let Some(inner_map) = inner_map {
+            for k in inner_map.keys() {
+                s.serialize_element(k)?;
+            }
+        }
+
+        s.end()
+    }
+}
+
 impl<T> RumorStore<T>
 where
     T: Rumor,
@@ -391,6 +418,16 @@ where
         list.get_mut(key).and_then(|r| r.remove(id));
     }
 
+    /// Remove every rumor stored under the given key. Returns true if there were any.
+    pub fn remove_key(&self, key: &str) -> bool {
+        let mut list = self.list.write().expect("Rumor store lock poisoned");
+        let removed = list.remove(key).map_or(false, |r| !r.is_empty());
+        if removed {
+            self.increment_update_counter();
+        }
+        removed
+    }
+
     pub fn with_keys<F>(&self, mut with_closure: F)
     where
         F: FnMut((&String, &HashMap<String, T>)),
@@ -460,9 +497,11 @@ impl RumorEnvelope {
                 RumorKind::ElectionUpdate(ElectionUpdate::from_proto(proto)?)
             }
             RumorType::Member => RumorKind::Membership(Membership::from_proto(proto)?),
+            RumorType::RingKey => RumorKind::RingKey(RingKey::from_proto(proto)?),
             RumorType::Service => RumorKind::Service(Service::from_proto(proto)?),
             RumorType::ServiceConfig => RumorKind::ServiceConfig(ServiceConfig::from_proto(proto)?),
             RumorType::ServiceFile => RumorKind::ServiceFile(ServiceFile::from_proto(proto)?),
+            RumorType::Tombstone => RumorKind::Tombstone(Tombstone::from_proto(proto)?),
             RumorType::Fake | RumorType::Fake2 => panic!("fake rumor"),
         };
         Ok(RumorEnvelope {
@@ -697,6 +736,19 @@ mod tests {
             assert_eq!(rs.insert(f2), false);
         }
 
+        #[test]
+        fn remove_key_removes_all_rumors_for_key() {
+            let rs = create_rumor_store();
+            let f1 = FakeRumor::default();
+            let key = f1.key.clone();
+            rs.insert(f1);
+            rs.insert(FakeRumor::default());
+            assert!(rs.remove_key(&key));
+            assert_eq!(rs.len(), 0);
+            assert_eq!(rs.get_update_counter(), 3);
+            assert_eq!(rs.remove_key(&key), false);
+        }
+
         #[test]
         fn with_rumor_calls_closure_with_rumor() {
             let rs = create_rumor_store();
@@ -0,0 +1,178 @@
+// Copyright (c) 2018 Chef Software Inc. and/or applicable contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The RingKey rumor.
+//!
+//! RingKey rumors distribute a new revision of the ring key when it is rotated. Butterfly only
+//! gossips them; it is up to the Supervisor to write each new revision to its key cache and add it
+//! to the revisions the server accepts. They are never persisted to the rumor file, and the key
+//! itself is never serialized. Once every member which hasn't departed holds a revision, its
+//! rumor is removed.
+
+use std::cmp::Ordering;
+
+use error::{Error, Result};
+use protocol::{self, newscast, newscast::Rumor as ProtoRumor, FromProto};
+use rumor::{Rumor, RumorPayload, RumorType};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RingKey {
+    pub from_id: String,
+    pub name_with_rev: String,
+    /// The contents of the secret key file.
+    #[serde(skip_serializing)]
+    pub content: String,
+}
+
+impl RingKey {
+    pub fn new<S1, S2, S3>(member_id: S1, name_with_rev: S2, content: S3) -> Self
+    where
+        S1: Into<String>,
+        S2: Into<String>,
+        S3: Into<String>,
+    {
+        RingKey {
+            from_id: member_id.into(),
+            name_with_rev: name_with_rev.into(),
+            content: content.into(),
+        }
+    }
+
+    /// The revision of the key this rumor distributes.
+    pub fn rev(&self) -> &str {
+        self.name_with_rev
+            .rsplit('-')
+            .next()
+            .unwrap_or(&self.name_with_rev)
+    }
+}
+
+impl PartialOrd for RingKey {
+    fn partial_cmp(&self, other: &RingKey) -> Option<Ordering> {
+        if self.name_with_rev != other.name_with_rev {
+            None
+        } else {
+            Some(Ordering::Equal)
+        }
+    }
+}
+
+impl PartialEq for RingKey {
+    fn eq(&self, other: &RingKey) -> bool {
+        self.name_with_rev == other.name_with_rev
+    }
+}
+
+impl protocol::Message<ProtoRumor> for RingKey {}
+
+impl FromProto<ProtoRumor> for RingKey {
+    fn from_proto(rumor: ProtoRumor) -> Result<Self> {
+        let payload = match rumor.payload.ok_or(Error::ProtocolMismatch("payload"))? {
+            RumorPayload::RingKey(payload) => payload,
+            _ => panic!("from-bytes ring key"),
+        };
+        Ok(RingKey {
+            from_id: rumor.from_id.ok_or(Error::ProtocolMismatch("from-id"))?,
+            name_with_rev: payload
+                .name_with_rev
+                .ok_or(Error::ProtocolMismatch("name-with-rev"))?,
+            content: payload.content.ok_or(Error::ProtocolMismatch("content"))?,
+        })
+    }
+}
+
+impl From<RingKey> for newscast::RingKey {
+    fn from(value: RingKey) -> Self {
+        newscast::RingKey {
+            name_with_rev: Some(value.name_with_rev),
+            content: Some(value.content),
+        }
+    }
+}
+
+impl Rumor for RingKey {
+    /// A revision of a key never changes, so the first one we hear about is kept.
+    fn merge(&mut self, _other: RingKey) -> bool {
+        false
+    }
+
+    fn kind(&self) -> RumorType {
+        RumorType::RingKey
+    }
+
+    fn id(&self) -> &str {
+        &self.name_with_rev
+    }
+
+    fn key(&self) -> &str {
+        "ring_key"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json;
+
+    use super::RingKey;
+    use protocol::Message;
+    use rumor::{Rumor, RumorStore};
+
+    fn create_ring_key(rev: &str) -> RingKey {
+        RingKey::new(
+            "adam",
+            format!("wolverine-{}", rev),
+            format!("SYM-SEC-1\nwolverine-{}\n\nc2VjcmV0", rev),
+        )
+    }
+
+    #[test]
+    fn revisions_are_kept_side_by_side() {
+        let rs: RumorStore<RingKey> = RumorStore::default();
+        assert!(rs.insert(create_ring_key("20180101000000")));
+        assert!(rs.insert(create_ring_key("20180102000000")));
+        assert!(!rs.insert(create_ring_key("20180101000000")));
+
+        let list = rs.list.read().expect("Rumor store lock poisoned");
+        assert_eq!(list.get("ring_key").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn merge_never_changes_a_revision() {
+        let mut key = create_ring_key("20180101000000");
+        let mut other = create_ring_key("20180101000000");
+        other.content = String::from("something else");
+        assert!(!key.merge(other));
+        assert_eq!(key.content, create_ring_key("20180101000000").content);
+    }
+
+    #[test]
+    fn rev_is_the_suffix_of_the_name() {
+        assert_eq!(create_ring_key("20180101000000").rev(), "20180101000000");
+    }
+
+    #[test]
+    fn round_trips_through_bytes() {
+        let key = create_ring_key("20180101000000");
+        let decoded = RingKey::from_bytes(&key.write_to_bytes().unwrap()).unwrap();
+        assert_eq!(decoded.name_with_rev, key.name_with_rev);
+        assert_eq!(decoded.content, key.content);
+    }
+
+    #[test]
+    fn content_is_not_serialized() {
+        let json = serde_json::to_string(&create_ring_key("20180101000000")).unwrap();
+        assert!(json.contains("wolverine-20180101000000"));
+        assert!(!json.contains("c2VjcmV0"));
+    }
+}
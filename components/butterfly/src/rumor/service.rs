@@ -41,6 +41,10 @@ pub struct Service {
     pub pkg: String,
     pub cfg: Vec<u8>,
     pub sys: SysInfo,
+    /// The package whose smoke test failed on this member, if any.
+    pub failed_pkg: Option<String>,
+    /// The most recent health check status of the service on this member, if known.
+    pub health_check: Option<String>,
 }
 
 // Ensures that `cfg` is rendered as a map, and not an array of bytes
@@ -49,7 +53,7 @@ impl Serialize for Service {
     where
         S: Serializer,
     {
-        let mut strukt = serializer.serialize_struct("service", 7)?;
+        let mut strukt = serializer.serialize_struct("service", 9)?;
         let cfg = toml::from_slice(&self.cfg).unwrap_or(toml::value::Table::default());
         strukt.serialize_field("member_id", &self.member_id)?;
         strukt.serialize_field("service_group", &self.service_group)?;
@@ -58,6 +62,8 @@ impl Serialize for Service {
         strukt.serialize_field("cfg", &cfg)?;
         strukt.serialize_field("sys", &self.sys)?;
         strukt.serialize_field("initialized", &self.initialized)?;
+        strukt.serialize_field("failed_pkg", &self.failed_pkg)?;
+        strukt.serialize_field("health_check", &self.health_check)?;
         strukt.end()
     }
 }
@@ -115,6 +121,8 @@ impl Service {
             cfg: cfg
                 .map(|v| toml::ser::to_vec(v).expect("Struct should serialize to bytes"))
                 .unwrap_or_default(),
+            failed_pkg: None,
+            health_check: None,
         }
     }
 }
@@ -143,6 +151,8 @@ impl FromProto<newscast::Rumor> for Service {
                 .sys
                 .ok_or(Error::ProtocolMismatch("sys"))
                 .and_then(SysInfo::from_proto)?,
+            failed_pkg: payload.failed_pkg,
+            health_check: payload.health_check,
         })
     }
 }
@@ -157,6 +167,8 @@ impl From<Service> for newscast::Service {
             pkg: Some(value.pkg),
             cfg: Some(value.cfg),
             sys: Some(value.sys.into()),
+            failed_pkg: value.failed_pkg,
+            health_check: value.health_check,
         }
     }
 }
@@ -0,0 +1,198 @@
+// Copyright (c) 2018 Chef Software Inc. and/or applicable contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The Tombstone rumor.
+//!
+//! Tombstone rumors declare that a service group is gone. Members discard the group's
+//! configuration, files and elections on receipt, and ignore any of those rumors which are still
+//! being gossiped about for the group until a member advertises it again or the tombstone itself
+//! expires.
+
+use std::cmp::Ordering;
+use std::mem;
+use std::str::FromStr;
+
+use habitat_core::service::ServiceGroup;
+use time;
+
+use error::{Error, Result};
+use protocol::{self, newscast, newscast::Rumor as ProtoRumor, FromProto};
+use rumor::{Rumor, RumorPayload, RumorType};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Tombstone {
+    pub from_id: String,
+    pub service_group: ServiceGroup,
+    /// Seconds since the epoch at which the group was forgotten.
+    pub incarnation: u64,
+}
+
+impl Tombstone {
+    /// Creates a new Tombstone for a group forgotten now.
+    pub fn new<S>(member_id: S, service_group: ServiceGroup) -> Self
+    where
+        S: Into<String>,
+    {
+        Tombstone {
+            from_id: member_id.into(),
+            service_group: service_group,
+            incarnation: time::get_time().sec as u64,
+        }
+    }
+
+    /// Returns true if the tombstone is older than the given number of seconds.
+    pub fn is_older_than(&self, secs: u64) -> bool {
+        self.incarnation.saturating_add(secs) < time::get_time().sec as u64
+    }
+}
+
+impl PartialOrd for Tombstone {
+    fn partial_cmp(&self, other: &Tombstone) -> Option<Ordering> {
+        if self.service_group != other.service_group {
+            None
+        } else {
+            Some(self.incarnation.cmp(&other.incarnation))
+        }
+    }
+}
+
+impl PartialEq for Tombstone {
+    fn eq(&self, other: &Tombstone) -> bool {
+        self.service_group == other.service_group && self.incarnation == other.incarnation
+    }
+}
+
+impl protocol::Message<ProtoRumor> for Tombstone {}
+
+impl FromProto<ProtoRumor> for Tombstone {
+    fn from_proto(rumor: ProtoRumor) -> Result<Self> {
+        let payload = match rumor.payload.ok_or(Error::ProtocolMismatch("payload"))? {
+            RumorPayload::Tombstone(payload) => payload,
+            _ => panic!("from-bytes tombstone"),
+        };
+        Ok(Tombstone {
+            from_id: rumor.from_id.ok_or(Error::ProtocolMismatch("from-id"))?,
+            service_group: payload
+                .service_group
+                .ok_or(Error::ProtocolMismatch("service-group"))
+                .and_then(|s| ServiceGroup::from_str(&s).map_err(Error::from))?,
+            incarnation: payload.incarnation.unwrap_or(0),
+        })
+    }
+}
+
+impl From<Tombstone> for newscast::Tombstone {
+    fn from(value: Tombstone) -> Self {
+        newscast::Tombstone {
+            service_group: Some(value.service_group.to_string()),
+            incarnation: Some(value.incarnation),
+        }
+    }
+}
+
+impl Rumor for Tombstone {
+    /// The most recent tombstone for a group wins, so that forgetting a group again restarts its
+    /// expiry.
+    fn merge(&mut self, mut other: Tombstone) -> bool {
+        if *self >= other {
+            false
+        } else {
+            mem::swap(self, &mut other);
+            true
+        }
+    }
+
+    fn kind(&self) -> RumorType {
+        RumorType::Tombstone
+    }
+
+    fn id(&self) -> &str {
+        &self.service_group
+    }
+
+    fn key(&self) -> &str {
+        "tombstone"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+
+    use habitat_core::service::ServiceGroup;
+
+    use super::Tombstone;
+    use rumor::{Rumor, RumorStore};
+
+    fn create_tombstone(service: &str) -> Tombstone {
+        Tombstone::new(
+            "adam",
+            ServiceGroup::new(None, service, "production", None).unwrap(),
+        )
+    }
+
+    #[test]
+    fn multiple_tombstones_are_all_under_the_same_key() {
+        let rs: RumorStore<Tombstone> = RumorStore::default();
+        rs.insert(create_tombstone("neurosis"));
+        rs.insert(create_tombstone("isis"));
+
+        let list = rs.list.read().expect("Rumor store lock poisoned");
+        assert_eq!(list.len(), 1);
+        assert_eq!(list.get("tombstone").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn tombstones_with_different_incarnations_are_ordered_by_them() {
+        let s1 = create_tombstone("neurosis");
+        let mut s2 = create_tombstone("neurosis");
+        s2.incarnation += 1;
+        assert_eq!(s1.partial_cmp(&s2), Some(Ordering::Less));
+        assert_eq!(s2.partial_cmp(&s1), Some(Ordering::Greater));
+    }
+
+    #[test]
+    fn tombstones_for_different_service_groups_are_not_comparable() {
+        let s1 = create_tombstone("neurosis");
+        let s2 = create_tombstone("isis");
+        assert_eq!(s1.partial_cmp(&s2), None);
+    }
+
+    #[test]
+    fn merge_chooses_the_later_tombstone() {
+        let mut s1 = create_tombstone("neurosis");
+        let mut s2 = create_tombstone("neurosis");
+        s2.incarnation += 1;
+        let s2_check = s2.clone();
+        assert_eq!(s1.merge(s2), true);
+        assert_eq!(s1, s2_check);
+    }
+
+    #[test]
+    fn merge_returns_false_if_nothing_changed() {
+        let mut s1 = create_tombstone("neurosis");
+        let s1_check = s1.clone();
+        let s2 = s1.clone();
+        assert_eq!(s1.merge(s2), false);
+        assert_eq!(s1, s1_check);
+    }
+
+    #[test]
+    fn tombstones_expire() {
+        let mut tombstone = create_tombstone("neurosis");
+        assert!(!tombstone.is_older_than(60));
+        tombstone.incarnation -= 120;
+        assert!(tombstone.is_older_than(60));
+    }
+}
@@ -14,16 +14,23 @@
 
 //! Periodically check membership rumors to automatically "time out"
 //! `Suspect` rumors to `Confirmed`, and `Confirmed` rumors to
-//! `Departed`.
+//! `Departed`. Also discards the rumors of service groups which no
+//! alive member has run for longer than the rumor expiry, and
+//! tombstones older than it.
 
+use std::collections::HashMap;
 use std::thread;
 use std::time::Duration;
 
+use time::{Duration as TimeDuration, SteadyTime};
+
 use rumor::{RumorKey, RumorType};
 use server::timing::Timing;
 use server::Server;
 
 const LOOP_DELAY_MS: u64 = 500;
+/// How often service groups are checked for expired rumors.
+const RUMOR_EXPIRY_CHECK_MS: i64 = 60_000;
 
 pub struct Expire {
     pub server: Server,
@@ -39,6 +46,8 @@ impl Expire {
     }
 
     pub fn run(&self) {
+        let mut unadvertised_since = HashMap::new();
+        let mut next_rumor_expiry_check = SteadyTime::now();
         loop {
             let newly_confirmed_members = self
                 .server
@@ -63,7 +72,39 @@ impl Expire {
                     .start_hot_rumor(RumorKey::new(RumorType::Member, id, ""));
             }
 
+            self.server.rotate_ring_keys();
+
+            if SteadyTime::now() >= next_rumor_expiry_check {
+                self.expire_service_rumors(&mut unadvertised_since);
+                next_rumor_expiry_check =
+                    SteadyTime::now() + TimeDuration::milliseconds(RUMOR_EXPIRY_CHECK_MS);
+            }
+
             thread::sleep(Duration::from_millis(LOOP_DELAY_MS));
         }
     }
+
+    /// Expire the rumors of service groups which haven't been advertised by an alive member for
+    /// longer than the rumor expiry, tracking when each group was last seen advertised in
+    /// `unadvertised_since`.
+    fn expire_service_rumors(&self, unadvertised_since: &mut HashMap<String, SteadyTime>) {
+        let now = SteadyTime::now();
+        let expiry = self.timing.rumor_expiry_duration();
+        let service_groups = self.server.service_groups();
+        unadvertised_since.retain(|service_group, _| service_groups.contains(service_group));
+        for service_group in service_groups {
+            if self.server.is_advertised(&service_group) {
+                unadvertised_since.remove(&service_group);
+                continue;
+            }
+            let since = *unadvertised_since
+                .entry(service_group.clone())
+                .or_insert(now);
+            if now - since >= expiry {
+                self.server.expire_service_group(&service_group);
+                unadvertised_since.remove(&service_group);
+            }
+        }
+        self.server.expire_tombstones(expiry.num_seconds() as u64);
+    }
 }
@@ -24,7 +24,7 @@ use std::time::Duration;
 use super::AckSender;
 use member::Health;
 use server::{outbound, Server};
-use swim::{Ack, Ping, PingReq, Swim, SwimKind};
+use swim::{Ack, Ping, PingReq, Swim, SwimKind, SWIM_MAX_PACKET_LEN};
 use trace::TraceKind;
 
 /// Takes the Server and a channel to send received Acks to the outbound thread.
@@ -44,9 +44,10 @@ impl Inbound {
         }
     }
 
-    /// Run the thread. Listens for messages up to 1k in size, and then processes them accordingly.
+    /// Run the thread. Listens for messages up to `SWIM_MAX_PACKET_LEN` in size, and then
+    /// processes them accordingly.
     pub fn run(&self) {
-        let mut recv_buffer: Vec<u8> = vec![0; 1024];
+        let mut recv_buffer: Vec<u8> = vec![0; SWIM_MAX_PACKET_LEN];
         loop {
             if self.server.pause.load(Ordering::Relaxed) {
                 thread::sleep(Duration::from_millis(100));
@@ -54,8 +55,8 @@ impl Inbound {
             }
             match self.socket.recv_from(&mut recv_buffer[..]) {
                 Ok((length, addr)) => {
-                    let swim_payload = match self.server.unwrap_wire(&recv_buffer[0..length]) {
-                        Ok(swim_payload) => swim_payload,
+                    let wire = match self.server.unwrap_wire(&recv_buffer[0..length]) {
+                        Ok(wire) => wire,
                         Err(e) => {
                             // NOTE: In the future, we might want to block people who send us
                             // garbage all the time.
@@ -63,7 +64,7 @@ impl Inbound {
                             continue;
                         }
                     };
-                    let msg = match Swim::decode(&swim_payload) {
+                    let msg = match Swim::decode(&wire.payload) {
                         Ok(msg) => msg,
                         Err(e) => {
                             // NOTE: In the future, we might want to block people who send us
@@ -82,6 +83,10 @@ impl Inbound {
                                 );
                                 continue;
                             }
+                            // Pings always come straight from their sender, so they tell us
+                            // what it can receive.
+                            self.server
+                                .set_accepts_compact(&ping.from.id, wire.accepts_compact);
                             self.process_ping(addr, ping);
                         }
                         SwimKind::Ack(ack) => {
@@ -25,6 +25,7 @@ mod incarnation_store;
 mod outbound;
 mod pull;
 mod push;
+mod ring_keys;
 pub mod timing;
 
 use std::collections::HashSet;
@@ -33,29 +34,34 @@ use std::fmt::{self, Debug};
 use std::fs;
 use std::io;
 use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::result;
-use std::sync::atomic::{AtomicBool, AtomicIsize, Ordering};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicIsize, AtomicUsize, Ordering};
 use std::sync::mpsc::{self, channel};
 use std::sync::{Arc, RwLock};
 use std::thread;
 use std::time::{Duration, Instant};
 
 use habitat_core::crypto::SymKey;
+use habitat_core::service::ServiceGroup;
 use serde::ser::SerializeStruct;
 use serde::{Serialize, Serializer};
 
 use self::incarnation_store::IncarnationStore;
+use self::ring_keys::RingKeys;
 use error::{Error, Result};
 use member::{Health, Incarnation, Member, MemberList, MemberListProxy};
-use message;
+use message::{self, WireMessage};
 use rumor::dat_file::DatFile;
 use rumor::departure::Departure;
 use rumor::election::{Election, ElectionUpdate};
 use rumor::heat::RumorHeat;
+use rumor::ring_key::RingKey;
 use rumor::service::Service;
 use rumor::service_config::ServiceConfig;
 use rumor::service_file::ServiceFile;
+use rumor::tombstone::Tombstone;
 use rumor::{Rumor, RumorKey, RumorStore, RumorStoreProxy, RumorType};
 use swim::Ack;
 use trace::{Trace, TraceKind};
@@ -63,6 +69,8 @@ use trace::{Trace, TraceKind};
 /// The maximum number of other members we should notify when we shut
 /// down and leave the ring.
 const SELF_DEPARTURE_RUMOR_FANOUT: usize = 10;
+/// The file in a server's data path recording the revision of the ring key it encrypts with.
+const ACTIVE_RING_KEY_FILE: &'static str = "ACTIVE_RING_KEY";
 
 type AckReceiver = mpsc::Receiver<(SocketAddr, Ack)>;
 type AckSender = mpsc::Sender<(SocketAddr, Ack)>;
@@ -189,6 +197,11 @@ impl Myself {
         self.member.departed = true
     }
 
+    /// Records the newest revision of the ring key we hold, to report to the rest of the ring.
+    fn set_ring_key_rev(&mut self, rev: Option<String>) {
+        self.member.ring_key_rev = rev;
+    }
+
     /// Return a copy of the underlying `Member`.
     pub fn as_member(&self) -> Member {
         self.member.clone()
@@ -213,7 +226,7 @@ pub struct Server {
     // depends on it being so. Refactor so it can be private.
     pub member: Arc<RwLock<Myself>>,
     pub member_list: MemberList,
-    ring_key: Arc<Option<SymKey>>,
+    ring_keys: Arc<RwLock<RingKeys>>,
     rumor_heat: RumorHeat,
     pub service_store: RumorStore<Service>,
     pub service_config_store: RumorStore<ServiceConfig>,
@@ -221,6 +234,8 @@ pub struct Server {
     pub election_store: RumorStore<Election>,
     pub update_store: RumorStore<ElectionUpdate>,
     pub departure_store: RumorStore<Departure>,
+    pub tombstone_store: RumorStore<Tombstone>,
+    pub ring_key_store: RumorStore<RingKey>,
     swim_addr: Arc<RwLock<SocketAddr>>,
     gossip_addr: Arc<RwLock<SocketAddr>>,
     suitability_lookup: Arc<Box<Suitability>>,
@@ -233,7 +248,10 @@ pub struct Server {
     pub trace: Arc<RwLock<Trace>>,
     swim_rounds: Arc<AtomicIsize>,
     gossip_rounds: Arc<AtomicIsize>,
+    push_threads: Arc<AtomicUsize>,
+    push_sockets: Arc<AtomicUsize>,
     block_list: Arc<RwLock<HashSet<String>>>,
+    compact_members: Arc<RwLock<HashSet<String>>>,
 }
 
 impl Clone for Server {
@@ -243,7 +261,7 @@ impl Clone for Server {
             member_id: self.member_id.clone(),
             member: self.member.clone(),
             member_list: self.member_list.clone(),
-            ring_key: self.ring_key.clone(),
+            ring_keys: self.ring_keys.clone(),
             rumor_heat: self.rumor_heat.clone(),
             service_store: self.service_store.clone(),
             service_config_store: self.service_config_store.clone(),
@@ -251,6 +269,8 @@ impl Clone for Server {
             election_store: self.election_store.clone(),
             update_store: self.update_store.clone(),
             departure_store: self.departure_store.clone(),
+            tombstone_store: self.tombstone_store.clone(),
+            ring_key_store: self.ring_key_store.clone(),
             swim_addr: self.swim_addr.clone(),
             gossip_addr: self.gossip_addr.clone(),
             suitability_lookup: self.suitability_lookup.clone(),
@@ -261,7 +281,10 @@ impl Clone for Server {
             trace: self.trace.clone(),
             swim_rounds: self.swim_rounds.clone(),
             gossip_rounds: self.gossip_rounds.clone(),
+            push_threads: self.push_threads.clone(),
+            push_sockets: self.push_sockets.clone(),
             block_list: self.block_list.clone(),
+            compact_members: self.compact_members.clone(),
             socket: None,
         }
     }
@@ -296,6 +319,7 @@ impl Server {
             (Ok(Some(swim_socket_addr)), Ok(Some(gossip_socket_addr))) => {
                 member.swim_port = swim_socket_addr.port();
                 member.gossip_port = gossip_socket_addr.port();
+                member.ring_key_rev = ring_key.as_ref().map(|k| k.rev.clone());
 
                 let member_id = member.id.clone();
 
@@ -313,7 +337,7 @@ impl Server {
                     member_id: Arc::new(member_id),
                     member: Arc::new(RwLock::new(myself)),
                     member_list: MemberList::new(),
-                    ring_key: Arc::new(ring_key),
+                    ring_keys: Arc::new(RwLock::new(RingKeys::new(ring_key))),
                     rumor_heat: RumorHeat::default(),
                     service_store: RumorStore::default(),
                     service_config_store: RumorStore::default(),
@@ -321,6 +345,8 @@ impl Server {
                     election_store: RumorStore::default(),
                     update_store: RumorStore::default(),
                     departure_store: RumorStore::default(),
+                    tombstone_store: RumorStore::default(),
+                    ring_key_store: RumorStore::default(),
                     swim_addr: Arc::new(RwLock::new(swim_socket_addr)),
                     gossip_addr: Arc::new(RwLock::new(gossip_socket_addr)),
                     suitability_lookup: Arc::new(suitability_lookup),
@@ -331,7 +357,10 @@ impl Server {
                     trace: Arc::new(RwLock::new(trace)),
                     swim_rounds: Arc::new(AtomicIsize::new(0)),
                     gossip_rounds: Arc::new(AtomicIsize::new(0)),
+                    push_threads: Arc::new(AtomicUsize::new(0)),
+                    push_sockets: Arc::new(AtomicUsize::new(0)),
                     block_list: Arc::new(RwLock::new(HashSet::new())),
+                    compact_members: Arc::new(RwLock::new(HashSet::new())),
                     socket: None,
                 })
             }
@@ -369,6 +398,172 @@ impl Server {
         }
     }
 
+    /// The revision of the ring key payloads are encrypted with, if the ring is encrypted.
+    pub fn ring_key(&self) -> Option<SymKey> {
+        self.ring_keys
+            .read()
+            .expect("Ring keys lock poisoned")
+            .active()
+            .cloned()
+    }
+
+    /// Every revision of the ring key we accept, newest first.
+    pub fn ring_key_revs(&self) -> Vec<String> {
+        self.ring_keys
+            .read()
+            .expect("Ring keys lock poisoned")
+            .all()
+            .iter()
+            .map(|k| k.name_with_rev())
+            .collect()
+    }
+
+    /// The revision of the ring key the server with the given data path last encrypted with, if
+    /// it recorded one.
+    pub fn active_ring_key_rev<P: AsRef<Path>>(data_path: P) -> Option<String> {
+        fs::read_to_string(data_path.as_ref().join(ACTIVE_RING_KEY_FILE))
+            .ok()
+            .map(|rev| rev.trim().to_string())
+            .filter(|rev| !rev.is_empty())
+    }
+
+    /// Records the revision of the ring key we encrypt with in our data path. A server restarted
+    /// while the ring key is being rotated keeps encrypting with it, rather than with a newer
+    /// revision the rest of the ring may not hold yet.
+    fn persist_active_ring_key(&self) {
+        let path = match *self.data_path {
+            Some(ref path) => path.join(ACTIVE_RING_KEY_FILE),
+            None => return,
+        };
+        let rev = match self.ring_key() {
+            Some(key) => key.rev,
+            None => return,
+        };
+        let tmp = path.with_extension("tmp");
+        if let Err(err) = fs::write(&tmp, rev.as_bytes()).and_then(|_| fs::rename(&tmp, &path)) {
+            warn!(
+                "Failed to record ring key revision {} in {}, {}",
+                rev,
+                path.display(),
+                err
+            );
+        }
+    }
+
+    /// Adds a revision of the ring key, which we accept payloads encrypted with from now on and
+    /// report holding to the rest of the ring. Returns false if we already hold it, or it is
+    /// older than the revision we encrypt with.
+    pub fn add_ring_key(&self, key: SymKey) -> bool {
+        let newest = {
+            let mut ring_keys = self.ring_keys.write().expect("Ring keys lock poisoned");
+            if !ring_keys.insert(key) {
+                return false;
+            }
+            ring_keys.newest().map(|k| k.rev.clone())
+        };
+        let member = {
+            let mut me = self.member.write().expect("Member lock is poisoned");
+            me.set_ring_key_rev(newest);
+            me.increment_incarnation();
+            me.as_member()
+        };
+        self.insert_member(member, Health::Alive);
+        true
+    }
+
+    /// Starts encrypting with the newest revision of the ring key once every live member reports
+    /// holding it. Older revisions are only retired, and the RingKey rumors which distributed
+    /// newer ones removed, once every member which hasn't departed holds them; a Confirmed member
+    /// may yet come back, still encrypting with the revision it last held.
+    fn rotate_ring_keys(&self) {
+        let (newest, active) = {
+            let ring_keys = self.ring_keys.read().expect("Ring keys lock poisoned");
+            match (ring_keys.newest(), ring_keys.active()) {
+                (Some(newest), Some(active)) => (newest.rev.clone(), active.rev.clone()),
+                _ => return,
+            }
+        };
+        let health = self.member_list.health_by_id();
+        let mut live_members_hold_newest = true;
+        let me = self
+            .member
+            .read()
+            .expect("Member lock is poisoned")
+            .as_member();
+        let mut held_revs = vec![me.ring_key_rev];
+        self.member_list.with_members(|member| {
+            if member.id == *self.member_id {
+                return;
+            }
+            match health.get(&member.id) {
+                Some(&Health::Alive) | Some(&Health::Suspect) => {
+                    if member
+                        .ring_key_rev
+                        .as_ref()
+                        .map_or(true, |rev| *rev < newest)
+                    {
+                        live_members_hold_newest = false;
+                    }
+                }
+                Some(&Health::Confirmed) => (),
+                _ => return,
+            }
+            held_revs.push(member.ring_key_rev.clone());
+        });
+        // The newest revision every member which hasn't departed holds, if they all hold one.
+        let held_by_all = held_revs
+            .into_iter()
+            .collect::<Option<Vec<String>>>()
+            .and_then(|revs| revs.into_iter().min());
+        let activated = {
+            let mut ring_keys = self.ring_keys.write().expect("Ring keys lock poisoned");
+            let activated = live_members_hold_newest && ring_keys.activate_newest();
+            if activated {
+                info!(
+                    "Every member holds ring key revision {}; encrypting with it",
+                    newest
+                );
+            }
+            if held_by_all.as_ref().map_or(false, |rev| *rev >= active) {
+                for name_with_rev in ring_keys.retire() {
+                    info!("Retired ring key {}", name_with_rev);
+                }
+            }
+            activated
+        };
+        if activated {
+            self.persist_active_ring_key();
+        }
+        if let Some(rev) = held_by_all {
+            self.remove_adopted_ring_keys(&rev);
+        }
+    }
+
+    /// Stops gossiping, and forgets, the RingKey rumors of every revision up to `held_by_all`;
+    /// every member already holds them, and they are never expired otherwise.
+    fn remove_adopted_ring_keys(&self, held_by_all: &str) {
+        let mut adopted = Vec::new();
+        self.ring_key_store.with_rumors("ring_key", |ring_key| {
+            if ring_key.rev() <= held_by_all {
+                adopted.push(ring_key.name_with_rev.clone());
+            }
+        });
+        for name_with_rev in adopted {
+            debug!(
+                "Every member holds ring key {}; removing its rumor",
+                name_with_rev
+            );
+            self.ring_key_store.remove("ring_key", &name_with_rev);
+            self.rumor_heat
+                .stop_rumors(|rk| rk.kind == RumorType::RingKey && rk.id == name_with_rev);
+        }
+    }
+
+    /// The number of rumors which are still being actively gossiped.
+    pub fn hot_rumor_count(&self) -> usize {
+        self.rumor_heat.len()
+    }
+
     /// Every iteration of the gossip protocol (which means every member has been sent if they
     /// are available) increments the round. If we exceed an isize in rounds, we reset to 0.
     ///
@@ -395,6 +590,16 @@ impl Server {
         }
     }
 
+    /// The number of push worker threads currently running.
+    pub fn push_threads(&self) -> usize {
+        self.push_threads.load(Ordering::SeqCst)
+    }
+
+    /// The number of push sockets the push workers currently hold open to other members.
+    pub fn push_sockets(&self) -> usize {
+        self.push_sockets.load(Ordering::SeqCst)
+    }
+
     /// Start the server, along with a `Timing` for outbound connections. Spawns the `inbound`,
     /// `outbound`, and `expire` threads.
     ///
@@ -436,6 +641,7 @@ impl Server {
                 me.sync_incarnation()?;
             }
         }
+        self.persist_active_ring_key();
 
         let socket = match UdpSocket::bind(
             *self
@@ -563,6 +769,31 @@ impl Server {
         block_list.contains(member_id)
     }
 
+    /// Records whether a member can receive compressed and batched gossip, as advertised on the
+    /// pings it sends us.
+    fn set_accepts_compact(&self, member_id: &str, accepts_compact: bool) {
+        let mut compact_members = self
+            .compact_members
+            .write()
+            .expect("Write lock for compact_members is poisoned");
+        if accepts_compact {
+            if !compact_members.contains(member_id) {
+                compact_members.insert(member_id.to_string());
+            }
+        } else {
+            compact_members.remove(member_id);
+        }
+    }
+
+    /// Check if a given member has advertised that it can receive compressed and batched gossip.
+    pub fn accepts_compact(&self, member_id: &str) -> bool {
+        let compact_members = self
+            .compact_members
+            .read()
+            .expect("Read lock for compact_members is poisoned");
+        compact_members.contains(member_id)
+    }
+
     /// Stop the outbound and inbound threads from processing work.
     pub fn pause(&mut self) {
         self.pause.compare_and_swap(false, true, Ordering::Relaxed);
@@ -776,6 +1007,10 @@ impl Server {
     /// Insert a service config rumor into the service store.
     pub fn insert_service_config(&self, service_config: ServiceConfig) {
         let rk = RumorKey::from(&service_config);
+        if self.is_forgotten(&rk.key) {
+            debug!("Ignoring rumor for forgotten service group: {:?}", rk);
+            return;
+        }
         if self.service_config_store.insert(service_config) {
             self.rumor_heat.start_hot_rumor(rk);
         }
@@ -784,11 +1019,23 @@ impl Server {
     /// Insert a service file rumor into the service file store.
     pub fn insert_service_file(&self, service_file: ServiceFile) {
         let rk = RumorKey::from(&service_file);
+        if self.is_forgotten(&rk.key) {
+            debug!("Ignoring rumor for forgotten service group: {:?}", rk);
+            return;
+        }
         if self.service_file_store.insert(service_file) {
             self.rumor_heat.start_hot_rumor(rk);
         }
     }
 
+    /// Insert a ring key rumor into the ring key store.
+    pub fn insert_ring_key(&self, ring_key: RingKey) {
+        let rk = RumorKey::from(&ring_key);
+        if self.ring_key_store.insert(ring_key) {
+            self.rumor_heat.start_hot_rumor(rk);
+        }
+    }
+
     /// Insert a departure rumor into the departure store.
     pub fn insert_departure(&self, departure: Departure) {
         let rk = RumorKey::from(&departure);
@@ -812,6 +1059,129 @@ impl Server {
         }
     }
 
+    /// Insert a tombstone rumor into the tombstone store, discarding the rumors of the service
+    /// group it forgets.
+    pub fn insert_tombstone(&self, tombstone: Tombstone) {
+        let rk = RumorKey::from(&tombstone);
+        if self.store_tombstone(tombstone) {
+            self.rumor_heat.start_hot_rumor(rk);
+        }
+    }
+
+    /// Like `insert_tombstone`, but for a tombstone read back from the rumor file, which isn't
+    /// gossiped again; it may be one only ever kept locally for an expired service group.
+    pub fn restore_tombstone(&self, tombstone: Tombstone) {
+        self.store_tombstone(tombstone);
+    }
+
+    /// Returns true if the tombstone was new. The rumors of a service group which is still
+    /// running somewhere are kept; the tombstone only stops them being taken in again once it
+    /// isn't.
+    fn store_tombstone(&self, tombstone: Tombstone) -> bool {
+        let service_group = tombstone.service_group.to_string();
+        if !self.tombstone_store.insert(tombstone) {
+            return false;
+        }
+        if !self.is_advertised(&service_group) {
+            self.discard_service_group(&service_group);
+        }
+        true
+    }
+
+    /// Returns true if this member or any alive member is running the given service group.
+    pub fn is_advertised(&self, service_group: &str) -> bool {
+        let mut advertised = false;
+        self.service_store.with_rumors(service_group, |s| {
+            advertised |= s.member_id == self.member_id()
+                || self
+                    .member_list
+                    .check_health_of_by_id(&s.member_id, Health::Alive);
+        });
+        advertised
+    }
+
+    /// Returns true if the given service group has been forgotten, and hasn't been advertised by
+    /// any alive member since.
+    fn is_forgotten(&self, service_group: &str) -> bool {
+        self.tombstone_store
+            .contains_rumor("tombstone", service_group)
+            && !self.is_advertised(service_group)
+    }
+
+    /// Every service group which rumors are held for.
+    pub fn service_groups(&self) -> HashSet<String> {
+        let mut service_groups = HashSet::new();
+        self.service_store.with_keys(|(key, _)| {
+            service_groups.insert(key.clone());
+        });
+        self.service_config_store.with_keys(|(key, _)| {
+            service_groups.insert(key.clone());
+        });
+        self.service_file_store.with_keys(|(key, _)| {
+            service_groups.insert(key.clone());
+        });
+        self.election_store.with_keys(|(key, _)| {
+            service_groups.insert(key.clone());
+        });
+        self.update_store.with_keys(|(key, _)| {
+            service_groups.insert(key.clone());
+        });
+        service_groups
+    }
+
+    /// Discard the rumors of a service group which hasn't been advertised for longer than the
+    /// rumor expiry. A tombstone is kept for the group, though not gossiped, so that the rumors
+    /// aren't taken in again from members which have yet to expire them.
+    pub fn expire_service_group(&self, service_group: &str) {
+        debug!("Expiring rumors for service group {}", service_group);
+        if let Ok(sg) = ServiceGroup::from_str(service_group) {
+            self.tombstone_store
+                .insert(Tombstone::new(self.member_id(), sg));
+        }
+        self.discard_service_group(service_group);
+    }
+
+    /// Remove tombstones older than the given number of seconds.
+    pub fn expire_tombstones(&self, secs: u64) {
+        let mut expired = vec![];
+        self.tombstone_store.with_rumors("tombstone", |t| {
+            if t.is_older_than(secs) {
+                expired.push(t.service_group.to_string());
+            }
+        });
+        for service_group in expired {
+            debug!("Expiring tombstone for service group {}", service_group);
+            self.tombstone_store.remove("tombstone", &service_group);
+            self.rumor_heat
+                .stop_rumors(|rk| rk.kind == RumorType::Tombstone && rk.id == service_group);
+        }
+    }
+
+    /// Remove the configuration, files and elections of a service group, along with the service
+    /// rumors of those of its members which are no longer alive.
+    fn discard_service_group(&self, service_group: &str) {
+        self.service_config_store.remove_key(service_group);
+        self.service_file_store.remove_key(service_group);
+        self.election_store.remove_key(service_group);
+        self.update_store.remove_key(service_group);
+        let mut gone = HashSet::new();
+        self.service_store.with_rumors(service_group, |s| {
+            if s.member_id != self.member_id()
+                && !self
+                    .member_list
+                    .check_health_of_by_id(&s.member_id, Health::Alive)
+            {
+                gone.insert(s.member_id.clone());
+            }
+        });
+        for member_id in gone.iter() {
+            self.service_store.remove(service_group, member_id);
+        }
+        self.rumor_heat.stop_rumors(|rk| {
+            rk.key == service_group && (rk.kind != RumorType::Service || gone.contains(&rk.id))
+        });
+    }
+
     /// Get all the Member ID's who are present in a given service group, and eligible to vote
     /// (alive)
     fn get_electorate(&self, key: &str) -> Vec<String> {
@@ -1005,6 +1375,10 @@ impl Server {
     pub fn insert_election(&self, mut election: Election) {
         debug!("insert_election: {:?}", election);
         let rk = RumorKey::from(&election);
+        if self.is_forgotten(&rk.key) {
+            debug!("Ignoring rumor for forgotten service group: {:?}", rk);
+            return;
+        }
 
         // If this is an election for a service group we care about
         if self
@@ -1080,6 +1454,10 @@ impl Server {
     pub fn insert_update_election(&self, mut election: ElectionUpdate) {
         debug!("insert_update_election: {:?}", election);
         let rk = RumorKey::from(&election);
+        if self.is_forgotten(&rk.key) {
+            debug!("Ignoring rumor for forgotten service group: {:?}", rk);
+            return;
+        }
 
         // If this is an election for a service group we care about
         if self
@@ -1153,11 +1531,18 @@ impl Server {
     }
 
     fn generate_wire(&self, payload: Vec<u8>) -> Result<Vec<u8>> {
-        message::generate_wire(payload, (*self.ring_key).as_ref())
+        let ring_keys = self.ring_keys.read().expect("Ring keys lock poisoned");
+        message::generate_wire(payload, ring_keys.active())
+    }
+
+    fn generate_batch_wire(&self, payloads: Vec<Vec<u8>>) -> Result<Vec<u8>> {
+        let ring_keys = self.ring_keys.read().expect("Ring keys lock poisoned");
+        message::generate_batch_wire(payloads, ring_keys.active())
     }
 
-    fn unwrap_wire(&self, payload: &[u8]) -> Result<Vec<u8>> {
-        message::unwrap_wire(payload, (*self.ring_key).as_ref())
+    fn unwrap_wire(&self, payload: &[u8]) -> Result<WireMessage> {
+        let ring_keys = self.ring_keys.read().expect("Ring keys lock poisoned");
+        message::unwrap_wire(payload, ring_keys.all())
     }
 
     pub fn persist_data(&self) {
@@ -1181,7 +1566,7 @@ impl Serialize for Server {
     where
         S: Serializer,
     {
-        let mut strukt = serializer.serialize_struct("butterfly", 7)?;
+        let mut strukt = serializer.serialize_struct("butterfly", 8)?;
         strukt.serialize_field("member", &self.member_list)?;
         strukt.serialize_field("service", &self.service_store)?;
         strukt.serialize_field("service_config", &self.service_config_store)?;
@@ -1189,6 +1574,7 @@ impl Serialize for Server {
         strukt.serialize_field("election", &self.election_store)?;
         strukt.serialize_field("election_update", &self.update_store)?;
         strukt.serialize_field("departure", &self.departure_store)?;
+        strukt.serialize_field("tombstone", &self.tombstone_store)?;
         strukt.end()
     }
 }
@@ -1237,6 +1623,7 @@ impl<'a> Serialize for ServerProxy<'a> {
         let eusp = RumorStoreProxy::new(&self.0.update_store);
         let scsp = RumorStoreProxy::new(&self.0.service_config_store);
         let sfsp = RumorStoreProxy::new(&self.0.service_file_store);
+        let tsp = RumorStoreProxy::new(&self.0.tombstone_store);
         let mlp = MemberListProxy::new(&self.0.member_list);
 
         let mut strukt = serializer.serialize_struct("butterfly_server", 7)?;
@@ -1254,6 +1641,8 @@ impl<'a> Serialize for ServerProxy<'a> {
         strukt.serialize_field("latest_election_update", &eusp)?;
         strukt.serialize_field("departure", &self.0.departure_store)?;
         strukt.serialize_field("departed_members", &dsp)?;
+        strukt.serialize_field("tombstone", &self.0.tombstone_store)?;
+        strukt.serialize_field("forgotten_service_groups", &tsp)?;
         strukt.end()
     }
 }
@@ -1322,14 +1711,21 @@ mod tests {
     }
 
     mod server {
+        use habitat_core::crypto::SymKey;
+        use habitat_core::package::PackageIdent;
+        use habitat_core::service::ServiceGroup;
         use member::Member;
+        use rumor::service::SysInfo;
+        use rumor::{RumorType, Service, ServiceConfig, ServiceFile, Tombstone};
         use server::timing::Timing;
         use server::{Server, Suitability};
         use std::fs::File;
         use std::io::prelude::*;
-        use std::path::PathBuf;
+        use std::path::{Path, PathBuf};
+        use std::str::FromStr;
         use std::sync::Mutex;
         use tempfile::TempDir;
+        use test_helpers::ring_key;
         use trace::Trace;
 
         lazy_static! {
@@ -1346,6 +1742,10 @@ mod tests {
         }
 
         fn start_server() -> Server {
+            start_server_with(None, None)
+        }
+
+        fn start_server_with(ring_key: Option<SymKey>, data_path: Option<&Path>) -> Server {
             let swim_port;
             {
                 let mut swim_port_guard = SWIM_PORT.lock().expect("SWIM_PORT poisoned");
@@ -1368,9 +1768,9 @@ mod tests {
                 &gossip_listen[..],
                 member,
                 Trace::default(),
+                ring_key,
                 None,
-                None,
-                None::<PathBuf>,
+                data_path,
                 Box::new(ZeroSuitability),
             ).unwrap()
         }
@@ -1449,5 +1849,117 @@ mod tests {
                 .start(Timing::default())
                 .expect("Server failed to start");
         }
+
+        #[test]
+        fn records_the_ring_key_revision_it_encrypts_with() {
+            let tmpdir = TempDir::new().unwrap();
+            let key = ring_key("20180101000000");
+            let mut server = start_server_with(Some(key), Some(tmpdir.path()));
+            server
+                .start(Timing::default())
+                .expect("Server failed to start");
+            assert_eq!(
+                Server::active_ring_key_rev(tmpdir.path()),
+                Some("20180101000000".to_string())
+            );
+
+            assert!(server.add_ring_key(ring_key("20180102000000")));
+            assert_eq!(
+                Server::active_ring_key_rev(tmpdir.path()),
+                Some("20180101000000".to_string())
+            );
+
+            server.rotate_ring_keys();
+            assert_eq!(server.ring_key().unwrap().rev, "20180102000000");
+            assert_eq!(
+                Server::active_ring_key_rev(tmpdir.path()),
+                Some("20180102000000".to_string())
+            );
+        }
+
+        fn service_config(service_group: &ServiceGroup) -> ServiceConfig {
+            ServiceConfig::new(
+                "adam",
+                service_group.clone(),
+                Vec::from("tcp-backlog = 128"),
+            )
+        }
+
+        #[test]
+        fn tombstone_discards_service_group_rumors() {
+            let server = start_server();
+            let sg = ServiceGroup::from_str("witcher.prod").unwrap();
+            server.insert_service_config(service_config(&sg));
+            server.insert_service_file(ServiceFile::new(
+                "adam",
+                sg.clone(),
+                "yep",
+                Vec::from("body"),
+            ));
+            server.insert_tombstone(Tombstone::new("adam", sg.clone()));
+            assert_eq!(server.service_config_store.len(), 0);
+            assert_eq!(server.service_file_store.len(), 0);
+
+            // Rumors still being gossiped about the forgotten group aren't taken in again
+            server.insert_service_config(service_config(&sg));
+            assert_eq!(server.service_config_store.len(), 0);
+        }
+
+        #[test]
+        fn tombstone_keeps_rumors_of_an_advertised_group() {
+            let server = start_server();
+            let sg = ServiceGroup::from_str("witcher.prod").unwrap();
+            let ident = PackageIdent::from_str("core/witcher/1.2.3/20161208121212").unwrap();
+            server.insert_service(Service::new(
+                server.member_id(),
+                &ident,
+                sg.clone(),
+                SysInfo::default(),
+                None,
+            ));
+            server.insert_service_config(service_config(&sg));
+            server.insert_tombstone(Tombstone::new("adam", sg.clone()));
+            assert_eq!(server.service_config_store.len(), 1);
+            assert_eq!(server.service_store.len(), 1);
+        }
+
+        #[test]
+        fn restored_tombstone_is_not_gossiped() {
+            let server = start_server();
+            let sg = ServiceGroup::from_str("witcher.prod").unwrap();
+            server.insert_service_config(service_config(&sg));
+            server.restore_tombstone(Tombstone::new(server.member_id(), sg.clone()));
+            assert_eq!(server.service_config_store.len(), 0);
+            let hot = server.rumor_heat.currently_hot_rumors("adam");
+            assert!(hot.iter().all(|rk| rk.kind != RumorType::Tombstone));
+        }
+
+        #[test]
+        fn expired_service_group_keeps_a_tombstone() {
+            let server = start_server();
+            let sg = ServiceGroup::from_str("witcher.prod").unwrap();
+            server.insert_service_config(service_config(&sg));
+            assert!(server.service_groups().contains("witcher.prod"));
+            server.expire_service_group("witcher.prod");
+            assert!(server.service_groups().is_empty());
+            assert!(
+                server
+                    .tombstone_store
+                    .contains_rumor("tombstone", "witcher.prod")
+            );
+        }
+
+        #[test]
+        fn old_tombstones_expire() {
+            let server = start_server();
+            let sg = ServiceGroup::from_str("witcher.prod").unwrap();
+            let mut tombstone = Tombstone::new("adam", sg.clone());
+            tombstone.incarnation -= 120;
+            server.insert_tombstone(tombstone);
+            server.expire_tombstones(60);
+            assert_eq!(server.tombstone_store.len(), 0);
+            server.insert_service_config(service_config(&sg));
+            assert_eq!(server.service_config_store.len(), 1);
+        }
     }
 }
@@ -245,14 +245,14 @@ impl Outbound {
     }
 }
 
-/// Populate a SWIM message with rumors.
+/// Populate a SWIM message with rumors, as many as fit in a single packet.
 pub fn populate_membership_rumors(server: &Server, target: &Member, swim: &mut Swim) {
     // If this isn't the first time we are communicating with this target, we want to include this
     // targets current status. This ensures that members always get a "Confirmed" rumor, before we
     // have the chance to flip it to "Alive", which helps make sure we heal from a partition.
     if server.member_list.contains_member(&target.id) {
         if let Some(always_target) = server.member_list.membership_for(&target.id) {
-            swim.membership.push(always_target);
+            swim.push_membership(always_target);
         }
     }
 
@@ -266,16 +266,21 @@ pub fn populate_membership_rumors(server: &Server, target: &Member, swim: &mut S
         .take(5) // TODO (CM): magic number!
         .collect();
 
-    for ref rkey in rumors.iter() {
+    // Rumors which don't fit in the packet stay hot, to be sent next time.
+    let mut sent = Vec::with_capacity(rumors.len());
+    for rkey in rumors {
         if let Some(member) = server.member_list.membership_for(&rkey.key()) {
-            swim.membership.push(member);
+            if !swim.push_membership(member) {
+                continue;
+            }
         }
+        sent.push(rkey);
     }
     // We don't want to update the heat for rumors that we know we are sending to a target that is
     // confirmed dead; the odds are, they won't receive them. Lets spam them a little harder with
     // rumors.
     if !server.member_list.persistent_and_confirmed(target) {
-        server.rumor_heat.cool_rumors(&target.id, &rumors);
+        server.rumor_heat.cool_rumors(&target.id, &sent);
     }
 }
 
@@ -66,8 +66,8 @@ impl Pull {
                     continue 'recv;
                 }
             };
-            let payload = match self.server.unwrap_wire(&msg) {
-                Ok(payload) => payload,
+            let payloads = match self.server.unwrap_wire(&msg).and_then(|w| w.payloads()) {
+                Ok(payloads) => payloads,
                 Err(e) => {
                     // NOTE: In the future, we might want to block people who send us
                     // garbage all the time.
@@ -75,42 +75,55 @@ impl Pull {
                     continue;
                 }
             };
-            let proto = match RumorEnvelope::decode(&payload) {
-                Ok(proto) => proto,
-                Err(e) => {
-                    error!("Error parsing protocol message: {:?}", e);
-                    continue 'recv;
-                }
-            };
-            if self.server.is_member_blocked(&proto.from_id) {
-                warn!(
-                    "Not processing message from {} - it is blocked",
-                    proto.from_id
-                );
-                continue 'recv;
+            for payload in payloads {
+                self.process_rumor(&payload);
             }
-            trace_it!(GOSSIP: &self.server, TraceKind::RecvRumor, &proto.from_id, &proto);
-            match proto.kind {
-                RumorKind::Membership(membership) => {
-                    self.server
-                        .insert_member_from_rumor(membership.member, membership.health);
-                }
-                RumorKind::Service(service) => self.server.insert_service(service),
-                RumorKind::ServiceConfig(service_config) => {
-                    self.server.insert_service_config(service_config);
-                }
-                RumorKind::ServiceFile(service_file) => {
-                    self.server.insert_service_file(service_file);
-                }
-                RumorKind::Election(election) => {
-                    self.server.insert_election(election);
-                }
-                RumorKind::ElectionUpdate(election) => {
-                    self.server.insert_update_election(election);
-                }
-                RumorKind::Departure(departure) => {
-                    self.server.insert_departure(departure);
-                }
+        }
+    }
+
+    /// Decode a single rumor and insert it into the server.
+    fn process_rumor(&self, payload: &[u8]) {
+        let proto = match RumorEnvelope::decode(payload) {
+            Ok(proto) => proto,
+            Err(e) => {
+                error!("Error parsing protocol message: {:?}", e);
+                return;
+            }
+        };
+        if self.server.is_member_blocked(&proto.from_id) {
+            warn!(
+                "Not processing message from {} - it is blocked",
+                proto.from_id
+            );
+            return;
+        }
+        trace_it!(GOSSIP: &self.server, TraceKind::RecvRumor, &proto.from_id, &proto);
+        match proto.kind {
+            RumorKind::Membership(membership) => {
+                self.server
+                    .insert_member_from_rumor(membership.member, membership.health);
+            }
+            RumorKind::Service(service) => self.server.insert_service(service),
+            RumorKind::ServiceConfig(service_config) => {
+                self.server.insert_service_config(service_config);
+            }
+            RumorKind::ServiceFile(service_file) => {
+                self.server.insert_service_file(service_file);
+            }
+            RumorKind::Election(election) => {
+                self.server.insert_election(election);
+            }
+            RumorKind::ElectionUpdate(election) => {
+                self.server.insert_update_election(election);
+            }
+            RumorKind::Departure(departure) => {
+                self.server.insert_departure(departure);
+            }
+            RumorKind::Tombstone(tombstone) => {
+                self.server.insert_tombstone(tombstone);
+            }
+            RumorKind::RingKey(ring_key) => {
+                self.server.insert_ring_key(ring_key);
             }
         }
     }
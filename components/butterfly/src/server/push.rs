@@ -16,12 +16,25 @@
 //!
 //! This is the thread for distributing rumors to members. It distributes to `FANOUT` members, no
 //! more often than `Timing::GOSSIP_PERIOD_DEFAULT_MS`.
+//!
+//! The sending itself is done by a fixed pool of long-lived worker threads. Each member is always
+//! handed to the same worker, which keeps a push socket to it open across gossip rounds rather
+//! than connecting afresh every time. Sends never block: a member which isn't accepting messages
+//! is skipped for the round, so one unreachable member doesn't hold up the others its worker
+//! sends to. Members which accept compact payloads are sent their rumors in compressed batches;
+//! everyone else gets one uncompressed message per rumor.
 
-use std::sync::atomic::Ordering;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::mem;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::Arc;
 use std::thread;
 use std::time::Duration;
 
-use time::SteadyTime;
+use time::{self, SteadyTime};
 use zmq;
 
 use member::{Member, Membership};
@@ -32,12 +45,19 @@ use trace::TraceKind;
 use ZMQ_CONTEXT;
 
 const FANOUT: usize = 5;
+/// The number of push worker threads; one per member in a fanout batch.
+const PUSH_WORKERS: usize = FANOUT;
+/// How long a push socket may go unused before its worker closes it.
+const PUSH_SOCKET_IDLE_MS: i64 = 300_000;
+/// Once a batch of rumors reaches this many bytes it is sent, and a new batch started.
+const MAX_BATCH_LEN: usize = 256 * 1024;
 
 /// The Push server
 #[derive(Debug)]
 pub struct Push {
     pub server: Server,
     pub timing: Timing,
+    workers: Vec<Option<Sender<PushJob>>>,
 }
 
 impl Push {
@@ -46,6 +66,7 @@ impl Push {
         Push {
             server: server,
             timing: timing,
+            workers: (0..PUSH_WORKERS).map(|_| None).collect(),
         }
     }
 
@@ -66,7 +87,6 @@ impl Push {
             let long_wait = self.timing.gossip_timeout();
 
             'fanout: loop {
-                let mut thread_list = Vec::with_capacity(FANOUT);
                 if check_list.len() == 0 {
                     break 'fanout;
                 }
@@ -76,6 +96,7 @@ impl Push {
                     check_list.len()
                 };
                 let next_gossip = self.timing.gossip_timeout();
+                let (done_tx, done_rx) = mpsc::channel();
                 for member in check_list.drain(0..drain_length) {
                     if self.server.is_member_blocked(&member.id) {
                         debug!("Not sending rumors to {} - it is blocked", member.id);
@@ -90,29 +111,19 @@ impl Push {
                     {
                         let rumors = self.server.rumor_heat.currently_hot_rumors(&member.id);
                         if rumors.len() > 0 {
-                            let sc = self.server.clone();
-
-                            let guard = match thread::Builder::new()
-                                .name(String::from("push-worker"))
-                                .spawn(move || {
-                                    PushWorker::new(sc).send_rumors(member, rumors);
-                                }) {
-                                Ok(guard) => guard,
-                                Err(e) => {
-                                    error!("Could not spawn thread: {}", e);
-                                    continue;
-                                }
-                            };
-                            thread_list.push(guard);
+                            self.dispatch(PushJob {
+                                member: member,
+                                rumors: rumors,
+                                _done: done_tx.clone(),
+                            });
                         }
                     }
                 }
-                let num_threads = thread_list.len();
-                for guard in thread_list.drain(0..num_threads) {
-                    let _ = guard
-                        .join()
-                        .map_err(|e| error!("Push worker died: {:?}", e));
-                }
+                // Every job holds a clone of `done_tx`, which is dropped once its worker is
+                // finished with it, whether or not the rumors made it out. The receiver runs dry
+                // when the whole batch has been sent.
+                drop(done_tx);
+                for _ in done_rx.iter() {}
                 if SteadyTime::now() < next_gossip {
                     let wait_time = (next_gossip - SteadyTime::now()).num_milliseconds();
                     if wait_time > 0 {
@@ -128,24 +139,61 @@ impl Push {
             }
         }
     }
+
+    /// Hands a job to the worker responsible for its member, starting the worker if it isn't
+    /// running yet or has died.
+    fn dispatch(&mut self, job: PushJob) {
+        let mut hasher = DefaultHasher::new();
+        job.member.id.hash(&mut hasher);
+        let index = hasher.finish() as usize % self.workers.len();
+        let job = match self.workers[index] {
+            Some(ref tx) => match tx.send(job) {
+                Ok(()) => return,
+                Err(mpsc::SendError(job)) => {
+                    warn!("Push worker {} died; restarting it", index);
+                    job
+                }
+            },
+            None => job,
+        };
+        let (tx, rx) = mpsc::channel();
+        let server = self.server.clone();
+        match thread::Builder::new()
+            .name(format!("push-worker-{}", index))
+            .spawn(move || PushWorker::new(server).run(rx))
+        {
+            Ok(_) => {
+                // The receiver only goes away if the worker dies, in which case the job is lost
+                // just as it would be had the worker died while sending it.
+                let _ = tx.send(job);
+                self.workers[index] = Some(tx);
+            }
+            Err(e) => {
+                error!("Could not spawn thread: {}", e);
+                self.workers[index] = None;
+            }
+        }
+    }
 }
 
-/// A worker thread for pushing messages to a target
-struct PushWorker {
-    pub server: Server,
+/// A batch of rumors for a push worker to send to a member.
+struct PushJob {
+    member: Member,
+    rumors: Vec<RumorKey>,
+    /// Dropped once the job is finished with, to let the push thread know.
+    _done: Sender<()>,
 }
 
-impl PushWorker {
-    /// Create a new PushWorker.
-    pub fn new(server: Server) -> PushWorker {
-        PushWorker { server: server }
-    }
+/// An open push socket to a member.
+struct PushSocket {
+    address: String,
+    socket: zmq::Socket,
+    last_used: SteadyTime,
+    count: Arc<AtomicUsize>,
+}
 
-    /// Send the list of rumors to a given member. This method creates an outbound socket and then
-    /// closes the connection as soon as we are done sending rumors. ZeroMQ may choose to keep the
-    /// connection and socket open for 1 second longer - so it is possible, but unlikely, that this
-    /// method can loose messages.
-    fn send_rumors(&self, member: Member, rumors: Vec<RumorKey>) {
+impl PushSocket {
+    fn connect(address: String, count: Arc<AtomicUsize>) -> zmq::Result<PushSocket> {
         let socket = (**ZMQ_CONTEXT)
             .as_mut()
             .socket(zmq::PUSH)
@@ -162,17 +210,88 @@ impl PushWorker {
         socket
             .set_sndhwm(1000)
             .expect("Failure to set the ZMQ push socket hwm");
-        socket
-            .set_sndtimeo(500)
-            .expect("Failure to set the ZMQ send timeout");
-        let to_addr = format!("{}:{}", member.address, member.gossip_port);
-        match socket.connect(&format!("tcp://{}", to_addr)) {
-            Ok(()) => debug!("Connected push socket to {:?}", member),
+        socket.connect(&format!("tcp://{}", address))?;
+        count.fetch_add(1, Ordering::SeqCst);
+        Ok(PushSocket {
+            address: address,
+            socket: socket,
+            last_used: SteadyTime::now(),
+            count: count,
+        })
+    }
+}
+
+impl Drop for PushSocket {
+    fn drop(&mut self) {
+        self.count.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// A worker thread for pushing messages to the members it is handed
+struct PushWorker {
+    pub server: Server,
+    sockets: HashMap<String, PushSocket>,
+}
+
+impl PushWorker {
+    /// Create a new PushWorker.
+    pub fn new(server: Server) -> PushWorker {
+        server.push_threads.fetch_add(1, Ordering::SeqCst);
+        PushWorker {
+            server: server,
+            sockets: HashMap::new(),
+        }
+    }
+
+    /// Sends each job received, closing sockets which have gone unused for
+    /// `PUSH_SOCKET_IDLE_MS` along the way. Returns once the push thread hangs up.
+    fn run(&mut self, rx: Receiver<PushJob>) {
+        let idle = time::Duration::milliseconds(PUSH_SOCKET_IDLE_MS);
+        loop {
+            match rx.recv_timeout(Duration::from_millis(PUSH_SOCKET_IDLE_MS as u64)) {
+                Ok(job) => self.send_rumors(job.member, job.rumors),
+                Err(RecvTimeoutError::Timeout) => (),
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+            let now = SteadyTime::now();
+            self.sockets.retain(|_, s| now - s.last_used < idle);
+        }
+    }
+
+    /// Takes the open socket to a member out of the pool, connecting a new one if there isn't
+    /// one or the member has moved. The socket should be put back once it's been used.
+    fn take_socket(&mut self, member: &Member) -> Option<PushSocket> {
+        let address = format!("{}:{}", member.address, member.gossip_port);
+        if let Some(mut push_socket) = self.sockets.remove(&member.id) {
+            if push_socket.address == address {
+                push_socket.last_used = SteadyTime::now();
+                return Some(push_socket);
+            }
+        }
+        match PushSocket::connect(address, self.server.push_sockets.clone()) {
+            Ok(push_socket) => {
+                debug!("Connected push socket to {:?}", member);
+                Some(push_socket)
+            }
             Err(e) => {
                 error!("Cannot connect push socket to {:?}: {:?}", member, e);
-                return;
+                None
             }
         }
+    }
+
+    /// Send the list of rumors to a given member over the worker's socket to it. ZeroMQ sends
+    /// asynchronously, and without waiting: if the member isn't accepting messages, the rest of
+    /// the rumors are dropped and left hot, to be sent again next round.
+    fn send_rumors(&mut self, member: Member, rumors: Vec<RumorKey>) {
+        let push_socket = match self.take_socket(&member) {
+            Some(push_socket) => push_socket,
+            None => return,
+        };
+        let compact = self.server.accepts_compact(&member.id);
+        let mut batch = Vec::new();
+        let mut batch_len = 0;
+        let mut accepted = true;
         'rumorlist: for ref rumor_key in rumors.iter() {
             let rumor_as_bytes = match rumor_key.kind {
                 RumorType::Member => {
@@ -274,6 +393,36 @@ impl PushWorker {
                         continue 'rumorlist;
                     }
                 },
+                RumorType::Tombstone => match self
+                    .server
+                    .tombstone_store
+                    .encode(&rumor_key.key, &rumor_key.id)
+                {
+                    Ok(bytes) => bytes,
+                    Err(e) => {
+                        error!(
+                            "Could not write our own rumor to bytes; abandoning \
+                             sending rumor: {:?}",
+                            e
+                        );
+                        continue 'rumorlist;
+                    }
+                },
+                RumorType::RingKey => match self
+                    .server
+                    .ring_key_store
+                    .encode(&rumor_key.key, &rumor_key.id)
+                {
+                    Ok(bytes) => bytes,
+                    Err(e) => {
+                        error!(
+                            "Could not write our own rumor to bytes; abandoning \
+                             sending rumor: {:?}",
+                            e
+                        );
+                        continue 'rumorlist;
+                    }
+                },
                 RumorType::Election => {
                     // trace_it!(GOSSIP: &self.server,
                     //           TraceKind::SendRumor,
@@ -315,6 +464,19 @@ impl PushWorker {
                     continue 'rumorlist;
                 }
             };
+            if compact {
+                batch_len += rumor_as_bytes.len();
+                batch.push(rumor_as_bytes);
+                if batch_len >= MAX_BATCH_LEN {
+                    let batch = mem::replace(&mut batch, Vec::new());
+                    batch_len = 0;
+                    if !self.send_batch(&push_socket, &member, batch) {
+                        accepted = false;
+                        break 'rumorlist;
+                    }
+                }
+                continue 'rumorlist;
+            }
             let payload = match self.server.generate_wire(rumor_as_bytes) {
                 Ok(payload) => payload,
                 Err(e) => {
@@ -322,15 +484,57 @@ impl PushWorker {
                     continue 'rumorlist;
                 }
             };
-            match socket.send(&payload, 0) {
+            match push_socket.socket.send(&payload, zmq::DONTWAIT) {
                 Ok(()) => debug!("Sent rumor {:?} to {:?}", rumor_key, member),
+                Err(zmq::Error::EAGAIN) => {
+                    debug!(
+                        "{} isn't accepting rumors; skipping it this round",
+                        member.id
+                    );
+                    accepted = false;
+                    break 'rumorlist;
+                }
                 Err(e) => warn!(
                     "Could not send rumor to {:?} @ {:?}; ZMQ said: {:?}",
-                    member.id, to_addr, e
+                    member.id, push_socket.address, e
                 ),
             }
         }
-        self.server.rumor_heat.cool_rumors(&member.id, &rumors);
+        if accepted && !batch.is_empty() {
+            accepted = self.send_batch(&push_socket, &member, batch);
+        }
+        if accepted {
+            self.server.rumor_heat.cool_rumors(&member.id, &rumors);
+        }
+        self.sockets.insert(member.id.clone(), push_socket);
+    }
+
+    /// Send a batch of encoded rumors to a member as a single message. Returns false if the
+    /// member isn't accepting messages.
+    fn send_batch(&self, push_socket: &PushSocket, member: &Member, batch: Vec<Vec<u8>>) -> bool {
+        let count = batch.len();
+        let payload = match self.server.generate_batch_wire(batch) {
+            Ok(payload) => payload,
+            Err(e) => {
+                error!("Generating protobuf failed: {}", e);
+                return true;
+            }
+        };
+        match push_socket.socket.send(&payload, zmq::DONTWAIT) {
+            Ok(()) => debug!("Sent {} rumors to {:?}", count, member),
+            Err(zmq::Error::EAGAIN) => {
+                debug!(
+                    "{} isn't accepting rumors; skipping it this round",
+                    member.id
+                );
+                return false;
+            }
+            Err(e) => warn!(
+                "Could not send {} rumors to {:?} @ {:?}; ZMQ said: {:?}",
+                count, member.id, push_socket.address, e
+            ),
+        }
+        true
     }
 
     /// Given a rumorkey, creates a protobuf rumor for sharing.
@@ -360,3 +564,9 @@ impl PushWorker {
         Some(rumor)
     }
 }
+
+impl Drop for PushWorker {
+    fn drop(&mut self) {
+        self.server.push_threads.fetch_sub(1, Ordering::SeqCst);
+    }
+}
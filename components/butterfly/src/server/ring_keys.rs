@@ -0,0 +1,166 @@
+// Copyright (c) 2018 Chef Software Inc. and/or applicable contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The revisions of the ring key a server accepts.
+//!
+//! Rotating the ring key adds a new revision alongside the old ones. Payloads are decrypted with
+//! whichever revision their sender names, but are only encrypted with the new revision once it
+//! has been activated, which the server does when every live member reports holding it. Older
+//! revisions are retired once every member which hasn't departed holds the new one, and it has
+//! been active for long enough that they will have activated it too.
+
+use habitat_core::crypto::SymKey;
+use time::{Duration, SteadyTime};
+
+/// How long a revision must have been active before older revisions are retired.
+pub const RETIRE_AFTER_MS: i64 = 300_000;
+
+#[derive(Debug)]
+pub struct RingKeys {
+    /// Every revision we accept, newest first.
+    keys: Vec<SymKey>,
+    /// The revision payloads are encrypted with.
+    active: Option<String>,
+    active_since: SteadyTime,
+}
+
+impl RingKeys {
+    pub fn new(ring_key: Option<SymKey>) -> RingKeys {
+        RingKeys {
+            active: ring_key.as_ref().map(|k| k.rev.clone()),
+            keys: ring_key.into_iter().collect(),
+            active_since: SteadyTime::now(),
+        }
+    }
+
+    /// The revision to encrypt payloads with, if the ring is encrypted.
+    pub fn active(&self) -> Option<&SymKey> {
+        match self.active {
+            Some(ref rev) => self.get(rev),
+            None => None,
+        }
+    }
+
+    /// The newest revision we hold.
+    pub fn newest(&self) -> Option<&SymKey> {
+        self.keys.first()
+    }
+
+    /// The given revision, if we hold it.
+    pub fn get(&self, rev: &str) -> Option<&SymKey> {
+        self.keys.iter().find(|k| k.rev == rev)
+    }
+
+    /// Every revision we hold, newest first.
+    pub fn all(&self) -> &[SymKey] {
+        &self.keys
+    }
+
+    /// Adds a revision, returning false if we already hold it or it is older than the active
+    /// one, and so may already have been retired.
+    pub fn insert(&mut self, key: SymKey) -> bool {
+        if self.get(&key.rev).is_some() || self.active.as_ref().map_or(false, |a| key.rev < *a) {
+            return false;
+        }
+        self.keys.push(key);
+        self.keys.sort_by(|a, b| b.rev.cmp(&a.rev));
+        true
+    }
+
+    /// Starts encrypting payloads with the newest revision, returning false if it is already
+    /// active.
+    pub fn activate_newest(&mut self) -> bool {
+        let newest = match self.newest() {
+            Some(key) => key.rev.clone(),
+            None => return false,
+        };
+        if self.active.as_ref() == Some(&newest) {
+            return false;
+        }
+        self.active = Some(newest);
+        self.active_since = SteadyTime::now();
+        true
+    }
+
+    /// Drops every revision older than the active one, once it has been active for
+    /// `RETIRE_AFTER_MS`. Returns the revisions retired.
+    pub fn retire(&mut self) -> Vec<String> {
+        let active = match self.active {
+            Some(ref rev) => rev.clone(),
+            None => return vec![],
+        };
+        if SteadyTime::now() - self.active_since < Duration::milliseconds(RETIRE_AFTER_MS) {
+            return vec![];
+        }
+        let retired = self
+            .keys
+            .iter()
+            .filter(|k| k.rev < active)
+            .map(|k| k.name_with_rev())
+            .collect();
+        self.keys.retain(|k| k.rev >= active);
+        retired
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::{Duration, SteadyTime};
+
+    use super::*;
+    use test_helpers::ring_key;
+
+    #[test]
+    fn new_revisions_are_accepted_but_not_active() {
+        let mut keys = RingKeys::new(Some(ring_key("20180101000000")));
+        assert!(keys.insert(ring_key("20180102000000")));
+        assert!(!keys.insert(ring_key("20180102000000")));
+        assert!(!keys.insert(ring_key("20171231000000")));
+        assert_eq!(keys.all().len(), 2);
+        assert_eq!(keys.newest().unwrap().rev, "20180102000000");
+        assert_eq!(keys.active().unwrap().rev, "20180101000000");
+        assert!(keys.get("20180101000000").is_some());
+    }
+
+    #[test]
+    fn activating_the_newest_revision() {
+        let mut keys = RingKeys::new(Some(ring_key("20180101000000")));
+        assert!(!keys.activate_newest());
+        keys.insert(ring_key("20180102000000"));
+        assert!(keys.activate_newest());
+        assert_eq!(keys.active().unwrap().rev, "20180102000000");
+    }
+
+    #[test]
+    fn older_revisions_are_retired_once_the_active_one_has_settled() {
+        let mut keys = RingKeys::new(Some(ring_key("20180101000000")));
+        keys.insert(ring_key("20180102000000"));
+        keys.activate_newest();
+        assert!(keys.retire().is_empty());
+        assert_eq!(keys.all().len(), 2);
+
+        keys.active_since = SteadyTime::now() - Duration::milliseconds(RETIRE_AFTER_MS);
+        assert_eq!(keys.retire(), vec!["wolverine-20180101000000".to_string()]);
+        assert_eq!(keys.all().len(), 1);
+        assert_eq!(keys.active().unwrap().rev, "20180102000000");
+    }
+
+    #[test]
+    fn unencrypted_rings_have_no_keys() {
+        let mut keys = RingKeys::new(None);
+        assert!(keys.active().is_none());
+        assert!(keys.retire().is_empty());
+        assert!(!keys.activate_newest());
+    }
+}
@@ -25,6 +25,9 @@ const GOSSIP_PERIOD_DEFAULT_MS: i64 = 1000;
 /// How long before we set a confirmed member to a departed member, removing them from quorums
 ///   just for your own sanity - this is 3 days.
 const DEPARTURE_TIMEOUT_DEFAULT_MS: i64 = 259200000;
+/// How long a service group can go without any alive member running it before its rumors are
+///   discarded, and how long a forgotten group's tombstone is kept - this is 14 days.
+const RUMOR_EXPIRY_DEFAULT_MS: i64 = 1209600000;
 
 /// The timing of the outbound threads.
 #[derive(Debug, Clone)]
@@ -34,6 +37,7 @@ pub struct Timing {
     pub gossip_period_ms: i64,
     pub suspicion_timeout_protocol_periods: i64,
     pub departure_timeout_ms: i64,
+    pub rumor_expiry_ms: i64,
 }
 
 impl Default for Timing {
@@ -44,6 +48,7 @@ impl Default for Timing {
             gossip_period_ms: GOSSIP_PERIOD_DEFAULT_MS,
             suspicion_timeout_protocol_periods: SUSPICION_TIMEOUT_DEFAULT_PROTOCOL_PERIODS,
             departure_timeout_ms: DEPARTURE_TIMEOUT_DEFAULT_MS,
+            rumor_expiry_ms: RUMOR_EXPIRY_DEFAULT_MS,
         }
     }
 }
@@ -56,6 +61,7 @@ impl Timing {
         gossip_period_ms: i64,
         suspicion_timeout_protocol_periods: i64,
         departure_timeout_ms: i64,
+        rumor_expiry_ms: i64,
     ) -> Timing {
         Timing {
             ping_ms: ping_ms,
@@ -63,6 +69,7 @@ impl Timing {
             gossip_period_ms: gossip_period_ms,
             suspicion_timeout_protocol_periods: suspicion_timeout_protocol_periods,
             departure_timeout_ms: departure_timeout_ms,
+            rumor_expiry_ms: rumor_expiry_ms,
         }
     }
 
@@ -101,4 +108,8 @@ impl Timing {
     pub fn departure_timeout_duration(&self) -> TimeDuration {
         TimeDuration::milliseconds(self.departure_timeout_ms)
     }
+
+    pub fn rumor_expiry_duration(&self) -> TimeDuration {
+        TimeDuration::milliseconds(self.rumor_expiry_ms)
+    }
 }
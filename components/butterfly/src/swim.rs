@@ -23,6 +23,14 @@ use member::{Health, Member, Membership};
 pub use protocol::swim::{SwimPayload, SwimType};
 use protocol::{self, swim as proto, FromProto};
 
+/// The most bytes a SWIM message may take up on the wire. Each message is sent in a single UDP
+/// packet, small enough not to be fragmented on an Ethernet network, and is received into a
+/// buffer of this size.
+pub const SWIM_MAX_PACKET_LEN: usize = 1400;
+/// Room left in a packet for the wire envelope around the message: the nonce, authentication
+/// tag and key revision added when it is encrypted.
+const WIRE_OVERHEAD: usize = 128;
+
 #[derive(Debug, Clone, Serialize)]
 pub struct Ack {
     pub membership: Vec<Membership>,
@@ -298,6 +306,21 @@ impl Swim {
         })
     }
 
+    /// Piggyback `membership` on the message, unless the message would then be too large to send
+    /// in a single packet. Returns whether it was added.
+    pub fn push_membership(&mut self, membership: Membership) -> bool {
+        self.membership.push(membership);
+        if self.encoded_len() > SWIM_MAX_PACKET_LEN - WIRE_OVERHEAD {
+            self.membership.pop();
+            return false;
+        }
+        true
+    }
+
+    fn encoded_len(&self) -> usize {
+        proto::Swim::from(self.clone()).encoded_len()
+    }
+
     pub fn encode(self) -> Result<Vec<u8>> {
         let proto: proto::Swim = self.into();
         let mut buf = BytesMut::with_capacity(proto.encoded_len());
@@ -316,3 +339,59 @@ impl From<Swim> for proto::Swim {
     }
 }
 //
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use member::{check_tags, Incarnation, MAX_TAGS, MAX_TAGS_LEN};
+    use message;
+    use test_helpers::ring_key;
+
+    /// A member as large as one may be: the longest address, the largest incarnation, and as many
+    /// tags as are allowed, as large as they are allowed to be.
+    fn largest_member() -> Member {
+        let mut member = Member::default();
+        member.incarnation = Incarnation::from(u64::max_value());
+        member.address = "ffff:ffff:ffff:ffff:ffff:ffff:255.255.255.255".to_string();
+        member.swim_port = u16::max_value();
+        member.gossip_port = u16::max_value();
+        member.persistent = true;
+        member.departed = true;
+        member.ring_key_rev = Some("20181018123456".to_string());
+        let tag_len = MAX_TAGS_LEN / MAX_TAGS;
+        for i in 0..MAX_TAGS {
+            let key = format!("{:01$}", i, tag_len / 2);
+            let value = "v".repeat(tag_len - key.len());
+            member.tags.insert(key, value);
+        }
+        assert!(check_tags(&member.tags).is_ok());
+        member
+    }
+
+    #[test]
+    fn fully_loaded_ping_fits_in_a_packet() {
+        let ping = Ping {
+            membership: vec![],
+            from: largest_member(),
+            forward_to: Some(largest_member()),
+        };
+        let mut swim: Swim = ping.into();
+        for _ in 0..6 {
+            swim.push_membership(Membership {
+                member: largest_member(),
+                health: Health::Suspect,
+            });
+        }
+        assert!(!swim.membership.is_empty());
+        assert!(swim.membership.len() < 6);
+
+        let key = ring_key("20181018123456");
+        let wire = message::generate_wire(swim.encode().unwrap(), Some(&key)).unwrap();
+        assert!(
+            wire.len() <= SWIM_MAX_PACKET_LEN,
+            "{} byte packet is larger than {} bytes",
+            wire.len(),
+            SWIM_MAX_PACKET_LEN
+        );
+    }
+}
@@ -0,0 +1,23 @@
+// Copyright (c) 2018 Chef Software Inc. and/or applicable contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use habitat_core::crypto::SymKey;
+
+/// Generates an in memory ring key with the given revision.
+pub fn ring_key(rev: &str) -> SymKey {
+    let mut key = SymKey::generate_pair_for_ring("wolverine")
+        .expect("Failed to generate an in memory symkey");
+    key.rev = rev.to_string();
+    key
+}
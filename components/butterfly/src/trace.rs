@@ -388,6 +388,10 @@ macro_rules! trace_it {
                     election.votes
                 ),
                 rumor::RumorKind::Departure(ref departure) => format!("{}", departure.member_id),
+                rumor::RumorKind::RingKey(ref ring_key) => format!("{}", ring_key.name_with_rev),
+                rumor::RumorKind::Tombstone(ref tombstone) => {
+                    format!("{}-{}", tombstone.service_group, tombstone.incarnation)
+                }
             };
 
             let mut tw = TraceWrite::new($msg_type, module_path!(), line!(), thread_name);
@@ -30,3 +30,31 @@ fn symmetric_encryption_of_wire_payloads() {
         .service_store
         .with_rumor("beast.prod", net[0].member_id(), |u| assert!(u.is_some()));
 }
+
+#[test]
+fn rotating_the_ring_key_keeps_the_ring_together() {
+    let ring_key = SymKey::generate_pair_for_ring("wolverine")
+        .expect("Failed to generate an in memory symkey");
+    let mut net = btest::SwimNet::new_ring_encryption(2, Some(ring_key));
+    net.connect(0, 1);
+    assert_wait_for_health_of!(net, [0..2, 0..2], Health::Alive);
+
+    let mut rotated = SymKey::generate_pair_for_ring("wolverine")
+        .expect("Failed to generate an in memory symkey");
+    rotated.rev = String::from("99991231235959");
+    assert!(net[0].add_ring_key(rotated.clone()));
+    net.wait_for_gossip_rounds(2);
+    assert_ne!(net[0].ring_key().unwrap().rev, "99991231235959");
+    assert!(net[1].add_ring_key(rotated));
+    for _ in 0..5 {
+        net.wait_protocol_period();
+    }
+    assert_eq!(net[0].ring_key().unwrap().rev, "99991231235959");
+    assert_eq!(net[1].ring_key().unwrap().rev, "99991231235959");
+
+    net.add_service(0, "core/beast/1.2.3/20161208121212");
+    net.wait_for_gossip_rounds(2);
+    net[1]
+        .service_store
+        .with_rumor("beast.prod", net[0].member_id(), |u| assert!(u.is_some()));
+}
@@ -17,3 +17,4 @@ pub mod election;
 pub mod service;
 pub mod service_config;
 pub mod service_file;
+pub mod tombstone;
@@ -26,6 +26,41 @@ fn two_members_share_services() {
         .with_rumor("witcher.prod", net[0].member_id(), |u| assert!(u.is_some()));
 }
 
+#[test]
+fn push_sockets_are_reused_across_rounds() {
+    let mut net = btest::SwimNet::new(3);
+    net.mesh();
+    net.add_service(0, "core/witcher/1.2.3/20161208121212");
+    net.wait_for_rounds(2);
+    net.add_service(0, "core/geralt/1.2.3/20161208121212");
+    net.wait_for_rounds(2);
+    net[1]
+        .service_store
+        .with_rumor("geralt.prod", net[0].member_id(), |u| assert!(u.is_some()));
+    assert!(net[0].push_threads() > 0);
+    assert!(net[0].push_threads() <= 5);
+    assert!(net[0].push_sockets() > 0);
+    assert!(net[0].push_sockets() <= 2);
+}
+
+#[test]
+fn members_exchange_compact_gossip() {
+    let mut net = btest::SwimNet::new(2);
+    net.mesh();
+    net.wait_for_rounds(2);
+    assert!(net[0].accepts_compact(net[1].member_id()));
+    assert!(net[1].accepts_compact(net[0].member_id()));
+    net.add_service(0, "core/witcher/1.2.3/20161208121212");
+    net.add_service(0, "core/geralt/1.2.3/20161208121212");
+    net.wait_for_rounds(2);
+    net[1]
+        .service_store
+        .with_rumor("witcher.prod", net[0].member_id(), |u| assert!(u.is_some()));
+    net[1]
+        .service_store
+        .with_rumor("geralt.prod", net[0].member_id(), |u| assert!(u.is_some()));
+}
+
 #[test]
 fn six_members_unmeshed_with_same_service_forces_departure_on_new_members() {
     let mut net = btest::SwimNet::new(6);
@@ -0,0 +1,42 @@
+// Copyright (c) 2018 Chef Software Inc. and/or applicable contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use btest;
+
+#[test]
+fn two_members_share_tombstones() {
+    let mut net = btest::SwimNet::new(2);
+    net.mesh();
+    net.add_tombstone(0, "witcher");
+    net.wait_for_gossip_rounds(1);
+    net[1]
+        .tombstone_store
+        .with_rumor("tombstone", "witcher.prod", |u| assert!(u.is_some()));
+}
+
+#[test]
+fn tombstones_discard_service_config_everywhere() {
+    let mut net = btest::SwimNet::new(2);
+    net.mesh();
+    net.add_service_config(0, "witcher", "tcp-backlog = 128");
+    net.wait_for_gossip_rounds(1);
+    net[1]
+        .service_config_store
+        .with_rumor("witcher.prod", "service_config", |u| assert!(u.is_some()));
+    net.add_tombstone(0, "witcher");
+    net.wait_for_gossip_rounds(1);
+    net[1]
+        .service_config_store
+        .with_rumor("witcher.prod", "service_config", |u| assert!(u.is_none()));
+}
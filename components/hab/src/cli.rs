@@ -17,6 +17,7 @@ use std::path::Path;
 use std::result;
 use std::str::FromStr;
 
+use chrono::DateTime;
 use clap::{App, AppSettings, Arg};
 use hcore::package::ident;
 use hcore::package::{Identifiable, PackageIdent};
@@ -74,6 +75,7 @@ pub fn get() -> App<'static, 'static> {
                     "A package identifier (ex: core/redis, core/busybox-static/1.42.2)")
                 (@arg REMOTE_SUP: --("remote-sup") -r +takes_value
                     "Address to a remote Supervisor's Control Gateway [default: 127.0.0.1:9632]")
+                (args: &ctl_tls_args())
             )
         )
         (@subcommand file =>
@@ -91,6 +93,7 @@ pub fn get() -> App<'static, 'static> {
                 (@arg USER: -u --user +takes_value "Name of the user key")
                 (@arg REMOTE_SUP: --("remote-sup") -r +takes_value
                     "Address to a remote Supervisor's Control Gateway [default: 127.0.0.1:9632]")
+                (args: &ctl_tls_args())
             )
         )
         (@subcommand bldr =>
@@ -582,6 +585,15 @@ pub fn get() -> App<'static, 'static> {
                     (aliases: &["g", "ge", "gen", "gene", "gener", "genera", "generat"])
                     (@arg RING: +required +takes_value "Ring key name")
                 )
+                (@subcommand rotate =>
+                    (about: "Has a Supervisor generate a new revision of its ring key and \
+                        distribute it to every member of the ring. Members start encrypting with \
+                        it once all of them hold it.")
+                    (aliases: &["r", "ro", "rot", "rota", "rotat"])
+                    (@arg REMOTE_SUP: --("remote-sup") -r +takes_value
+                        "Address to a remote Supervisor's Control Gateway [default: 127.0.0.1:9632]")
+                    (args: &ctl_tls_args())
+                )
             )
         )
         (@subcommand sup =>
@@ -598,6 +610,36 @@ pub fn get() -> App<'static, 'static> {
             (about: "Commands relating to Habitat services")
             (aliases: &["sv", "ser", "serv", "service"])
             (@setting ArgRequiredElseHelp)
+            (@subcommand exec =>
+                (about: "Runs a command in a running Habitat service's environment, as the \
+                    service's user and with its run hook's environment")
+                (aliases: &["e", "ex", "exe"])
+                (@arg PKG_IDENT: +required +takes_value {valid_ident}
+                    "A Habitat package identifier (ex: core/redis)")
+                (@arg CMD: +required +takes_value
+                    "The command to run (ex: redis-cli)")
+                (@arg ARGS: +takes_value +multiple
+                    "Arguments to the command, given after -- (ex: -- info memory)")
+                (@arg REMOTE_SUP: --("remote-sup") -r +takes_value
+                    "Address to a remote Supervisor's Control Gateway [default: 127.0.0.1:9632]")
+                (args: &ctl_tls_args())
+            )
+            (@subcommand group =>
+                (about: "Commands relating to Habitat service groups")
+                (aliases: &["g", "gr", "gro", "grou"])
+                (@setting ArgRequiredElseHelp)
+                (@subcommand forget =>
+                    (about: "Forgets a service group which is no longer running anywhere, \
+                        discarding its configuration, files and elections across the ring. \
+                        Rumors about the group are ignored until a member runs it again.")
+                    (aliases: &["f", "fo", "for", "forg", "forge"])
+                    (@arg SERVICE_GROUP: +required +takes_value {valid_service_group}
+                        "Target service group service.group[@organization] (ex: redis.default or foo.default@bazcorp)")
+                    (@arg REMOTE_SUP: --("remote-sup") -r +takes_value
+                        "Address to a remote Supervisor's Control Gateway [default: 127.0.0.1:9632]")
+                    (args: &ctl_tls_args())
+                )
+            )
             (@subcommand key =>
                 (about: "Commands relating to Habitat service keys")
                 (aliases: &["k", "ke"])
@@ -611,6 +653,17 @@ pub fn get() -> App<'static, 'static> {
                 )
             )
             (subcommand: sub_svc_load().aliases(&["l", "lo", "loa"]))
+            (@subcommand signal =>
+                (about: "Send a signal to a running Habitat service's process")
+                (aliases: &["sig", "sign", "signa"])
+                (@arg PKG_IDENT: +required +takes_value {valid_ident}
+                    "A Habitat package identifier (ex: core/redis)")
+                (@arg SIGNAL: +required +takes_value {valid_signal}
+                    "The signal to send: TERM, INT, QUIT, HUP, USR1, USR2 or KILL")
+                (@arg REMOTE_SUP: --("remote-sup") -r +takes_value
+                    "Address to a remote Supervisor's Control Gateway [default: 127.0.0.1:9632]")
+                (args: &ctl_tls_args())
+            )
             (subcommand: sub_svc_start().aliases(&["star"]))
             (subcommand: sub_svc_status().aliases(&["stat", "statu"]))
             (subcommand: sub_svc_stop().aliases(&["sto"]))
@@ -622,6 +675,7 @@ pub fn get() -> App<'static, 'static> {
                     "A Habitat package identifier (ex: core/redis)")
                 (@arg REMOTE_SUP: --("remote-sup") -r +takes_value
                     "Address to a remote Supervisor's Control Gateway [default: 127.0.0.1:9632]")
+                (args: &ctl_tls_args())
             )
         )
         (@subcommand studio =>
@@ -796,6 +850,7 @@ fn sub_config_apply() -> App<'static, 'static> {
         (@arg USER: -u --user +takes_value "Name of a user key to use for encryption")
         (@arg REMOTE_SUP: --("remote-sup") -r +takes_value
             "Address to a remote Supervisor's Control Gateway [default: 127.0.0.1:9632]")
+        (args: &ctl_tls_args())
     )
 }
 
@@ -809,6 +864,28 @@ pub fn sub_sup_depart() -> App<'static, 'static> {
         (@arg MEMBER_ID: +required +takes_value "The member-id of the Supervisor to depart")
         (@arg REMOTE_SUP: --("remote-sup") -r +takes_value
             "Address to a remote Supervisor's Control Gateway [default: 127.0.0.1:9632]")
+        (args: &ctl_tls_args())
+    )
+}
+
+pub fn sub_sup_audit() -> App<'static, 'static> {
+    clap_app!(@subcommand audit =>
+        (about: "Display the requests made to the Supervisor's Control Gateway which changed \
+            its state or were refused")
+        // set custom usage string, otherwise the binary
+        // is displayed confusingly as `hab-sup`
+        // see: https://github.com/kbknapp/clap-rs/blob/2724ec5399c500b12a1a24d356f4090f4816f5e2/src/app/mod.rs#L373-L394
+        (usage: "hab sup audit [OPTIONS]")
+        (@arg SERVICE_GROUP: --("service-group") -g +takes_value {valid_service_group}
+            "Only display requests targeting this service group (ex: redis.default)")
+        (@arg MESSAGE: --message -m +takes_value
+            "Only display requests of this type (ex: SvcLoad)")
+        (@arg CREDENTIAL: --credential -c +takes_value
+            "Only display requests made with this named credential")
+        (@arg OUTCOME: --outcome -o +takes_value possible_value[succeeded failed refused]
+            "Only display requests with this outcome")
+        (@arg SINCE: --since -s +takes_value {valid_utc_timestamp}
+            "Only display requests made at or after this UTC timestamp (ex: 2018-06-01T00:00:00Z)")
     )
 }
 
@@ -847,14 +924,38 @@ pub fn sub_sup_run() -> App<'static, 'static> {
             be taken from the HAB_LISTEN_HTTP environment variable if defined. [default: 0.0.0.0:9631]")
         (@arg HTTP_DISABLE: --("http-disable") -D
             "Disable the HTTP Gateway completely [default: false]")
+        (@arg HTTP_SERVER_CERTIFICATE: --("http-server-certificate") +takes_value {file_exists}
+            requires[HTTP_SERVER_KEY]
+            "Path to a PEM encoded certificate to serve the HTTP Gateway over HTTPS with")
+        (@arg HTTP_SERVER_KEY: --("http-server-key") +takes_value {file_exists}
+            requires[HTTP_SERVER_CERTIFICATE]
+            "Path to the PEM encoded private key for the HTTP Gateway certificate")
+        (@arg HTTP_AUTH_TOKEN: --("http-auth-token") +takes_value
+            "A bearer token which HTTP Gateway clients must present for all but health checks, \
+            enabling its control endpoints. If not specified, the value will be taken from the \
+            HAB_SUP_GATEWAY_AUTH_TOKEN environment variable if defined.")
         (@arg LISTEN_CTL: --("listen-ctl") +takes_value {valid_socket_addr}
             "The listen address for the Control Gateway. If not specified, the value will \
             be taken from the HAB_LISTEN_CTL environment variable if defined. [default: 127.0.0.1:9632]")
+        (@arg CTL_SERVER_CERTIFICATE: --("ctl-server-certificate") +takes_value {file_exists}
+            requires[CTL_SERVER_KEY CTL_CLIENT_CA_CERTIFICATE]
+            "Path to a PEM encoded certificate used to secure remote Control Gateway \
+            connections with TLS. Connections from localhost are not affected.")
+        (@arg CTL_SERVER_KEY: --("ctl-server-key") +takes_value {file_exists}
+            requires[CTL_SERVER_CERTIFICATE]
+            "Path to the PEM encoded private key for the Control Gateway certificate")
+        (@arg CTL_CLIENT_CA_CERTIFICATE: --("ctl-client-ca-certificate") +takes_value {file_exists}
+            requires[CTL_SERVER_CERTIFICATE]
+            "Path to a PEM encoded CA certificate which remote Control Gateway clients must \
+            present a certificate signed by")
         (@arg ORGANIZATION: --org +takes_value
             "The organization that the Supervisor and its subsequent services are part of.")
         (@arg PEER: --peer +takes_value +multiple
             "The listen address of one or more initial peers (IP[:PORT])")
         (@arg PERMANENT_PEER: --("permanent-peer") -I "If this Supervisor is a permanent peer")
+        (@arg TAG: --tag +takes_value +multiple {valid_tag}
+            "One or more key=value tags describing this Supervisor, gossiped to the rest of the \
+            ring (ex: zone=us-east-1a)")
         (@arg PEER_WATCH_FILE: --("peer-watch-file") +takes_value conflicts_with[peer]
             "Watch this file for connecting to the ring"
         )
@@ -886,16 +987,82 @@ pub fn sub_sup_run() -> App<'static, 'static> {
         (@arg TOPOLOGY: --topology -t +takes_value possible_value[standalone leader]
             "Service topology; [default: none]")
         (@arg STRATEGY: --strategy -s +takes_value {valid_update_strategy}
-            "The update strategy; [default: none] [values: none, at-once, rolling]")
+            "The update strategy; [default: none] [values: none, at-once, rolling, canary]")
         (@arg BIND: --bind +takes_value +multiple
-            "One or more service groups to bind to a configuration")
+            "One or more service groups to bind to a configuration. A bind may be limited to \
+            members with matching tags (ex: backend:redis.default[zone,rack=12]); a tag without \
+            a value matches this Supervisor's own value for it")
         (@arg BINDING_MODE: --("binding-mode") +takes_value {valid_binding_mode}
             "Governs how the presence or absence of binds affects service startup. `strict` blocks \
              startup until all binds are present. [default: strict] [values: relaxed, strict]")
+        (@arg HEALTH_CHECK_INTERVAL: --("health-check-interval") +takes_value
+            {valid_nonzero_numeric::<u64>}
+            "The interval (seconds) on which to run health checks [default: 30]")
+        (@arg HEALTH_CHECK_TIMEOUT: --("health-check-timeout") +takes_value
+            {valid_nonzero_numeric::<u64>}
+            "The time (seconds) a health_check hook may run before it is terminated [default: 30]")
+        (@arg HEALTH_CHECK_FAILURE_THRESHOLD: --("health-check-failure-threshold") +takes_value
+            {valid_nonzero_numeric::<u32>}
+            "The number of consecutive failed health checks before the service is reported \
+             unhealthy [default: 1]")
+        (@arg HEALTH_CHECK_SUCCESS_THRESHOLD: --("health-check-success-threshold") +takes_value
+            {valid_nonzero_numeric::<u32>}
+            "The number of consecutive passing health checks before the service is reported \
+             healthy again [default: 1]")
+        (@arg RESTART_AFTER_CRITICAL: --("restart-after-critical") +takes_value
+            {valid_numeric::<u32>}
+            "Restart the service after this many consecutive critical health checks; \
+             0 disables automatic restarts [default: 0]")
+        (@arg RESTART_BACKOFF: --("restart-backoff") +takes_value {valid_numeric::<u64>}
+            "The time (seconds) to wait after an automatic restart before another, doubled \
+             for each further restart [default: 10]")
+        (@arg RESTART_MAX: --("restart-max") +takes_value {valid_numeric::<u32>}
+            "The number of automatic restarts within the restart window after which the \
+             service is considered crash-looping and no longer restarted [default: 5]")
+        (@arg RESTART_WINDOW: --("restart-window") +takes_value {valid_numeric::<u64>}
+            "The time (seconds) over which automatic restarts are counted [default: 600]")
+        (@arg CANARY_SIZE: --("canary-size") +takes_value {valid_canary_size}
+            "The number of members, or percentage of the group (e.g. 25%), which take a new \
+             package first when using the canary update strategy [default: 1]")
+        (@arg CANARY_SOAK: --("canary-soak") +takes_value {valid_numeric::<u64>}
+            "The time (seconds) the canaries must run a new package healthily before the rest \
+             of the group updates [default: 300]")
+        (@arg UPDATE_WINDOW: --("update-window") +takes_value +multiple
+            {valid_maintenance_window}
+            "One or more cron-style schedules (minute hour day month weekday, in UTC) during \
+             which the service may be updated; updates found outside of them are queued \
+             [default: any time]")
+        (@arg UPDATE_VERSION: --("update-version") +takes_value {valid_version_constraint}
+            "Only update the service to versions matching this constraint (ex: 1.2.x)")
+        (@arg LOG_CAPTURE: --("log-capture")
+            "Also write the service's output to rotated log files in its logs directory")
+        (@arg LOG_MAX_SIZE: --("log-max-size") +takes_value {valid_numeric::<u64>}
+            "The size (bytes) a captured log file may grow to before it is rotated; 0 disables \
+             size-based rotation [default: 10485760]")
+        (@arg LOG_MAX_AGE: --("log-max-age") +takes_value {valid_numeric::<u64>}
+            "The time (seconds) a captured log file is written to before it is rotated; 0 \
+             disables time-based rotation [default: 86400]")
+        (@arg LOG_RETAIN: --("log-retain") +takes_value {valid_numeric::<u32>}
+            "The number of rotated log files to keep for each output stream [default: 5]")
+        (@arg CPU_LIMIT: --("cpu-limit") +takes_value {valid_numeric::<u32>}
+            "The CPU time (thousandths of a CPU) the service may use, overriding its package's \
+             limit [default: the package's limit, if any]")
+        (@arg MEMORY_LIMIT: --("memory-limit") +takes_value {valid_numeric::<u64>}
+            "The memory (bytes) the service may use, overriding its package's limit [default: \
+             the package's limit, if any]")
+        (@arg PIDS_LIMIT: --("pids-limit") +takes_value {valid_numeric::<u64>}
+            "The number of processes the service may run, overriding its package's limit \
+             [default: the package's limit, if any]")
+        (@arg SHUTDOWN_SIGNAL: --("shutdown-signal") +takes_value {valid_signal}
+            "The signal sent to the service to ask it to stop (TERM, INT, QUIT, HUP, USR1, USR2 \
+             or KILL) [default: TERM]")
+        (@arg SHUTDOWN_TIMEOUT: --("shutdown-timeout") +takes_value {valid_numeric::<u32>}
+            "The time (seconds) the service is given to stop after the shutdown signal before it \
+             is killed [default: 8]")
         (@arg VERBOSE: -v "Verbose output; shows file and line/column numbers")
         (@arg NO_COLOR: --("no-color") "Turn ANSI color off")
-        (@arg JSON: --("json-logging") "Use structured JSON logging for the Supervisor. \
-            Implies NO_COLOR")
+        (@arg JSON: --("json-logging") "Use structured JSON logging for the Supervisor and \
+            the output of its services. Implies NO_COLOR")
     )
 }
 
@@ -926,6 +1093,7 @@ fn sub_svc_start() -> App<'static, 'static> {
             "A Habitat package identifier (ex: core/redis)")
         (@arg REMOTE_SUP: --("remote-sup") -r +takes_value
             "Address to a remote Supervisor's Control Gateway [default: 127.0.0.1:9632]")
+        (args: &ctl_tls_args())
     )
 }
 
@@ -937,6 +1105,7 @@ pub fn sub_svc_status() -> App<'static, 'static> {
         (@arg PKG_IDENT: +takes_value {valid_ident} "A Habitat package identifier (ex: core/redis)")
         (@arg REMOTE_SUP: --("remote-sup") -r +takes_value
         "Address to a remote Supervisor's Control Gateway [default: 127.0.0.1:9632]")
+        (args: &ctl_tls_args())
     )
 }
 
@@ -947,6 +1116,7 @@ fn sub_svc_stop() -> App<'static, 'static> {
             "A Habitat package identifier (ex: core/redis)")
         (@arg REMOTE_SUP: --("remote-sup") -r +takes_value
             "Address to a remote Supervisor's Control Gateway [default: 127.0.0.1:9632]")
+        (args: &ctl_tls_args())
     )
 }
 
@@ -973,16 +1143,83 @@ fn sub_svc_load() -> App<'static, 'static> {
         (@arg TOPOLOGY: --topology -t +takes_value possible_value[standalone leader]
             "Service topology; [default: none]")
         (@arg STRATEGY: --strategy -s +takes_value {valid_update_strategy}
-            "The update strategy; [default: none] [values: none, at-once, rolling]")
+            "The update strategy; [default: none] [values: none, at-once, rolling, canary]")
         (@arg BIND: --bind +takes_value +multiple
-            "One or more service groups to bind to a configuration")
+            "One or more service groups to bind to a configuration. A bind may be limited to \
+            members with matching tags (ex: backend:redis.default[zone,rack=12]); a tag without \
+            a value matches this Supervisor's own value for it")
         (@arg BINDING_MODE: --("binding-mode") +takes_value {valid_binding_mode}
              "Governs how the presence or absence of binds affects service startup. `strict` blocks \
               startup until all binds are present. [default: strict] [values: relaxed, strict]")
+        (@arg HEALTH_CHECK_INTERVAL: --("health-check-interval") +takes_value
+            {valid_nonzero_numeric::<u64>}
+            "The interval (seconds) on which to run health checks [default: 30]")
+        (@arg HEALTH_CHECK_TIMEOUT: --("health-check-timeout") +takes_value
+            {valid_nonzero_numeric::<u64>}
+            "The time (seconds) a health_check hook may run before it is terminated [default: 30]")
+        (@arg HEALTH_CHECK_FAILURE_THRESHOLD: --("health-check-failure-threshold") +takes_value
+            {valid_nonzero_numeric::<u32>}
+            "The number of consecutive failed health checks before the service is reported \
+             unhealthy [default: 1]")
+        (@arg HEALTH_CHECK_SUCCESS_THRESHOLD: --("health-check-success-threshold") +takes_value
+            {valid_nonzero_numeric::<u32>}
+            "The number of consecutive passing health checks before the service is reported \
+             healthy again [default: 1]")
+        (@arg RESTART_AFTER_CRITICAL: --("restart-after-critical") +takes_value
+            {valid_numeric::<u32>}
+            "Restart the service after this many consecutive critical health checks; \
+             0 disables automatic restarts [default: 0]")
+        (@arg RESTART_BACKOFF: --("restart-backoff") +takes_value {valid_numeric::<u64>}
+            "The time (seconds) to wait after an automatic restart before another, doubled \
+             for each further restart [default: 10]")
+        (@arg RESTART_MAX: --("restart-max") +takes_value {valid_numeric::<u32>}
+            "The number of automatic restarts within the restart window after which the \
+             service is considered crash-looping and no longer restarted [default: 5]")
+        (@arg RESTART_WINDOW: --("restart-window") +takes_value {valid_numeric::<u64>}
+            "The time (seconds) over which automatic restarts are counted [default: 600]")
+        (@arg CANARY_SIZE: --("canary-size") +takes_value {valid_canary_size}
+            "The number of members, or percentage of the group (e.g. 25%), which take a new \
+             package first when using the canary update strategy [default: 1]")
+        (@arg CANARY_SOAK: --("canary-soak") +takes_value {valid_numeric::<u64>}
+            "The time (seconds) the canaries must run a new package healthily before the rest \
+             of the group updates [default: 300]")
+        (@arg UPDATE_WINDOW: --("update-window") +takes_value +multiple
+            {valid_maintenance_window}
+            "One or more cron-style schedules (minute hour day month weekday, in UTC) during \
+             which the service may be updated; updates found outside of them are queued \
+             [default: any time]")
+        (@arg UPDATE_VERSION: --("update-version") +takes_value {valid_version_constraint}
+            "Only update the service to versions matching this constraint (ex: 1.2.x)")
+        (@arg LOG_CAPTURE: --("log-capture")
+            "Also write the service's output to rotated log files in its logs directory")
+        (@arg LOG_MAX_SIZE: --("log-max-size") +takes_value {valid_numeric::<u64>}
+            "The size (bytes) a captured log file may grow to before it is rotated; 0 disables \
+             size-based rotation [default: 10485760]")
+        (@arg LOG_MAX_AGE: --("log-max-age") +takes_value {valid_numeric::<u64>}
+            "The time (seconds) a captured log file is written to before it is rotated; 0 \
+             disables time-based rotation [default: 86400]")
+        (@arg LOG_RETAIN: --("log-retain") +takes_value {valid_numeric::<u32>}
+            "The number of rotated log files to keep for each output stream [default: 5]")
+        (@arg CPU_LIMIT: --("cpu-limit") +takes_value {valid_numeric::<u32>}
+            "The CPU time (thousandths of a CPU) the service may use, overriding its package's \
+             limit [default: the package's limit, if any]")
+        (@arg MEMORY_LIMIT: --("memory-limit") +takes_value {valid_numeric::<u64>}
+            "The memory (bytes) the service may use, overriding its package's limit [default: \
+             the package's limit, if any]")
+        (@arg PIDS_LIMIT: --("pids-limit") +takes_value {valid_numeric::<u64>}
+            "The number of processes the service may run, overriding its package's limit \
+             [default: the package's limit, if any]")
+        (@arg SHUTDOWN_SIGNAL: --("shutdown-signal") +takes_value {valid_signal}
+            "The signal sent to the service to ask it to stop (TERM, INT, QUIT, HUP, USR1, USR2 \
+             or KILL) [default: TERM]")
+        (@arg SHUTDOWN_TIMEOUT: --("shutdown-timeout") +takes_value {valid_numeric::<u32>}
+            "The time (seconds) the service is given to stop after the shutdown signal before it \
+             is killed [default: 8]")
         (@arg FORCE: --force -f "Load or reload an already loaded service. If the service \
             was previously loaded and running this operation will also restart the service")
         (@arg REMOTE_SUP: --("remote-sup") -r +takes_value
             "Address to a remote Supervisor's Control Gateway [default: 127.0.0.1:9632]")
+        (args: &ctl_tls_args())
     )
 }
 
@@ -1009,20 +1246,109 @@ fn sub_svc_load() -> App<'static, 'static> {
         (@arg TOPOLOGY: --topology -t +takes_value possible_value[standalone leader]
             "Service topology; [default: none]")
         (@arg STRATEGY: --strategy -s +takes_value {valid_update_strategy}
-            "The update strategy; [default: none] [values: none, at-once, rolling]")
+            "The update strategy; [default: none] [values: none, at-once, rolling, canary]")
         (@arg BIND: --bind +takes_value +multiple
-            "One or more service groups to bind to a configuration")
+            "One or more service groups to bind to a configuration. A bind may be limited to \
+            members with matching tags (ex: backend:redis.default[zone,rack=12]); a tag without \
+            a value matches this Supervisor's own value for it")
         (@arg BINDING_MODE: --("binding-mode") +takes_value {valid_binding_mode}
              "Governs how the presence or absence of binds affects service startup. `strict` blocks \
               startup until all binds are present. [default: strict] [values: relaxed, strict]")
+        (@arg HEALTH_CHECK_INTERVAL: --("health-check-interval") +takes_value
+            {valid_nonzero_numeric::<u64>}
+            "The interval (seconds) on which to run health checks [default: 30]")
+        (@arg HEALTH_CHECK_TIMEOUT: --("health-check-timeout") +takes_value
+            {valid_nonzero_numeric::<u64>}
+            "The time (seconds) a health_check hook may run before it is terminated [default: 30]")
+        (@arg HEALTH_CHECK_FAILURE_THRESHOLD: --("health-check-failure-threshold") +takes_value
+            {valid_nonzero_numeric::<u32>}
+            "The number of consecutive failed health checks before the service is reported \
+             unhealthy [default: 1]")
+        (@arg HEALTH_CHECK_SUCCESS_THRESHOLD: --("health-check-success-threshold") +takes_value
+            {valid_nonzero_numeric::<u32>}
+            "The number of consecutive passing health checks before the service is reported \
+             healthy again [default: 1]")
+        (@arg RESTART_AFTER_CRITICAL: --("restart-after-critical") +takes_value
+            {valid_numeric::<u32>}
+            "Restart the service after this many consecutive critical health checks; \
+             0 disables automatic restarts [default: 0]")
+        (@arg RESTART_BACKOFF: --("restart-backoff") +takes_value {valid_numeric::<u64>}
+            "The time (seconds) to wait after an automatic restart before another, doubled \
+             for each further restart [default: 10]")
+        (@arg RESTART_MAX: --("restart-max") +takes_value {valid_numeric::<u32>}
+            "The number of automatic restarts within the restart window after which the \
+             service is considered crash-looping and no longer restarted [default: 5]")
+        (@arg RESTART_WINDOW: --("restart-window") +takes_value {valid_numeric::<u64>}
+            "The time (seconds) over which automatic restarts are counted [default: 600]")
+        (@arg CANARY_SIZE: --("canary-size") +takes_value {valid_canary_size}
+            "The number of members, or percentage of the group (e.g. 25%), which take a new \
+             package first when using the canary update strategy [default: 1]")
+        (@arg CANARY_SOAK: --("canary-soak") +takes_value {valid_numeric::<u64>}
+            "The time (seconds) the canaries must run a new package healthily before the rest \
+             of the group updates [default: 300]")
+        (@arg UPDATE_WINDOW: --("update-window") +takes_value +multiple
+            {valid_maintenance_window}
+            "One or more cron-style schedules (minute hour day month weekday, in UTC) during \
+             which the service may be updated; updates found outside of them are queued \
+             [default: any time]")
+        (@arg UPDATE_VERSION: --("update-version") +takes_value {valid_version_constraint}
+            "Only update the service to versions matching this constraint (ex: 1.2.x)")
+        (@arg LOG_CAPTURE: --("log-capture")
+            "Also write the service's output to rotated log files in its logs directory")
+        (@arg LOG_MAX_SIZE: --("log-max-size") +takes_value {valid_numeric::<u64>}
+            "The size (bytes) a captured log file may grow to before it is rotated; 0 disables \
+             size-based rotation [default: 10485760]")
+        (@arg LOG_MAX_AGE: --("log-max-age") +takes_value {valid_numeric::<u64>}
+            "The time (seconds) a captured log file is written to before it is rotated; 0 \
+             disables time-based rotation [default: 86400]")
+        (@arg LOG_RETAIN: --("log-retain") +takes_value {valid_numeric::<u32>}
+            "The number of rotated log files to keep for each output stream [default: 5]")
+        (@arg CPU_LIMIT: --("cpu-limit") +takes_value {valid_numeric::<u32>}
+            "The CPU time (thousandths of a CPU) the service may use, overriding its package's \
+             limit [default: the package's limit, if any]")
+        (@arg MEMORY_LIMIT: --("memory-limit") +takes_value {valid_numeric::<u64>}
+            "The memory (bytes) the service may use, overriding its package's limit [default: \
+             the package's limit, if any]")
+        (@arg PIDS_LIMIT: --("pids-limit") +takes_value {valid_numeric::<u64>}
+            "The number of processes the service may run, overriding its package's limit \
+             [default: the package's limit, if any]")
+        (@arg SHUTDOWN_SIGNAL: --("shutdown-signal") +takes_value {valid_signal}
+            "The signal sent to the service to ask it to stop (TERM, INT, QUIT, HUP, USR1, USR2 \
+             or KILL) [default: TERM]")
+        (@arg SHUTDOWN_TIMEOUT: --("shutdown-timeout") +takes_value {valid_numeric::<u32>}
+            "The time (seconds) the service is given to stop after the shutdown signal before it \
+             is killed [default: 8]")
         (@arg FORCE: --force -f "Load or reload an already loaded service. If the service \
             was previously loaded and running this operation will also restart the service")
         (@arg PASSWORD: --password +takes_value "Password of the service user")
         (@arg REMOTE_SUP: --("remote-sup") -r +takes_value
             "Address to a remote Supervisor's Control Gateway [default: 127.0.0.1:9632]")
+        (args: &ctl_tls_args())
     )
 }
 
+/// Arguments for connecting to a remote Supervisor's Control Gateway over TLS, shared by every
+/// subcommand which takes `--remote-sup`.
+fn ctl_tls_args() -> Vec<Arg<'static, 'static>> {
+    vec![
+        Arg::with_name("CTL_CLIENT_CERTIFICATE")
+            .help("Path to a PEM encoded certificate to present to a remote Supervisor's Control Gateway")
+            .long("ctl-client-certificate")
+            .takes_value(true)
+            .validator(file_exists),
+        Arg::with_name("CTL_CLIENT_KEY")
+            .help("Path to the PEM encoded private key for the Control Gateway client certificate")
+            .long("ctl-client-key")
+            .takes_value(true)
+            .validator(file_exists),
+        Arg::with_name("CTL_SERVER_CA_CERTIFICATE")
+            .help("Path to a PEM encoded CA certificate to verify a remote Supervisor's Control Gateway with")
+            .long("ctl-server-ca-certificate")
+            .takes_value(true)
+            .validator(file_exists),
+    ]
+}
+
 fn file_exists(val: String) -> result::Result<(), String> {
     if Path::new(&val).is_file() {
         Ok(())
@@ -1048,6 +1374,26 @@ fn valid_binding_mode(val: String) -> result::Result<(), String> {
     }
 }
 
+fn valid_tag(val: String) -> result::Result<(), String> {
+    let mut parts = val.splitn(2, '=');
+    let has_key = parts.next().map_or(false, |key| !key.is_empty());
+    if has_key && parts.next().is_some() && !val.contains(|c| c == ',' || c == '[' || c == ']') {
+        Ok(())
+    } else {
+        Err(format!(
+            "Tag: '{}' is not valid, must be of the form KEY=VALUE without ',', '[' or ']'",
+            &val
+        ))
+    }
+}
+
+fn valid_signal(val: String) -> result::Result<(), String> {
+    match protocol::types::Signal::from_str(&val) {
+        Ok(_) => Ok(()),
+        Err(_) => Err(format!("Signal: '{}' is not valid", &val)),
+    }
+}
+
 fn valid_pair_type(val: String) -> result::Result<(), String> {
     match PairType::from_str(&val) {
         Ok(_) => Ok(()),
@@ -1062,6 +1408,16 @@ fn valid_service_group(val: String) -> result::Result<(), String> {
     ServiceGroup::validate(&val).map_err(|e| e.to_string())
 }
 
+fn valid_utc_timestamp(val: String) -> result::Result<(), String> {
+    match DateTime::parse_from_rfc3339(&val) {
+        Ok(ref timestamp) if timestamp.offset().local_minus_utc() == 0 => Ok(()),
+        _ => Err(format!(
+            "Timestamp: '{}' is not a valid RFC 3339 UTC timestamp (ex: 2018-06-01T00:00:00Z)",
+            &val
+        )),
+    }
+}
+
 fn dir_exists(val: String) -> result::Result<(), String> {
     if Path::new(&val).is_dir() {
         Ok(())
@@ -1093,6 +1449,17 @@ fn valid_numeric<T: FromStr>(val: String) -> result::Result<(), String> {
     }
 }
 
+fn valid_nonzero_numeric<T>(val: String) -> result::Result<(), String>
+where
+    T: FromStr + Default + PartialEq,
+{
+    match val.parse::<T>() {
+        Ok(ref n) if *n == T::default() => Err(format!("'{}' must be greater than 0", &val)),
+        Ok(_) => Ok(()),
+        Err(_) => Err(format!("'{}' is not a valid number", &val)),
+    }
+}
+
 fn valid_update_strategy(val: String) -> result::Result<(), String> {
     match protocol::types::UpdateStrategy::from_str(&val) {
         Ok(_) => Ok(()),
@@ -1100,6 +1467,33 @@ fn valid_update_strategy(val: String) -> result::Result<(), String> {
     }
 }
 
+fn valid_canary_size(val: String) -> result::Result<(), String> {
+    match protocol::types::CanarySize::from_str(&val) {
+        Ok(_) => Ok(()),
+        Err(_) => Err(format!(
+            "Canary size: '{}' is not valid; expected a member count or a percentage",
+            &val
+        )),
+    }
+}
+
+fn valid_maintenance_window(val: String) -> result::Result<(), String> {
+    match protocol::types::MaintenanceWindow::from_str(&val) {
+        Ok(_) => Ok(()),
+        Err(_) => Err(format!(
+            "Update window: '{}' is not valid; expected five cron-style fields",
+            &val
+        )),
+    }
+}
+
+fn valid_version_constraint(val: String) -> result::Result<(), String> {
+    match protocol::types::VersionConstraint::from_str(&val) {
+        Ok(_) => Ok(()),
+        Err(_) => Err(format!("Update version: '{}' is not valid", &val)),
+    }
+}
+
 fn valid_ident(val: String) -> result::Result<(), String> {
     match PackageIdent::from_str(&val) {
         Ok(_) => Ok(()),
@@ -25,12 +25,20 @@ use toml;
 use error::{Error, Result};
 
 const CLI_CONFIG_PATH: &'static str = "hab/etc/cli.toml";
+/// Default file names, alongside the CLI config, of the files used to connect to a remote
+/// Supervisor's Control Gateway with TLS.
+pub const CTL_CLIENT_CERTIFICATE_FILENAME: &'static str = "ctl-client.crt";
+pub const CTL_CLIENT_KEY_FILENAME: &'static str = "ctl-client.key";
+pub const CTL_SERVER_CA_CERTIFICATE_FILENAME: &'static str = "ctl-server-ca.crt";
 
 #[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
 pub struct Config {
     pub auth_token: Option<String>,
     pub origin: Option<String>,
     pub ctl_secret: Option<String>,
+    pub ctl_client_certificate: Option<PathBuf>,
+    pub ctl_client_key: Option<PathBuf>,
+    pub ctl_server_ca_certificate: Option<PathBuf>,
     pub bldr_url: Option<String>,
 }
 
@@ -44,6 +52,9 @@ impl Default for Config {
             auth_token: None,
             origin: None,
             ctl_secret: None,
+            ctl_client_certificate: None,
+            ctl_client_key: None,
+            ctl_server_ca_certificate: None,
             bldr_url: None,
         }
     }
@@ -78,6 +89,14 @@ pub fn save(config: &Config) -> Result<()> {
     Ok(())
 }
 
+/// Returns the default location of the given Control Gateway TLS file if it exists.
+pub fn ctl_tls_default_path(file_name: &str) -> Option<PathBuf> {
+    cli_config_path()
+        .parent()
+        .map(|dir| dir.join(file_name))
+        .and_then(|path| if path.is_file() { Some(path) } else { None })
+}
+
 fn cli_config_path() -> PathBuf {
     if !am_i_root() {
         if let Some(home) = dirs::home_dir() {
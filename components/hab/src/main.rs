@@ -66,6 +66,7 @@ use hcore::url::{bldr_url_from_env, default_bldr_url};
 use protocol::codec::*;
 use protocol::ctl::ServiceBindList;
 use protocol::net::ErrCode;
+use protocol::tls::TlsConfig;
 use protocol::types::*;
 use sup_client::{SrvClient, SrvClientError};
 use tabwriter::TabWriter;
@@ -96,6 +97,10 @@ lazy_static! {
             "elapsed (s)",
             "pid",
             "group",
+            "update",
+            "cpu (s)",
+            "memory (bytes)",
+            "pids",
         ]
     };
 
@@ -244,11 +249,17 @@ fn start(ui: &mut UI) -> Result<()> {
                 ("export", Some(sc)) => sub_ring_key_export(sc)?,
                 ("import", Some(_)) => sub_ring_key_import(ui)?,
                 ("generate", Some(sc)) => sub_ring_key_generate(ui, sc)?,
+                ("rotate", Some(sc)) => sub_ring_key_rotate(sc)?,
                 _ => unreachable!(),
             },
             _ => unreachable!(),
         },
         ("svc", Some(matches)) => match matches.subcommand() {
+            ("exec", Some(m)) => sub_svc_exec(m)?,
+            ("group", Some(m)) => match m.subcommand() {
+                ("forget", Some(sc)) => sub_svc_group_forget(sc)?,
+                _ => unreachable!(),
+            },
             ("key", Some(m)) => match m.subcommand() {
                 ("generate", Some(sc)) => sub_service_key_generate(ui, sc)?,
                 _ => unreachable!(),
@@ -257,6 +268,7 @@ fn start(ui: &mut UI) -> Result<()> {
             ("unload", Some(m)) => sub_svc_unload(m)?,
             ("start", Some(m)) => sub_svc_start(m)?,
             ("stop", Some(m)) => sub_svc_stop(m)?,
+            ("signal", Some(m)) => sub_svc_signal(m)?,
             ("status", Some(m)) => sub_svc_status(m)?,
             _ => unreachable!(),
         },
@@ -799,6 +811,7 @@ fn sub_svc_set(m: &ArgMatches) -> Result<()> {
     let cfg = config::load()?;
     let sup_addr = sup_addr_from_input(m)?;
     let secret_key = ctl_secret_key(&cfg)?;
+    let tls = ctl_tls_from_input(m, &cfg)?;
     let service_group = ServiceGroup::from_str(m.value_of("SERVICE_GROUP").unwrap())?;
     let mut ui = ui();
     let mut validate = protocol::ctl::SvcValidateCfg::default();
@@ -852,7 +865,7 @@ fn sub_svc_set(m: &ArgMatches) -> Result<()> {
             .unwrap_or("UNKNOWN".to_string()),
     ))?;
     ui.status(Status::Creating, format!("service configuration"))?;
-    SrvClient::connect(&sup_addr, &secret_key)
+    SrvClient::connect(&sup_addr, &secret_key, tls.as_ref())
         .and_then(|conn| {
             conn.call(validate)
                 .for_each(|reply| match reply.message_id() {
@@ -876,7 +889,7 @@ fn sub_svc_set(m: &ArgMatches) -> Result<()> {
     // JW: We should not need to make two connections here. I need a way to return the
     // SrvClient from a for_each iterator so we can chain upon a successful stream but I don't
     // know if it's possible with this version of futures.
-    SrvClient::connect(&sup_addr, secret_key)
+    SrvClient::connect(&sup_addr, secret_key, tls.as_ref())
         .and_then(|conn| {
             conn.call(set).for_each(|reply| match reply.message_id() {
                 "NetOk" => Ok(()),
@@ -898,9 +911,10 @@ fn sub_svc_config(m: &ArgMatches) -> Result<()> {
     let cfg = config::load()?;
     let sup_addr = sup_addr_from_input(m)?;
     let secret_key = ctl_secret_key(&cfg)?;
+    let tls = ctl_tls_from_input(m, &cfg)?;
     let mut msg = protocol::ctl::SvcGetDefaultCfg::default();
     msg.ident = Some(ident.into());
-    SrvClient::connect(&sup_addr, secret_key)
+    SrvClient::connect(&sup_addr, secret_key, tls.as_ref())
         .and_then(|conn| {
             conn.call(msg).for_each(|reply| match reply.message_id() {
                 "ServiceCfg" => {
@@ -924,11 +938,12 @@ fn sub_svc_load(m: &ArgMatches) -> Result<()> {
     let cfg = config::load()?;
     let sup_addr = sup_addr_from_input(m)?;
     let secret_key = ctl_secret_key(&cfg)?;
+    let tls = ctl_tls_from_input(m, &cfg)?;
     let mut msg = protocol::ctl::SvcLoad::default();
     update_svc_load_from_input(m, &mut msg)?;
     let ident: PackageIdent = m.value_of("PKG_IDENT").unwrap().parse()?;
     msg.ident = Some(ident.into());
-    SrvClient::connect(&sup_addr, secret_key)
+    SrvClient::connect(&sup_addr, secret_key, tls.as_ref())
         .and_then(|conn| conn.call(msg).for_each(handle_ctl_reply))
         .wait()?;
     Ok(())
@@ -939,9 +954,10 @@ fn sub_svc_unload(m: &ArgMatches) -> Result<()> {
     let cfg = config::load()?;
     let sup_addr = sup_addr_from_input(m)?;
     let secret_key = ctl_secret_key(&cfg)?;
+    let tls = ctl_tls_from_input(m, &cfg)?;
     let mut msg = protocol::ctl::SvcUnload::default();
     msg.ident = Some(ident.into());
-    SrvClient::connect(&sup_addr, secret_key)
+    SrvClient::connect(&sup_addr, secret_key, tls.as_ref())
         .and_then(|conn| conn.call(msg).for_each(handle_ctl_reply))
         .wait()?;
     Ok(())
@@ -952,9 +968,10 @@ fn sub_svc_start(m: &ArgMatches) -> Result<()> {
     let cfg = config::load()?;
     let sup_addr = sup_addr_from_input(m)?;
     let secret_key = ctl_secret_key(&cfg)?;
+    let tls = ctl_tls_from_input(m, &cfg)?;
     let mut msg = protocol::ctl::SvcStart::default();
     msg.ident = Some(ident.into());
-    SrvClient::connect(&sup_addr, secret_key)
+    SrvClient::connect(&sup_addr, secret_key, tls.as_ref())
         .and_then(|conn| conn.call(msg).for_each(handle_ctl_reply))
         .wait()?;
     Ok(())
@@ -964,12 +981,13 @@ fn sub_svc_status(m: &ArgMatches) -> Result<()> {
     let cfg = config::load()?;
     let sup_addr = sup_addr_from_input(m)?;
     let secret_key = ctl_secret_key(&cfg)?;
+    let tls = ctl_tls_from_input(m, &cfg)?;
     let mut msg = protocol::ctl::SvcStatus::default();
     if let Some(pkg) = m.value_of("PKG_IDENT") {
         msg.ident = Some(PackageIdent::from_str(pkg)?.into());
     }
 
-    SrvClient::connect(&sup_addr, secret_key)
+    SrvClient::connect(&sup_addr, secret_key, tls.as_ref())
         .and_then(|conn| {
             let mut out = TabWriter::new(io::stdout());
             conn.call(msg)
@@ -1003,9 +1021,59 @@ fn sub_svc_stop(m: &ArgMatches) -> Result<()> {
     let cfg = config::load()?;
     let sup_addr = sup_addr_from_input(m)?;
     let secret_key = ctl_secret_key(&cfg)?;
+    let tls = ctl_tls_from_input(m, &cfg)?;
     let mut msg = protocol::ctl::SvcStop::default();
     msg.ident = Some(ident.into());
-    SrvClient::connect(&sup_addr, secret_key)
+    SrvClient::connect(&sup_addr, secret_key, tls.as_ref())
+        .and_then(|conn| conn.call(msg).for_each(handle_ctl_reply))
+        .wait()?;
+    Ok(())
+}
+
+fn sub_svc_signal(m: &ArgMatches) -> Result<()> {
+    let ident = PackageIdent::from_str(m.value_of("PKG_IDENT").unwrap())?;
+    let signal = Signal::from_str(m.value_of("SIGNAL").unwrap())?;
+    let cfg = config::load()?;
+    let sup_addr = sup_addr_from_input(m)?;
+    let secret_key = ctl_secret_key(&cfg)?;
+    let tls = ctl_tls_from_input(m, &cfg)?;
+    let mut msg = protocol::ctl::SvcSignal::default();
+    msg.ident = Some(ident.into());
+    msg.signal = Some(signal as i32);
+    SrvClient::connect(&sup_addr, secret_key, tls.as_ref())
+        .and_then(|conn| conn.call(msg).for_each(handle_ctl_reply))
+        .wait()?;
+    Ok(())
+}
+
+fn sub_svc_exec(m: &ArgMatches) -> Result<()> {
+    let ident = PackageIdent::from_str(m.value_of("PKG_IDENT").unwrap())?;
+    let cfg = config::load()?;
+    let sup_addr = sup_addr_from_input(m)?;
+    let secret_key = ctl_secret_key(&cfg)?;
+    let tls = ctl_tls_from_input(m, &cfg)?;
+    let mut msg = protocol::ctl::SvcExec::default();
+    msg.ident = Some(ident.into());
+    msg.command = Some(m.value_of("CMD").unwrap().to_string());
+    msg.args = m
+        .values_of("ARGS")
+        .map(|args| args.map(|arg| arg.to_string()).collect())
+        .unwrap_or_default();
+    SrvClient::connect(&sup_addr, secret_key, tls.as_ref())
+        .and_then(|conn| conn.call(msg).for_each(handle_ctl_reply))
+        .wait()?;
+    Ok(())
+}
+
+fn sub_svc_group_forget(m: &ArgMatches) -> Result<()> {
+    let service_group = ServiceGroup::from_str(m.value_of("SERVICE_GROUP").unwrap())?;
+    let cfg = config::load()?;
+    let sup_addr = sup_addr_from_input(m)?;
+    let secret_key = ctl_secret_key(&cfg)?;
+    let tls = ctl_tls_from_input(m, &cfg)?;
+    let mut msg = protocol::ctl::SvcGroupForget::default();
+    msg.service_group = Some(service_group.into());
+    SrvClient::connect(&sup_addr, secret_key, tls.as_ref())
         .and_then(|conn| conn.call(msg).for_each(handle_ctl_reply))
         .wait()?;
     Ok(())
@@ -1016,6 +1084,7 @@ fn sub_file_put(m: &ArgMatches) -> Result<()> {
     let cfg = config::load()?;
     let sup_addr = sup_addr_from_input(m)?;
     let secret_key = ctl_secret_key(&cfg)?;
+    let tls = ctl_tls_from_input(m, &cfg)?;
     let mut ui = ui();
     let mut msg = protocol::ctl::SvcFilePut::default();
     let file = Path::new(m.value_of("FILE").unwrap());
@@ -1062,7 +1131,7 @@ fn sub_file_put(m: &ArgMatches) -> Result<()> {
         }
         _ => msg.content = Some(buf.to_vec()),
     }
-    SrvClient::connect(&sup_addr, secret_key)
+    SrvClient::connect(&sup_addr, secret_key, tls.as_ref())
         .and_then(|conn| {
             ui.status(Status::Applying, format!("via peer {}", sup_addr))
                 .unwrap();
@@ -1091,10 +1160,11 @@ fn sub_sup_depart(m: &ArgMatches) -> Result<()> {
     let cfg = config::load()?;
     let sup_addr = sup_addr_from_input(m)?;
     let secret_key = ctl_secret_key(&cfg)?;
+    let tls = ctl_tls_from_input(m, &cfg)?;
     let mut ui = ui();
     let mut msg = protocol::ctl::SupDepart::default();
     msg.member_id = Some(m.value_of("MEMBER_ID").unwrap().to_string());
-    SrvClient::connect(&sup_addr, secret_key)
+    SrvClient::connect(&sup_addr, secret_key, tls.as_ref())
         .and_then(|conn| {
             ui.begin(format!(
                 "Permanently marking {} as departed",
@@ -1148,6 +1218,18 @@ fn sub_ring_key_generate(ui: &mut UI, m: &ArgMatches) -> Result<()> {
     command::ring::key::generate::start(ui, ring, &default_cache_key_path(Some(&*FS_ROOT)))
 }
 
+fn sub_ring_key_rotate(m: &ArgMatches) -> Result<()> {
+    let cfg = config::load()?;
+    let sup_addr = sup_addr_from_input(m)?;
+    let secret_key = ctl_secret_key(&cfg)?;
+    let tls = ctl_tls_from_input(m, &cfg)?;
+    let msg = protocol::ctl::RingKeyRotate::default();
+    SrvClient::connect(&sup_addr, secret_key, tls.as_ref())
+        .and_then(|conn| conn.call(msg).for_each(handle_ctl_reply))
+        .wait()?;
+    Ok(())
+}
+
 fn sub_ring_key_import(ui: &mut UI) -> Result<()> {
     let mut content = String::new();
     io::stdin().read_to_string(&mut content)?;
@@ -1208,6 +1290,7 @@ fn exec_subcommand_if_called(ui: &mut UI) -> Result<()> {
         | ("sup", "run", "-h")
         | ("sup", "run", "--help")
         | ("sup", "term", _)
+        | ("sup", "audit", _)
         | ("sup", "bash", _)
         | ("sup", "sh", _)
         | ("sup", "-h", _)
@@ -1279,6 +1362,46 @@ fn ctl_secret_key(config: &Config) -> Result<String> {
     }
 }
 
+/// Find the certificate, key, and CA certificate used to connect to a remote Supervisor's Control
+/// Gateway with TLS. Each is taken from the command line, then the CLI config, and finally from
+/// its default location alongside the CLI config. Returns `None` if no client certificate is
+/// found.
+fn ctl_tls_from_input(m: &ArgMatches, config: &Config) -> Result<Option<TlsConfig>> {
+    let find = |arg: &str, configured: &Option<PathBuf>, file_name: &str| {
+        m.value_of(arg)
+            .map(PathBuf::from)
+            .or_else(|| configured.clone())
+            .or_else(|| config::ctl_tls_default_path(file_name))
+    };
+    let certificate = find(
+        "CTL_CLIENT_CERTIFICATE",
+        &config.ctl_client_certificate,
+        config::CTL_CLIENT_CERTIFICATE_FILENAME,
+    );
+    let key = find(
+        "CTL_CLIENT_KEY",
+        &config.ctl_client_key,
+        config::CTL_CLIENT_KEY_FILENAME,
+    );
+    let ca_certificate = find(
+        "CTL_SERVER_CA_CERTIFICATE",
+        &config.ctl_server_ca_certificate,
+        config::CTL_SERVER_CA_CERTIFICATE_FILENAME,
+    );
+    match (certificate, key, ca_certificate) {
+        (None, None, _) => Ok(None),
+        (Some(certificate), Some(key), Some(ca_certificate)) => {
+            Ok(Some(TlsConfig::new(certificate, key, ca_certificate)))
+        }
+        (Some(_), Some(_), None) => Err(Error::ArgumentError(
+            "A CA certificate is required to verify the Supervisor's Control Gateway",
+        )),
+        _ => Err(Error::ArgumentError(
+            "A Control Gateway client certificate and key must be given together",
+        )),
+    }
+}
+
 /// Check to see if an auth token exists and convert it to a string slice if it does. Unlike
 /// auth_token_param_or_env, it's ok for no auth token to be present here. This is useful for
 /// commands that can optionally take an auth token for operating on private packages.
@@ -1457,10 +1580,30 @@ where
     let svc_desired_state = status
         .desired_state
         .map_or("<none>".to_string(), |s| s.to_string());
+    let usage = status.process.as_ref().and_then(|p| p.usage.as_ref());
+    let (svc_cpu, svc_memory, svc_pids) = match usage {
+        Some(usage) => (
+            format!(
+                "{:.2}",
+                usage.cpu_usec.unwrap_or_default() as f64 / 1_000_000.0
+            ),
+            usage.memory_bytes.unwrap_or_default().to_string(),
+            usage.pids.unwrap_or_default().to_string(),
+        ),
+        None => (
+            "<none>".to_string(),
+            "<none>".to_string(),
+            "<none>".to_string(),
+        ),
+    };
     let (svc_state, svc_pid, svc_elapsed) = {
         match status.process {
             Some(process) => (
-                process.state.to_string(),
+                if process.crash_looping.unwrap_or(false) {
+                    "crash-looping".to_string()
+                } else {
+                    ProcessState::from_str(&process.state.to_string())?.to_string()
+                },
                 process
                     .pid
                     .map_or_else(|| "<none>".to_string(), |p| p.to_string()),
@@ -1478,14 +1621,18 @@ where
     }
     write!(
         out,
-        "{}\t{}\t{}\t{}\t{}\t{}\t{}\n",
+        "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\n",
         status.ident,
         svc_type,
         DesiredState::from_str(&svc_desired_state)?,
-        ProcessState::from_str(&svc_state)?,
+        svc_state,
         svc_elapsed,
         svc_pid,
         status.service_group,
+        status.update_status.unwrap_or("<none>".to_string()),
+        svc_cpu,
+        svc_memory,
+        svc_pids,
     )?;
     return Ok(());
 }
@@ -1635,5 +1782,43 @@ fn update_svc_load_from_input(m: &ArgMatches, msg: &mut protocol::ctl::SvcLoad)
     msg.binding_mode = get_binding_mode_from_input(m).map(|v| v as i32);
     msg.topology = get_topology_from_input(m).map(|v| v as i32);
     msg.update_strategy = get_strategy_from_input(m).map(|v| v as i32);
+    msg.health_check_interval = m
+        .value_of("HEALTH_CHECK_INTERVAL")
+        .and_then(|v| v.parse().ok());
+    msg.health_check_timeout = m
+        .value_of("HEALTH_CHECK_TIMEOUT")
+        .and_then(|v| v.parse().ok());
+    msg.health_check_failure_threshold = m
+        .value_of("HEALTH_CHECK_FAILURE_THRESHOLD")
+        .and_then(|v| v.parse().ok());
+    msg.health_check_success_threshold = m
+        .value_of("HEALTH_CHECK_SUCCESS_THRESHOLD")
+        .and_then(|v| v.parse().ok());
+    msg.restart_after_critical = m
+        .value_of("RESTART_AFTER_CRITICAL")
+        .and_then(|v| v.parse().ok());
+    msg.restart_backoff = m.value_of("RESTART_BACKOFF").and_then(|v| v.parse().ok());
+    msg.restart_max = m.value_of("RESTART_MAX").and_then(|v| v.parse().ok());
+    msg.restart_window = m.value_of("RESTART_WINDOW").and_then(|v| v.parse().ok());
+    msg.canary_size = m.value_of("CANARY_SIZE").map(str::to_string);
+    msg.canary_soak = m.value_of("CANARY_SOAK").and_then(|v| v.parse().ok());
+    if let Some(windows) = m.values_of("UPDATE_WINDOW") {
+        msg.update_windows = windows.map(str::to_string).collect();
+    }
+    msg.update_version = m.value_of("UPDATE_VERSION").map(str::to_string);
+    if m.is_present("LOG_CAPTURE") {
+        msg.log_capture = Some(true);
+    }
+    msg.log_max_size = m.value_of("LOG_MAX_SIZE").and_then(|v| v.parse().ok());
+    msg.log_max_age = m.value_of("LOG_MAX_AGE").and_then(|v| v.parse().ok());
+    msg.log_retain = m.value_of("LOG_RETAIN").and_then(|v| v.parse().ok());
+    msg.cpu_limit = m.value_of("CPU_LIMIT").and_then(|v| v.parse().ok());
+    msg.memory_limit = m.value_of("MEMORY_LIMIT").and_then(|v| v.parse().ok());
+    msg.pids_limit = m.value_of("PIDS_LIMIT").and_then(|v| v.parse().ok());
+    msg.shutdown_signal = m
+        .value_of("SHUTDOWN_SIGNAL")
+        .and_then(|v| Signal::from_str(v).ok())
+        .map(|v| v as i32);
+    msg.shutdown_timeout = m.value_of("SHUTDOWN_TIMEOUT").and_then(|v| v.parse().ok());
     Ok(())
 }
@@ -15,7 +15,7 @@
 use std::collections::HashMap;
 use std::fs;
 use std::io;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use core::os::process::Pid;
 use ipc_channel::ipc::{IpcOneShotServer, IpcReceiver, IpcSender};
@@ -27,10 +27,45 @@ use error::{Error, Result};
 type Env = HashMap<String, String>;
 type IpcServer = IpcOneShotServer<Vec<u8>>;
 
+/// Files the Launcher should capture a service's output to, and when it should rotate them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OutputLogs {
+    pub stdout: PathBuf,
+    pub stderr: PathBuf,
+    /// Bytes a log file may grow to before it is rotated; `0` disables size-based rotation.
+    pub max_size: u64,
+    /// Seconds a log file is written to before it is rotated; `0` disables time-based rotation.
+    pub max_age: u64,
+    /// Rotated log files kept for each stream.
+    pub retain: u32,
+}
+
+/// Resources the Launcher should allow a service to use. A value of `0` leaves the resource
+/// unlimited.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ResourceLimits {
+    /// CPU time, in thousandths of a CPU.
+    pub cpu: u32,
+    /// Bytes of memory.
+    pub memory: u64,
+    /// Number of processes.
+    pub pids: u64,
+}
+
+/// How the Launcher should stop a service: the signal it sends first, and the seconds it waits
+/// for the service to exit before killing it. A timeout of `0` uses the Launcher's default.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ShutdownConfig {
+    pub signal: protocol::Signal,
+    pub timeout: u32,
+}
+
 pub struct LauncherCli {
     tx: IpcSender<Vec<u8>>,
     rx: IpcReceiver<Vec<u8>>,
     pipe: String,
+    /// Protocol version spoken by the Launcher, `0` for Launchers which predate versioning.
+    version: u32,
 }
 
 impl Drop for LauncherCli {
@@ -54,10 +89,19 @@ impl LauncherCli {
         Self::send(&tx, &cmd)?;
         let (rx, raw) = ipc_srv.accept().map_err(|_| Error::AcceptConn)?;
         Self::read::<protocol::NetOk>(&raw)?;
+        let version = core::env::var(protocol::LAUNCHER_PROTOCOL_VERSION_ENV)
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(0);
+        debug!(
+            "Connected to Launcher speaking protocol version {}",
+            version
+        );
         Ok(LauncherCli {
             tx: tx,
             rx: rx,
             pipe: cmd.take_pipe(),
+            version: version,
         })
     }
 
@@ -123,6 +167,8 @@ impl LauncherCli {
     }
 
     /// Restart a running process with the same arguments
+    ///
+    /// Returns once the process has exited, within its shutdown timeout, and been spawned again.
     pub fn restart(&self, pid: Pid) -> Result<Pid> {
         let mut msg = protocol::Restart::new();
         msg.set_pid(pid.into());
@@ -136,6 +182,10 @@ impl LauncherCli {
     /// `user` and `group` are string names, while `user_id` and
     /// `group_id` are numeric IDs. Newer versions of the Launcher can
     /// accept either, but prefer numeric IDs.
+    ///
+    /// Older Launchers ignore `logs` and only write the service's
+    /// output to their own stdout and stderr. They also ignore
+    /// `shutdown`, always sending `SIGTERM` and waiting 8 seconds.
     pub fn spawn<I, B, U, G, P>(
         &self,
         id: I,
@@ -146,6 +196,9 @@ impl LauncherCli {
         group_id: Option<u32>,
         password: Option<P>,
         env: Env,
+        logs: Option<&OutputLogs>,
+        limits: &ResourceLimits,
+        shutdown: &ShutdownConfig,
     ) -> Result<Pid>
     where
         I: ToString,
@@ -180,6 +233,18 @@ impl LauncherCli {
         if let Some(password) = password {
             msg.set_svc_password(password.to_string());
         }
+        if let Some(logs) = logs {
+            msg.set_stdout_log(logs.stdout.to_string_lossy().into_owned());
+            msg.set_stderr_log(logs.stderr.to_string_lossy().into_owned());
+            msg.set_log_max_size(logs.max_size);
+            msg.set_log_max_age(logs.max_age);
+            msg.set_log_retain(logs.retain);
+        }
+        msg.set_cpu_limit(limits.cpu);
+        msg.set_memory_limit(limits.memory);
+        msg.set_pids_limit(limits.pids);
+        msg.set_shutdown_signal(shutdown.signal);
+        msg.set_shutdown_timeout(shutdown.timeout);
         msg.set_env(env);
         msg.set_id(id.to_string());
         Self::send(&self.tx, &msg)?;
@@ -187,11 +252,34 @@ impl LauncherCli {
         Ok(reply.get_pid() as Pid)
     }
 
-    pub fn terminate(&self, pid: Pid) -> Result<i32> {
+    /// Send a signal to the service running as `pid`, and to the rest of its process group.
+    ///
+    /// Older Launchers ignore this message without replying, so it is
+    /// refused with `Error::LauncherTooOld` rather than sent to them.
+    pub fn signal(&self, pid: Pid, signal: protocol::Signal) -> Result<()> {
+        if self.version < 1 {
+            return Err(Error::LauncherTooOld("signal services", 1));
+        }
+        let mut msg = protocol::SendSignal::new();
+        msg.set_pid(pid.into());
+        msg.set_signal(signal);
+        Self::send(&self.tx, &msg)?;
+        Self::recv::<protocol::NetOk>(&self.rx)?;
+        Ok(())
+    }
+
+    /// Stop the service running as `pid`, as given by `shutdown` rather than the configuration
+    /// it was spawned with.
+    ///
+    /// Returns once the service has been asked to stop; the Launcher kills it if it has not
+    /// exited within the shutdown timeout. Older Launchers return once it has exited.
+    pub fn terminate(&self, pid: Pid, shutdown: &ShutdownConfig) -> Result<()> {
         let mut msg = protocol::Terminate::new();
         msg.set_pid(pid.into());
+        msg.set_shutdown_signal(shutdown.signal);
+        msg.set_shutdown_timeout(shutdown.timeout);
         Self::send(&self.tx, &msg)?;
-        let reply = Self::recv::<protocol::TerminateOk>(&self.rx)?;
-        Ok(reply.get_exit_code())
+        Self::recv::<protocol::TerminateOk>(&self.rx)?;
+        Ok(())
     }
 }
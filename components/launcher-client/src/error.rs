@@ -31,6 +31,7 @@ pub enum Error {
     Deserialize(protobuf::ProtobufError),
     IPCBincode(String),
     IPCIO(io::ErrorKind),
+    LauncherTooOld(&'static str, u32),
     Protocol(protocol::NetErr),
     Send(ipc_channel::Error),
     Serialize(protobuf::ProtobufError),
@@ -51,6 +52,11 @@ impl fmt::Display for Error {
                 format!("Unable to read message frame from Launcher, {}", e)
             }
             Error::IPCIO(ref e) => format!("Unable to receive message from Launcher, {:?}", e),
+            Error::LauncherTooOld(action, version) => format!(
+                "The Launcher is too old to {}, it must speak protocol version {} or newer. \
+                 Install a newer core/hab-launcher and restart the Supervisor.",
+                action, version
+            ),
             Error::Protocol(ref e) => format!("{}", e),
             Error::Send(ref e) => format!("Unable to send to Launcher's pipe, {}", e),
             Error::Serialize(ref e) => format!("Unable to serialize message to Launcher, {}", e),
@@ -68,6 +74,7 @@ impl error::Error for Error {
             Error::Deserialize(_) => "Unable to deserialize message from Launcher",
             Error::IPCBincode(_) => "Unable to encode/decode message framing to/from Launcher",
             Error::IPCIO(_) => "Unable to receive message from Launcher",
+            Error::LauncherTooOld(_, _) => "Launcher is too old to handle the request",
             Error::Protocol(_) => "Received an error from Launcher",
             Error::Send(_) => "Unable to send to Launcher's pipe",
             Error::Serialize(_) => "Unable to serialize message to Launcher",
@@ -24,10 +24,10 @@ mod client;
 pub mod error;
 
 pub use protocol::{
-    ERR_NO_RETRY_EXCODE, LAUNCHER_LOCK_CLEAN_ENV, LAUNCHER_PID_ENV, OK_NO_RETRY_EXCODE,
+    Signal, ERR_NO_RETRY_EXCODE, LAUNCHER_LOCK_CLEAN_ENV, LAUNCHER_PID_ENV, OK_NO_RETRY_EXCODE,
 };
 
-pub use client::LauncherCli;
+pub use client::{LauncherCli, OutputLogs, ResourceLimits, ShutdownConfig};
 pub use error::Error;
 
 pub fn env_pipe() -> Option<String> {
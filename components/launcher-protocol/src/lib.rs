@@ -27,6 +27,11 @@ pub use message::supervisor::*;
 
 pub const LAUNCHER_PIPE_ENV: &'static str = "HAB_LAUNCHER_PIPE";
 pub const LAUNCHER_PID_ENV: &'static str = "HAB_LAUNCHER_PID";
+/// Set by the Launcher to the `PROTOCOL_VERSION` it speaks. Launchers which predate it leave it
+/// unset, and never reply to the messages added since.
+pub const LAUNCHER_PROTOCOL_VERSION_ENV: &'static str = "HAB_LAUNCHER_PROTOCOL_VERSION";
+/// Version of the protocol spoken by this Launcher. Version 1 added `SendSignal`.
+pub const PROTOCOL_VERSION: u32 = 1;
 // Set to instruct the Supervisor to clean the Launcher's process LOCK on startup. This is useful
 // when restarting a Supervisor which terminated normally.
 pub const LAUNCHER_LOCK_CLEAN_ENV: &'static str = "HAB_LAUNCHER_LOCK_CLEAN";
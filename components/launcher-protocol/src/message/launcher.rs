@@ -391,6 +391,16 @@ pub struct Spawn {
     pub env: ::std::collections::HashMap<::std::string::String, ::std::string::String>,
     svc_user_id: ::std::option::Option<u32>,
     svc_group_id: ::std::option::Option<u32>,
+    stdout_log: ::protobuf::SingularField<::std::string::String>,
+    stderr_log: ::protobuf::SingularField<::std::string::String>,
+    log_max_size: ::std::option::Option<u64>,
+    log_max_age: ::std::option::Option<u64>,
+    log_retain: ::std::option::Option<u32>,
+    cpu_limit: ::std::option::Option<u32>,
+    memory_limit: ::std::option::Option<u64>,
+    pids_limit: ::std::option::Option<u64>,
+    shutdown_signal: ::std::option::Option<Signal>,
+    shutdown_timeout: ::std::option::Option<u32>,
     // special fields
     unknown_fields: ::protobuf::UnknownFields,
     cached_size: ::protobuf::CachedSize,
@@ -720,6 +730,310 @@ impl Spawn {
     fn mut_svc_group_id_for_reflect(&mut self) -> &mut ::std::option::Option<u32> {
         &mut self.svc_group_id
     }
+
+    // optional string stdout_log = 9;
+
+    pub fn clear_stdout_log(&mut self) {
+        self.stdout_log.clear();
+    }
+
+    pub fn has_stdout_log(&self) -> bool {
+        self.stdout_log.is_some()
+    }
+
+    // Param is passed by value, moved
+    pub fn set_stdout_log(&mut self, v: ::std::string::String) {
+        self.stdout_log = ::protobuf::SingularField::some(v);
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_stdout_log(&mut self) -> &mut ::std::string::String {
+        if self.stdout_log.is_none() {
+            self.stdout_log.set_default();
+        }
+        self.stdout_log.as_mut().unwrap()
+    }
+
+    // Take field
+    pub fn take_stdout_log(&mut self) -> ::std::string::String {
+        self.stdout_log.take().unwrap_or_else(|| ::std::string::String::new())
+    }
+
+    pub fn get_stdout_log(&self) -> &str {
+        match self.stdout_log.as_ref() {
+            Some(v) => &v,
+            None => "",
+        }
+    }
+
+    fn get_stdout_log_for_reflect(&self) -> &::protobuf::SingularField<::std::string::String> {
+        &self.stdout_log
+    }
+
+    fn mut_stdout_log_for_reflect(&mut self) -> &mut ::protobuf::SingularField<::std::string::String> {
+        &mut self.stdout_log
+    }
+
+    // optional string stderr_log = 10;
+
+    pub fn clear_stderr_log(&mut self) {
+        self.stderr_log.clear();
+    }
+
+    pub fn has_stderr_log(&self) -> bool {
+        self.stderr_log.is_some()
+    }
+
+    // Param is passed by value, moved
+    pub fn set_stderr_log(&mut self, v: ::std::string::String) {
+        self.stderr_log = ::protobuf::SingularField::some(v);
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_stderr_log(&mut self) -> &mut ::std::string::String {
+        if self.stderr_log.is_none() {
+            self.stderr_log.set_default();
+        }
+        self.stderr_log.as_mut().unwrap()
+    }
+
+    // Take field
+    pub fn take_stderr_log(&mut self) -> ::std::string::String {
+        self.stderr_log.take().unwrap_or_else(|| ::std::string::String::new())
+    }
+
+    pub fn get_stderr_log(&self) -> &str {
+        match self.stderr_log.as_ref() {
+            Some(v) => &v,
+            None => "",
+        }
+    }
+
+    fn get_stderr_log_for_reflect(&self) -> &::protobuf::SingularField<::std::string::String> {
+        &self.stderr_log
+    }
+
+    fn mut_stderr_log_for_reflect(&mut self) -> &mut ::protobuf::SingularField<::std::string::String> {
+        &mut self.stderr_log
+    }
+
+    // optional uint64 log_max_size = 11;
+
+    pub fn clear_log_max_size(&mut self) {
+        self.log_max_size = ::std::option::Option::None;
+    }
+
+    pub fn has_log_max_size(&self) -> bool {
+        self.log_max_size.is_some()
+    }
+
+    // Param is passed by value, moved
+    pub fn set_log_max_size(&mut self, v: u64) {
+        self.log_max_size = ::std::option::Option::Some(v);
+    }
+
+    pub fn get_log_max_size(&self) -> u64 {
+        self.log_max_size.unwrap_or(0)
+    }
+
+    fn get_log_max_size_for_reflect(&self) -> &::std::option::Option<u64> {
+        &self.log_max_size
+    }
+
+    fn mut_log_max_size_for_reflect(&mut self) -> &mut ::std::option::Option<u64> {
+        &mut self.log_max_size
+    }
+
+    // optional uint64 log_max_age = 12;
+
+    pub fn clear_log_max_age(&mut self) {
+        self.log_max_age = ::std::option::Option::None;
+    }
+
+    pub fn has_log_max_age(&self) -> bool {
+        self.log_max_age.is_some()
+    }
+
+    // Param is passed by value, moved
+    pub fn set_log_max_age(&mut self, v: u64) {
+        self.log_max_age = ::std::option::Option::Some(v);
+    }
+
+    pub fn get_log_max_age(&self) -> u64 {
+        self.log_max_age.unwrap_or(0)
+    }
+
+    fn get_log_max_age_for_reflect(&self) -> &::std::option::Option<u64> {
+        &self.log_max_age
+    }
+
+    fn mut_log_max_age_for_reflect(&mut self) -> &mut ::std::option::Option<u64> {
+        &mut self.log_max_age
+    }
+
+    // optional uint32 log_retain = 13;
+
+    pub fn clear_log_retain(&mut self) {
+        self.log_retain = ::std::option::Option::None;
+    }
+
+    pub fn has_log_retain(&self) -> bool {
+        self.log_retain.is_some()
+    }
+
+    // Param is passed by value, moved
+    pub fn set_log_retain(&mut self, v: u32) {
+        self.log_retain = ::std::option::Option::Some(v);
+    }
+
+    pub fn get_log_retain(&self) -> u32 {
+        self.log_retain.unwrap_or(0)
+    }
+
+    fn get_log_retain_for_reflect(&self) -> &::std::option::Option<u32> {
+        &self.log_retain
+    }
+
+    fn mut_log_retain_for_reflect(&mut self) -> &mut ::std::option::Option<u32> {
+        &mut self.log_retain
+    }
+
+    // optional uint32 cpu_limit = 14;
+
+    pub fn clear_cpu_limit(&mut self) {
+        self.cpu_limit = ::std::option::Option::None;
+    }
+
+    pub fn has_cpu_limit(&self) -> bool {
+        self.cpu_limit.is_some()
+    }
+
+    // Param is passed by value, moved
+    pub fn set_cpu_limit(&mut self, v: u32) {
+        self.cpu_limit = ::std::option::Option::Some(v);
+    }
+
+    pub fn get_cpu_limit(&self) -> u32 {
+        self.cpu_limit.unwrap_or(0)
+    }
+
+    fn get_cpu_limit_for_reflect(&self) -> &::std::option::Option<u32> {
+        &self.cpu_limit
+    }
+
+    fn mut_cpu_limit_for_reflect(&mut self) -> &mut ::std::option::Option<u32> {
+        &mut self.cpu_limit
+    }
+
+    // optional uint64 memory_limit = 15;
+
+    pub fn clear_memory_limit(&mut self) {
+        self.memory_limit = ::std::option::Option::None;
+    }
+
+    pub fn has_memory_limit(&self) -> bool {
+        self.memory_limit.is_some()
+    }
+
+    // Param is passed by value, moved
+    pub fn set_memory_limit(&mut self, v: u64) {
+        self.memory_limit = ::std::option::Option::Some(v);
+    }
+
+    pub fn get_memory_limit(&self) -> u64 {
+        self.memory_limit.unwrap_or(0)
+    }
+
+    fn get_memory_limit_for_reflect(&self) -> &::std::option::Option<u64> {
+        &self.memory_limit
+    }
+
+    fn mut_memory_limit_for_reflect(&mut self) -> &mut ::std::option::Option<u64> {
+        &mut self.memory_limit
+    }
+
+    // optional uint64 pids_limit = 16;
+
+    pub fn clear_pids_limit(&mut self) {
+        self.pids_limit = ::std::option::Option::None;
+    }
+
+    pub fn has_pids_limit(&self) -> bool {
+        self.pids_limit.is_some()
+    }
+
+    // Param is passed by value, moved
+    pub fn set_pids_limit(&mut self, v: u64) {
+        self.pids_limit = ::std::option::Option::Some(v);
+    }
+
+    pub fn get_pids_limit(&self) -> u64 {
+        self.pids_limit.unwrap_or(0)
+    }
+
+    fn get_pids_limit_for_reflect(&self) -> &::std::option::Option<u64> {
+        &self.pids_limit
+    }
+
+    fn mut_pids_limit_for_reflect(&mut self) -> &mut ::std::option::Option<u64> {
+        &mut self.pids_limit
+    }
+
+    // optional .launcher.Signal shutdown_signal = 17;
+
+    pub fn clear_shutdown_signal(&mut self) {
+        self.shutdown_signal = ::std::option::Option::None;
+    }
+
+    pub fn has_shutdown_signal(&self) -> bool {
+        self.shutdown_signal.is_some()
+    }
+
+    // Param is passed by value, moved
+    pub fn set_shutdown_signal(&mut self, v: Signal) {
+        self.shutdown_signal = ::std::option::Option::Some(v);
+    }
+
+    pub fn get_shutdown_signal(&self) -> Signal {
+        self.shutdown_signal.unwrap_or(Signal::TERM)
+    }
+
+    fn get_shutdown_signal_for_reflect(&self) -> &::std::option::Option<Signal> {
+        &self.shutdown_signal
+    }
+
+    fn mut_shutdown_signal_for_reflect(&mut self) -> &mut ::std::option::Option<Signal> {
+        &mut self.shutdown_signal
+    }
+
+    // optional uint32 shutdown_timeout = 18;
+
+    pub fn clear_shutdown_timeout(&mut self) {
+        self.shutdown_timeout = ::std::option::Option::None;
+    }
+
+    pub fn has_shutdown_timeout(&self) -> bool {
+        self.shutdown_timeout.is_some()
+    }
+
+    // Param is passed by value, moved
+    pub fn set_shutdown_timeout(&mut self, v: u32) {
+        self.shutdown_timeout = ::std::option::Option::Some(v);
+    }
+
+    pub fn get_shutdown_timeout(&self) -> u32 {
+        self.shutdown_timeout.unwrap_or(0)
+    }
+
+    fn get_shutdown_timeout_for_reflect(&self) -> &::std::option::Option<u32> {
+        &self.shutdown_timeout
+    }
+
+    fn mut_shutdown_timeout_for_reflect(&mut self) -> &mut ::std::option::Option<u32> {
+        &mut self.shutdown_timeout
+    }
 }
 
 impl ::protobuf::Message for Spawn {
@@ -763,6 +1077,64 @@ impl ::protobuf::Message for Spawn {
                     let tmp = is.read_uint32()?;
                     self.svc_group_id = ::std::option::Option::Some(tmp);
                 },
+                9 => {
+                    ::protobuf::rt::read_singular_string_into(wire_type, is, &mut self.stdout_log)?;
+                },
+                10 => {
+                    ::protobuf::rt::read_singular_string_into(wire_type, is, &mut self.stderr_log)?;
+                },
+                11 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.log_max_size = ::std::option::Option::Some(tmp);
+                },
+                12 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.log_max_age = ::std::option::Option::Some(tmp);
+                },
+                13 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint32()?;
+                    self.log_retain = ::std::option::Option::Some(tmp);
+                },
+                14 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint32()?;
+                    self.cpu_limit = ::std::option::Option::Some(tmp);
+                },
+                15 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.memory_limit = ::std::option::Option::Some(tmp);
+                },
+                16 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.pids_limit = ::std::option::Option::Some(tmp);
+                },
+                17 => {
+                    ::protobuf::rt::read_proto2_enum_with_unknown_fields_into(wire_type, is, &mut self.shutdown_signal, 17, &mut self.unknown_fields)?
+                },
+                18 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint32()?;
+                    self.shutdown_timeout = ::std::option::Option::Some(tmp);
+                },
                 _ => {
                     ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
                 },
@@ -797,6 +1169,36 @@ impl ::protobuf::Message for Spawn {
         if let Some(v) = self.svc_group_id {
             my_size += ::protobuf::rt::value_size(8, v, ::protobuf::wire_format::WireTypeVarint);
         }
+        if let Some(ref v) = self.stdout_log.as_ref() {
+            my_size += ::protobuf::rt::string_size(9, &v);
+        }
+        if let Some(ref v) = self.stderr_log.as_ref() {
+            my_size += ::protobuf::rt::string_size(10, &v);
+        }
+        if let Some(v) = self.log_max_size {
+            my_size += ::protobuf::rt::value_size(11, v, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if let Some(v) = self.log_max_age {
+            my_size += ::protobuf::rt::value_size(12, v, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if let Some(v) = self.log_retain {
+            my_size += ::protobuf::rt::value_size(13, v, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if let Some(v) = self.cpu_limit {
+            my_size += ::protobuf::rt::value_size(14, v, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if let Some(v) = self.memory_limit {
+            my_size += ::protobuf::rt::value_size(15, v, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if let Some(v) = self.pids_limit {
+            my_size += ::protobuf::rt::value_size(16, v, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if let Some(v) = self.shutdown_signal {
+            my_size += ::protobuf::rt::enum_size(17, v);
+        }
+        if let Some(v) = self.shutdown_timeout {
+            my_size += ::protobuf::rt::value_size(18, v, ::protobuf::wire_format::WireTypeVarint);
+        }
         my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
         self.cached_size.set(my_size);
         my_size
@@ -825,6 +1227,36 @@ impl ::protobuf::Message for Spawn {
         if let Some(v) = self.svc_group_id {
             os.write_uint32(8, v)?;
         }
+        if let Some(ref v) = self.stdout_log.as_ref() {
+            os.write_string(9, &v)?;
+        }
+        if let Some(ref v) = self.stderr_log.as_ref() {
+            os.write_string(10, &v)?;
+        }
+        if let Some(v) = self.log_max_size {
+            os.write_uint64(11, v)?;
+        }
+        if let Some(v) = self.log_max_age {
+            os.write_uint64(12, v)?;
+        }
+        if let Some(v) = self.log_retain {
+            os.write_uint32(13, v)?;
+        }
+        if let Some(v) = self.cpu_limit {
+            os.write_uint32(14, v)?;
+        }
+        if let Some(v) = self.memory_limit {
+            os.write_uint64(15, v)?;
+        }
+        if let Some(v) = self.pids_limit {
+            os.write_uint64(16, v)?;
+        }
+        if let Some(v) = self.shutdown_signal {
+            os.write_enum(17, v.value())?;
+        }
+        if let Some(v) = self.shutdown_timeout {
+            os.write_uint32(18, v)?;
+        }
         os.write_unknown_fields(self.get_unknown_fields())?;
         ::std::result::Result::Ok(())
     }
@@ -909,6 +1341,56 @@ impl ::protobuf::MessageStatic for Spawn {
                     Spawn::get_svc_group_id_for_reflect,
                     Spawn::mut_svc_group_id_for_reflect,
                 ));
+                fields.push(::protobuf::reflect::accessor::make_singular_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "stdout_log",
+                    Spawn::get_stdout_log_for_reflect,
+                    Spawn::mut_stdout_log_for_reflect,
+                ));
+                fields.push(::protobuf::reflect::accessor::make_singular_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "stderr_log",
+                    Spawn::get_stderr_log_for_reflect,
+                    Spawn::mut_stderr_log_for_reflect,
+                ));
+                fields.push(::protobuf::reflect::accessor::make_option_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                    "log_max_size",
+                    Spawn::get_log_max_size_for_reflect,
+                    Spawn::mut_log_max_size_for_reflect,
+                ));
+                fields.push(::protobuf::reflect::accessor::make_option_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                    "log_max_age",
+                    Spawn::get_log_max_age_for_reflect,
+                    Spawn::mut_log_max_age_for_reflect,
+                ));
+                fields.push(::protobuf::reflect::accessor::make_option_accessor::<_, ::protobuf::types::ProtobufTypeUint32>(
+                    "log_retain",
+                    Spawn::get_log_retain_for_reflect,
+                    Spawn::mut_log_retain_for_reflect,
+                ));
+                fields.push(::protobuf::reflect::accessor::make_option_accessor::<_, ::protobuf::types::ProtobufTypeUint32>(
+                    "cpu_limit",
+                    Spawn::get_cpu_limit_for_reflect,
+                    Spawn::mut_cpu_limit_for_reflect,
+                ));
+                fields.push(::protobuf::reflect::accessor::make_option_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                    "memory_limit",
+                    Spawn::get_memory_limit_for_reflect,
+                    Spawn::mut_memory_limit_for_reflect,
+                ));
+                fields.push(::protobuf::reflect::accessor::make_option_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                    "pids_limit",
+                    Spawn::get_pids_limit_for_reflect,
+                    Spawn::mut_pids_limit_for_reflect,
+                ));
+                fields.push(::protobuf::reflect::accessor::make_option_accessor::<_, ::protobuf::types::ProtobufTypeEnum<Signal>>(
+                    "shutdown_signal",
+                    Spawn::get_shutdown_signal_for_reflect,
+                    Spawn::mut_shutdown_signal_for_reflect,
+                ));
+                fields.push(::protobuf::reflect::accessor::make_option_accessor::<_, ::protobuf::types::ProtobufTypeUint32>(
+                    "shutdown_timeout",
+                    Spawn::get_shutdown_timeout_for_reflect,
+                    Spawn::mut_shutdown_timeout_for_reflect,
+                ));
                 ::protobuf::reflect::MessageDescriptor::new::<Spawn>(
                     "Spawn",
                     fields,
@@ -929,6 +1411,16 @@ impl ::protobuf::Clear for Spawn {
         self.clear_env();
         self.clear_svc_user_id();
         self.clear_svc_group_id();
+        self.clear_stdout_log();
+        self.clear_stderr_log();
+        self.clear_log_max_size();
+        self.clear_log_max_age();
+        self.clear_log_retain();
+        self.clear_cpu_limit();
+        self.clear_memory_limit();
+        self.clear_pids_limit();
+        self.clear_shutdown_signal();
+        self.clear_shutdown_timeout();
         self.unknown_fields.clear();
     }
 }
@@ -1122,6 +1614,8 @@ impl ::protobuf::reflect::ProtobufValue for SpawnOk {
 pub struct Terminate {
     // message fields
     pid: ::std::option::Option<i64>,
+    shutdown_signal: ::std::option::Option<Signal>,
+    shutdown_timeout: ::std::option::Option<u32>,
     // special fields
     unknown_fields: ::protobuf::UnknownFields,
     cached_size: ::protobuf::CachedSize,
@@ -1171,6 +1665,60 @@ impl Terminate {
     fn mut_pid_for_reflect(&mut self) -> &mut ::std::option::Option<i64> {
         &mut self.pid
     }
+
+    // optional .launcher.Signal shutdown_signal = 2;
+
+    pub fn clear_shutdown_signal(&mut self) {
+        self.shutdown_signal = ::std::option::Option::None;
+    }
+
+    pub fn has_shutdown_signal(&self) -> bool {
+        self.shutdown_signal.is_some()
+    }
+
+    // Param is passed by value, moved
+    pub fn set_shutdown_signal(&mut self, v: Signal) {
+        self.shutdown_signal = ::std::option::Option::Some(v);
+    }
+
+    pub fn get_shutdown_signal(&self) -> Signal {
+        self.shutdown_signal.unwrap_or(Signal::TERM)
+    }
+
+    fn get_shutdown_signal_for_reflect(&self) -> &::std::option::Option<Signal> {
+        &self.shutdown_signal
+    }
+
+    fn mut_shutdown_signal_for_reflect(&mut self) -> &mut ::std::option::Option<Signal> {
+        &mut self.shutdown_signal
+    }
+
+    // optional uint32 shutdown_timeout = 3;
+
+    pub fn clear_shutdown_timeout(&mut self) {
+        self.shutdown_timeout = ::std::option::Option::None;
+    }
+
+    pub fn has_shutdown_timeout(&self) -> bool {
+        self.shutdown_timeout.is_some()
+    }
+
+    // Param is passed by value, moved
+    pub fn set_shutdown_timeout(&mut self, v: u32) {
+        self.shutdown_timeout = ::std::option::Option::Some(v);
+    }
+
+    pub fn get_shutdown_timeout(&self) -> u32 {
+        self.shutdown_timeout.unwrap_or(0)
+    }
+
+    fn get_shutdown_timeout_for_reflect(&self) -> &::std::option::Option<u32> {
+        &self.shutdown_timeout
+    }
+
+    fn mut_shutdown_timeout_for_reflect(&mut self) -> &mut ::std::option::Option<u32> {
+        &mut self.shutdown_timeout
+    }
 }
 
 impl ::protobuf::Message for Terminate {
@@ -1189,6 +1737,16 @@ impl ::protobuf::Message for Terminate {
                     let tmp = is.read_int64()?;
                     self.pid = ::std::option::Option::Some(tmp);
                 },
+                2 => {
+                    ::protobuf::rt::read_proto2_enum_with_unknown_fields_into(wire_type, is, &mut self.shutdown_signal, 2, &mut self.unknown_fields)?
+                },
+                3 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint32()?;
+                    self.shutdown_timeout = ::std::option::Option::Some(tmp);
+                },
                 _ => {
                     ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
                 },
@@ -1204,6 +1762,12 @@ impl ::protobuf::Message for Terminate {
         if let Some(v) = self.pid {
             my_size += ::protobuf::rt::value_size(1, v, ::protobuf::wire_format::WireTypeVarint);
         }
+        if let Some(v) = self.shutdown_signal {
+            my_size += ::protobuf::rt::enum_size(2, v);
+        }
+        if let Some(v) = self.shutdown_timeout {
+            my_size += ::protobuf::rt::value_size(3, v, ::protobuf::wire_format::WireTypeVarint);
+        }
         my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
         self.cached_size.set(my_size);
         my_size
@@ -1213,6 +1777,12 @@ impl ::protobuf::Message for Terminate {
         if let Some(v) = self.pid {
             os.write_int64(1, v)?;
         }
+        if let Some(v) = self.shutdown_signal {
+            os.write_enum(2, v.value())?;
+        }
+        if let Some(v) = self.shutdown_timeout {
+            os.write_uint32(3, v)?;
+        }
         os.write_unknown_fields(self.get_unknown_fields())?;
         ::std::result::Result::Ok(())
     }
@@ -1262,6 +1832,16 @@ impl ::protobuf::MessageStatic for Terminate {
                     Terminate::get_pid_for_reflect,
                     Terminate::mut_pid_for_reflect,
                 ));
+                fields.push(::protobuf::reflect::accessor::make_option_accessor::<_, ::protobuf::types::ProtobufTypeEnum<Signal>>(
+                    "shutdown_signal",
+                    Terminate::get_shutdown_signal_for_reflect,
+                    Terminate::mut_shutdown_signal_for_reflect,
+                ));
+                fields.push(::protobuf::reflect::accessor::make_option_accessor::<_, ::protobuf::types::ProtobufTypeUint32>(
+                    "shutdown_timeout",
+                    Terminate::get_shutdown_timeout_for_reflect,
+                    Terminate::mut_shutdown_timeout_for_reflect,
+                ));
                 ::protobuf::reflect::MessageDescriptor::new::<Terminate>(
                     "Terminate",
                     fields,
@@ -1275,6 +1855,8 @@ impl ::protobuf::MessageStatic for Terminate {
 impl ::protobuf::Clear for Terminate {
     fn clear(&mut self) {
         self.clear_pid();
+        self.clear_shutdown_signal();
+        self.clear_shutdown_timeout();
         self.unknown_fields.clear();
     }
 }
@@ -1507,6 +2089,222 @@ impl ::protobuf::reflect::ProtobufValue for TerminateOk {
     }
 }
 
+#[derive(PartialEq,Clone,Default)]
+pub struct SendSignal {
+    // message fields
+    pid: ::std::option::Option<i64>,
+    signal: ::std::option::Option<Signal>,
+    // special fields
+    unknown_fields: ::protobuf::UnknownFields,
+    cached_size: ::protobuf::CachedSize,
+}
+
+// see codegen.rs for the explanation why impl Sync explicitly
+unsafe impl ::std::marker::Sync for SendSignal {}
+
+impl SendSignal {
+    pub fn new() -> SendSignal {
+        ::std::default::Default::default()
+    }
+
+    pub fn default_instance() -> &'static SendSignal {
+        static mut instance: ::protobuf::lazy::Lazy<SendSignal> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const SendSignal,
+        };
+        unsafe {
+            instance.get(SendSignal::new)
+        }
+    }
+
+    // optional int64 pid = 1;
+
+    pub fn clear_pid(&mut self) {
+        self.pid = ::std::option::Option::None;
+    }
+
+    pub fn has_pid(&self) -> bool {
+        self.pid.is_some()
+    }
+
+    // Param is passed by value, moved
+    pub fn set_pid(&mut self, v: i64) {
+        self.pid = ::std::option::Option::Some(v);
+    }
+
+    pub fn get_pid(&self) -> i64 {
+        self.pid.unwrap_or(0)
+    }
+
+    fn get_pid_for_reflect(&self) -> &::std::option::Option<i64> {
+        &self.pid
+    }
+
+    fn mut_pid_for_reflect(&mut self) -> &mut ::std::option::Option<i64> {
+        &mut self.pid
+    }
+
+    // optional .launcher.Signal signal = 2;
+
+    pub fn clear_signal(&mut self) {
+        self.signal = ::std::option::Option::None;
+    }
+
+    pub fn has_signal(&self) -> bool {
+        self.signal.is_some()
+    }
+
+    // Param is passed by value, moved
+    pub fn set_signal(&mut self, v: Signal) {
+        self.signal = ::std::option::Option::Some(v);
+    }
+
+    pub fn get_signal(&self) -> Signal {
+        self.signal.unwrap_or(Signal::TERM)
+    }
+
+    fn get_signal_for_reflect(&self) -> &::std::option::Option<Signal> {
+        &self.signal
+    }
+
+    fn mut_signal_for_reflect(&mut self) -> &mut ::std::option::Option<Signal> {
+        &mut self.signal
+    }
+}
+
+impl ::protobuf::Message for SendSignal {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_int64()?;
+                    self.pid = ::std::option::Option::Some(tmp);
+                },
+                2 => {
+                    ::protobuf::rt::read_proto2_enum_with_unknown_fields_into(wire_type, is, &mut self.signal, 2, &mut self.unknown_fields)?
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if let Some(v) = self.pid {
+            my_size += ::protobuf::rt::value_size(1, v, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if let Some(v) = self.signal {
+            my_size += ::protobuf::rt::enum_size(2, v);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream) -> ::protobuf::ProtobufResult<()> {
+        if let Some(v) = self.pid {
+            os.write_int64(1, v)?;
+        }
+        if let Some(v) = self.signal {
+            os.write_enum(2, v.value())?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &::std::any::Any {
+        self as &::std::any::Any
+    }
+    fn as_any_mut(&mut self) -> &mut ::std::any::Any {
+        self as &mut ::std::any::Any
+    }
+    fn into_any(self: Box<Self>) -> ::std::boxed::Box<::std::any::Any> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        ::protobuf::MessageStatic::descriptor_static(None::<Self>)
+    }
+}
+
+impl ::protobuf::MessageStatic for SendSignal {
+    fn new() -> SendSignal {
+        SendSignal::new()
+    }
+
+    fn descriptor_static(_: ::std::option::Option<SendSignal>) -> &'static ::protobuf::reflect::MessageDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const ::protobuf::reflect::MessageDescriptor,
+        };
+        unsafe {
+            descriptor.get(|| {
+                let mut fields = ::std::vec::Vec::new();
+                fields.push(::protobuf::reflect::accessor::make_option_accessor::<_, ::protobuf::types::ProtobufTypeInt64>(
+                    "pid",
+                    SendSignal::get_pid_for_reflect,
+                    SendSignal::mut_pid_for_reflect,
+                ));
+                fields.push(::protobuf::reflect::accessor::make_option_accessor::<_, ::protobuf::types::ProtobufTypeEnum<Signal>>(
+                    "signal",
+                    SendSignal::get_signal_for_reflect,
+                    SendSignal::mut_signal_for_reflect,
+                ));
+                ::protobuf::reflect::MessageDescriptor::new::<SendSignal>(
+                    "SendSignal",
+                    fields,
+                    file_descriptor_proto()
+                )
+            })
+        }
+    }
+}
+
+impl ::protobuf::Clear for SendSignal {
+    fn clear(&mut self) {
+        self.clear_pid();
+        self.clear_signal();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for SendSignal {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for SendSignal {
+    fn as_ref(&self) -> ::protobuf::reflect::ProtobufValueRef {
+        ::protobuf::reflect::ProtobufValueRef::Message(self)
+    }
+}
+
 #[derive(Clone,PartialEq,Eq,Debug,Hash)]
 pub enum ShutdownMethod {
     AlreadyExited = 0,
@@ -1559,40 +2357,119 @@ impl ::protobuf::reflect::ProtobufValue for ShutdownMethod {
     }
 }
 
+#[derive(Clone,PartialEq,Eq,Debug,Hash)]
+pub enum Signal {
+    TERM = 1,
+    INT = 2,
+    QUIT = 3,
+    HUP = 4,
+    USR1 = 5,
+    USR2 = 6,
+    KILL = 7,
+}
+
+impl ::protobuf::ProtobufEnum for Signal {
+    fn value(&self) -> i32 {
+        *self as i32
+    }
+
+    fn from_i32(value: i32) -> ::std::option::Option<Signal> {
+        match value {
+            1 => ::std::option::Option::Some(Signal::TERM),
+            2 => ::std::option::Option::Some(Signal::INT),
+            3 => ::std::option::Option::Some(Signal::QUIT),
+            4 => ::std::option::Option::Some(Signal::HUP),
+            5 => ::std::option::Option::Some(Signal::USR1),
+            6 => ::std::option::Option::Some(Signal::USR2),
+            7 => ::std::option::Option::Some(Signal::KILL),
+            _ => ::std::option::Option::None
+        }
+    }
+
+    fn values() -> &'static [Self] {
+        static values: &'static [Signal] = &[
+            Signal::TERM,
+            Signal::INT,
+            Signal::QUIT,
+            Signal::HUP,
+            Signal::USR1,
+            Signal::USR2,
+            Signal::KILL,
+        ];
+        values
+    }
+
+    fn enum_descriptor_static(_: ::std::option::Option<Signal>) -> &'static ::protobuf::reflect::EnumDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::EnumDescriptor> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const ::protobuf::reflect::EnumDescriptor,
+        };
+        unsafe {
+            descriptor.get(|| {
+                ::protobuf::reflect::EnumDescriptor::new("Signal", file_descriptor_proto())
+            })
+        }
+    }
+}
+
+impl ::std::marker::Copy for Signal {
+}
+
+impl ::protobuf::reflect::ProtobufValue for Signal {
+    fn as_ref(&self) -> ::protobuf::reflect::ProtobufValueRef {
+        ::protobuf::reflect::ProtobufValueRef::Enum(self.descriptor())
+    }
+}
+
 static file_descriptor_proto_data: &'static [u8] = b"\
     \n\x18protocols/launcher.proto\x12\x08launcher\"\x1e\n\x08Register\x12\
     \x12\n\x04pipe\x18\x01\x20\x01(\tR\x04pipe\"\x1b\n\x07Restart\x12\x10\n\
-    \x03pid\x18\x01\x20\x01(\x03R\x03pid\"\xb0\x02\n\x05Spawn\x12\x0e\n\x02i\
+    \x03pid\x18\x01\x20\x01(\x03R\x03pid\"\x94\x05\n\x05Spawn\x12\x0e\n\x02i\
     d\x18\x01\x20\x01(\tR\x02id\x12\x16\n\x06binary\x18\x02\x20\x01(\tR\x06b\
     inary\x12\x19\n\x08svc_user\x18\x03\x20\x01(\tR\x07svcUser\x12\x1b\n\tsv\
     c_group\x18\x04\x20\x01(\tR\x08svcGroup\x12!\n\x0csvc_password\x18\x05\
     \x20\x01(\tR\x0bsvcPassword\x12*\n\x03env\x18\x06\x20\x03(\x0b2\x18.laun\
     cher.Spawn.EnvEntryR\x03env\x12\x1e\n\x0bsvc_user_id\x18\x07\x20\x01(\rR\
     \tsvcUserId\x12\x20\n\x0csvc_group_id\x18\x08\x20\x01(\rR\nsvcGroupId\
-    \x1a6\n\x08EnvEntry\x12\x10\n\x03key\x18\x01\x20\x01(\tR\x03key\x12\x14\
-    \n\x05value\x18\x02\x20\x01(\tR\x05value:\x028\x01\"\x1b\n\x07SpawnOk\
-    \x12\x10\n\x03pid\x18\x01\x20\x01(\x03R\x03pid\"\x1d\n\tTerminate\x12\
-    \x10\n\x03pid\x18\x01\x20\x01(\x03R\x03pid\"m\n\x0bTerminateOk\x12\x1b\n\
-    \texit_code\x18\x01\x20\x01(\x05R\x08exitCode\x12A\n\x0fshutdown_method\
-    \x18\x02\x20\x01(\x0e2\x18.launcher.ShutdownMethodR\x0eshutdownMethod*H\
-    \n\x0eShutdownMethod\x12\x11\n\rAlreadyExited\x10\0\x12\x17\n\x13Gracefu\
-    lTermination\x10\x01\x12\n\n\x06Killed\x10\x02J\x86\n\n\x06\x12\x04\0\0(\
-    \x01\n\x08\n\x01\x0c\x12\x03\0\0\x12\n\x08\n\x01\x02\x12\x03\x02\x08\x10\
-    \n\n\n\x02\x04\0\x12\x04\x04\0\x06\x01\n\n\n\x03\x04\0\x01\x12\x03\x04\
-    \x08\x10\n\x0b\n\x04\x04\0\x02\0\x12\x03\x05\x02\x1b\n\x0c\n\x05\x04\0\
-    \x02\0\x04\x12\x03\x05\x02\n\n\x0c\n\x05\x04\0\x02\0\x05\x12\x03\x05\x0b\
-    \x11\n\x0c\n\x05\x04\0\x02\0\x01\x12\x03\x05\x12\x16\n\x0c\n\x05\x04\0\
-    \x02\0\x03\x12\x03\x05\x19\x1a\n\n\n\x02\x04\x01\x12\x04\x08\0\n\x01\n\n\
-    \n\x03\x04\x01\x01\x12\x03\x08\x08\x0f\n\x0b\n\x04\x04\x01\x02\0\x12\x03\
-    \t\x02\x19\n\x0c\n\x05\x04\x01\x02\0\x04\x12\x03\t\x02\n\n\x0c\n\x05\x04\
-    \x01\x02\0\x05\x12\x03\t\x0b\x10\n\x0c\n\x05\x04\x01\x02\0\x01\x12\x03\t\
-    \x11\x14\n\x0c\n\x05\x04\x01\x02\0\x03\x12\x03\t\x17\x18\n\n\n\x02\x04\
-    \x02\x12\x04\x0c\0\x15\x01\n\n\n\x03\x04\x02\x01\x12\x03\x0c\x08\r\n\x0b\
-    \n\x04\x04\x02\x02\0\x12\x03\r\x02\x19\n\x0c\n\x05\x04\x02\x02\0\x04\x12\
-    \x03\r\x02\n\n\x0c\n\x05\x04\x02\x02\0\x05\x12\x03\r\x0b\x11\n\x0c\n\x05\
-    \x04\x02\x02\0\x01\x12\x03\r\x12\x14\n\x0c\n\x05\x04\x02\x02\0\x03\x12\
-    \x03\r\x17\x18\n\x0b\n\x04\x04\x02\x02\x01\x12\x03\x0e\x02\x1d\n\x0c\n\
-    \x05\x04\x02\x02\x01\x04\x12\x03\x0e\x02\n\n\x0c\n\x05\x04\x02\x02\x01\
+    \x12\x1d\n\nstdout_log\x18\t\x20\x01(\tR\tstdoutLog\x12\x1d\n\nstderr_lo\
+    g\x18\n\x20\x01(\tR\tstderrLog\x12\x20\n\x0clog_max_size\x18\x0b\x20\x01\
+    (\x04R\nlogMaxSize\x12\x1e\n\x0blog_max_age\x18\x0c\x20\x01(\x04R\tlogMa\
+    xAge\x12\x1d\n\nlog_retain\x18\r\x20\x01(\rR\tlogRetain\x12\x1b\n\tcpu_l\
+    imit\x18\x0e\x20\x01(\rR\x08cpuLimit\x12!\n\x0cmemory_limit\x18\x0f\x20\
+    \x01(\x04R\x0bmemoryLimit\x12\x1d\n\npids_limit\x18\x10\x20\x01(\x04R\tp\
+    idsLimit\x129\n\x0fshutdown_signal\x18\x11\x20\x01(\x0e2\x10.launcher.Si\
+    gnalR\x0eshutdownSignal\x12)\n\x10shutdown_timeout\x18\x12\x20\x01(\rR\
+    \x0fshutdownTimeout\x1a6\n\x08EnvEntry\x12\x10\n\x03key\x18\x01\x20\x01(\
+    \tR\x03key\x12\x14\n\x05value\x18\x02\x20\x01(\tR\x05value:\x028\x01\"\
+    \x1b\n\x07SpawnOk\x12\x10\n\x03pid\x18\x01\x20\x01(\x03R\x03pid\"\x83\
+    \x01\n\tTerminate\x12\x10\n\x03pid\x18\x01\x20\x01(\x03R\x03pid\x129\n\
+    \x0fshutdown_signal\x18\x02\x20\x01(\x0e2\x10.launcher.SignalR\x0eshutdo\
+    wnSignal\x12)\n\x10shutdown_timeout\x18\x03\x20\x01(\rR\x0fshutdownTimeo\
+    ut\"m\n\x0bTerminateOk\x12\x1b\n\texit_code\x18\x01\x20\x01(\x05R\x08exi\
+    tCode\x12A\n\x0fshutdown_method\x18\x02\x20\x01(\x0e2\x18.launcher.Shutd\
+    ownMethodR\x0eshutdownMethod\"H\n\nSendSignal\x12\x10\n\x03pid\x18\x01\
+    \x20\x01(\x03R\x03pid\x12(\n\x06signal\x18\x02\x20\x01(\x0e2\x10.launche\
+    r.SignalR\x06signal*H\n\x0eShutdownMethod\x12\x11\n\rAlreadyExited\x10\0\
+    \x12\x17\n\x13GracefulTermination\x10\x01\x12\n\n\x06Killed\x10\x02*L\n\
+    \x06Signal\x12\x08\n\x04TERM\x10\x01\x12\x07\n\x03INT\x10\x02\x12\x08\n\
+    \x04QUIT\x10\x03\x12\x07\n\x03HUP\x10\x04\x12\x08\n\x04USR1\x10\x05\x12\
+    \x08\n\x04USR2\x10\x06\x12\x08\n\x04KILL\x10\x07J\x86\n\n\x06\x12\x04\0\
+    \0(\x01\n\x08\n\x01\x0c\x12\x03\0\0\x12\n\x08\n\x01\x02\x12\x03\x02\x08\
+    \x10\n\n\n\x02\x04\0\x12\x04\x04\0\x06\x01\n\n\n\x03\x04\0\x01\x12\x03\
+    \x04\x08\x10\n\x0b\n\x04\x04\0\x02\0\x12\x03\x05\x02\x1b\n\x0c\n\x05\x04\
+    \0\x02\0\x04\x12\x03\x05\x02\n\n\x0c\n\x05\x04\0\x02\0\x05\x12\x03\x05\
+    \x0b\x11\n\x0c\n\x05\x04\0\x02\0\x01\x12\x03\x05\x12\x16\n\x0c\n\x05\x04\
+    \0\x02\0\x03\x12\x03\x05\x19\x1a\n\n\n\x02\x04\x01\x12\x04\x08\0\n\x01\n\
+    \n\n\x03\x04\x01\x01\x12\x03\x08\x08\x0f\n\x0b\n\x04\x04\x01\x02\0\x12\
+    \x03\t\x02\x19\n\x0c\n\x05\x04\x01\x02\0\x04\x12\x03\t\x02\n\n\x0c\n\x05\
+    \x04\x01\x02\0\x05\x12\x03\t\x0b\x10\n\x0c\n\x05\x04\x01\x02\0\x01\x12\
+    \x03\t\x11\x14\n\x0c\n\x05\x04\x01\x02\0\x03\x12\x03\t\x17\x18\n\n\n\x02\
+    \x04\x02\x12\x04\x0c\0\x15\x01\n\n\n\x03\x04\x02\x01\x12\x03\x0c\x08\r\n\
+    \x0b\n\x04\x04\x02\x02\0\x12\x03\r\x02\x19\n\x0c\n\x05\x04\x02\x02\0\x04\
+    \x12\x03\r\x02\n\n\x0c\n\x05\x04\x02\x02\0\x05\x12\x03\r\x0b\x11\n\x0c\n\
+    \x05\x04\x02\x02\0\x01\x12\x03\r\x12\x14\n\x0c\n\x05\x04\x02\x02\0\x03\
+    \x12\x03\r\x17\x18\n\x0b\n\x04\x04\x02\x02\x01\x12\x03\x0e\x02\x1d\n\x0c\
+    \n\x05\x04\x02\x02\x01\x04\x12\x03\x0e\x02\n\n\x0c\n\x05\x04\x02\x02\x01\
     \x05\x12\x03\x0e\x0b\x11\n\x0c\n\x05\x04\x02\x02\x01\x01\x12\x03\x0e\x12\
     \x18\n\x0c\n\x05\x04\x02\x02\x01\x03\x12\x03\x0e\x1b\x1c\n\x0b\n\x04\x04\
     \x02\x02\x02\x12\x03\x0f\x02\x1f\n\x0c\n\x05\x04\x02\x02\x02\x04\x12\x03\
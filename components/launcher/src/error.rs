@@ -33,7 +33,9 @@ pub enum Error {
     OpenPipe(io::Error),
     Send(ipc_channel::Error),
     Serialize(protobuf::ProtobufError),
+    Signal(String),
     Spawn(io::Error),
+    Stopping(String, u32),
     SupBinaryVersion,
     SupBinaryNotFound,
     SupPackageNotFound,
@@ -59,7 +61,12 @@ impl fmt::Display for Error {
             Error::OpenPipe(ref e) => format!("Unable to open Launcher's comm channel, {}", e),
             Error::Send(ref e) => format!("Unable to send to Launcher's comm channel, {}", e),
             Error::Serialize(ref e) => format!("Unable to serialize message to Supervisor, {}", e),
+            Error::Signal(ref e) => format!("Unable to signal process, {}", e),
             Error::Spawn(ref e) => format!("Unable to spawn process, {}", e),
+            Error::Stopping(ref id, pid) => format!(
+                "Unable to spawn {} while its previous process, {}, is stopping",
+                id, pid
+            ),
             Error::SupBinaryVersion => format!("Unsupported Supervisor binary version"),
             Error::SupBinaryNotFound => {
                 format!("Supervisor package didn't contain '{}' binary", SUP_CMD)
@@ -86,7 +93,9 @@ impl error::Error for Error {
             Error::OpenPipe(_) => "Unable to open Launcher's pipe",
             Error::Send(_) => "Unable to send to Launcher's pipe",
             Error::Serialize(_) => "Unable to serialize message to Supervisor",
+            Error::Signal(_) => "Unable to signal process",
             Error::Spawn(_) => "Unable to spawn process",
+            Error::Stopping(_, _) => "Previous process of the service is stopping",
             Error::SupBinaryVersion => "Unsupported Supervisor binary version",
             Error::SupBinaryNotFound => "Unable to locate Supervisor binary in package",
             Error::SupPackageNotFound => "Unable to locate Supervisor package on disk",
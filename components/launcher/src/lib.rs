@@ -22,11 +22,18 @@ extern crate libc;
 extern crate log;
 extern crate protobuf;
 extern crate semver;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json;
+#[cfg(test)]
+extern crate tempfile;
 extern crate time;
 #[cfg(windows)]
 extern crate winapi;
 
 pub mod error;
+mod log_file;
 pub mod server;
 pub mod service;
 mod sys;
@@ -0,0 +1,258 @@
+// Copyright (c) 2018 Chef Software Inc. and/or applicable contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Log files capturing a service's output.
+//!
+//! When a `Spawn` message names log files for a service, each line the service writes to its
+//! standard output or error is appended to the matching file. Files are rotated once they reach
+//! their maximum size or age by renaming them to `<file>.1`, shifting older rotations along to
+//! `<file>.2` and so on, and removing any beyond the number to retain.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use protocol;
+
+/// When a log file is rotated and how many rotated files are kept.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Rotation {
+    /// Bytes the file may grow to before it is rotated; `0` disables size-based rotation.
+    pub max_size: u64,
+    /// How long the file is written to before it is rotated; `None` disables time-based
+    /// rotation.
+    pub max_age: Option<Duration>,
+    /// Rotated files kept, the oldest being removed first.
+    pub retain: u32,
+}
+
+impl<'a> From<&'a protocol::Spawn> for Rotation {
+    fn from(spawn: &'a protocol::Spawn) -> Self {
+        Rotation {
+            max_size: spawn.get_log_max_size(),
+            max_age: match spawn.get_log_max_age() {
+                0 => None,
+                secs => Some(Duration::from_secs(secs)),
+            },
+            retain: spawn.get_log_retain(),
+        }
+    }
+}
+
+pub struct LogFile {
+    path: PathBuf,
+    rotation: Rotation,
+    file: File,
+    size: u64,
+    /// When the file was created, which its age is measured from so that reopening it, as
+    /// happens whenever the service restarts, doesn't put off its rotation.
+    created: SystemTime,
+}
+
+impl LogFile {
+    /// Open the log file at the given path for appending, creating it and its parent directory
+    /// if they don't exist.
+    pub fn open<P>(path: P, rotation: Rotation) -> io::Result<Self>
+    where
+        P: Into<PathBuf>,
+    {
+        let path = path.into();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = Self::append(&path)?;
+        let metadata = file.metadata()?;
+        // Not every platform and filesystem records when a file was created, in which case the
+        // last time it was written to is the best estimate available.
+        let created = metadata
+            .created()
+            .or_else(|_| metadata.modified())
+            .unwrap_or_else(|_| SystemTime::now());
+        Ok(LogFile {
+            path: path,
+            rotation: rotation,
+            file: file,
+            size: metadata.len(),
+            created: created,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append a line of output, rotating the file first if the line would take it over its
+    /// maximum size or the file has reached its maximum age.
+    pub fn write_line(&mut self, line: &str) -> io::Result<()> {
+        let len = if line.ends_with('\n') {
+            line.len()
+        } else {
+            line.len() + 1
+        };
+        if self.needs_rotation(len as u64) {
+            self.rotate()?;
+        }
+        self.file.write_all(line.as_bytes())?;
+        if !line.ends_with('\n') {
+            self.file.write_all(b"\n")?;
+        }
+        self.size += len as u64;
+        Ok(())
+    }
+
+    fn needs_rotation(&self, incoming: u64) -> bool {
+        if self.size == 0 {
+            return false;
+        }
+        let too_big = self.rotation.max_size > 0 && self.size + incoming > self.rotation.max_size;
+        let too_old = self.rotation.max_age.map_or(false, |age| self.age() >= age);
+        too_big || too_old
+    }
+
+    fn age(&self) -> Duration {
+        SystemTime::now()
+            .duration_since(self.created)
+            .unwrap_or(Duration::from_secs(0))
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        if self.rotation.retain == 0 {
+            remove_if_exists(&self.path)?;
+        } else {
+            remove_if_exists(&self.rotated_path(self.rotation.retain))?;
+            for n in (1..self.rotation.retain).rev() {
+                let from = self.rotated_path(n);
+                if from.exists() {
+                    fs::rename(&from, self.rotated_path(n + 1))?;
+                }
+            }
+            fs::rename(&self.path, self.rotated_path(1))?;
+        }
+        self.file = Self::append(&self.path)?;
+        self.size = 0;
+        self.created = SystemTime::now();
+        Ok(())
+    }
+
+    fn rotated_path(&self, n: u32) -> PathBuf {
+        let mut name = self.path.as_os_str().to_os_string();
+        name.push(format!(".{}", n));
+        PathBuf::from(name)
+    }
+
+    fn append(path: &Path) -> io::Result<File> {
+        OpenOptions::new().create(true).append(true).open(path)
+    }
+}
+
+fn remove_if_exists(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(ref err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Read;
+    use std::thread;
+
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn contents(path: &Path) -> String {
+        let mut s = String::new();
+        File::open(path).unwrap().read_to_string(&mut s).unwrap();
+        s
+    }
+
+    #[test]
+    fn rotates_by_size_and_keeps_retained_files() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("logs").join("run.stdout.log");
+        let rotation = Rotation {
+            max_size: 10,
+            max_age: None,
+            retain: 2,
+        };
+        let mut log = LogFile::open(&path, rotation).unwrap();
+        for line in &["one", "two", "three", "four", "five", "six"] {
+            log.write_line(line).unwrap();
+        }
+
+        assert_eq!(contents(&path), "six\n");
+        assert_eq!(contents(&log.rotated_path(1)), "four\nfive\n");
+        assert_eq!(contents(&log.rotated_path(2)), "three\n");
+        assert!(!log.rotated_path(3).exists());
+    }
+
+    #[test]
+    fn rotates_by_age() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("run.stderr.log");
+        let rotation = Rotation {
+            max_size: 0,
+            max_age: Some(Duration::from_millis(10)),
+            retain: 1,
+        };
+        let mut log = LogFile::open(&path, rotation).unwrap();
+        log.write_line("before\n").unwrap();
+        thread::sleep(Duration::from_millis(20));
+        log.write_line("after\n").unwrap();
+
+        assert_eq!(contents(&path), "after\n");
+        assert_eq!(contents(&log.rotated_path(1)), "before\n");
+    }
+
+    #[test]
+    fn age_of_reopened_file_is_kept() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("run.stderr.log");
+        let rotation = Rotation {
+            max_size: 0,
+            max_age: Some(Duration::from_millis(50)),
+            retain: 1,
+        };
+        LogFile::open(&path, rotation)
+            .unwrap()
+            .write_line("before")
+            .unwrap();
+        thread::sleep(Duration::from_millis(100));
+        LogFile::open(&path, rotation)
+            .unwrap()
+            .write_line("after")
+            .unwrap();
+
+        assert_eq!(contents(&path), "after\n");
+        assert_eq!(contents(&path.with_extension("log.1")), "before\n");
+    }
+
+    #[test]
+    fn appends_to_existing_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("run.stdout.log");
+        LogFile::open(&path, Rotation::default())
+            .unwrap()
+            .write_line("first")
+            .unwrap();
+        LogFile::open(&path, Rotation::default())
+            .unwrap()
+            .write_line("second")
+            .unwrap();
+
+        assert_eq!(contents(&path), "first\nsecond\n");
+    }
+}
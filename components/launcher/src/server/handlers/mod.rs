@@ -13,10 +13,12 @@
 // limitations under the License.
 
 mod restart;
+mod send_signal;
 mod spawn;
 mod terminate;
 
 pub use self::restart::*;
+pub use self::send_signal::*;
 pub use self::spawn::*;
 pub use self::terminate::*;
 
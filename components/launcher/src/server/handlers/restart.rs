@@ -14,39 +14,32 @@
 
 use protocol;
 
-use super::{HandleResult, Handler};
-use server::ServiceTable;
-use service;
+use server::{reply, Sender, ServiceTable};
 
+/// Restarts a service without blocking the Launcher: the service is asked to stop, and is spawned
+/// again by `ServiceTable::reap_services` once it has exited, which answers the request.
 pub struct RestartHandler;
-impl Handler for RestartHandler {
-    type Message = protocol::Restart;
-    type Reply = protocol::SpawnOk;
-
-    fn handle(msg: Self::Message, services: &mut ServiceTable) -> HandleResult<Self::Reply> {
-        let mut service = match services.remove(msg.get_pid() as u32) {
-            Some(service) => service,
-            None => {
-                let mut reply = protocol::NetErr::new();
-                reply.set_code(protocol::ErrCode::NoPID);
-                return Err(reply);
+impl RestartHandler {
+    pub fn run(tx: &Sender, txn: protocol::NetTxn, services: &mut ServiceTable) {
+        let msg = match txn.decode::<protocol::Restart>() {
+            Ok(msg) => msg,
+            Err(err) => {
+                error!("{}: decoding, {}", txn.message_id(), err);
+                return;
             }
         };
-        service.kill();
-        match service.wait() {
-            Ok(_status) => match service::run(service.take_args()) {
-                Ok(new_service) => {
-                    let mut reply = protocol::SpawnOk::new();
-                    reply.set_pid(new_service.id().into());
-                    services.insert(new_service);
-                    Ok(reply)
+        trace!("{}, {:?}, {:?}", txn.message_id(), msg, services);
+        match services.get_mut(msg.get_pid() as u32) {
+            Some(service) => {
+                debug!("Restarting: {}", service.id());
+                service.restart(txn);
+            }
+            None => {
+                let mut err = protocol::NetErr::new();
+                err.set_code(protocol::ErrCode::NoPID);
+                if let Err(err) = reply(tx, &txn, &err) {
+                    error!("{}: replying, {}", txn.message_id(), err);
                 }
-                Err(err) => Err(protocol::error(err)),
-            },
-            Err(_) => {
-                let mut reply = protocol::NetErr::new();
-                reply.set_code(protocol::ErrCode::ExecWait);
-                Err(reply)
             }
         }
     }
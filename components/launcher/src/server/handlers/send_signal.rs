@@ -0,0 +1,41 @@
+// Copyright (c) 2018 Chef Software Inc. and/or applicable contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use protocol;
+
+use super::{HandleResult, Handler};
+use server::ServiceTable;
+
+pub struct SendSignalHandler;
+impl Handler for SendSignalHandler {
+    type Message = protocol::SendSignal;
+    type Reply = protocol::NetOk;
+
+    fn handle(msg: Self::Message, services: &mut ServiceTable) -> HandleResult<Self::Reply> {
+        match services.get(msg.get_pid() as u32) {
+            Some(service) => {
+                debug!("Signaling: {}, {:?}", service.id(), msg.get_signal());
+                match service.signal(msg.get_signal()) {
+                    Ok(()) => Ok(protocol::NetOk::new()),
+                    Err(err) => Err(protocol::error(err)),
+                }
+            }
+            None => {
+                let mut reply = protocol::NetErr::new();
+                reply.set_code(protocol::ErrCode::NoPID);
+                Err(reply)
+            }
+        }
+    }
+}
@@ -15,6 +15,7 @@
 use protocol;
 
 use super::{HandleResult, Handler};
+use error::Error;
 use server::ServiceTable;
 use service;
 
@@ -24,6 +25,13 @@ impl Handler for SpawnHandler {
     type Reply = protocol::SpawnOk;
 
     fn handle(msg: Self::Message, services: &mut ServiceTable) -> HandleResult<Self::Reply> {
+        // A service's next process isn't started while its previous one may still be running.
+        if let Some(pid) = services.stopping(msg.get_id()) {
+            return Err(protocol::error(Error::Stopping(
+                msg.get_id().to_string(),
+                pid,
+            )));
+        }
         match service::run(msg) {
             Ok(service) => {
                 let mut reply = protocol::SpawnOk::new();
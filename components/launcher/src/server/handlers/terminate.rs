@@ -26,20 +26,10 @@ impl Handler for TerminateHandler {
         match services.get_mut(msg.get_pid() as u32) {
             Some(service) => {
                 debug!("Terminating: {}", service.id());
-                let shutdown_method = service.kill();
-                match service.wait() {
-                    Ok(status) => {
-                        let mut reply = protocol::TerminateOk::new();
-                        reply.set_exit_code(status.code().unwrap_or(0));
-                        reply.set_shutdown_method(shutdown_method);
-                        Ok(reply)
-                    }
-                    Err(_) => {
-                        let mut reply = protocol::NetErr::new();
-                        reply.set_code(protocol::ErrCode::ExecWait);
-                        Err(reply)
-                    }
-                }
+                let shutdown = service.shutdown().with_overrides(&msg);
+                let mut reply = protocol::TerminateOk::new();
+                reply.set_shutdown_method(service.terminate(&shutdown));
+                Ok(reply)
             }
             None => {
                 let mut reply = protocol::NetErr::new();
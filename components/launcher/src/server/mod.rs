@@ -43,7 +43,7 @@ use semver::{Version, VersionReq};
 
 use self::handlers::Handler;
 use error::{Error, Result};
-use service::Service;
+use service::{self, Service};
 use {SUP_CMD, SUP_PACKAGE_IDENT};
 
 const IPC_CONNECT_TIMEOUT_SECS: &'static str = "HAB_LAUNCH_SUP_CONNECT_TIMEOUT_SECS";
@@ -193,7 +193,7 @@ impl Server {
     }
 
     fn reap_services(&mut self) {
-        self.services.reap_services()
+        self.services.reap_services(&self.tx)
     }
 
     fn shutdown(&mut self) {
@@ -371,6 +371,14 @@ impl ServiceTable {
         self.0.remove(&pid)
     }
 
+    /// The pid of the process spawned as `id` which has been asked to stop but hasn't exited.
+    pub fn stopping(&self, id: &str) -> Option<u32> {
+        self.0
+            .values()
+            .find(|service| service.name() == id && service.is_stopping())
+            .map(Service::id)
+    }
+
     fn kill_all(&mut self) {
         for service in self.0.values_mut() {
             outputln!(preamble service.name(), "Stopping...");
@@ -379,11 +387,11 @@ impl ServiceTable {
         }
     }
 
-    fn reap_services(&mut self) {
+    fn reap_services(&mut self, tx: &Sender) {
         let mut dead: Vec<u32> = vec![];
         for service in self.0.values_mut() {
             match service.try_wait() {
-                Ok(None) => (),
+                Ok(None) => service.kill_if_overdue(),
                 Ok(Some(code)) => {
                     outputln!(
                         "Child for service '{}' with PID {} exited with code {}",
@@ -400,7 +408,28 @@ impl ServiceTable {
             }
         }
         for pid in dead {
-            self.0.remove(&pid);
+            if let Some(mut service) = self.0.remove(&pid) {
+                if let Some(txn) = service.take_restart() {
+                    self.respawn(tx, &txn, service.take_args());
+                }
+            }
+        }
+    }
+
+    /// Spawn a restarted service again now its previous process has exited, and answer the
+    /// `Restart` request with the new pid.
+    fn respawn(&mut self, tx: &Sender, txn: &protocol::NetTxn, args: protocol::Spawn) {
+        let result = match service::run(args) {
+            Ok(service) => {
+                let mut spawned = protocol::SpawnOk::new();
+                spawned.set_pid(service.id().into());
+                self.insert(service);
+                reply(tx, txn, &spawned)
+            }
+            Err(err) => reply(tx, txn, &protocol::error(err)),
+        };
+        if let Err(err) = result {
+            error!("{}: replying, {}", txn.message_id(), err);
         }
     }
 }
@@ -464,6 +493,7 @@ fn dispatch(tx: &Sender, bytes: &[u8], services: &mut ServiceTable) {
     };
     let func = match msg.message_id() {
         "Restart" => handlers::RestartHandler::run,
+        "SendSignal" => handlers::SendSignalHandler::run,
         "Spawn" => handlers::SpawnHandler::run,
         "Terminate" => handlers::TerminateHandler::run,
         unknown => {
@@ -602,6 +632,9 @@ fn spawn_supervisor(pipe: &str, args: &[String], clean: bool) -> Result<Child> {
         .env(
             protocol::LAUNCHER_PID_ENV,
             process::current_pid().to_string(),
+        ).env(
+            protocol::LAUNCHER_PROTOCOL_VERSION_ENV,
+            protocol::PROTOCOL_VERSION.to_string(),
         ).args(args)
         .spawn()
         .map_err(Error::SupSpawn)?;
@@ -17,16 +17,63 @@ use std::io::{self, BufRead, BufReader, Read, Write};
 #[cfg(unix)]
 use std::process::{ChildStderr, ChildStdout, ExitStatus};
 use std::thread;
+use std::time::{Duration, Instant};
 
+use core::output;
 #[cfg(windows)]
 use core::os::process::windows_child::{ChildStderr, ChildStdout, ExitStatus};
 use protocol;
+use serde_json;
+use time;
 
+use error::Result;
+use log_file::{LogFile, Rotation};
 pub use sys::service::*;
 
+/// Seconds a service is given to stop before it is killed, when not given in its `Spawn` message.
+const DEFAULT_SHUTDOWN_TIMEOUT: u64 = 8;
+
+/// How a service is asked to stop, and how long it is given to do so before it is killed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Shutdown {
+    pub signal: protocol::Signal,
+    pub timeout: Duration,
+}
+
+impl Shutdown {
+    /// Override the signal and timeout with any given in a `Terminate` message.
+    pub fn with_overrides(mut self, msg: &protocol::Terminate) -> Self {
+        if msg.has_shutdown_signal() {
+            self.signal = msg.get_shutdown_signal();
+        }
+        if msg.get_shutdown_timeout() > 0 {
+            self.timeout = Duration::from_secs(msg.get_shutdown_timeout().into());
+        }
+        self
+    }
+}
+
+impl<'a> From<&'a protocol::Spawn> for Shutdown {
+    fn from(spawn: &'a protocol::Spawn) -> Self {
+        Shutdown {
+            signal: spawn.get_shutdown_signal(),
+            timeout: match spawn.get_shutdown_timeout() {
+                0 => Duration::from_secs(DEFAULT_SHUTDOWN_TIMEOUT),
+                secs => Duration::from_secs(secs.into()),
+            },
+        }
+    }
+}
+
 pub struct Service {
     args: protocol::Spawn,
     process: Process,
+    /// Set once the service has been asked to stop: when it is killed if it has not exited.
+    kill_at: Option<Instant>,
+    /// Whether the service has been asked to stop.
+    stopping: bool,
+    /// A `Restart` request, answered once the service has exited and been spawned again.
+    restart: Option<protocol::NetTxn>,
 }
 
 impl Service {
@@ -36,23 +83,29 @@ impl Service {
         stdout: Option<ChildStdout>,
         stderr: Option<ChildStderr>,
     ) -> Self {
+        let pid = process.id();
         if let Some(stdout) = stdout {
             let id = spawn.get_id().to_string();
+            let log = open_log(spawn.get_stdout_log(), &spawn);
             thread::Builder::new()
                 .name(format!("{}-out", spawn.get_id()))
-                .spawn(move || pipe_stdout(stdout, id))
+                .spawn(move || pipe_stdout(stdout, id, pid, log))
                 .ok();
         }
         if let Some(stderr) = stderr {
             let id = spawn.get_id().to_string();
+            let log = open_log(spawn.get_stderr_log(), &spawn);
             thread::Builder::new()
                 .name(format!("{}-err", spawn.get_id()))
-                .spawn(move || pipe_stderr(stderr, id))
+                .spawn(move || pipe_stderr(stderr, id, pid, log))
                 .ok();
         }
         Service {
             args: spawn,
             process: process,
+            kill_at: None,
+            stopping: false,
+            restart: None,
         }
     }
 
@@ -64,16 +117,67 @@ impl Service {
         self.process.id()
     }
 
-    /// Attempt to gracefully terminate a proccess and then forcefully kill it after
-    /// 8 seconds if it has not terminated.
+    /// Attempt to gracefully terminate a proccess and then forcefully kill it if it has not
+    /// terminated within the service's shutdown timeout.
     pub fn kill(&mut self) -> protocol::ShutdownMethod {
-        self.process.kill()
+        let shutdown = self.shutdown();
+        self.process.kill(&shutdown)
+    }
+
+    /// Ask the service to stop as `shutdown` describes, without waiting for it to. It is killed
+    /// by `kill_if_overdue` if it has not exited within the shutdown timeout.
+    pub fn terminate(&mut self, shutdown: &Shutdown) -> protocol::ShutdownMethod {
+        let shutdown_method = self.process.stop(shutdown.signal);
+        self.stopping = true;
+        if shutdown_method == protocol::ShutdownMethod::GracefulTermination {
+            self.kill_at = Some(Instant::now() + shutdown.timeout);
+        }
+        shutdown_method
+    }
+
+    /// Forcefully kill the service if it was asked to stop and its shutdown timeout has passed.
+    pub fn kill_if_overdue(&mut self) {
+        match self.kill_at {
+            Some(kill_at) if Instant::now() >= kill_at => {
+                self.kill_at = None;
+                let shutdown_method = self.process.force_kill();
+                outputln!(preamble self.name(), "Shutdown OK: {}", shutdown_method);
+            }
+            _ => (),
+        }
+    }
+
+    /// Whether the service has been asked to stop by `terminate`.
+    pub fn is_stopping(&self) -> bool {
+        self.stopping
+    }
+
+    /// Ask the service to stop, and spawn it again with the same arguments once it has exited.
+    /// `txn` is the `Restart` request, answered with the new process' pid.
+    pub fn restart(&mut self, txn: protocol::NetTxn) -> protocol::ShutdownMethod {
+        let shutdown = self.shutdown();
+        self.restart = Some(txn);
+        self.terminate(&shutdown)
+    }
+
+    /// The `Restart` request waiting on the service to exit, if any.
+    pub fn take_restart(&mut self) -> Option<protocol::NetTxn> {
+        self.restart.take()
     }
 
     pub fn name(&self) -> &str {
         self.args.get_id()
     }
 
+    pub fn signal(&self, sig: protocol::Signal) -> Result<()> {
+        self.process.signal(sig)
+    }
+
+    /// How the service is stopped, as given when it was spawned.
+    pub fn shutdown(&self) -> Shutdown {
+        Shutdown::from(&self.args)
+    }
+
     pub fn take_args(self) -> protocol::Spawn {
         self.args
     }
@@ -93,30 +197,145 @@ impl fmt::Debug for Service {
     }
 }
 
+/// A line of a service's output, written by the Launcher when `--json-logging` is set. It
+/// carries the same `preamble`, `logkey` and `content` fields as the Supervisor's own JSON output
+/// so that both can be parsed alike.
+#[derive(Serialize)]
+struct OutputRecord<'a> {
+    /// The service group the output came from.
+    preamble: &'a str,
+    /// `O` for standard output and `E` for standard error.
+    logkey: &'a str,
+    pid: u32,
+    timestamp: String,
+    content: &'a str,
+}
+
+/// Format a line of a service's output for the Launcher's own stdout or stderr.
+fn format_line(id: &str, logkey: &str, pid: u32, line: &str) -> String {
+    if output::is_json() {
+        json_record(id, logkey, pid, line)
+    } else {
+        output_format!(preamble id, logkey logkey, line)
+    }
+}
+
+fn json_record(id: &str, logkey: &str, pid: u32, line: &str) -> String {
+    let record = OutputRecord {
+        preamble: id,
+        logkey: logkey,
+        pid: pid,
+        timestamp: time::now_utc().rfc3339().to_string(),
+        content: line.trim_right_matches(|c| c == '\r' || c == '\n'),
+    };
+    serde_json::to_string(&record).expect("OutputRecord is serializable")
+}
+
+/// Open the log file a service's output stream should be captured to, if the Supervisor asked
+/// for one. Output is still written to the Launcher's own stdout and stderr if the file can't
+/// be opened.
+fn open_log(path: &str, spawn: &protocol::Spawn) -> Option<LogFile> {
+    if path.is_empty() {
+        return None;
+    }
+    match LogFile::open(path, Rotation::from(spawn)) {
+        Ok(log) => Some(log),
+        Err(err) => {
+            error!("{}: unable to open log file {}, {}", spawn.get_id(), path, err);
+            None
+        }
+    }
+}
+
+/// Append a line of output to the service's log file, if it has one. The log file is abandoned
+/// after the first failed write rather than reporting an error for every line.
+fn write_log(log: &mut Option<LogFile>, id: &str, line: &str) {
+    let failed = match *log {
+        Some(ref mut file) => match file.write_line(line) {
+            Ok(()) => false,
+            Err(err) => {
+                error!("{}: unable to write to {}, {}", id, file.path().display(), err);
+                true
+            }
+        },
+        None => false,
+    };
+    if failed {
+        *log = None;
+    }
+}
+
 /// Consume output from a child process until EOF, then finish
-fn pipe_stdout<T>(out: T, id: String)
+fn pipe_stdout<T>(out: T, id: String, pid: u32, mut log: Option<LogFile>)
 where
     T: Read,
 {
     let mut reader = BufReader::new(out);
     let mut buffer = String::new();
     while reader.read_line(&mut buffer).unwrap() > 0 {
-        let line = output_format!(preamble &id, logkey "O", buffer);
+        write_log(&mut log, &id, &buffer);
+        let line = format_line(&id, "O", pid, &buffer);
         writeln!(&mut io::stdout(), "{}", line).expect("unable to write to stdout");
         buffer.clear();
     }
 }
 
 /// Consume standard error from a child process until EOF, then finish
-fn pipe_stderr<T>(err: T, id: String)
+fn pipe_stderr<T>(err: T, id: String, pid: u32, mut log: Option<LogFile>)
 where
     T: Read,
 {
     let mut reader = BufReader::new(err);
     let mut buffer = String::new();
     while reader.read_line(&mut buffer).unwrap() > 0 {
-        let line = output_format!(preamble &id, logkey "E", buffer);
+        write_log(&mut log, &id, &buffer);
+        let line = format_line(&id, "E", pid, &buffer);
         writeln!(&mut io::stderr(), "{}", line).expect("unable to write to stderr");
         buffer.clear();
     }
 }
+
+#[cfg(test)]
+mod test {
+    use serde_json::Value;
+
+    use super::*;
+
+    #[test]
+    fn json_record_of_output_line() {
+        let record = json_record("redis.default", "E", 4242, "Ready to accept connections\n");
+        let record: Value = serde_json::from_str(&record).unwrap();
+
+        assert_eq!(record["preamble"], "redis.default");
+        assert_eq!(record["logkey"], "E");
+        assert_eq!(record["pid"], 4242);
+        assert_eq!(record["content"], "Ready to accept connections");
+        let timestamp = record["timestamp"].as_str().unwrap();
+        assert!(time::strptime(timestamp, "%Y-%m-%dT%H:%M:%SZ").is_ok());
+    }
+
+    #[test]
+    fn shutdown_from_spawn_with_terminate_overrides() {
+        let mut spawn = protocol::Spawn::new();
+        let shutdown = Shutdown::from(&spawn);
+        assert_eq!(shutdown.signal, protocol::Signal::TERM);
+        assert_eq!(
+            shutdown.timeout,
+            Duration::from_secs(DEFAULT_SHUTDOWN_TIMEOUT)
+        );
+
+        spawn.set_shutdown_signal(protocol::Signal::INT);
+        spawn.set_shutdown_timeout(300);
+        let shutdown = Shutdown::from(&spawn);
+        assert_eq!(shutdown.signal, protocol::Signal::INT);
+        assert_eq!(shutdown.timeout, Duration::from_secs(300));
+
+        let mut terminate = protocol::Terminate::new();
+        assert_eq!(shutdown.with_overrides(&terminate), shutdown);
+        terminate.set_shutdown_signal(protocol::Signal::QUIT);
+        terminate.set_shutdown_timeout(5);
+        let shutdown = shutdown.with_overrides(&terminate);
+        assert_eq!(shutdown.signal, protocol::Signal::QUIT);
+        assert_eq!(shutdown.timeout, Duration::from_secs(5));
+    }
+}
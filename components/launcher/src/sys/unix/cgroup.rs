@@ -0,0 +1,329 @@
+// Copyright (c) 2018 Chef Software Inc. and/or applicable contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Control groups constraining the resources a service may use.
+//!
+//! Each service with resource limits is placed in its own cgroup, `habitat/<service id>`, under
+//! whichever hierarchy is mounted at `/sys/fs/cgroup`: the unified (v2) hierarchy when it is
+//! present, otherwise the `cpu`, `memory` and `pids` controllers of the v1 hierarchy. The
+//! Supervisor reads the service's resource usage back out of the same cgroup.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::path::{Path, PathBuf};
+
+use libc;
+use protocol;
+
+pub const CGROUP_ROOT: &'static str = "/sys/fs/cgroup";
+
+/// The cgroup, relative to the hierarchy root, under which services' cgroups are created.
+const CGROUP_PARENT: &'static str = "habitat";
+/// Period over which a CPU limit is enforced.
+const CPU_PERIOD_USEC: u64 = 100_000;
+const CONTROLLERS: &'static [&'static str] = &["cpu", "memory", "pids"];
+
+/// Resources a service may use. A value of `0` leaves the resource unlimited.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Limits {
+    /// CPU time, in thousandths of a CPU.
+    pub cpu: u32,
+    /// Bytes of memory.
+    pub memory: u64,
+    /// Number of processes.
+    pub pids: u64,
+}
+
+impl Limits {
+    pub fn is_empty(&self) -> bool {
+        self.cpu == 0 && self.memory == 0 && self.pids == 0
+    }
+
+    /// The CPU quota to allow in each `CPU_PERIOD_USEC`.
+    fn cpu_quota(&self) -> u64 {
+        u64::from(self.cpu) * CPU_PERIOD_USEC / 1000
+    }
+}
+
+impl<'a> From<&'a protocol::Spawn> for Limits {
+    fn from(spawn: &'a protocol::Spawn) -> Self {
+        Limits {
+            cpu: spawn.get_cpu_limit(),
+            memory: spawn.get_memory_limit(),
+            pids: spawn.get_pids_limit(),
+        }
+    }
+}
+
+/// A service's cgroup, removed again when dropped.
+#[derive(Debug)]
+pub struct Cgroup {
+    /// The cgroup's directory in each hierarchy it was created in: just the one for v2, one per
+    /// controller for v1.
+    dirs: Vec<PathBuf>,
+}
+
+impl Cgroup {
+    /// Create the cgroup for the service with the given id under the hierarchy mounted at
+    /// `root` and apply its limits. Returns `None` if no cgroup hierarchy is mounted there.
+    pub fn create<P>(root: P, id: &str, limits: &Limits) -> io::Result<Option<Self>>
+    where
+        P: AsRef<Path>,
+    {
+        let root = root.as_ref();
+        let controllers = root.join("cgroup.controllers");
+        if controllers.is_file() {
+            Self::create_v2(root, &fs::read_to_string(controllers)?, id, limits).map(Some)
+        } else if CONTROLLERS.iter().any(|c| root.join(c).is_dir()) {
+            Self::create_v1(root, id, limits).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn create_v2(root: &Path, available: &str, id: &str, limits: &Limits) -> io::Result<Self> {
+        let enable = available
+            .split_whitespace()
+            .filter(|c| CONTROLLERS.contains(c))
+            .map(|c| format!("+{}", c))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let parent = root.join(CGROUP_PARENT);
+        fs::create_dir_all(&parent)?;
+        if !enable.is_empty() {
+            write(&root.join("cgroup.subtree_control"), &enable)?;
+            write(&parent.join("cgroup.subtree_control"), &enable)?;
+        }
+        let cgroup = Cgroup {
+            dirs: vec![parent.join(id)],
+        };
+        let dir = &cgroup.dirs[0];
+        fs::create_dir_all(dir)?;
+        if limits.cpu > 0 {
+            let max = format!("{} {}", limits.cpu_quota(), CPU_PERIOD_USEC);
+            write(&dir.join("cpu.max"), &max)?;
+        }
+        if limits.memory > 0 {
+            write(&dir.join("memory.max"), &limits.memory.to_string())?;
+        }
+        if limits.pids > 0 {
+            write(&dir.join("pids.max"), &limits.pids.to_string())?;
+        }
+        Ok(cgroup)
+    }
+
+    fn create_v1(root: &Path, id: &str, limits: &Limits) -> io::Result<Self> {
+        let mut cgroup = Cgroup { dirs: vec![] };
+        for controller in CONTROLLERS {
+            let hierarchy = root.join(controller);
+            if !hierarchy.is_dir() {
+                continue;
+            }
+            let dir = hierarchy.join(CGROUP_PARENT).join(id);
+            fs::create_dir_all(&dir)?;
+            cgroup.dirs.push(dir.clone());
+            match *controller {
+                "cpu" if limits.cpu > 0 => {
+                    write(&dir.join("cpu.cfs_period_us"), &CPU_PERIOD_USEC.to_string())?;
+                    write(
+                        &dir.join("cpu.cfs_quota_us"),
+                        &limits.cpu_quota().to_string(),
+                    )?;
+                }
+                "memory" if limits.memory > 0 => {
+                    write(
+                        &dir.join("memory.limit_in_bytes"),
+                        &limits.memory.to_string(),
+                    )?;
+                }
+                "pids" if limits.pids > 0 => {
+                    write(&dir.join("pids.max"), &limits.pids.to_string())?;
+                }
+                _ => (),
+            }
+        }
+        Ok(cgroup)
+    }
+
+    /// Open the `cgroup.procs` file of each of the cgroup's directories, so a newly forked
+    /// process can join the cgroup with `join` before it execs.
+    pub fn procs(&self) -> io::Result<Vec<File>> {
+        self.dirs
+            .iter()
+            .map(|dir| {
+                OpenOptions::new()
+                    .write(true)
+                    .open(dir.join("cgroup.procs"))
+            })
+            .collect()
+    }
+}
+
+impl Drop for Cgroup {
+    fn drop(&mut self) {
+        // A cgroup can only be removed once the last of its processes has exited; if any are
+        // left, or a restarted service has already moved into it again, the cgroup is kept.
+        for dir in &self.dirs {
+            if let Err(err) = fs::remove_dir(dir) {
+                debug!("Unable to remove cgroup {}, {}", dir.display(), err);
+            }
+        }
+    }
+}
+
+/// The raw descriptors of a cgroup's opened `cgroup.procs` files.
+pub fn raw_procs(procs: &[File]) -> Vec<RawFd> {
+    procs.iter().map(|file| file.as_raw_fd()).collect()
+}
+
+/// Move the calling process into the cgroup whose `cgroup.procs` files are open at the given
+/// descriptors. Called between fork and exec, so sticks to async-signal-safe calls.
+///
+/// Failures are ignored: a service which no longer runs as root may not be allowed to move
+/// itself, and is moved by `add` once it has been spawned instead.
+pub fn join(procs: &[RawFd]) -> io::Result<()> {
+    for fd in procs {
+        // Writing "0" moves the writing process itself.
+        unsafe { libc::write(*fd, b"0".as_ptr() as *const libc::c_void, 1) };
+    }
+    Ok(())
+}
+
+/// Move the process `pid` into the cgroup whose `cgroup.procs` files are given.
+pub fn add(procs: &[File], pid: u32) -> io::Result<()> {
+    for file in procs {
+        (&*file).write_all(pid.to_string().as_bytes())?;
+    }
+    Ok(())
+}
+
+fn write(path: &Path, value: &str) -> io::Result<()> {
+    fs::write(path, value).map_err(|err| {
+        io::Error::new(
+            err.kind(),
+            format!("unable to write '{}' to {}, {}", value, path.display(), err),
+        )
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn read(path: PathBuf) -> String {
+        fs::read_to_string(path).unwrap()
+    }
+
+    #[test]
+    fn creates_v2_cgroup_with_limits() {
+        let root = TempDir::new().unwrap();
+        fs::write(
+            root.path().join("cgroup.controllers"),
+            "cpuset cpu io memory pids\n",
+        )
+        .unwrap();
+        let limits = Limits {
+            cpu: 500,
+            memory: 1024,
+            pids: 0,
+        };
+        let cgroup = Cgroup::create(root.path(), "redis.default", &limits)
+            .unwrap()
+            .unwrap();
+        let dir = root.path().join("habitat").join("redis.default");
+
+        assert_eq!(cgroup.dirs, vec![dir.clone()]);
+        assert_eq!(
+            read(root.path().join("cgroup.subtree_control")),
+            "+cpu +memory +pids"
+        );
+        assert_eq!(
+            read(root.path().join("habitat").join("cgroup.subtree_control")),
+            "+cpu +memory +pids"
+        );
+        assert_eq!(read(dir.join("cpu.max")), "50000 100000");
+        assert_eq!(read(dir.join("memory.max")), "1024");
+        assert!(!dir.join("pids.max").exists());
+    }
+
+    #[test]
+    fn creates_v1_cgroup_in_each_mounted_controller() {
+        let root = TempDir::new().unwrap();
+        fs::create_dir(root.path().join("cpu")).unwrap();
+        fs::create_dir(root.path().join("pids")).unwrap();
+        let limits = Limits {
+            cpu: 2000,
+            memory: 1024,
+            pids: 10,
+        };
+        let cgroup = Cgroup::create(root.path(), "redis.default", &limits)
+            .unwrap()
+            .unwrap();
+        let cpu = root
+            .path()
+            .join("cpu")
+            .join("habitat")
+            .join("redis.default");
+        let pids = root
+            .path()
+            .join("pids")
+            .join("habitat")
+            .join("redis.default");
+
+        assert_eq!(cgroup.dirs, vec![cpu.clone(), pids.clone()]);
+        assert_eq!(read(cpu.join("cpu.cfs_period_us")), "100000");
+        assert_eq!(read(cpu.join("cpu.cfs_quota_us")), "200000");
+        assert_eq!(read(pids.join("pids.max")), "10");
+    }
+
+    #[test]
+    fn no_cgroup_without_a_mounted_hierarchy() {
+        let root = TempDir::new().unwrap();
+        let cgroup = Cgroup::create(root.path(), "redis.default", &Limits::default()).unwrap();
+
+        assert!(cgroup.is_none());
+    }
+
+    #[test]
+    fn removes_cgroup_when_dropped() {
+        let root = TempDir::new().unwrap();
+        fs::write(root.path().join("cgroup.controllers"), "").unwrap();
+        let cgroup = Cgroup::create(root.path(), "redis.default", &Limits::default())
+            .unwrap()
+            .unwrap();
+        let dir = cgroup.dirs[0].clone();
+        assert!(dir.is_dir());
+
+        drop(cgroup);
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn adds_spawned_process_to_cgroup() {
+        let root = TempDir::new().unwrap();
+        fs::write(root.path().join("cgroup.controllers"), "").unwrap();
+        let cgroup = Cgroup::create(root.path(), "redis.default", &Limits::default())
+            .unwrap()
+            .unwrap();
+        let path = cgroup.dirs[0].join("cgroup.procs");
+        fs::write(&path, "").unwrap();
+        let procs = cgroup.procs().unwrap();
+
+        add(&procs, 4242).unwrap();
+        assert_eq!(read(path), "4242");
+    }
+}
@@ -12,75 +12,113 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::cmp;
+use std::fs::File;
 use std::io;
 use std::ops::Neg;
 use std::os::unix::process::CommandExt;
 use std::process::{Child, Command, ExitStatus, Stdio};
 use std::result;
+use std::thread;
+use std::time::{Duration, Instant};
 
 use core::os;
 use core::os::process::{signal, Signal};
 use libc;
 use protocol::{self, ShutdownMethod};
-use time::{Duration, SteadyTime};
 
+use super::cgroup::{self, Cgroup, Limits, CGROUP_ROOT};
 use error::{Error, Result};
-use service::Service;
+use service::{Service, Shutdown};
 
-pub struct Process(Child);
+/// How often a stopping process is checked on while waiting for it to exit.
+const SHUTDOWN_POLL_INTERVAL_MS: u64 = 100;
+
+pub struct Process {
+    child: Child,
+    /// The cgroup the process was placed in, removed once the process is dropped.
+    #[allow(dead_code)]
+    cgroup: Option<Cgroup>,
+}
 
 impl Process {
     pub fn id(&self) -> u32 {
-        self.0.id()
+        self.child.id()
     }
 
-    /// Attempt to gracefully terminate a process and then forcefully kill it after
-    /// 8 seconds if it has not terminated.
-    pub fn kill(&mut self) -> ShutdownMethod {
-        let mut pid_to_kill = self.0.id() as i32;
-        // check the group of the process being killed
-        // if it is the root process of the process group
-        // we send our signals to the entire process group
-        // to prevent orphaned processes.
-        let pgid = unsafe { libc::getpgid(pid_to_kill) };
-        if pid_to_kill == pgid {
-            debug!(
-                "pid to kill {} is the process group root. Sending signal to process group.",
-                pid_to_kill
-            );
-            // sending a signal to the negative pid sends it to the
-            // entire process group instead just the single pid
-            pid_to_kill = pid_to_kill.neg();
+    /// Attempt to gracefully terminate a process with the shutdown signal and then forcefully
+    /// kill it if it has not terminated within the shutdown timeout.
+    pub fn kill(&mut self, shutdown: &Shutdown) -> ShutdownMethod {
+        match self.stop(shutdown.signal) {
+            ShutdownMethod::GracefulTermination => (),
+            method => return method,
         }
-
-        // JW TODO: Determine if the error represents a case where the process was already
-        // exited before we return out and assume so.
-        if signal(pid_to_kill, Signal::TERM).is_err() {
-            return ShutdownMethod::AlreadyExited;
-        }
-        let stop_time = SteadyTime::now() + Duration::seconds(8);
+        let stop_time = Instant::now() + shutdown.timeout;
         loop {
             if let Ok(Some(_status)) = self.try_wait() {
                 return ShutdownMethod::GracefulTermination;
             }
-            if SteadyTime::now() < stop_time {
-                continue;
-            }
-            // JW TODO: Determine if the error represents a case where the process was already
-            // exited before we return out and assume so.
-            if signal(pid_to_kill, Signal::KILL).is_err() {
-                return ShutdownMethod::GracefulTermination;
+            let now = Instant::now();
+            if now >= stop_time {
+                break;
             }
-            return ShutdownMethod::Killed;
+            thread::sleep(cmp::min(
+                stop_time - now,
+                Duration::from_millis(SHUTDOWN_POLL_INTERVAL_MS),
+            ));
+        }
+        self.force_kill()
+    }
+
+    /// Ask the process to stop by sending it the shutdown signal, without waiting for it to.
+    pub fn stop(&mut self, sig: protocol::Signal) -> ShutdownMethod {
+        // JW TODO: Determine if the error represents a case where the process was already
+        // exited before we return out and assume so.
+        if signal(self.signal_target(), os_signal(sig)).is_err() {
+            return ShutdownMethod::AlreadyExited;
+        }
+        ShutdownMethod::GracefulTermination
+    }
+
+    /// Forcefully kill a process which has not stopped when asked to.
+    pub fn force_kill(&mut self) -> ShutdownMethod {
+        // JW TODO: Determine if the error represents a case where the process was already
+        // exited before we return out and assume so.
+        if signal(self.signal_target(), Signal::KILL).is_err() {
+            return ShutdownMethod::GracefulTermination;
+        }
+        ShutdownMethod::Killed
+    }
+
+    /// Send a signal to the process, and to the rest of its process group if it leads one.
+    pub fn signal(&self, sig: protocol::Signal) -> Result<()> {
+        signal(self.signal_target(), os_signal(sig)).map_err(|err| Error::Signal(err.to_string()))
+    }
+
+    /// The pid to send signals to. If the process is the root of its process group, signals are
+    /// sent to the entire group to prevent orphaned processes.
+    fn signal_target(&self) -> i32 {
+        let pid = self.child.id() as i32;
+        let pgid = unsafe { libc::getpgid(pid) };
+        if pid == pgid {
+            debug!(
+                "pid to signal {} is the process group root. Sending signal to process group.",
+                pid
+            );
+            // sending a signal to the negative pid sends it to the
+            // entire process group instead just the single pid
+            pid.neg()
+        } else {
+            pid
         }
     }
 
     pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
-        self.0.try_wait()
+        self.child.try_wait()
     }
 
     pub fn wait(&mut self) -> io::Result<ExitStatus> {
-        self.0.wait()
+        self.child.wait()
     }
 }
 
@@ -102,7 +140,12 @@ pub fn run(msg: protocol::Spawn) -> Result<Service> {
             .ok_or(Error::GroupNotFound(msg.get_svc_group().to_string()))?
     };
 
+    let svc_cgroup = create_cgroup(&msg);
     cmd.before_exec(owned_pgid);
+    if let Some((_, ref procs)) = svc_cgroup {
+        let fds = cgroup::raw_procs(procs);
+        cmd.before_exec(move || cgroup::join(&fds));
+    }
     cmd.stdin(Stdio::null())
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
@@ -112,12 +155,70 @@ pub fn run(msg: protocol::Spawn) -> Result<Service> {
         cmd.env(key, val);
     }
     let mut child = cmd.spawn().map_err(Error::Spawn)?;
+    if let Some((_, ref procs)) = svc_cgroup {
+        // The service may have given up root before it could join the cgroup itself.
+        if let Err(err) = cgroup::add(procs, child.id()) {
+            error!(
+                "Unable to apply resource limits to {}, {}",
+                msg.get_id(),
+                err
+            );
+        }
+    }
     let stdout = child.stdout.take();
     let stderr = child.stderr.take();
-    let process = Process(child);
+    let process = Process {
+        child: child,
+        cgroup: svc_cgroup.map(|(cgroup, _)| cgroup),
+    };
     Ok(Service::new(msg, process, stdout, stderr))
 }
 
+/// Create the service's cgroup and open it for the service to join. Services without resource
+/// limits aren't given a cgroup. Services are still run if this fails, but without their
+/// resource limits.
+fn create_cgroup(msg: &protocol::Spawn) -> Option<(Cgroup, Vec<File>)> {
+    let limits = Limits::from(msg);
+    if msg.get_id().is_empty() || limits.is_empty() {
+        return None;
+    }
+    let result = match Cgroup::create(CGROUP_ROOT, msg.get_id(), &limits) {
+        Ok(Some(cgroup)) => cgroup.procs().map(|procs| (cgroup, procs)),
+        Ok(None) => {
+            error!(
+                "Unable to apply resource limits to {}, no cgroup hierarchy is mounted at {}",
+                msg.get_id(),
+                CGROUP_ROOT
+            );
+            return None;
+        }
+        Err(err) => Err(err),
+    };
+    match result {
+        Ok(cgroup) => Some(cgroup),
+        Err(err) => {
+            error!(
+                "Unable to apply resource limits to {}, {}",
+                msg.get_id(),
+                err
+            );
+            None
+        }
+    }
+}
+
+fn os_signal(signal: protocol::Signal) -> Signal {
+    match signal {
+        protocol::Signal::TERM => Signal::TERM,
+        protocol::Signal::INT => Signal::INT,
+        protocol::Signal::QUIT => Signal::QUIT,
+        protocol::Signal::HUP => Signal::HUP,
+        protocol::Signal::USR1 => Signal::USR1,
+        protocol::Signal::USR2 => Signal::USR2,
+        protocol::Signal::KILL => Signal::KILL,
+    }
+}
+
 // we want the command to spawn processes in their own process group
 // and not the same group as the Launcher. Otherwise if a child process
 // sends SIGTERM to the group, the Launcher could be terminated.
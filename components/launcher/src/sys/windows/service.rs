@@ -12,14 +12,16 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::cmp;
 use std::collections::HashMap;
 use std::io;
 use std::mem;
+use std::thread;
+use std::time::{Duration, Instant};
 
 use core::os::process::handle_from_pid;
 use core::os::process::windows_child::{Child, ExitStatus, Handle};
 use protocol::{self, ShutdownMethod};
-use time::{Duration, SteadyTime};
 use winapi::shared::minwindef::{DWORD, LPDWORD, MAX_PATH};
 use winapi::shared::winerror::{ERROR_FILE_NOT_FOUND, WAIT_TIMEOUT};
 use winapi::um::handleapi::{self, INVALID_HANDLE_VALUE};
@@ -30,9 +32,11 @@ use winapi::um::winbase::{INFINITE, WAIT_OBJECT_0};
 use winapi::um::wincon;
 
 use error::{Error, Result};
-use service::Service;
+use service::{Service, Shutdown};
 
 const PROCESS_ACTIVE: u32 = 259;
+/// How often a stopping process is checked on while waiting for it to exit.
+const SHUTDOWN_POLL_INTERVAL_MS: u64 = 100;
 type ProcessTable = HashMap<DWORD, Vec<DWORD>>;
 
 pub struct Process {
@@ -52,9 +56,33 @@ impl Process {
         unsafe { processthreadsapi::GetProcessId(self.handle.raw()) as u32 }
     }
 
-    /// Attempt to gracefully terminate a process and then forcefully kill it after
-    /// 8 seconds if it has not terminated.
-    pub fn kill(&mut self) -> ShutdownMethod {
+    /// Attempt to gracefully terminate a process and then forcefully kill it if it has not
+    /// terminated within the shutdown timeout.
+    pub fn kill(&mut self, shutdown: &Shutdown) -> ShutdownMethod {
+        match self.stop(shutdown.signal) {
+            ShutdownMethod::GracefulTermination => (),
+            method => return method,
+        }
+        let stop_time = Instant::now() + shutdown.timeout;
+        loop {
+            if self.status().is_some() {
+                return ShutdownMethod::GracefulTermination;
+            }
+            let now = Instant::now();
+            if now > stop_time {
+                return self.force_kill();
+            }
+            thread::sleep(cmp::min(
+                stop_time - now,
+                Duration::from_millis(SHUTDOWN_POLL_INTERVAL_MS),
+            ));
+        }
+    }
+
+    /// Ask the process to stop without waiting for it to. Services are sent a Ctrl-Break rather
+    /// than the shutdown signal, which has no equivalent on Windows, and are killed at once if
+    /// it can't be sent.
+    pub fn stop(&mut self, _sig: protocol::Signal) -> ShutdownMethod {
         if self.status().is_some() {
             return ShutdownMethod::AlreadyExited;
         }
@@ -65,20 +93,25 @@ impl Process {
                 self.id(),
                 io::Error::last_os_error()
             );
+            return self.force_kill();
         }
+        ShutdownMethod::GracefulTermination
+    }
 
-        let stop_time = SteadyTime::now() + Duration::seconds(8);
-        loop {
-            if ret == 0 || SteadyTime::now() > stop_time {
-                let proc_table = build_proc_table();
-                terminate_process_descendants(&proc_table, self.id());
-                return ShutdownMethod::Killed;
-            }
+    /// Forcefully kill a process, and its descendants, which has not stopped when asked to.
+    pub fn force_kill(&mut self) -> ShutdownMethod {
+        let proc_table = build_proc_table();
+        terminate_process_descendants(&proc_table, self.id());
+        ShutdownMethod::Killed
+    }
 
-            if self.status().is_some() {
-                return ShutdownMethod::GracefulTermination;
-            }
-        }
+    /// Signals have no equivalent on Windows, so sending them is unsupported.
+    pub fn signal(&self, sig: protocol::Signal) -> Result<()> {
+        Err(Error::Signal(format!(
+            "unable to send SIG{:?} to pid {}, signals are not supported on Windows",
+            sig,
+            self.id()
+        )))
     }
 
     pub fn wait(&mut self) -> io::Result<ExitStatus> {
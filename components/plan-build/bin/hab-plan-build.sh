@@ -211,6 +211,18 @@
 #   [storage]="port host"
 # )
 #
+# ### pkg_svc_limits
+# An associative array of resource limits the Supervisor should place on the service: `cpu` in
+# millicores (1000 is one full CPU), `memory` in bytes and `pids` as a number of processes. Limits
+# given when the service is loaded take precedence over these.
+# ```
+# pkg_svc_limits=(
+#   [cpu]=500
+#   [memory]=268435456
+#   [pids]=64
+# )
+# ```
+#
 # ### pkg_origin
 # A string to use for the origin. The origin is used to denote a particular upstream of a
 # package; when we resolve dependencies, we consider a version of a package to be equal
@@ -408,6 +420,8 @@ pkg_exposes=()
 declare -A pkg_exports
 declare -A pkg_binds
 declare -A pkg_binds_optional
+# Resource limits to place on the service
+declare -A pkg_svc_limits
 # The user to run the service as
 pkg_svc_user=hab
 # The group to run the service as
@@ -1894,6 +1908,7 @@ do_default_install() {
 # * `$pkg_prefix/EXPOSES` - An array of `pkg_exports` for which ports that this package exposes
 # * `$pkg_prefix/BINDS` - A list of services you connect to and keys that you expect to be exported
 # * `$pkg_prefix/BINDS_OPTIONAL` - Same as `BINDS` but not required for the service to start
+# * `$pkg_prefix/SVC_LIMITS` - Resource limits to place on the service
 # * `$pkg_prefix/FILES` - blake2b checksums of all files in the package
 # * `$pkg_prefix/LDFLAGS` - Any LDFLAGS for things that link against us
 # * `$pkg_prefix/LD_RUN_PATH` - The LD_RUN_PATH for things that link against us
@@ -1927,12 +1942,13 @@ _build_metadata() {
   _render_metadata_RUNTIME_ENVIRONMENT
   _render_metadata_RUNTIME_ENVIRONMENT_PROVENANCE
 
-  # Only generate `SVC_USER` & `SVC_GROUP` files if this package is a service.
+  # Only generate `SVC_USER`, `SVC_GROUP` & `SVC_LIMITS` files if this package is a service.
   # We determine this by checking if there is a `hooks/run` script and/or
   # a set `$pkg_svc_run` value.
   if [[ -f "$PLAN_CONTEXT/hooks/run" || -n "${pkg_svc_run:-}" ]]; then
     _render_metadata_SVC_USER
     _render_metadata_SVC_GROUP
+    _render_metadata_SVC_LIMITS
   fi
 
   return 0
@@ -239,6 +239,11 @@ _render_metadata_SVC_GROUP() {
   echo "$pkg_svc_group" > "$pkg_prefix"/SVC_GROUP
 }
 
+_render_metadata_SVC_LIMITS() {
+  debug "Rendering SVC_LIMITS metadata file"
+  _render_associative_array_file "${pkg_prefix}" SVC_LIMITS pkg_svc_limits
+}
+
 _render_metadata_SVC_USER() {
   debug "Rendering SVC_USER metadata file"
   # shellcheck disable=2154
@@ -20,7 +20,7 @@
 //! # RPC Call Example
 //!
 //! ```ignore
-//! let conn = SrvClient::connect(&listen_addr, secret_key).wait()?;
+//! let conn = SrvClient::connect(&listen_addr, secret_key, None).wait()?;
 //! let msg = protocols::ctl::ServiceGetDefaultCfg::new();
 //! conn.call(msg).for_each(|reply| {
 //!     match reply.message_id() {
@@ -53,10 +53,12 @@ use std::io;
 use std::net::SocketAddr;
 use std::path::PathBuf;
 
+use futures::future::{self, Either};
 use futures::prelude::*;
 use futures::sink;
 use protocol::codec::*;
 use protocol::net::NetErr;
+use protocol::tls::{self, CtlStream, TlsConfig};
 use tokio::net::TcpStream;
 use tokio_codec::Framed;
 
@@ -149,17 +151,33 @@ pub struct SrvClient {
 
 impl SrvClient {
     /// Connect to the given remote server and authenticate with the given secret_key.
+    ///
+    /// If a `TlsConfig` is given, connections to a non-loopback address are secured with TLS and
+    /// present the configured client certificate before authenticating.
     pub fn connect<T>(
         addr: &SocketAddr,
         secret_key: T,
+        tls: Option<&TlsConfig>,
     ) -> Box<Future<Item = SrvClient, Error = SrvClientError> + 'static>
     where
         T: ToString,
     {
         let secret_key = secret_key.to_string();
+        let connector = match tls {
+            Some(tls) if !tls::is_plaintext_addr(addr) => match tls.connector() {
+                Ok(connector) => Some(connector),
+                Err(err) => return Box::new(future::err(SrvClientError::from(err))),
+            },
+            _ => None,
+        };
         let conn = TcpStream::connect(addr)
             .map_err(SrvClientError::from)
-            .and_then(move |socket| {
+            .and_then(move |socket| match connector {
+                Some(connector) => Either::A(
+                    CtlStream::connect(&connector, socket).map_err(SrvClientError::from),
+                ),
+                None => Either::B(future::ok(CtlStream::Plain(socket))),
+            }).and_then(move |socket| {
                 let client = Self::new(socket, None);
                 let mut request = protocol::ctl::Handshake::default();
                 request.secret_key = Some(secret_key);
@@ -188,7 +206,7 @@ impl SrvClient {
         Ok(buf)
     }
 
-    fn new(socket: TcpStream, current_txn: Option<SrvTxn>) -> Self {
+    fn new(socket: CtlStream, current_txn: Option<SrvTxn>) -> Self {
         SrvClient {
             socket: Framed::new(socket, SrvCodec::new()),
             current_txn: current_txn.unwrap_or_default(),
@@ -61,11 +61,11 @@ use std::str;
 use bytes::{Buf, BufMut, Bytes, BytesMut};
 use futures;
 use prost::{self, Message};
-use tokio::net::TcpStream;
 use tokio_codec::{Decoder, Encoder, Framed};
 
 use message::MessageStatic;
 use net::{NetErr, NetResult};
+use tls::CtlStream;
 
 const BODY_LEN_MASK: u32 = 0xFFFFF;
 const HEADER_LEN: usize = 4;
@@ -80,9 +80,9 @@ const RESPONSE_MASK: u32 = 0x1;
 const COMPLETE_OFFSET: u32 = 30;
 const COMPLETE_MASK: u32 = 0x1;
 
-/// A `TcpStream` framed with `SrvCodec`. This is the base socket connection that the CtlGateway
+/// A `CtlStream` framed with `SrvCodec`. This is the base socket connection that the CtlGateway
 /// client and server speak.
-pub type SrvStream = Framed<TcpStream, SrvCodec>;
+pub type SrvStream = Framed<CtlStream, SrvCodec>;
 
 /// Sending half of `SrvStream`.
 pub type SrvSink = futures::stream::SplitSink<SrvStream>;
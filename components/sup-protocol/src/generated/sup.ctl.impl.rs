@@ -9,6 +9,9 @@ impl message::MessageStatic for Handshake {
 impl message::MessageStatic for ServiceBindList {
     const MESSAGE_ID: &'static str = "ServiceBindList";
 }
+impl message::MessageStatic for RingKeyRotate {
+    const MESSAGE_ID: &'static str = "RingKeyRotate";
+}
 impl message::MessageStatic for SupDepart {
     const MESSAGE_ID: &'static str = "SupDepart";
 }
@@ -36,6 +39,15 @@ impl message::MessageStatic for SvcStart {
 impl message::MessageStatic for SvcStop {
     const MESSAGE_ID: &'static str = "SvcStop";
 }
+impl message::MessageStatic for SvcSignal {
+    const MESSAGE_ID: &'static str = "SvcSignal";
+}
+impl message::MessageStatic for SvcExec {
+    const MESSAGE_ID: &'static str = "SvcExec";
+}
+impl message::MessageStatic for SvcGroupForget {
+    const MESSAGE_ID: &'static str = "SvcGroupForget";
+}
 impl message::MessageStatic for SvcStatus {
     const MESSAGE_ID: &'static str = "SvcStatus";
 }
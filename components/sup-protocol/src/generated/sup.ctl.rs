@@ -31,6 +31,13 @@ pub struct ServiceBindList {
     #[prost(message, repeated, tag="1")]
     pub binds: ::std::vec::Vec<super::types::ServiceBind>,
 }
+/// Request to rotate the ring key to a new revision, distributing it to every member of the ring.
+/// The Supervisor generates the new revision itself, so the key is never sent by the client.
+#[derive(Clone, PartialEq, Message)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct RingKeyRotate {
+}
 #[derive(Clone, PartialEq, Message)]
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
@@ -142,6 +149,78 @@ pub struct SvcLoad {
     /// Update strategy for the service.
     #[prost(enumeration="super::types::UpdateStrategy", optional, tag="13")]
     pub update_strategy: ::std::option::Option<i32>,
+    /// Seconds between runs of the service's health check.
+    #[prost(uint64, optional, tag="15")]
+    pub health_check_interval: ::std::option::Option<u64>,
+    /// Seconds a health_check hook may run before it is terminated.
+    #[prost(uint64, optional, tag="16")]
+    pub health_check_timeout: ::std::option::Option<u64>,
+    /// Consecutive failed health checks before the service is reported unhealthy.
+    #[prost(uint32, optional, tag="17")]
+    pub health_check_failure_threshold: ::std::option::Option<u32>,
+    /// Consecutive passing health checks before the service is reported healthy again.
+    #[prost(uint32, optional, tag="18")]
+    pub health_check_success_threshold: ::std::option::Option<u32>,
+    /// Consecutive critical health checks after which the service is restarted. Zero disables
+    /// automatic restarts.
+    #[prost(uint32, optional, tag="19")]
+    pub restart_after_critical: ::std::option::Option<u32>,
+    /// Seconds to wait after an automatic restart before another, doubled for each further restart.
+    #[prost(uint64, optional, tag="20")]
+    pub restart_backoff: ::std::option::Option<u64>,
+    /// Maximum automatic restarts within the restart window before the service is considered to be
+    /// crash-looping.
+    #[prost(uint32, optional, tag="21")]
+    pub restart_max: ::std::option::Option<u32>,
+    /// Seconds over which automatic restarts are counted.
+    #[prost(uint64, optional, tag="22")]
+    pub restart_window: ::std::option::Option<u64>,
+    /// Members which take a new package first under the canary update strategy, given as a count
+    /// ("2") or a percentage of the group ("25%").
+    #[prost(string, optional, tag="23")]
+    pub canary_size: ::std::option::Option<String>,
+    /// Seconds the canaries must run a new package healthily before the rest of the group updates.
+    #[prost(uint64, optional, tag="24")]
+    pub canary_soak: ::std::option::Option<u64>,
+    /// Cron-style schedules, in UTC, of the minutes during which the service may be updated.
+    /// Updates found outside of these windows are queued until the next one opens.
+    #[prost(string, repeated, tag="25")]
+    pub update_windows: ::std::vec::Vec<String>,
+    /// Restricts the versions the service may be updated to, e.g. "1.2.x".
+    #[prost(string, optional, tag="26")]
+    pub update_version: ::std::option::Option<String>,
+    /// If set to true, the Launcher also writes the service's output to log files in its logs
+    /// directory.
+    #[prost(bool, optional, tag="27")]
+    pub log_capture: ::std::option::Option<bool>,
+    /// Bytes a captured log file may grow to before it is rotated. Zero disables size-based
+    /// rotation.
+    #[prost(uint64, optional, tag="28")]
+    pub log_max_size: ::std::option::Option<u64>,
+    /// Seconds a captured log file is written to before it is rotated. Zero disables time-based
+    /// rotation.
+    #[prost(uint64, optional, tag="29")]
+    pub log_max_age: ::std::option::Option<u64>,
+    /// Rotated log files kept for each of the service's output streams.
+    #[prost(uint32, optional, tag="30")]
+    pub log_retain: ::std::option::Option<u32>,
+    /// Resource limits to place on the service, overriding any declared by its package. Zero leaves
+    /// the resource unlimited.
+    /// CPU time the service may use, in thousandths of a CPU.
+    #[prost(uint32, optional, tag="31")]
+    pub cpu_limit: ::std::option::Option<u32>,
+    /// Bytes of memory the service may use.
+    #[prost(uint64, optional, tag="32")]
+    pub memory_limit: ::std::option::Option<u64>,
+    /// Number of processes the service may run.
+    #[prost(uint64, optional, tag="33")]
+    pub pids_limit: ::std::option::Option<u64>,
+    /// Signal sent to the service to ask it to stop.
+    #[prost(enumeration="super::types::Signal", optional, tag="34")]
+    pub shutdown_signal: ::std::option::Option<i32>,
+    /// Seconds the service is given to stop after the shutdown signal before it is killed.
+    #[prost(uint32, optional, tag="35")]
+    pub shutdown_timeout: ::std::option::Option<u32>,
 }
 /// Request to unload a loaded service.
 #[derive(Clone, PartialEq, Message)]
@@ -167,6 +246,38 @@ pub struct SvcStop {
     #[prost(message, optional, tag="1")]
     pub ident: ::std::option::Option<super::types::PackageIdent>,
 }
+/// Request to send a signal to a running service's process.
+#[derive(Clone, PartialEq, Message)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct SvcSignal {
+    #[prost(message, optional, tag="1")]
+    pub ident: ::std::option::Option<super::types::PackageIdent>,
+    #[prost(enumeration="super::types::Signal", optional, tag="2")]
+    pub signal: ::std::option::Option<i32>,
+}
+/// Request to run a one-off command in a running service's environment. Output of the command is
+/// streamed back as ConsoleLine replies.
+#[derive(Clone, PartialEq, Message)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct SvcExec {
+    #[prost(message, optional, tag="1")]
+    pub ident: ::std::option::Option<super::types::PackageIdent>,
+    #[prost(string, optional, tag="2")]
+    pub command: ::std::option::Option<String>,
+    #[prost(string, repeated, tag="3")]
+    pub args: ::std::vec::Vec<String>,
+}
+/// Request to forget a service group which is no longer running anywhere, discarding its
+/// configuration, files and elections across the ring.
+#[derive(Clone, PartialEq, Message)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct SvcGroupForget {
+    #[prost(message, optional, tag="1")]
+    pub service_group: ::std::option::Option<super::types::ServiceGroup>,
+}
 /// Request to retrieve the service status of one or all services.
 #[derive(Clone, PartialEq, Message)]
 #[derive(Serialize, Deserialize)]
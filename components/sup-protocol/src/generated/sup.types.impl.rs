@@ -9,6 +9,9 @@ impl message::MessageStatic for PackageIdent {
 impl message::MessageStatic for ProcessStatus {
     const MESSAGE_ID: &'static str = "ProcessStatus";
 }
+impl message::MessageStatic for ResourceUsage {
+    const MESSAGE_ID: &'static str = "ResourceUsage";
+}
 impl message::MessageStatic for ServiceBind {
     const MESSAGE_ID: &'static str = "ServiceBind";
 }
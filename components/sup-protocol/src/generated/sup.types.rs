@@ -30,6 +30,27 @@ pub struct ProcessStatus {
     pub pid: ::std::option::Option<u32>,
     #[prost(enumeration="ProcessState", required, tag="3")]
     pub state: i32,
+    /// Set when the service has been restarted too often by its restart policy and further
+    /// restarts are being withheld.
+    #[prost(bool, optional, tag="4")]
+    pub crash_looping: ::std::option::Option<bool>,
+    /// Resources in use by the service, if the Launcher placed it in its own cgroup.
+    #[prost(message, optional, tag="5")]
+    pub usage: ::std::option::Option<ResourceUsage>,
+}
+#[derive(Clone, PartialEq, Message)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ResourceUsage {
+    /// CPU time used, in microseconds.
+    #[prost(uint64, optional, tag="1")]
+    pub cpu_usec: ::std::option::Option<u64>,
+    /// Bytes of memory in use.
+    #[prost(uint64, optional, tag="2")]
+    pub memory_bytes: ::std::option::Option<u64>,
+    /// Number of processes running.
+    #[prost(uint64, optional, tag="3")]
+    pub pids: ::std::option::Option<u64>,
 }
 #[derive(Clone, PartialEq, Message)]
 #[derive(Serialize, Deserialize)]
@@ -41,6 +62,10 @@ pub struct ServiceBind {
     pub service_group: ServiceGroup,
     #[prost(string, optional, tag="3")]
     pub service_name: ::std::option::Option<String>,
+    /// Restricts the bind to members whose Supervisor tags match, each either `key=value` or a bare
+    /// `key` matching the binding Supervisor's own value.
+    #[prost(string, repeated, tag="4")]
+    pub tags: ::std::vec::Vec<String>,
 }
 #[derive(Clone, PartialEq, Message)]
 #[derive(Serialize, Deserialize)]
@@ -89,6 +114,9 @@ pub struct ServiceStatus {
     pub composite: ::std::option::Option<String>,
     #[prost(enumeration="DesiredState", optional, tag="5")]
     pub desired_state: ::std::option::Option<i32>,
+    /// Progress of the service's update strategy, if an update is under way.
+    #[prost(string, optional, tag="6")]
+    pub update_status: ::std::option::Option<String>,
 }
 /// Encapsulate all possible sources we can install packages from.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Enumeration)]
@@ -132,6 +160,9 @@ pub enum UpdateStrategy {
     None = 0,
     AtOnce = 1,
     Rolling = 2,
+    /// A subset of the group takes each new package first, and the rest follow once it has run
+    /// healthily for a soak period.
+    Canary = 3,
 }
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Enumeration)]
 #[derive(Serialize, Deserialize)]
@@ -142,3 +173,16 @@ pub enum BindingMode {
     /// Service start-up is blocked until all binds are available
     Strict = 1,
 }
+/// Signals which may be sent to a service's process.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Enumeration)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Signal {
+    Term = 0,
+    Int = 1,
+    Quit = 2,
+    Hup = 3,
+    Usr1 = 4,
+    Usr2 = 5,
+    Kill = 6,
+}
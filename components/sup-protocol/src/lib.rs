@@ -48,6 +48,7 @@ extern crate habitat_core as core;
 extern crate lazy_static;
 #[macro_use]
 extern crate log;
+extern crate openssl;
 extern crate prost;
 #[macro_use]
 extern crate prost_derive;
@@ -57,12 +58,14 @@ extern crate serde;
 extern crate serde_derive;
 extern crate tokio;
 extern crate tokio_codec;
+extern crate tokio_openssl;
 
 pub mod butterfly;
 pub mod codec;
 pub mod ctl;
 pub mod message;
 pub mod net;
+pub mod tls;
 pub mod types;
 
 use core::env as henv;
@@ -0,0 +1,375 @@
+// Copyright (c) 2018 Chef Software Inc. and/or applicable contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Optional mutual TLS for connections to the CtlGateway.
+//!
+//! When the CtlGateway is configured with a certificate, a key, and a CA certificate to verify
+//! clients against, every connection from a non-loopback address must complete a TLS handshake
+//! and present a client certificate signed by that CA before the secret key handshake takes
+//! place. Connections from a loopback address continue to speak SrvProtocol over plain TCP.
+//!
+//! Clients verify the server's certificate against their own CA certificate. Supervisors are
+//! usually addressed by IP, so the server's hostname is not checked.
+
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+
+use futures::future;
+use futures::prelude::*;
+use openssl::error::ErrorStack;
+use openssl::nid::Nid;
+use openssl::ssl::{SslConnector, SslFiletype, SslMethod, SslVerifyMode};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpStream;
+use tokio_openssl::{ConnectConfigurationExt, SslAcceptorExt, SslStream};
+
+pub use openssl::ssl::SslAcceptor;
+
+use net::{self, ErrCode, NetErr, NetResult};
+
+/// Paths to the PEM encoded files used to secure one end of a CtlGateway connection.
+///
+/// On the server `ca_certificate` verifies client certificates while on the client it verifies
+/// the server's certificate.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TlsConfig {
+    pub certificate: PathBuf,
+    pub key: PathBuf,
+    pub ca_certificate: PathBuf,
+}
+
+impl TlsConfig {
+    pub fn new<C, K, A>(certificate: C, key: K, ca_certificate: A) -> Self
+    where
+        C: Into<PathBuf>,
+        K: Into<PathBuf>,
+        A: Into<PathBuf>,
+    {
+        TlsConfig {
+            certificate: certificate.into(),
+            key: key.into(),
+            ca_certificate: ca_certificate.into(),
+        }
+    }
+
+    /// Build an acceptor which requires connecting clients to present a certificate signed by
+    /// our CA certificate.
+    pub fn acceptor(&self) -> NetResult<SslAcceptor> {
+        let mut builder = SslAcceptor::mozilla_intermediate(SslMethod::tls())
+            .map_err(|e| tls_err("creating acceptor", e))?;
+        builder
+            .set_certificate_chain_file(&self.certificate)
+            .map_err(|e| file_err(&self.certificate, e))?;
+        builder
+            .set_private_key_file(&self.key, SslFiletype::PEM)
+            .map_err(|e| file_err(&self.key, e))?;
+        builder
+            .check_private_key()
+            .map_err(|e| file_err(&self.key, e))?;
+        builder
+            .set_ca_file(&self.ca_certificate)
+            .map_err(|e| file_err(&self.ca_certificate, e))?;
+        builder.set_verify(SslVerifyMode::PEER | SslVerifyMode::FAIL_IF_NO_PEER_CERT);
+        Ok(builder.build())
+    }
+
+    /// Build a connector which presents our certificate and verifies the server's certificate
+    /// against our CA certificate.
+    pub fn connector(&self) -> NetResult<SslConnector> {
+        let mut builder =
+            SslConnector::builder(SslMethod::tls()).map_err(|e| tls_err("creating connector", e))?;
+        builder
+            .set_certificate_chain_file(&self.certificate)
+            .map_err(|e| file_err(&self.certificate, e))?;
+        builder
+            .set_private_key_file(&self.key, SslFiletype::PEM)
+            .map_err(|e| file_err(&self.key, e))?;
+        builder
+            .check_private_key()
+            .map_err(|e| file_err(&self.key, e))?;
+        builder
+            .set_ca_file(&self.ca_certificate)
+            .map_err(|e| file_err(&self.ca_certificate, e))?;
+        Ok(builder.build())
+    }
+}
+
+/// Returns true if connections between the given address and the CtlGateway speak plain TCP
+/// regardless of whether TLS has been configured.
+pub fn is_plaintext_addr(addr: &SocketAddr) -> bool {
+    addr.ip().is_loopback()
+}
+
+/// A socket connected to the CtlGateway, either over plain TCP or TLS.
+pub enum CtlStream {
+    Plain(TcpStream),
+    Tls(SslStream<TcpStream>),
+}
+
+impl CtlStream {
+    /// Perform the client side of a TLS handshake over the given socket.
+    pub fn connect(
+        connector: &SslConnector,
+        socket: TcpStream,
+    ) -> Box<Future<Item = CtlStream, Error = io::Error>> {
+        let config = match connector.configure() {
+            Ok(mut config) => {
+                config.set_use_server_name_indication(false);
+                config.set_verify_hostname(false);
+                config
+            }
+            Err(err) => {
+                return Box::new(future::err(io::Error::new(
+                    io::ErrorKind::Other,
+                    err.to_string(),
+                )))
+            }
+        };
+        Box::new(
+            config
+                .connect_async("", socket)
+                .map(CtlStream::Tls)
+                .map_err(|err| {
+                    io::Error::new(
+                        io::ErrorKind::ConnectionAborted,
+                        format!("TLS handshake failed, {}", err),
+                    )
+                }),
+        )
+    }
+
+    /// Perform the server side of a TLS handshake over the given socket.
+    pub fn accept(
+        acceptor: &SslAcceptor,
+        socket: TcpStream,
+    ) -> Box<Future<Item = CtlStream, Error = io::Error>> {
+        Box::new(
+            acceptor
+                .accept_async(socket)
+                .map(CtlStream::Tls)
+                .map_err(|err| {
+                    io::Error::new(
+                        io::ErrorKind::ConnectionAborted,
+                        format!("TLS handshake failed, {}", err),
+                    )
+                }),
+        )
+    }
+
+    /// The common name of the certificate presented by the remote, if any.
+    pub fn peer_common_name(&self) -> Option<String> {
+        match *self {
+            CtlStream::Plain(_) => None,
+            CtlStream::Tls(ref stream) => stream
+                .get_ref()
+                .ssl()
+                .peer_certificate()
+                .and_then(|cert| {
+                    cert.subject_name()
+                        .entries_by_nid(Nid::COMMONNAME)
+                        .next()
+                        .and_then(|entry| entry.data().as_utf8().ok())
+                        .map(|name| name.to_string())
+                }),
+        }
+    }
+}
+
+impl fmt::Debug for CtlStream {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CtlStream::Plain(ref stream) => write!(f, "CtlStream::Plain({:?})", stream),
+            CtlStream::Tls(ref stream) => {
+                write!(f, "CtlStream::Tls({:?})", stream.get_ref().get_ref())
+            }
+        }
+    }
+}
+
+impl Read for CtlStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match *self {
+            CtlStream::Plain(ref mut stream) => stream.read(buf),
+            CtlStream::Tls(ref mut stream) => stream.read(buf),
+        }
+    }
+}
+
+impl Write for CtlStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match *self {
+            CtlStream::Plain(ref mut stream) => stream.write(buf),
+            CtlStream::Tls(ref mut stream) => stream.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match *self {
+            CtlStream::Plain(ref mut stream) => stream.flush(),
+            CtlStream::Tls(ref mut stream) => stream.flush(),
+        }
+    }
+}
+
+impl AsyncRead for CtlStream {}
+
+impl AsyncWrite for CtlStream {
+    fn shutdown(&mut self) -> Poll<(), io::Error> {
+        match *self {
+            CtlStream::Plain(ref mut stream) => AsyncWrite::shutdown(stream),
+            CtlStream::Tls(ref mut stream) => AsyncWrite::shutdown(stream),
+        }
+    }
+}
+
+fn tls_err(action: &str, err: ErrorStack) -> NetErr {
+    net::err(ErrCode::Internal, format!("TLS error {}, {}", action, err))
+}
+
+fn file_err(path: &Path, err: ErrorStack) -> NetErr {
+    net::err(
+        ErrCode::Io,
+        format!("Unable to load TLS file {}, {}", path.display(), err),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    extern crate tempfile;
+
+    use std::fs::File;
+
+    use openssl::asn1::Asn1Time;
+    use openssl::bn::BigNum;
+    use openssl::hash::MessageDigest;
+    use openssl::pkey::{PKey, Private};
+    use openssl::rsa::Rsa;
+    use openssl::x509::extension::BasicConstraints;
+    use openssl::x509::{X509NameBuilder, X509};
+    use tokio::net::TcpListener;
+    use tokio::runtime::current_thread::Runtime;
+
+    use self::tempfile::TempDir;
+    use super::*;
+
+    /// Issue a certificate for the given common name, signed by the given CA or self-signed as
+    /// a CA when there is none.
+    fn issue(name: &str, serial: u32, ca: Option<&(X509, PKey<Private>)>) -> (X509, PKey<Private>) {
+        let key = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        let mut subject = X509NameBuilder::new().unwrap();
+        subject.append_entry_by_nid(Nid::COMMONNAME, name).unwrap();
+        let subject = subject.build();
+        let mut builder = X509::builder().unwrap();
+        builder.set_version(2).unwrap();
+        builder
+            .set_serial_number(&BigNum::from_u32(serial).unwrap().to_asn1_integer().unwrap())
+            .unwrap();
+        builder.set_subject_name(&subject).unwrap();
+        builder.set_pubkey(&key).unwrap();
+        builder
+            .set_not_before(&Asn1Time::days_from_now(0).unwrap())
+            .unwrap();
+        builder
+            .set_not_after(&Asn1Time::days_from_now(1).unwrap())
+            .unwrap();
+        match ca {
+            Some(&(ref ca_cert, ref ca_key)) => {
+                builder.set_issuer_name(ca_cert.subject_name()).unwrap();
+                builder.sign(ca_key, MessageDigest::sha256()).unwrap();
+            }
+            None => {
+                builder.set_issuer_name(&subject).unwrap();
+                builder
+                    .append_extension(BasicConstraints::new().critical().ca().build().unwrap())
+                    .unwrap();
+                builder.sign(&key, MessageDigest::sha256()).unwrap();
+            }
+        }
+        (builder.build(), key)
+    }
+
+    fn write_pem(dir: &TempDir, file: &str, pem: &[u8]) -> PathBuf {
+        let path = dir.path().join(file);
+        File::create(&path).unwrap().write_all(pem).unwrap();
+        path
+    }
+
+    /// Write out a CA and certificates signed by it for a server and a client named "client".
+    fn tls_configs(dir: &TempDir) -> (TlsConfig, TlsConfig) {
+        let ca = issue("ca", 1, None);
+        let ca_path = write_pem(dir, "ca.crt", &ca.0.to_pem().unwrap());
+        let mut configs = vec![];
+        for &(serial, name) in [(2, "server"), (3, "client")].iter() {
+            let (cert, key) = issue(name, serial, Some(&ca));
+            configs.push(TlsConfig::new(
+                write_pem(dir, &format!("{}.crt", name), &cert.to_pem().unwrap()),
+                write_pem(
+                    dir,
+                    &format!("{}.key", name),
+                    &key.private_key_to_pem_pkcs8().unwrap(),
+                ),
+                ca_path.clone(),
+            ));
+        }
+        let client = configs.pop().unwrap();
+        (configs.pop().unwrap(), client)
+    }
+
+    /// Run both sides of a handshake over a loopback socket and return the result of each.
+    fn handshake(
+        acceptor: SslAcceptor,
+        connector: SslConnector,
+    ) -> (io::Result<CtlStream>, io::Result<CtlStream>) {
+        let listener = TcpListener::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = listener
+            .incoming()
+            .into_future()
+            .map_err(|(err, _)| err)
+            .and_then(move |(socket, _)| CtlStream::accept(&acceptor, socket.unwrap()))
+            .then(Ok::<_, ()>);
+        let client = TcpStream::connect(&addr)
+            .and_then(move |socket| CtlStream::connect(&connector, socket))
+            .then(Ok::<_, ()>);
+        Runtime::new()
+            .unwrap()
+            .block_on(server.join(client))
+            .unwrap()
+    }
+
+    #[test]
+    fn handshake_with_a_client_certificate_signed_by_the_ca() {
+        let dir = TempDir::new().unwrap();
+        let (server, client) = tls_configs(&dir);
+        let (accepted, connected) =
+            handshake(server.acceptor().unwrap(), client.connector().unwrap());
+        assert!(connected.is_ok());
+        assert_eq!(
+            accepted.unwrap().peer_common_name(),
+            Some("client".to_string())
+        );
+    }
+
+    #[test]
+    fn handshake_without_a_client_certificate_is_refused() {
+        let dir = TempDir::new().unwrap();
+        let (server, _) = tls_configs(&dir);
+        let mut builder = SslConnector::builder(SslMethod::tls()).unwrap();
+        builder.set_ca_file(&server.ca_certificate).unwrap();
+        let (accepted, _) = handshake(server.acceptor().unwrap(), builder.build());
+        assert!(accepted.is_err());
+    }
+}
@@ -29,6 +29,7 @@ use core;
 use core::package::{self, Identifiable};
 
 use net::{self, ErrCode, NetErr};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 
 impl ServiceGroup {
     pub fn validate(value: &str) -> core::Result<()> {
@@ -156,24 +157,81 @@ impl FromStr for DesiredState {
     }
 }
 
+impl FromStr for Signal {
+    type Err = NetErr;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let name = value.to_uppercase();
+        let name = if name.starts_with("SIG") {
+            &name[3..]
+        } else {
+            name.as_str()
+        };
+        match name {
+            "TERM" => Ok(Signal::Term),
+            "INT" => Ok(Signal::Int),
+            "QUIT" => Ok(Signal::Quit),
+            "HUP" => Ok(Signal::Hup),
+            "USR1" => Ok(Signal::Usr1),
+            "USR2" => Ok(Signal::Usr2),
+            "KILL" => Ok(Signal::Kill),
+            _ => Err(net::err(
+                ErrCode::InvalidPayload,
+                format!(
+                    "Invalid signal \"{}\", must be one of TERM, INT, QUIT, HUP, USR1, USR2 or \
+                     KILL.",
+                    value
+                ),
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Signal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let value = match *self {
+            Signal::Term => "TERM",
+            Signal::Int => "INT",
+            Signal::Quit => "QUIT",
+            Signal::Hup => "HUP",
+            Signal::Usr1 => "USR1",
+            Signal::Usr2 => "USR2",
+            Signal::Kill => "KILL",
+        };
+        write!(f, "{}", value)
+    }
+}
+
 impl FromStr for ServiceBind {
     type Err = NetErr;
 
     fn from_str(bind_str: &str) -> Result<Self, Self::Err> {
-        let values: Vec<&str> = bind_str.split(':').collect();
-        if !(values.len() == 3 || values.len() == 2) {
-            return Err(net::err(
+        let invalid = || {
+            net::err(
                 ErrCode::InvalidPayload,
                 format!(
                     "Invalid binding \"{}\", must be of the form <NAME>:<SERVICE_GROUP> or \
                     <SERVICE_NAME>:<NAME>:<SERVICE_GROUP> where <NAME> is a service name,
                     <SERVICE_GROUP> is a valid service group, and <SERVICE_NAME> is the name of
-                    a service within a composite if the given bind is for a composite service.",
+                    a service within a composite if the given bind is for a composite service.
+                    It may be followed by a tag filter such as [zone,rack=12].",
                     bind_str
                 ),
-            ));
+            )
+        };
+        let (target, tags) = split_bind_tags(bind_str).ok_or_else(&invalid)?;
+        let values: Vec<&str> = target.split(':').collect();
+        if !(values.len() == 3 || values.len() == 2) {
+            return Err(invalid());
         }
         let mut bind = ServiceBind::default();
+        bind.tags = tags
+            .into_iter()
+            .map(|(key, value)| match value {
+                Some(value) => format!("{}={}", key, value),
+                None => key,
+            })
+            .collect();
         if values.len() == 3 {
             bind.name = values[1].to_string();
             bind.service_group = ServiceGroup::from_str(values[2])?;
@@ -186,6 +244,32 @@ impl FromStr for ServiceBind {
     }
 }
 
+/// Splits a binding such as `name:service.group[zone,rack=12]` into its target and the entries of
+/// its tag filter. Returns `None` if the tag filter is malformed.
+pub fn split_bind_tags(bind_str: &str) -> Option<(&str, Vec<(String, Option<String>)>)> {
+    match bind_str.find('[') {
+        Some(idx) if bind_str.ends_with(']') => {
+            let tags = bind_str[idx + 1..bind_str.len() - 1]
+                .split(',')
+                .map(parse_bind_tag)
+                .collect::<Option<Vec<_>>>()?;
+            Some((&bind_str[..idx], tags))
+        }
+        Some(_) => None,
+        None => Some((bind_str, vec![])),
+    }
+}
+
+/// Parses one entry of a bind's tag filter, either `key=value` or a bare `key`.
+pub fn parse_bind_tag(tag: &str) -> Option<(String, Option<String>)> {
+    let mut parts = tag.trim().splitn(2, '=');
+    let key = parts.next().unwrap_or("").trim();
+    if key.is_empty() {
+        return None;
+    }
+    Some((key.to_string(), parts.next().map(|v| v.trim().to_string())))
+}
+
 impl FromStr for ServiceGroup {
     type Err = NetErr;
 
@@ -360,6 +444,7 @@ impl UpdateStrategy {
             UpdateStrategy::None => "none",
             UpdateStrategy::AtOnce => "at-once",
             UpdateStrategy::Rolling => "rolling",
+            UpdateStrategy::Canary => "canary",
         }
     }
 }
@@ -372,6 +457,7 @@ impl FromStr for UpdateStrategy {
             "none" => Ok(UpdateStrategy::None),
             "at-once" => Ok(UpdateStrategy::AtOnce),
             "rolling" => Ok(UpdateStrategy::Rolling),
+            "canary" => Ok(UpdateStrategy::Canary),
             _ => Err(net::err(
                 ErrCode::InvalidPayload,
                 "Invalid update strategy.",
@@ -386,6 +472,290 @@ impl fmt::Display for UpdateStrategy {
     }
 }
 
+/// The number of members of a service group which take a new package first under the `Canary`
+/// update strategy, given either as a count or as a percentage of the group.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CanarySize {
+    Count(u32),
+    Percent(u32),
+}
+
+impl CanarySize {
+    /// The number of canaries to use in a group of the given population. There is always at
+    /// least one canary, and never more than the population itself.
+    pub fn members(&self, population: usize) -> usize {
+        let size = match *self {
+            CanarySize::Count(count) => count as usize,
+            CanarySize::Percent(percent) => (population * percent as usize + 99) / 100,
+        };
+        size.max(1).min(population)
+    }
+}
+
+impl Default for CanarySize {
+    fn default() -> Self {
+        CanarySize::Count(1)
+    }
+}
+
+impl FromStr for CanarySize {
+    type Err = NetErr;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let size = if value.ends_with('%') {
+            value[..value.len() - 1]
+                .parse::<u32>()
+                .ok()
+                .and_then(|percent| match percent {
+                    1..=100 => Some(CanarySize::Percent(percent)),
+                    _ => None,
+                })
+        } else {
+            value
+                .parse::<u32>()
+                .ok()
+                .and_then(|count| match count {
+                    0 => None,
+                    _ => Some(CanarySize::Count(count)),
+                })
+        };
+        size.ok_or(net::err(
+            ErrCode::InvalidPayload,
+            format!(
+                "Invalid canary size \"{}\", must be a member count or a percentage.",
+                value
+            ),
+        ))
+    }
+}
+
+impl fmt::Display for CanarySize {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CanarySize::Count(count) => write!(f, "{}", count),
+            CanarySize::Percent(percent) => write!(f, "{}%", percent),
+        }
+    }
+}
+
+/// A cron-style schedule of the minutes, in UTC, during which a service may be updated.
+///
+/// The five fields are minute (0-59), hour (0-23), day of the month (1-31), month (1-12) and
+/// day of the week (0-7, where both 0 and 7 are Sunday). Each field is `*` or a list of values
+/// and ranges, any of which may carry a `/step`. For example, `* 2-4 * * 6` allows updates from
+/// 02:00 until 04:59 every Saturday. As with cron, when both the day of the month and the day
+/// of the week are restricted, a day matching either is allowed.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MaintenanceWindow {
+    expr: String,
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    any_day: bool,
+    any_weekday: bool,
+}
+
+impl MaintenanceWindow {
+    /// Whether the given time falls within the window. `month` counts from 1 and `weekday`
+    /// from 0 for Sunday.
+    pub fn contains(&self, minute: u32, hour: u32, day: u32, month: u32, weekday: u32) -> bool {
+        let matches = |set: u64, value: u32| value < 64 && set & (1 << value) != 0;
+        let day_matches = match (self.any_day, self.any_weekday) {
+            (true, true) => true,
+            (false, true) => matches(self.days, day),
+            (true, false) => matches(self.weekdays, weekday),
+            (false, false) => matches(self.days, day) || matches(self.weekdays, weekday),
+        };
+        day_matches
+            && matches(self.minutes, minute)
+            && matches(self.hours, hour)
+            && matches(self.months, month)
+    }
+
+    /// Parse a single field into a set of values, represented as a bit mask.
+    fn parse_field(field: &str, min: u32, max: u32) -> Option<u64> {
+        let mut set = 0;
+        for part in field.split(',') {
+            let mut pieces = part.splitn(2, '/');
+            let range = pieces.next().unwrap_or_default();
+            let step = match pieces.next() {
+                Some(step) => step.parse::<u32>().ok().and_then(|step| match step {
+                    0 => None,
+                    step => Some(step),
+                })?,
+                None => 1,
+            };
+            let (start, end) = if range == "*" {
+                (min, max)
+            } else {
+                let mut bounds = range.splitn(2, '-');
+                let start = bounds.next().unwrap_or_default().parse::<u32>().ok()?;
+                let end = match bounds.next() {
+                    Some(end) => end.parse::<u32>().ok()?,
+                    None if step > 1 => max,
+                    None => start,
+                };
+                (start, end)
+            };
+            if start < min || end > max || start > end {
+                return None;
+            }
+            let mut value = start;
+            while value <= end {
+                set |= 1 << value;
+                value += step;
+            }
+        }
+        Some(set)
+    }
+}
+
+impl FromStr for MaintenanceWindow {
+    type Err = NetErr;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let err = || {
+            net::err(
+                ErrCode::InvalidPayload,
+                format!(
+                    "Invalid maintenance window \"{}\", must be a cron-style schedule of five \
+                     fields: minute, hour, day of month, month and day of week.",
+                    value
+                ),
+            )
+        };
+        let fields: Vec<&str> = value.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(err());
+        }
+        let mut weekdays = Self::parse_field(fields[4], 0, 7).ok_or_else(err)?;
+        // Sunday may be given as either 0 or 7
+        if weekdays & (1 << 7) != 0 {
+            weekdays = (weekdays | 1) & !(1 << 7);
+        }
+        Ok(MaintenanceWindow {
+            expr: fields.join(" "),
+            minutes: Self::parse_field(fields[0], 0, 59).ok_or_else(err)?,
+            hours: Self::parse_field(fields[1], 0, 23).ok_or_else(err)?,
+            days: Self::parse_field(fields[2], 1, 31).ok_or_else(err)?,
+            months: Self::parse_field(fields[3], 1, 12).ok_or_else(err)?,
+            weekdays: weekdays,
+            any_day: fields[2] == "*",
+            any_weekday: fields[4] == "*",
+        })
+    }
+}
+
+impl fmt::Display for MaintenanceWindow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.expr)
+    }
+}
+
+impl Serialize for MaintenanceWindow {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.expr)
+    }
+}
+
+impl<'de> Deserialize<'de> for MaintenanceWindow {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+        MaintenanceWindow::from_str(&value).map_err(de::Error::custom)
+    }
+}
+
+/// Restricts the package versions a service may be updated to, such as `1.2.x` to stay on the
+/// 1.2 series.
+///
+/// Each dot-separated component is either matched exactly or, when given as `x` or `*`,
+/// matches anything. A trailing wildcard also matches any further components.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct VersionConstraint {
+    expr: String,
+    components: Vec<Option<String>>,
+}
+
+impl VersionConstraint {
+    pub fn matches(&self, version: &str) -> bool {
+        let parts: Vec<&str> = version.split('.').collect();
+        let open_ended = self.components.last().map_or(false, Option::is_none);
+        for (i, component) in self.components.iter().enumerate() {
+            match (component, parts.get(i)) {
+                (&Some(ref expected), Some(part)) if expected.as_str() == *part => (),
+                (&None, Some(_)) => (),
+                (&None, None) if open_ended && i == self.components.len() - 1 => (),
+                _ => return false,
+            }
+        }
+        open_ended || parts.len() == self.components.len()
+    }
+}
+
+impl FromStr for VersionConstraint {
+    type Err = NetErr;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let components: Vec<Option<String>> = value
+            .trim()
+            .split('.')
+            .map(|component| match component {
+                "x" | "X" | "*" => None,
+                component => Some(component.to_string()),
+            }).collect();
+        if components
+            .iter()
+            .any(|c| c.as_ref().map_or(false, |c| c.is_empty() || c.contains('/')))
+        {
+            return Err(net::err(
+                ErrCode::InvalidPayload,
+                format!(
+                    "Invalid version constraint \"{}\", must be a version such as 1.2.3 with \
+                     any component replaced by x.",
+                    value
+                ),
+            ));
+        }
+        Ok(VersionConstraint {
+            expr: value.trim().to_string(),
+            components: components,
+        })
+    }
+}
+
+impl fmt::Display for VersionConstraint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.expr)
+    }
+}
+
+impl Serialize for VersionConstraint {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.expr)
+    }
+}
+
+impl<'de> Deserialize<'de> for VersionConstraint {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+        VersionConstraint::from_str(&value).map_err(de::Error::custom)
+    }
+}
+
 #[cfg(test)]
 mod test {
     extern crate toml;
@@ -394,6 +764,32 @@ mod test {
 
     use super::*;
 
+    #[test]
+    fn signal_from_str() {
+        assert_eq!(Signal::from_str("TERM").unwrap(), Signal::Term);
+        assert_eq!(Signal::from_str("sigusr1").unwrap(), Signal::Usr1);
+        assert_eq!(Signal::from_str("SIGHUP").unwrap(), Signal::Hup);
+        assert!(Signal::from_str("STOP").is_err());
+        assert_eq!(
+            Signal::from_str(&Signal::Quit.to_string()).unwrap(),
+            Signal::Quit
+        );
+    }
+
+    #[test]
+    fn service_bind_from_str_with_tags() {
+        let bind = ServiceBind::from_str("name:service.group[zone, rack=12]").unwrap();
+        assert_eq!(bind.name, "name");
+        assert_eq!(bind.service_group.to_string(), "service.group");
+        assert_eq!(bind.tags, vec!["zone".to_string(), "rack=12".to_string()]);
+        assert!(ServiceBind::from_str("name:service.group")
+            .unwrap()
+            .tags
+            .is_empty());
+        assert!(ServiceBind::from_str("name:service.group[]").is_err());
+        assert!(ServiceBind::from_str("name:service.group[zone").is_err());
+    }
+
     #[test]
     fn topology_default() {
         // This should always be the default topology, if this default gets changed, we have
@@ -507,4 +903,85 @@ mod test {
 
         assert!(toml.starts_with(r#"key = "at-once""#));
     }
+
+    #[test]
+    fn canary_size_from_str() {
+        assert_eq!(CanarySize::from_str("2").unwrap(), CanarySize::Count(2));
+        assert_eq!(CanarySize::from_str("25%").unwrap(), CanarySize::Percent(25));
+    }
+
+    #[test]
+    fn canary_size_from_str_invalid() {
+        assert!(CanarySize::from_str("0").is_err());
+        assert!(CanarySize::from_str("0%").is_err());
+        assert!(CanarySize::from_str("101%").is_err());
+        assert!(CanarySize::from_str("dope").is_err());
+    }
+
+    #[test]
+    fn canary_size_to_string() {
+        assert_eq!("3", CanarySize::Count(3).to_string());
+        assert_eq!("10%", CanarySize::Percent(10).to_string());
+    }
+
+    #[test]
+    fn maintenance_window_contains() {
+        let window = MaintenanceWindow::from_str("*/15 2-4 * * 6,7").unwrap();
+        // Saturday, 02:30
+        assert!(window.contains(30, 2, 10, 3, 6));
+        // Sunday, given as 7, 04:45
+        assert!(window.contains(45, 4, 11, 3, 0));
+        assert!(!window.contains(31, 2, 10, 3, 6));
+        assert!(!window.contains(30, 5, 10, 3, 6));
+        assert!(!window.contains(30, 2, 12, 3, 1));
+    }
+
+    #[test]
+    fn maintenance_window_days_match_either_field() {
+        let window = MaintenanceWindow::from_str("0 0 1 * 1").unwrap();
+        assert!(window.contains(0, 0, 1, 5, 3));
+        assert!(window.contains(0, 0, 7, 5, 1));
+        assert!(!window.contains(0, 0, 7, 5, 3));
+    }
+
+    #[test]
+    fn maintenance_window_from_str_invalid() {
+        assert!(MaintenanceWindow::from_str("* * * *").is_err());
+        assert!(MaintenanceWindow::from_str("60 * * * *").is_err());
+        assert!(MaintenanceWindow::from_str("* 4-2 * * *").is_err());
+        assert!(MaintenanceWindow::from_str("*/0 * * * *").is_err());
+        assert!(MaintenanceWindow::from_str("* * 0 * *").is_err());
+        assert!(MaintenanceWindow::from_str("* * * * sat").is_err());
+    }
+
+    #[test]
+    fn version_constraint_matches() {
+        let constraint = VersionConstraint::from_str("1.2.x").unwrap();
+        assert!(constraint.matches("1.2"));
+        assert!(constraint.matches("1.2.0"));
+        assert!(constraint.matches("1.2.10.1"));
+        assert!(!constraint.matches("1.3.0"));
+        assert!(!constraint.matches("1.20.0"));
+
+        let constraint = VersionConstraint::from_str("1.2.3").unwrap();
+        assert!(constraint.matches("1.2.3"));
+        assert!(!constraint.matches("1.2.3.1"));
+        assert!(!constraint.matches("1.2"));
+    }
+
+    #[test]
+    fn version_constraint_from_str_invalid() {
+        assert!(VersionConstraint::from_str("1..x").is_err());
+        assert!(VersionConstraint::from_str("").is_err());
+        assert!(VersionConstraint::from_str("core/redis").is_err());
+    }
+
+    #[test]
+    fn canary_size_members() {
+        assert_eq!(CanarySize::Count(2).members(5), 2);
+        assert_eq!(CanarySize::Count(8).members(5), 5);
+        assert_eq!(CanarySize::Percent(25).members(10), 3);
+        assert_eq!(CanarySize::Percent(1).members(10), 1);
+        assert_eq!(CanarySize::Percent(100).members(10), 10);
+    }
 }
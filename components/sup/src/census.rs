@@ -34,6 +34,7 @@ use serde::{Serialize, Serializer};
 use toml;
 
 use error::{Error, SupError};
+use manager::service::HealthCheck;
 
 static LOGKEY: &'static str = "CE";
 
@@ -535,9 +536,16 @@ impl CensusGroup {
     /// what the group exports. Until that time, the best we can do is
     /// ask an active member what *they* export (if there is a leader,
     /// though, we'll just ask them).
-    pub fn group_exports<'a>(&'a self) -> Result<HashSet<&'a String>, SupError> {
+    ///
+    /// Only members for which `matches` returns true are asked, such
+    /// as those satisfying a bind's tag filter.
+    pub fn group_exports<'a, F>(&'a self, matches: F) -> Result<HashSet<&'a String>, SupError>
+    where
+        F: Fn(&CensusMember) -> bool,
+    {
         self.leader()
-            .or_else(|| self.active_members().first().map(|m| *m))
+            .filter(|m| matches(m))
+            .or_else(|| self.active_members().into_iter().find(|m| matches(m)))
             .ok_or(sup_error!(Error::NoActiveMembers(
                 self.service_group.clone()
             ))).map(|m| m.cfg.keys().collect())
@@ -597,11 +605,19 @@ pub struct CensusMember {
     pub update_election_is_no_quorum: bool,
     pub update_election_is_finished: bool,
     pub sys: SysInfo,
+    /// A package which failed its smoke test on this member, causing
+    /// it to roll back to its previous package.
+    pub failed_pkg: Option<PackageIdent>,
+    /// The most recent health check status of the service on this
+    /// member.
+    pub health_check: HealthCheck,
 
     alive: bool,
     suspect: bool,
     confirmed: bool,
     departed: bool,
+    /// The tags of the member's Supervisor, e.g. `zone=us-east-1a`.
+    pub tags: BTreeMap<String, String>,
     // Maps must be represented last in a serializable struct for the current version of the toml
     // crate. Additionally, this deserialization method is required to correct any ordering issues
     // with the table being serialized - https://docs.rs/toml/0.4.0/toml/ser/fn.tables_last.html
@@ -626,6 +642,23 @@ impl CensusMember {
             Err(err) => warn!("Received a bad package ident from gossip data, err={}", err),
         };
         self.sys = rumor.sys.clone().into();
+        self.failed_pkg = match rumor.failed_pkg {
+            Some(ref pkg) => match PackageIdent::from_str(pkg) {
+                Ok(ident) => Some(ident),
+                Err(err) => {
+                    warn!("Received a bad package ident from gossip data, err={}", err);
+                    None
+                }
+            },
+            None => None,
+        };
+        self.health_check = match rumor.health_check {
+            Some(ref status) => HealthCheck::from_str(status).unwrap_or_else(|err| {
+                warn!("Received a bad health check from gossip data, err={}", err);
+                HealthCheck::default()
+            }),
+            None => HealthCheck::default(),
+        };
         self.cfg = toml::from_slice(&rumor.cfg).unwrap_or(toml::value::Table::default());
     }
 
@@ -665,6 +698,7 @@ impl CensusMember {
         self.sys.gossip_ip = member.address.to_string();
         self.sys.gossip_port = member.gossip_port as u32;
         self.persistent = true;
+        self.tags = member.tags.clone();
     }
 
     fn update_from_health(&mut self, health: Health) {
@@ -712,7 +746,7 @@ impl<'a> Serialize for CensusMemberProxy<'a> {
     where
         S: Serializer,
     {
-        let mut strukt = serializer.serialize_struct("census_member", 24)?;
+        let mut strukt = serializer.serialize_struct("census_member", 27)?;
         strukt.serialize_field("member_id", &self.0.member_id)?;
         strukt.serialize_field("pkg", &self.0.pkg)?;
 
@@ -728,6 +762,7 @@ impl<'a> Serialize for CensusMemberProxy<'a> {
         strukt.serialize_field("group", &self.0.group)?;
         strukt.serialize_field("org", &self.0.org)?;
         strukt.serialize_field("persistent", &self.0.persistent)?;
+        strukt.serialize_field("tags", &self.0.tags)?;
         strukt.serialize_field("leader", &self.0.leader)?;
         strukt.serialize_field("follower", &self.0.follower)?;
         strukt.serialize_field("update_leader", &self.0.update_leader)?;
@@ -748,6 +783,8 @@ impl<'a> Serialize for CensusMemberProxy<'a> {
             &self.0.update_election_is_finished,
         )?;
         strukt.serialize_field("sys", &self.0.sys)?;
+        strukt.serialize_field("failed_pkg", &self.0.failed_pkg)?;
+        strukt.serialize_field("health_check", &self.0.health_check)?;
         strukt.serialize_field("alive", &self.0.alive)?;
         strukt.serialize_field("suspect", &self.0.suspect)?;
         strukt.serialize_field("confirmed", &self.0.confirmed)?;
@@ -774,7 +811,7 @@ mod tests {
 
     use serde_json;
 
-    use butterfly::member::{Health, MemberList};
+    use butterfly::member::{Health, Member, MemberList};
     use butterfly::rumor::election::Election as ElectionRumor;
     use butterfly::rumor::election::ElectionUpdate as ElectionUpdateRumor;
     use butterfly::rumor::service::Service as ServiceRumor;
@@ -807,6 +844,8 @@ mod tests {
         let members = census_group_two.members();
         assert_eq!(members[0].member_id, "member-a");
         assert_eq!(members[1].member_id, "member-b");
+        assert_eq!(members[0].tags.get("zone").unwrap(), "us-east-1a");
+        assert!(members[1].tags.is_empty());
     }
 
     #[test]
@@ -872,6 +911,12 @@ mod tests {
         election_update_store.insert(election_update);
 
         let member_list = MemberList::new();
+        let mut member = Member::default();
+        member.id = "member-a".to_string();
+        member
+            .tags
+            .insert("zone".to_string(), "us-east-1a".to_string());
+        member_list.insert(member, Health::Alive);
 
         let service_config_store: RumorStore<ServiceConfigRumor> = RumorStore::default();
         let service_file_store: RumorStore<ServiceFileRumor> = RumorStore::default();
@@ -910,10 +955,13 @@ mod tests {
             update_election_is_no_quorum: false,
             update_election_is_finished: false,
             sys: SysInfo::default(),
+            failed_pkg: None,
+            health_check: HealthCheck::default(),
             alive: health == Health::Alive,
             suspect: health == Health::Suspect,
             confirmed: health == Health::Confirmed,
             departed: health == Health::Departed,
+            tags: BTreeMap::new(),
             cfg: BTreeMap::new(),
         }
     }
@@ -944,4 +992,34 @@ mod tests {
         assert_eq!(active_members[1].member_id, "suspect-one");
     }
 
+    #[test]
+    fn group_exports_only_asks_matching_members() {
+        let mut east = test_census_member("east-one".to_string(), Health::Alive);
+        east.tags.insert("zone".to_string(), "east".to_string());
+        east.cfg
+            .insert("port".to_string(), toml::Value::Integer(5432));
+        let mut west = test_census_member("west-one".to_string(), Health::Alive);
+        west.tags.insert("zone".to_string(), "west".to_string());
+        west.cfg
+            .insert("url".to_string(), toml::Value::String("west".to_string()));
+
+        let sg: ServiceGroup = "test-service.default"
+            .parse()
+            .expect("This should be a valid service group");
+        let mut census_group = CensusGroup::new(sg, &"east-one".to_string());
+        for member in vec![east, west] {
+            census_group
+                .population
+                .insert(member.member_id.clone(), member);
+        }
+
+        let exports = census_group
+            .group_exports(|m| m.tags.get("zone").map(String::as_str) == Some("west"))
+            .unwrap();
+        assert_eq!(exports.len(), 1);
+        assert!(exports.contains(&"url".to_string()));
+        assert!(census_group
+            .group_exports(|m| m.tags.contains_key("rack"))
+            .is_err());
+    }
 }
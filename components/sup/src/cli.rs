@@ -1,8 +1,8 @@
 use clap::App;
 
 use hab::cli::{
-    sub_sup_bash, sub_sup_depart, sub_sup_run, sub_sup_secret, sub_sup_sh, sub_sup_term,
-    sub_svc_status,
+    sub_sup_audit, sub_sup_bash, sub_sup_depart, sub_sup_run, sub_sup_secret, sub_sup_sh,
+    sub_sup_term, sub_svc_status,
 };
 use VERSION;
 
@@ -20,6 +20,7 @@ pub fn cli<'a, 'b>() -> App<'a, 'b> {
         // this is the _full_ list of supervisor related cmds
         // they are all enumerated here so that the entire help menu
         // can be displayed from `hab sup --help`
+        (subcommand: sub_sup_audit().aliases(&["au", "aud", "audi"]))
         (subcommand: sub_sup_bash().aliases(&["b", "ba", "bas"]))
         (subcommand: sub_sup_depart().aliases(&["d", "de", "dep", "depa", "depart"]))
         (subcommand: sub_sup_run().aliases(&["r", "ru"]))
@@ -72,6 +73,10 @@ mod test {
                         "BIND" => ["service.group1", "service.group2"],
                         "PKG_IDENT_OR_ARTIFACT" => "core/redis");
 
+        assert_cli_cmd!(should_handle_multiple_tag_flags,
+                        "hab-sup run --tag zone=us-east-1a --tag rack=12",
+                        "TAG" => ["zone=us-east-1a", "rack=12"]);
+
     }
 
 }
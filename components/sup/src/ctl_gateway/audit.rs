@@ -0,0 +1,462 @@
+// Copyright (c) 2018 Chef Software Inc. and/or applicable contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! An append-only record, one JSON object per line, of requests made to the CtlGateway and to the
+//! HTTPGateway's control endpoints.
+//!
+//! Every request which changes the Supervisor's state is recorded along with its outcome, as is
+//! every request refused for lack of the required role. The log is rotated once it reaches
+//! `MAX_LOG_SIZE`, keeping `MAX_ROTATED_LOGS` previous logs alongside it.
+
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+use hcore::package::PackageIdent;
+use hcore::service::ServiceGroup;
+use protocol;
+use protocol::codec::SrvMessage;
+use protocol::net::NetResult;
+use serde_json;
+use time;
+
+use error::{Error, Result};
+use manager::{Manager, ManagerConfig};
+
+/// Name of the audit log in the Supervisor's root directory.
+pub const AUDIT_LOG_FILENAME: &'static str = "AUDIT_LOG";
+/// Size in bytes at which the audit log is rotated.
+pub const MAX_LOG_SIZE: u64 = 10 * 1024 * 1024;
+/// Number of rotated audit logs to keep.
+pub const MAX_ROTATED_LOGS: usize = 5;
+
+/// Messages which change the Supervisor's state and are recorded whenever they are run.
+const MUTATIONS: &'static [&'static str] = &[
+    "SvcSetCfg",
+    "SvcFilePut",
+    "SvcLoad",
+    "SvcUnload",
+    "SvcStart",
+    "SvcStop",
+    "SvcSignal",
+    "SvcExec",
+    "SvcGroupForget",
+    "SupDepart",
+    "RingKeyRotate",
+];
+
+/// Returns true if every request with the given message ID is recorded in the audit log.
+pub fn is_mutation(message_id: &str) -> bool {
+    MUTATIONS.contains(&message_id)
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Outcome {
+    /// The request was run successfully.
+    Succeeded,
+    /// The request was run but returned an error.
+    Failed,
+    /// The request was refused because the client's credential lacks the required role.
+    Refused,
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let value = match *self {
+            Outcome::Succeeded => "succeeded",
+            Outcome::Failed => "failed",
+            Outcome::Refused => "refused",
+        };
+        write!(f, "{}", value)
+    }
+}
+
+impl FromStr for Outcome {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.to_lowercase().as_ref() {
+            "succeeded" => Ok(Outcome::Succeeded),
+            "failed" => Ok(Outcome::Failed),
+            "refused" => Ok(Outcome::Refused),
+            _ => Err(sup_error!(Error::BadAuditOutcome(value.to_string()))),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AuditEntry {
+    pub timestamp: String,
+    pub peer: String,
+    pub credential: String,
+    pub message: String,
+    /// The package the request targets, for requests which name a package.
+    pub ident: Option<String>,
+    /// The service groups the request targets. Requests naming a package target the service
+    /// groups of the services loaded for it.
+    pub service_groups: Vec<String>,
+    pub outcome: Outcome,
+    /// Why the request failed or was refused.
+    pub error: Option<String>,
+}
+
+impl AuditEntry {
+    /// Create an entry for the given request, assuming it succeeds.
+    pub fn new(peer: &SocketAddr, credential: &str, msg: &SrvMessage) -> Self {
+        let (ident, service_group) = target(msg);
+        AuditEntry {
+            timestamp: now(),
+            peer: peer.to_string(),
+            credential: credential.to_string(),
+            message: msg.message_id().to_string(),
+            ident: ident,
+            service_groups: service_group.into_iter().collect(),
+            outcome: Outcome::Succeeded,
+            error: None,
+        }
+    }
+
+    /// Create an entry for a request addressing a service by its service group rather than by
+    /// its package, assuming it succeeds.
+    pub fn for_service_group(
+        peer: &SocketAddr,
+        credential: &str,
+        message_id: &str,
+        service_group: &ServiceGroup,
+    ) -> Self {
+        AuditEntry {
+            timestamp: now(),
+            peer: peer.to_string(),
+            credential: credential.to_string(),
+            message: message_id.to_string(),
+            ident: None,
+            service_groups: vec![service_group.to_string()],
+            outcome: Outcome::Succeeded,
+            error: None,
+        }
+    }
+
+    /// Mark the request as refused for the given reason.
+    pub fn refused(mut self, reason: String) -> Self {
+        self.timestamp = now();
+        self.outcome = Outcome::Refused;
+        self.error = Some(reason);
+        self
+    }
+
+    /// Record the result of running the request.
+    pub fn completed(&mut self, result: &NetResult<()>) {
+        self.timestamp = now();
+        match *result {
+            Ok(()) => self.outcome = Outcome::Succeeded,
+            Err(ref err) => {
+                self.outcome = Outcome::Failed;
+                self.error = Some(err.msg.clone());
+            }
+        }
+    }
+
+    /// Fill in the service groups targeted by a request naming a package, if they aren't known
+    /// yet, from the services currently loaded for it.
+    pub fn resolve_service_groups(&mut self, cfg: &ManagerConfig) {
+        if !self.service_groups.is_empty() {
+            return;
+        }
+        if let Some(ident) = self
+            .ident
+            .as_ref()
+            .and_then(|ident| PackageIdent::from_str(ident).ok())
+        {
+            self.service_groups = Manager::service_groups_for_ident(cfg, &ident)
+                .iter()
+                .map(ToString::to_string)
+                .collect();
+        }
+    }
+}
+
+impl fmt::Display for AuditEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {}",
+            self.timestamp, self.peer, self.credential, self.message
+        )?;
+        if let Some(ref ident) = self.ident {
+            write!(f, " {}", ident)?;
+        }
+        if !self.service_groups.is_empty() {
+            write!(f, " [{}]", self.service_groups.join(", "))?;
+        }
+        write!(f, " {}", self.outcome)?;
+        if let Some(ref error) = self.error {
+            write!(f, ": {}", error)?;
+        }
+        Ok(())
+    }
+}
+
+/// The package and service group named by a request, where it names them.
+fn target(msg: &SrvMessage) -> (Option<String>, Option<String>) {
+    fn ident(ident: Option<protocol::types::PackageIdent>) -> (Option<String>, Option<String>) {
+        (ident.map(|ident| ident.to_string()), None)
+    }
+    fn group(group: Option<protocol::types::ServiceGroup>) -> (Option<String>, Option<String>) {
+        (None, group.map(|group| group.to_string()))
+    }
+
+    match msg.message_id() {
+        "SvcLoad" => msg
+            .parse::<protocol::ctl::SvcLoad>()
+            .ok()
+            .map_or((None, None), |m| ident(m.ident)),
+        "SvcUnload" => msg
+            .parse::<protocol::ctl::SvcUnload>()
+            .ok()
+            .map_or((None, None), |m| ident(m.ident)),
+        "SvcStart" => msg
+            .parse::<protocol::ctl::SvcStart>()
+            .ok()
+            .map_or((None, None), |m| ident(m.ident)),
+        "SvcStop" => msg
+            .parse::<protocol::ctl::SvcStop>()
+            .ok()
+            .map_or((None, None), |m| ident(m.ident)),
+        "SvcSignal" => msg
+            .parse::<protocol::ctl::SvcSignal>()
+            .ok()
+            .map_or((None, None), |m| ident(m.ident)),
+        "SvcExec" => msg
+            .parse::<protocol::ctl::SvcExec>()
+            .ok()
+            .map_or((None, None), |m| ident(m.ident)),
+        "SvcSetCfg" => msg
+            .parse::<protocol::ctl::SvcSetCfg>()
+            .ok()
+            .map_or((None, None), |m| group(m.service_group)),
+        "SvcFilePut" => msg
+            .parse::<protocol::ctl::SvcFilePut>()
+            .ok()
+            .map_or((None, None), |m| group(m.service_group)),
+        "SvcGroupForget" => msg
+            .parse::<protocol::ctl::SvcGroupForget>()
+            .ok()
+            .map_or((None, None), |m| group(m.service_group)),
+        _ => (None, None),
+    }
+}
+
+fn now() -> String {
+    time::now_utc().rfc3339().to_string()
+}
+
+/// Selects the entries of the audit log to display.
+#[derive(Debug, Default)]
+pub struct AuditFilter {
+    pub service_group: Option<String>,
+    pub message: Option<String>,
+    pub credential: Option<String>,
+    pub outcome: Option<Outcome>,
+    /// Only entries at or after this RFC 3339 timestamp.
+    pub since: Option<String>,
+}
+
+impl AuditFilter {
+    pub fn matches(&self, entry: &AuditEntry) -> bool {
+        self.service_group
+            .as_ref()
+            .map_or(true, |sg| entry.service_groups.contains(sg))
+            && self.message.as_ref().map_or(true, |m| *m == entry.message)
+            && self
+                .credential
+                .as_ref()
+                .map_or(true, |c| *c == entry.credential)
+            && self.outcome.map_or(true, |o| o == entry.outcome)
+            && self
+                .since
+                .as_ref()
+                .map_or(true, |since| entry.timestamp >= *since)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct AuditLog {
+    path: PathBuf,
+    /// Serializes writes from the CtlGateway and the main thread so that rotation doesn't race
+    /// with appending.
+    lock: Arc<Mutex<()>>,
+}
+
+impl AuditLog {
+    pub fn new<T>(sup_root: T) -> Self
+    where
+        T: AsRef<Path>,
+    {
+        AuditLog {
+            path: sup_root.as_ref().join(AUDIT_LOG_FILENAME),
+            lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// Append an entry to the log. Failing to record an entry is logged but doesn't fail the
+    /// request being audited.
+    pub fn record(&self, entry: &AuditEntry) {
+        let mut line = match serde_json::to_string(entry) {
+            Ok(line) => line,
+            Err(err) => {
+                warn!("Unable to serialize audit entry {:?}, {}", entry, err);
+                return;
+            }
+        };
+        line.push('\n');
+        let _guard = self.lock.lock().expect("AuditLog lock is poisoned");
+        if let Err(err) = self.rotate(line.len() as u64) {
+            warn!("Unable to rotate audit log {}, {}", self.path.display(), err);
+        }
+        if let Err(err) = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut f| f.write_all(line.as_bytes()))
+        {
+            warn!(
+                "Unable to write audit entry to {}, {}",
+                self.path.display(),
+                err
+            );
+        }
+    }
+
+    /// Read every entry matching the given filter, oldest first.
+    pub fn read(&self, filter: &AuditFilter) -> Result<Vec<AuditEntry>> {
+        let mut entries = vec![];
+        for n in (0..MAX_ROTATED_LOGS + 1).rev() {
+            let path = self.rotated_path(n);
+            if !path.is_file() {
+                continue;
+            }
+            let file = File::open(&path)?;
+            for line in BufReader::new(file).lines() {
+                let line = line?;
+                match serde_json::from_str::<AuditEntry>(&line) {
+                    Ok(entry) => {
+                        if filter.matches(&entry) {
+                            entries.push(entry);
+                        }
+                    }
+                    Err(err) => debug!(
+                        "Skipping unreadable audit entry in {}, {}",
+                        path.display(),
+                        err
+                    ),
+                }
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Rotate the log if appending `len` more bytes would take it past `MAX_LOG_SIZE`.
+    fn rotate(&self, len: u64) -> io::Result<()> {
+        let size = match fs::metadata(&self.path) {
+            Ok(metadata) => metadata.len(),
+            Err(_) => return Ok(()),
+        };
+        if size + len <= MAX_LOG_SIZE {
+            return Ok(());
+        }
+        for n in (1..MAX_ROTATED_LOGS).rev() {
+            let from = self.rotated_path(n);
+            if from.exists() {
+                fs::rename(&from, self.rotated_path(n + 1))?;
+            }
+        }
+        fs::rename(&self.path, self.rotated_path(1))
+    }
+
+    /// The path of the log rotated `n` times ago, where `0` is the current log.
+    fn rotated_path(&self, n: usize) -> PathBuf {
+        if n == 0 {
+            self.path.clone()
+        } else {
+            self.path.with_extension(n.to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use tempfile::TempDir;
+
+    fn entry(message: &str, service_group: &str, outcome: Outcome) -> AuditEntry {
+        AuditEntry {
+            timestamp: now(),
+            peer: "127.0.0.1:41234".to_string(),
+            credential: "default".to_string(),
+            message: message.to_string(),
+            ident: None,
+            service_groups: vec![service_group.to_string()],
+            outcome: outcome,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn record_and_read_entries() {
+        let tmp = TempDir::new().expect("create temp dir");
+        let log = AuditLog::new(tmp.path());
+        log.record(&entry("SvcStop", "redis.default", Outcome::Succeeded));
+        log.record(&entry("SvcSetCfg", "nginx.default", Outcome::Failed));
+        log.record(&entry("SvcStart", "redis.default", Outcome::Refused));
+
+        let all = log.read(&AuditFilter::default()).unwrap();
+        let messages: Vec<&str> = all.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(messages, vec!["SvcStop", "SvcSetCfg", "SvcStart"]);
+
+        let filter = AuditFilter {
+            service_group: Some("redis.default".to_string()),
+            outcome: Some(Outcome::Refused),
+            ..Default::default()
+        };
+        let refused = log.read(&filter).unwrap();
+        assert_eq!(refused.len(), 1);
+        assert_eq!(refused[0].message, "SvcStart");
+    }
+
+    #[test]
+    fn rotated_logs_are_read_oldest_first() {
+        let tmp = TempDir::new().expect("create temp dir");
+        let log = AuditLog::new(tmp.path());
+        log.record(&entry("SvcStop", "redis.default", Outcome::Succeeded));
+        fs::rename(log.rotated_path(0), log.rotated_path(1)).unwrap();
+        log.record(&entry("SvcStart", "redis.default", Outcome::Succeeded));
+
+        let all = log.read(&AuditFilter::default()).unwrap();
+        let messages: Vec<&str> = all.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(messages, vec!["SvcStop", "SvcStart"]);
+    }
+
+    #[test]
+    fn outcome_from_str() {
+        assert_eq!(Outcome::from_str("refused").unwrap(), Outcome::Refused);
+        assert_eq!(Outcome::from_str("Succeeded").unwrap(), Outcome::Succeeded);
+        assert!(Outcome::from_str("maybe").is_err());
+    }
+}
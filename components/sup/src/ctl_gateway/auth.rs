@@ -0,0 +1,239 @@
+// Copyright (c) 2018 Chef Software Inc. and/or applicable contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Named credentials for authenticating clients of the CtlGateway and the roles they grant.
+//!
+//! The Supervisor's secret key is always accepted as the `default` credential with the `admin`
+//! role. Further credentials may be listed in a TOML file named `CTL_CREDENTIALS` in the
+//! Supervisor's root directory:
+//!
+//! ```toml
+//! [[credential]]
+//! name = "monitoring"
+//! secret = "<generated with `hab sup secret generate`>"
+//! role = "read-only"
+//! ```
+
+use std::fmt;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::result;
+
+use crypto;
+use toml;
+
+use error::{Error, Result};
+
+/// Name of the file in the Supervisor's root directory listing additional credentials.
+pub const CTL_CREDENTIALS_FILENAME: &'static str = "CTL_CREDENTIALS";
+/// Name of the credential backed by the Supervisor's secret key.
+pub const DEFAULT_CREDENTIAL: &'static str = "default";
+
+/// The operations a credential is allowed to perform. Each role permits everything the roles
+/// before it do.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Role {
+    /// May inspect services and their configuration.
+    ReadOnly,
+    /// May additionally start, stop and signal services and change their configuration.
+    Operator,
+    /// May additionally load and unload services, depart Supervisors and rotate the ring key.
+    Admin,
+}
+
+impl Role {
+    /// The role required to send a message with the given ID, or `None` if the message isn't a
+    /// request handled by the CtlGateway. Such messages are refused whatever the role, so a
+    /// request added to the CtlGateway must be given a role here before anyone may send it.
+    pub fn required_for(message_id: &str) -> Option<Role> {
+        match message_id {
+            "SvcStatus" | "SvcGetDefaultCfg" | "SvcValidateCfg" => Some(Role::ReadOnly),
+            "SvcStart" | "SvcStop" | "SvcSignal" | "SvcSetCfg" | "SvcFilePut" => {
+                Some(Role::Operator)
+            }
+            "SvcLoad" | "SvcUnload" | "SvcExec" | "SvcGroupForget" | "SupDepart"
+            | "RingKeyRotate" => Some(Role::Admin),
+            _ => None,
+        }
+    }
+
+    pub fn permits(&self, required: Role) -> bool {
+        *self >= required
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let value = match *self {
+            Role::ReadOnly => "read-only",
+            Role::Operator => "operator",
+            Role::Admin => "admin",
+        };
+        write!(f, "{}", value)
+    }
+}
+
+#[derive(Clone, Deserialize)]
+pub struct Credential {
+    pub name: String,
+    secret: String,
+    pub role: Role,
+}
+
+// The secret is deliberately left out so that credentials may be logged.
+impl fmt::Debug for Credential {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Credential")
+            .field("name", &self.name)
+            .field("role", &self.role)
+            .finish()
+    }
+}
+
+#[derive(Deserialize)]
+struct CredentialsFile {
+    #[serde(default)]
+    credential: Vec<Credential>,
+}
+
+#[derive(Debug)]
+pub struct Credentials(Vec<Credential>);
+
+impl Credentials {
+    /// Load the credentials accepted by the Supervisor running from `sup_root`.
+    pub fn load<T>(sup_root: T, secret_key: String) -> Result<Self>
+    where
+        T: AsRef<Path>,
+    {
+        let path = sup_root.as_ref().join(CTL_CREDENTIALS_FILENAME);
+        if !path.is_file() {
+            return Ok(Self::from_toml("", secret_key).expect("empty credentials are valid"));
+        }
+        let mut contents = String::new();
+        File::open(&path)
+            .and_then(|mut f| f.read_to_string(&mut contents))
+            .map_err(|e| sup_error!(Error::CtlCredentials(path.clone(), e.to_string())))?;
+        Self::from_toml(&contents, secret_key)
+            .map_err(|e| sup_error!(Error::CtlCredentials(path, e)))
+    }
+
+    fn from_toml(contents: &str, secret_key: String) -> result::Result<Self, String> {
+        let file: CredentialsFile = toml::from_str(contents).map_err(|e| e.to_string())?;
+        let mut credentials = vec![Credential {
+            name: DEFAULT_CREDENTIAL.to_string(),
+            secret: secret_key,
+            role: Role::Admin,
+        }];
+        for credential in file.credential {
+            if credential.secret.is_empty() {
+                return Err(format!("credential '{}' has an empty secret", credential.name));
+            }
+            if credentials.iter().any(|c| c.name == credential.name) {
+                return Err(format!("credential '{}' is defined twice", credential.name));
+            }
+            if credentials.iter().any(|c| c.secret == credential.secret) {
+                return Err(format!(
+                    "credential '{}' shares its secret with another credential",
+                    credential.name
+                ));
+            }
+            credentials.push(credential);
+        }
+        Ok(Credentials(credentials))
+    }
+
+    /// Find the credential with the given secret. Every credential is compared so that the time
+    /// taken doesn't reveal which one matched.
+    pub fn authenticate(&self, secret: &str) -> Option<&Credential> {
+        let mut found = None;
+        for credential in self.0.iter() {
+            if crypto::util::fixed_time_eq(credential.secret.as_bytes(), secret.as_bytes()) {
+                found = Some(credential);
+            }
+        }
+        found
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const CREDENTIALS: &'static str = r#"
+        [[credential]]
+        name = "monitoring"
+        secret = "watching"
+        role = "read-only"
+
+        [[credential]]
+        name = "deploy"
+        secret = "deploying"
+        role = "operator"
+        "#;
+
+    #[test]
+    fn roles_permit_lesser_roles() {
+        assert!(Role::Admin.permits(Role::Operator));
+        assert!(Role::Operator.permits(Role::ReadOnly));
+        assert!(Role::Operator.permits(Role::Operator));
+        assert!(!Role::Operator.permits(Role::Admin));
+        assert!(!Role::ReadOnly.permits(Role::Operator));
+    }
+
+    #[test]
+    fn required_roles() {
+        assert_eq!(Role::required_for("SvcStatus"), Some(Role::ReadOnly));
+        assert_eq!(Role::required_for("SvcSetCfg"), Some(Role::Operator));
+        assert_eq!(Role::required_for("SvcSignal"), Some(Role::Operator));
+        assert_eq!(Role::required_for("SvcExec"), Some(Role::Admin));
+        assert_eq!(Role::required_for("SvcGroupForget"), Some(Role::Admin));
+        assert_eq!(Role::required_for("SupDepart"), Some(Role::Admin));
+        assert_eq!(Role::required_for("RingKeyRotate"), Some(Role::Admin));
+        assert_eq!(Role::required_for("Handshake"), None);
+        assert_eq!(Role::required_for("SvcUnknown"), None);
+    }
+
+    #[test]
+    fn authenticate_named_credentials() {
+        let credentials = Credentials::from_toml(CREDENTIALS, "sekrit".to_string()).unwrap();
+        let default = credentials.authenticate("sekrit").unwrap();
+        assert_eq!(default.name, DEFAULT_CREDENTIAL);
+        assert_eq!(default.role, Role::Admin);
+        let monitoring = credentials.authenticate("watching").unwrap();
+        assert_eq!(monitoring.name, "monitoring");
+        assert_eq!(monitoring.role, Role::ReadOnly);
+        assert_eq!(credentials.authenticate("deploying").unwrap().role, Role::Operator);
+        assert!(credentials.authenticate("guessing").is_none());
+    }
+
+    #[test]
+    fn reject_ambiguous_credentials() {
+        let duplicate_name = r#"
+            [[credential]]
+            name = "default"
+            secret = "other"
+            role = "read-only"
+            "#;
+        assert!(Credentials::from_toml(duplicate_name, "sekrit".to_string()).is_err());
+        let duplicate_secret = r#"
+            [[credential]]
+            name = "monitoring"
+            secret = "sekrit"
+            role = "read-only"
+            "#;
+        assert!(Credentials::from_toml(duplicate_secret, "sekrit".to_string()).is_err());
+    }
+}
@@ -20,6 +20,8 @@
 //! The [`ctl_gateway.client`] and [`ctl_gateway.server`] speak a streaming, multiplexed, binary
 //! protocol defined in [`protocol.codec`].
 
+pub mod audit;
+pub mod auth;
 pub mod server;
 
 use std::borrow::Cow;
@@ -45,6 +47,11 @@ lazy_static! {
         .unwrap();
 }
 
+/// Remove any ANSI color codes from the given text.
+pub fn strip_ansi_codes(text: &str) -> Cow<str> {
+    STRIP_ANSI_CODES.replace_all(text, "")
+}
+
 /// Time to wait in milliseconds for a client connection to timeout.
 pub const REQ_TIMEOUT: u64 = 10_000;
 static LOGKEY: &'static str = "AG";
@@ -103,6 +110,11 @@ impl CtlRequest {
         self.send_msg(msg, true);
     }
 
+    /// Returns true if the client has hung up, so no more replies to the transaction can be sent.
+    pub fn is_abandoned(&self) -> bool {
+        self.tx.as_ref().map_or(false, |tx| tx.is_closed())
+    }
+
     /// Returns true if the request is transactional and false if not.
     pub fn transactional(&self) -> bool {
         self.transaction.is_some() && self.tx.is_some()
@@ -199,7 +211,7 @@ impl io::Write for CtlRequest {
         // that CtlRequest is sending output to two destinations with
         // different formatting requirements complicates things a bit.
         let maybe_stripped = if output::is_json() || !output::is_color() {
-            STRIP_ANSI_CODES.replace_all(&line, "")
+            strip_ansi_codes(&line)
         } else {
             Cow::Owned(line)
         };
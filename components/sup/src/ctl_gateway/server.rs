@@ -31,7 +31,6 @@ use std::rc::Rc;
 use std::thread;
 use std::time::Duration;
 
-use crypto;
 use futures::future::{self, Either};
 use futures::prelude::*;
 use futures::sync::mpsc;
@@ -39,12 +38,16 @@ use prost;
 use protocol;
 use protocol::codec::*;
 use protocol::net::{self, ErrCode, NetErr, NetResult};
+use protocol::tls::{self, CtlStream, SslAcceptor};
 use tokio::net::TcpListener;
 use tokio_codec::Framed;
 use tokio_core::reactor;
 
+use super::audit::{self, AuditEntry, AuditLog};
+use super::auth::{Credential, Credentials, Role};
 use super::{CtlRequest, REQ_TIMEOUT};
 use manager::{Manager, ManagerState};
+use metrics;
 
 /// Sending half of an mpsc unbounded channel used for sending replies for a transactional message
 /// from the main thread back to the CtlGateway. This half is stored in a
@@ -125,6 +128,8 @@ pub struct CtlCommand {
     //
     // https://github.com/rust-lang/rust/issues/28796
     fun: Box<Fn(&ManagerState, &mut CtlRequest) -> NetResult<()> + Send>,
+    /// Where to record the outcome of running the command, if it is audited.
+    audit: Option<(AuditLog, AuditEntry)>,
 }
 
 impl CtlCommand {
@@ -136,17 +141,36 @@ impl CtlCommand {
         CtlCommand {
             fun: Box::new(fun),
             req: CtlRequest::new(tx, txn),
+            audit: None,
         }
     }
 
+    /// Record the outcome of running this command in the given audit log.
+    pub fn audited(mut self, log: AuditLog, entry: AuditEntry) -> Self {
+        self.audit = Some((log, entry));
+        self
+    }
+
     /// Run the contained closure with the given [`manager.ManagerState`].
     pub fn run(&mut self, state: &ManagerState) -> NetResult<()> {
-        (self.fun)(state, &mut self.req)
+        // Service groups are resolved both before and after running so that those of services
+        // being unloaded and those of services being loaded are both found.
+        if let Some((_, ref mut entry)) = self.audit {
+            entry.resolve_service_groups(&state.cfg);
+        }
+        let result = (self.fun)(state, &mut self.req);
+        if let Some((ref log, ref mut entry)) = self.audit {
+            entry.resolve_service_groups(&state.cfg);
+            entry.completed(&result);
+            log.record(entry);
+        }
+        result
     }
 }
 
 /// Server's client representation. Each new connection will allocate a new Client.
 struct Client {
+    addr: SocketAddr,
     handle: reactor::Handle,
     state: Rc<RefCell<SrvState>>,
 }
@@ -155,16 +179,24 @@ impl Client {
     /// Serve the client from the given framed socket stream.
     pub fn serve(self, socket: SrvStream) -> Box<Future<Item = (), Error = HandlerError>> {
         let mgr_tx = self.state.borrow().mgr_tx.clone();
+        let audit_log = self.state.borrow().audit_log.clone();
+        let addr = self.addr;
         Box::new(
             self.handshake(socket)
-                .and_then(|socket| SrvHandler::new(socket, mgr_tx)),
+                .and_then(move |(socket, credential)| {
+                    SrvHandler::new(socket, mgr_tx, addr, credential, audit_log)
+                }),
         )
     }
 
     /// Initiate a handshake with the connected client before allowing future requests. A failed
-    /// handshake will close the connection.
-    fn handshake(&self, socket: SrvStream) -> Box<Future<Item = SrvStream, Error = HandlerError>> {
-        let secret_key = self.state.borrow().secret_key.to_string();
+    /// handshake will close the connection. A successful handshake resolves to the credential
+    /// the client authenticated with.
+    fn handshake(
+        &self,
+        socket: SrvStream,
+    ) -> Box<Future<Item = (SrvStream, Credential), Error = HandlerError>> {
+        let state = self.state.clone();
         let handshake = socket
             .into_future()
             .map_err(|(err, _)| HandlerError::from(err))
@@ -191,14 +223,12 @@ impl Client {
                             Ok(decoded) => {
                                 trace!("Received handshake, {:?}", decoded);
                                 let decoded_key = decoded.secret_key.unwrap_or_default();
-                                Ok((
-                                    m,
-                                    crypto::util::fixed_time_eq(
-                                        decoded_key.as_bytes(),
-                                        secret_key.as_bytes(),
-                                    ),
-                                    io,
-                                ))
+                                let credential = state
+                                    .borrow()
+                                    .credentials
+                                    .authenticate(&decoded_key)
+                                    .cloned();
+                                Ok((m, credential, io))
                             }
                             Err(err) => {
                                 warn!("Handshake error, {:?}", err);
@@ -209,8 +239,8 @@ impl Client {
                         }
                     },
                 )
-            }).and_then(|(msg, success, socket)| {
-                let mut reply = if success {
+            }).and_then(|(msg, credential, socket)| {
+                let mut reply = if credential.is_some() {
                     SrvMessage::from(net::ok())
                 } else {
                     SrvMessage::from(net::err(ErrCode::Unauthorized, "secret key mismatch"))
@@ -219,14 +249,17 @@ impl Client {
                 socket
                     .send(reply)
                     .map_err(HandlerError::from)
-                    .and_then(move |io| Ok((io, success)))
+                    .and_then(move |io| Ok((io, credential)))
             });
         Box::new(
             handshake
                 .select2(self.timeout(REQ_TIMEOUT))
                 .then(|res| match res {
-                    Ok(Either::A(((io, true), _to))) => future::ok(io),
-                    Ok(Either::A(((_, false), _to))) => future::err(HandlerError::from(
+                    Ok(Either::A(((io, Some(credential)), _to))) => {
+                        debug!("Client authenticated as {:?}", credential);
+                        future::ok((io, credential))
+                    }
+                    Ok(Either::A(((_, None), _to))) => future::err(HandlerError::from(
                         io::Error::new(io::ErrorKind::ConnectionAborted, "handshake failed"),
                     )),
                     Ok(Either::B((_to, _hs))) => future::err(HandlerError::from(io::Error::new(
@@ -254,10 +287,19 @@ struct SrvHandler {
     mgr_tx: MgrSender,
     rx: CtlReceiver,
     tx: CtlSender,
+    addr: SocketAddr,
+    credential: Credential,
+    audit_log: AuditLog,
 }
 
 impl SrvHandler {
-    fn new(io: SrvStream, mgr_tx: MgrSender) -> Self {
+    fn new(
+        io: SrvStream,
+        mgr_tx: MgrSender,
+        addr: SocketAddr,
+        credential: Credential,
+        audit_log: AuditLog,
+    ) -> Self {
         let (tx, rx) = mpsc::unbounded();
         SrvHandler {
             io: io,
@@ -265,6 +307,55 @@ impl SrvHandler {
             mgr_tx: mgr_tx,
             rx: rx,
             tx: tx,
+            addr: addr,
+            credential: credential,
+            audit_log: audit_log,
+        }
+    }
+
+    /// Returns true if the client's credential grants the role required to send the given
+    /// message. Messages which no role may send are refused. A refused message is audited and
+    /// answered with an error.
+    fn authorize(&mut self, msg: &SrvMessage) -> bool {
+        let reason = match Role::required_for(msg.message_id()) {
+            Some(required) if self.credential.role.permits(required) => return true,
+            Some(required) => format!(
+                "{} requires the {} role but credential '{}' has the {} role",
+                msg.message_id(),
+                required,
+                self.credential.name,
+                self.credential.role
+            ),
+            None => format!(
+                "{} is not a request handled by the CtlGateway",
+                msg.message_id()
+            ),
+        };
+        warn!("Refused request from {}, {}", self.addr, reason);
+        let entry = AuditEntry::new(&self.addr, &self.credential.name, msg).refused(reason.clone());
+        self.audit_log.record(&entry);
+        if let Some(txn) = msg.transaction() {
+            let mut reply = SrvMessage::from(net::err(ErrCode::Unauthorized, reason));
+            reply.reply_for(txn, true);
+            self.tx.unbounded_send(reply).ok(); // ignore Err return
+        }
+        false
+    }
+
+    /// Returns true once the client has hung up. A client sends a single request per
+    /// connection, so any further message received while that request's replies are being sent
+    /// is discarded.
+    fn client_hung_up(&mut self) -> Result<bool, HandlerError> {
+        loop {
+            match self.io.poll()? {
+                Async::Ready(Some(msg)) => warn!(
+                    "Discarding {} from {}, sent before its previous request completed",
+                    msg.message_id(),
+                    self.addr
+                ),
+                Async::Ready(None) => return Ok(true),
+                Async::NotReady => return Ok(false),
+            }
         }
     }
 }
@@ -279,6 +370,12 @@ impl Future for SrvHandler {
                 SrvHandlerState::Receiving => match try_ready!(self.io.poll()) {
                     Some(msg) => {
                         trace!("OnMessage, {}", msg.message_id());
+                        let authorized = self.authorize(&msg);
+                        metrics::ctl_request(msg.message_id(), authorized);
+                        if !authorized {
+                            self.state = SrvHandlerState::Sending;
+                            continue;
+                        }
                         let cmd = match msg.message_id() {
                             "SvcGetDefaultCfg" => {
                                 let m = msg
@@ -368,6 +465,40 @@ impl Future for SrvHandler {
                                     move |state, req| Manager::service_stop(state, req, m.clone()),
                                 )
                             }
+                            "SvcSignal" => {
+                                let m = msg
+                                    .parse::<protocol::ctl::SvcSignal>()
+                                    .map_err(HandlerError::from)?;
+                                CtlCommand::new(
+                                    Some(self.tx.clone()),
+                                    msg.transaction(),
+                                    move |state, req| {
+                                        Manager::service_signal(state, req, m.clone())
+                                    },
+                                )
+                            }
+                            "SvcExec" => {
+                                let m = msg
+                                    .parse::<protocol::ctl::SvcExec>()
+                                    .map_err(HandlerError::from)?;
+                                CtlCommand::new(
+                                    Some(self.tx.clone()),
+                                    msg.transaction(),
+                                    move |state, req| Manager::service_exec(state, req, m.clone()),
+                                )
+                            }
+                            "SvcGroupForget" => {
+                                let m = msg
+                                    .parse::<protocol::ctl::SvcGroupForget>()
+                                    .map_err(HandlerError::from)?;
+                                CtlCommand::new(
+                                    Some(self.tx.clone()),
+                                    msg.transaction(),
+                                    move |state, req| {
+                                        Manager::service_group_forget(state, req, m.clone())
+                                    },
+                                )
+                            }
                             "SvcStatus" => {
                                 let m = msg
                                     .parse::<protocol::ctl::SvcStatus>()
@@ -392,11 +523,29 @@ impl Future for SrvHandler {
                                     },
                                 )
                             }
+                            "RingKeyRotate" => {
+                                let m = msg
+                                    .parse::<protocol::ctl::RingKeyRotate>()
+                                    .map_err(HandlerError::from)?;
+                                CtlCommand::new(
+                                    Some(self.tx.clone()),
+                                    msg.transaction(),
+                                    move |state, req| {
+                                        Manager::ring_key_rotate(state, req, m.clone())
+                                    },
+                                )
+                            }
                             _ => {
                                 warn!("Unhandled message, {}", msg.message_id());
                                 break;
                             }
                         };
+                        let cmd = if audit::is_mutation(msg.message_id()) {
+                            let entry = AuditEntry::new(&self.addr, &self.credential.name, &msg);
+                            cmd.audited(self.audit_log.clone(), entry)
+                        } else {
+                            cmd
+                        };
                         match self.mgr_tx.start_send(cmd) {
                             Ok(AsyncSink::Ready) => {
                                 self.state = SrvHandlerState::Sending;
@@ -422,7 +571,16 @@ impl Future for SrvHandler {
                         continue;
                     }
                     Ok(Async::Ready(None)) => self.state = SrvHandlerState::Sent,
-                    Ok(Async::NotReady) => return Ok(Async::NotReady),
+                    Ok(Async::NotReady) => {
+                        // Watch for the client hanging up while a long-running request, such as
+                        // a command run by `hab svc exec`, has nothing to send. Dropping the
+                        // handler lets the request see that it was abandoned.
+                        if self.client_hung_up()? {
+                            debug!("Client {} hung up before its request completed", self.addr);
+                            break;
+                        }
+                        return Ok(Async::NotReady);
+                    }
                     Err(()) => break,
                 },
                 SrvHandlerState::Sent => {
@@ -446,15 +604,24 @@ enum SrvHandlerState {
 }
 
 struct SrvState {
-    secret_key: String,
+    credentials: Credentials,
+    audit_log: AuditLog,
     mgr_tx: MgrSender,
 }
 
 /// Start a new thread which will run the CtlGateway server.
 ///
-/// New connections will be authenticated using `secret_key`. Messages from the main thread
-/// will be sent over the channel `mgr_tx`.
-pub fn run(listen_addr: SocketAddr, secret_key: String, mgr_tx: MgrSender) {
+/// New connections will be authenticated against `credentials`, and each request checked against
+/// the role of the credential used, with refusals recorded in `audit_log`. If an `acceptor` is
+/// given, clients connecting from a non-loopback address must first complete a TLS handshake
+/// with it. Messages from the main thread will be sent over the channel `mgr_tx`.
+pub fn run(
+    listen_addr: SocketAddr,
+    credentials: Credentials,
+    acceptor: Option<SslAcceptor>,
+    audit_log: AuditLog,
+    mgr_tx: MgrSender,
+) {
     thread::Builder::new()
         .name("ctl-gateway".to_string())
         .spawn(move || {
@@ -462,20 +629,33 @@ pub fn run(listen_addr: SocketAddr, secret_key: String, mgr_tx: MgrSender) {
             let handle = core.handle();
             let listener = TcpListener::bind(&listen_addr).unwrap();
             let state = SrvState {
-                secret_key: secret_key,
+                credentials: credentials,
+                audit_log: audit_log,
                 mgr_tx: mgr_tx,
             };
             let state = Rc::new(RefCell::new(state));
             let clients = listener.incoming().map(|socket| {
                 let addr = socket.peer_addr().unwrap();
-                let io = Framed::new(socket, SrvCodec::new());
-                (
-                    Client {
-                        handle: handle.clone(),
-                        state: state.clone(),
-                    }.serve(io),
-                    addr,
-                )
+                let stream: Box<Future<Item = CtlStream, Error = io::Error>> = match acceptor {
+                    Some(ref acceptor) if !tls::is_plaintext_addr(&addr) => {
+                        CtlStream::accept(acceptor, socket)
+                    }
+                    _ => Box::new(future::ok(CtlStream::Plain(socket))),
+                };
+                let client = Client {
+                    addr: addr,
+                    handle: handle.clone(),
+                    state: state.clone(),
+                };
+                let serve = stream
+                    .map_err(HandlerError::from)
+                    .and_then(move |stream| {
+                        if let Some(name) = stream.peer_common_name() {
+                            debug!("Client {} presented a certificate for {}", addr, name);
+                        }
+                        client.serve(Framed::new(stream, SrvCodec::new()))
+                    });
+                (serve, addr)
             });
             let server = clients.for_each(|(client, addr)| {
                 handle.spawn(client.then(move |res| {
@@ -106,11 +106,13 @@ impl SupError {
 pub enum Error {
     Departed,
     BadAddress(String),
+    BadAuditOutcome(String),
     BadCompositesPath(PathBuf, io::Error),
     BadDataFile(PathBuf, io::Error),
     BadDataPath(PathBuf, io::Error),
     BadDesiredState(String),
     BadElectionStatus(String),
+    BadHealthCheck(String),
     BadPackage(PackageInstall, hcore::error::Error),
     BadSpecsPath(PathBuf, io::Error),
     BadStartStyle(String),
@@ -120,6 +122,7 @@ pub enum Error {
     TestBootFail,
     ButterflyError(butterfly::error::Error),
     CtlSecretIo(PathBuf, io::Error),
+    CtlCredentials(PathBuf, String),
     APIClient(api_client::Error),
     EnvJoinPathsError(env::JoinPathsError),
     ExecCommandNotFound(String),
@@ -128,12 +131,15 @@ pub enum Error {
     GroupNotFound(String),
     HabitatCommon(common::Error),
     HabitatCore(hcore::Error),
+    HttpGatewayTls(PathBuf, String),
     TemplateFileError(handlebars::TemplateFileError),
     TemplateRenderError(handlebars::RenderError),
     InvalidBinding(String),
     InvalidBinds(Vec<String>),
+    InvalidHealthCheckSetting(&'static str),
     InvalidKeyParameter(String),
     InvalidPidFile,
+    InvalidTags(String),
     InvalidTopology(String),
     InvalidUpdateStrategy(String),
     Io(io::Error),
@@ -214,6 +220,10 @@ impl fmt::Display for SupError {
                 format!("Unknown service desired state style '{}'", state)
             }
             Error::BadElectionStatus(ref status) => format!("Unknown election status '{}'", status),
+            Error::BadAuditOutcome(ref outcome) => {
+                format!("Unknown audit outcome '{}'", outcome)
+            }
+            Error::BadHealthCheck(ref status) => format!("Unknown health check status '{}'", status),
             Error::BadPackage(ref pkg, ref err) => format!("Bad package, {}, {}", pkg, err),
             Error::BadSpecsPath(ref path, ref err) => format!(
                 "Unable to create the specs directory '{}' ({})",
@@ -233,12 +243,22 @@ impl fmt::Display for SupError {
                 path.display(),
                 err
             ),
+            Error::CtlCredentials(ref path, ref err) => format!(
+                "Unable to load ctl gateway credentials from {}, {}",
+                path.display(),
+                err
+            ),
             Error::ExecCommandNotFound(ref c) => {
                 format!("`{}' was not found on the filesystem or in PATH", c)
             }
             Error::Permissions(ref err) => format!("{}", err),
             Error::HabitatCommon(ref err) => format!("{}", err),
             Error::HabitatCore(ref err) => format!("{}", err),
+            Error::HttpGatewayTls(ref path, ref err) => format!(
+                "Unable to load http gateway TLS file {}, {}",
+                path.display(),
+                err
+            ),
             Error::TemplateFileError(ref err) => format!("{:?}", err),
             Error::TemplateRenderError(ref err) => format!("{}", err),
             Error::EnvJoinPathsError(ref err) => format!("{}", err),
@@ -253,10 +273,14 @@ impl fmt::Display for SupError {
                 binding
             ),
             Error::InvalidBinds(ref e) => format!("Invalid bind(s), {}", e.join(", ")),
+            Error::InvalidHealthCheckSetting(ref setting) => {
+                format!("Invalid {}, must be greater than 0", setting)
+            }
             Error::InvalidKeyParameter(ref e) => {
                 format!("Invalid parameter for key generation: {:?}", e)
             }
             Error::InvalidPidFile => format!("Invalid child process PID file"),
+            Error::InvalidTags(ref e) => format!("Invalid tags, {}", e),
             Error::InvalidTopology(ref t) => format!("Invalid topology: {}", t),
             Error::InvalidUpdateStrategy(ref s) => format!("Invalid update strategy: {}", s),
             Error::Io(ref err) => format!("{}", err),
@@ -363,6 +387,8 @@ impl error::Error for SupError {
             Error::BadDataFile(_, _) => "Unable to read or write to a data file",
             Error::BadDataPath(_, _) => "Unable to read or write to data directory",
             Error::BadElectionStatus(_) => "Unknown election status",
+            Error::BadAuditOutcome(_) => "Unknown audit outcome",
+            Error::BadHealthCheck(_) => "Unknown health check status",
             Error::BadDesiredState(_) => "Unknown desired state in service spec",
             Error::BadPackage(_, _) => "Package was malformed or contained malformed contents",
             Error::BadSpecsPath(_, _) => "Unable to create the specs directory",
@@ -373,12 +399,14 @@ impl error::Error for SupError {
             Error::TestBootFail => "Simulated boot failure",
             Error::ButterflyError(ref err) => err.description(),
             Error::CtlSecretIo(_, _) => "IoError while reading ctl secret",
+            Error::CtlCredentials(_, _) => "Unable to load ctl gateway credentials",
             Error::ExecCommandNotFound(_) => "Exec command was not found on filesystem or in PATH",
             Error::GroupNotFound(_) => "No matching GID for group found",
             Error::TemplateFileError(ref err) => err.description(),
             Error::TemplateRenderError(ref err) => err.description(),
             Error::HabitatCommon(ref err) => err.description(),
             Error::HabitatCore(ref err) => err.description(),
+            Error::HttpGatewayTls(_, _) => "Unable to load http gateway TLS file",
             Error::EnvJoinPathsError(ref err) => err.description(),
             Error::FileNotFound(_) => "File not found",
             Error::FileWatcherFileIsRoot => "Watched file is root",
@@ -386,8 +414,10 @@ impl error::Error for SupError {
             Error::InvalidBinds(_) => {
                 "Service binds detected that are neither required nor optional package binds"
             }
+            Error::InvalidHealthCheckSetting(_) => "Health check settings must be greater than 0",
             Error::InvalidKeyParameter(_) => "Key parameter error",
             Error::InvalidPidFile => "Invalid child process PID file",
+            Error::InvalidTags(_) => "Invalid tags",
             Error::InvalidTopology(_) => "Invalid topology",
             Error::InvalidUpdateStrategy(_) => "Invalid update strategy",
             Error::Io(ref err) => err.description(),
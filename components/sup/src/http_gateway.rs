@@ -12,30 +12,55 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::cmp;
 use std::fmt;
 use std::fs::File;
-use std::io::{self, Read};
+use std::io::{self, Read, Seek, SeekFrom};
 use std::net::{IpAddr, Ipv4Addr, SocketAddr, SocketAddrV4, ToSocketAddrs};
 use std::ops::{Deref, DerefMut};
 use std::option;
+use std::path::{Path as StdPath, PathBuf};
 use std::result;
 use std::str::FromStr;
 use std::sync::{Arc, Condvar, Mutex, RwLock};
 use std::thread;
+use std::time::{Duration, Instant};
 
 use actix;
 use actix_web::{
-    http::StatusCode, pred::Predicate, server, App, FromRequest, HttpRequest, HttpResponse, Path,
-    Request,
+    self,
+    http::{header, Method, StatusCode},
+    middleware::{Middleware, Started},
+    pred::Predicate,
+    server, App, AsyncResponder, FromRequest, FutureResponse, HttpMessage, HttpRequest,
+    HttpResponse, Path, Query, Request,
 };
+use bytes::Bytes;
+use crypto::util::fixed_time_eq;
+use futures::sync::mpsc;
+use futures::{future, stream};
+use futures::{Future, Stream};
+use hcore::package::PackageIdent;
 use hcore::service::ServiceGroup;
+use openssl::error::ErrorStack;
+use openssl::ssl::{SslAcceptor, SslAcceptorBuilder, SslFiletype, SslMethod};
+use protocol;
+use protocol::codec::{SrvMessage, SrvTxn};
+use protocol::net::{self, ErrCode, NetErr, NetResult};
 use protocol::socket_addr_env_or_default;
+use protocol::types::{BindingMode, ServiceBind, Topology, UpdateStrategy};
 use serde_json::{self, Value as Json};
+use tokio::timer::Interval;
 
+use ctl_gateway::audit::{AuditEntry, AuditLog};
+use ctl_gateway::server::{CtlCommand, MgrSender};
+use ctl_gateway::{self, CtlRequest};
 use error::{Error, Result, SupError};
-use manager;
-use manager::service::hooks::{self, HealthCheckHook};
+use manager::service::hooks::{self, HealthCheckHook, Hook, RunHook};
 use manager::service::HealthCheck;
+use manager::{self, Manager, ManagerState};
+use metrics;
+use state_events::StateEventBus;
 
 use feat;
 
@@ -48,6 +73,20 @@ pub const DEFAULT_PORT: u16 = 9631;
 /// Default environment variable override for HTTPGateway listener address.
 pub const DEFAULT_ADDRESS_ENVVAR: &'static str = "HAB_LISTEN_HTTP";
 
+/// Environment variable holding the bearer token required by the HTTPGateway's sensitive
+/// endpoints when `--http-auth-token` isn't given. Without a token every endpoint may be read
+/// without credentials and the control endpoints are disabled.
+pub const GATEWAY_AUTH_TOKEN_ENVVAR: &'static str = "HAB_SUP_GATEWAY_AUTH_TOKEN";
+
+/// Name recorded as the credential in the audit log for requests to the control endpoints.
+const AUDIT_CREDENTIAL: &'static str = "http-gateway";
+
+/// How often a followed log is checked for new output.
+const LOG_FOLLOW_INTERVAL_MS: u64 = 500;
+
+/// Number of bytes read at a time while looking back from the end of a log for its last lines.
+const TAIL_BLOCK_SIZE: u64 = 8192;
+
 #[derive(PartialEq, Eq, Debug, Clone)]
 pub struct ListenAddr(SocketAddr);
 
@@ -112,6 +151,46 @@ impl fmt::Display for ListenAddr {
     }
 }
 
+/// Paths to the PEM encoded certificate and private key the HTTPGateway serves HTTPS with.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TlsConfig {
+    pub certificate: PathBuf,
+    pub key: PathBuf,
+}
+
+impl TlsConfig {
+    pub fn new<C, K>(certificate: C, key: K) -> Self
+    where
+        C: Into<PathBuf>,
+        K: Into<PathBuf>,
+    {
+        TlsConfig {
+            certificate: certificate.into(),
+            key: key.into(),
+        }
+    }
+
+    /// Load the certificate and key into an acceptor for the HTTPGateway to bind with.
+    pub fn acceptor(&self) -> Result<SslAcceptorBuilder> {
+        let mut builder = SslAcceptor::mozilla_intermediate(SslMethod::tls())
+            .map_err(|e| tls_err(&self.certificate, e))?;
+        builder
+            .set_certificate_chain_file(&self.certificate)
+            .map_err(|e| tls_err(&self.certificate, e))?;
+        builder
+            .set_private_key_file(&self.key, SslFiletype::PEM)
+            .map_err(|e| tls_err(&self.key, e))?;
+        builder
+            .check_private_key()
+            .map_err(|e| tls_err(&self.key, e))?;
+        Ok(builder)
+    }
+}
+
+fn tls_err(path: &StdPath, err: ErrorStack) -> SupError {
+    sup_error!(Error::HttpGatewayTls(path.to_path_buf(), err.to_string()))
+}
+
 #[derive(Default, Serialize)]
 struct HealthCheckBody {
     status: String,
@@ -129,13 +208,153 @@ impl Into<StatusCode> for HealthCheck {
     }
 }
 
+/// Replies to a request made to one of the control endpoints.
+#[derive(Debug, Default, Serialize)]
+struct CommandBody {
+    /// Lines of output written by the Supervisor while running the command.
+    output: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// A request to load a service, accepting the most common of the options `hab svc load` does.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LoadBody {
+    ident: String,
+    group: Option<String>,
+    bldr_url: Option<String>,
+    channel: Option<String>,
+    topology: Option<String>,
+    strategy: Option<String>,
+    binds: Option<Vec<String>>,
+    binding_mode: Option<String>,
+    config_from: Option<String>,
+    #[serde(default)]
+    force: bool,
+}
+
+impl LoadBody {
+    fn into_msg(self) -> NetResult<protocol::ctl::SvcLoad> {
+        let ident = PackageIdent::from_str(&self.ident)
+            .map_err(|e| net::err(ErrCode::InvalidPayload, e.to_string()))?;
+        let mut msg = protocol::ctl::SvcLoad::default();
+        msg.ident = Some(ident.into());
+        msg.group = self.group;
+        msg.bldr_url = self.bldr_url;
+        msg.bldr_channel = self.channel;
+        msg.config_from = self.config_from;
+        msg.force = Some(self.force);
+        if let Some(topology) = self.topology {
+            msg.topology = Some(Topology::from_str(&topology)? as i32);
+        }
+        if let Some(strategy) = self.strategy {
+            msg.update_strategy = Some(UpdateStrategy::from_str(&strategy)? as i32);
+        }
+        if let Some(binding_mode) = self.binding_mode {
+            msg.binding_mode = Some(BindingMode::from_str(&binding_mode)? as i32);
+        }
+        if let Some(binds) = self.binds {
+            let mut list = protocol::ctl::ServiceBindList::default();
+            for bind in binds {
+                list.binds.push(ServiceBind::from_str(&bind)?);
+            }
+            msg.binds = Some(list);
+            msg.specified_binds = Some(true);
+        }
+        Ok(msg)
+    }
+}
+
+#[derive(Deserialize)]
+struct ConfigQuery {
+    version: u64,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum LogStream {
+    Stdout,
+    Stderr,
+}
+
+impl Default for LogStream {
+    fn default() -> Self {
+        LogStream::Stdout
+    }
+}
+
+#[derive(Deserialize)]
+struct LogQuery {
+    #[serde(default)]
+    stream: LogStream,
+    /// Return only this many of the last lines of the log.
+    lines: Option<usize>,
+    /// Keep the response open, sending output as it is appended to the log.
+    #[serde(default)]
+    follow: bool,
+}
+
+/// Reads whatever has been appended to a log file since it was last read.
+struct LogFollower {
+    path: PathBuf,
+    offset: u64,
+}
+
+impl LogFollower {
+    fn new(path: PathBuf, offset: u64) -> Self {
+        LogFollower {
+            path: path,
+            offset: offset,
+        }
+    }
+
+    fn read_new(&mut self) -> io::Result<Option<Bytes>> {
+        let mut file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(ref err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err),
+        };
+        let len = file.metadata()?.len();
+        // Hook logs are recreated each time their hook runs, so a log which has shrunk is read
+        // again from its start.
+        if len < self.offset {
+            self.offset = 0;
+        }
+        if len == self.offset {
+            return Ok(None);
+        }
+        file.seek(SeekFrom::Start(self.offset))?;
+        let mut chunk = vec![];
+        file.take(len - self.offset).read_to_end(&mut chunk)?;
+        self.offset += chunk.len() as u64;
+        Ok(Some(Bytes::from(chunk)))
+    }
+}
+
 struct AppState {
     gateway_state: Arc<RwLock<manager::GatewayState>>,
+    auth_token: Option<String>,
+    state_events: StateEventBus,
+    mgr_tx: MgrSender,
+    audit_log: AuditLog,
 }
 
 impl AppState {
-    fn new(gs: Arc<RwLock<manager::GatewayState>>) -> Self {
-        AppState { gateway_state: gs }
+    fn new(
+        gs: Arc<RwLock<manager::GatewayState>>,
+        auth_token: Option<String>,
+        state_events: StateEventBus,
+        mgr_tx: MgrSender,
+        audit_log: AuditLog,
+    ) -> Self {
+        AppState {
+            gateway_state: gs,
+            auth_token: auth_token,
+            state_events: state_events,
+            mgr_tx: mgr_tx,
+            audit_log: audit_log,
+        }
     }
 }
 
@@ -152,16 +371,33 @@ impl Server {
     pub fn run(
         listen_addr: ListenAddr,
         gateway_state: Arc<RwLock<manager::GatewayState>>,
+        tls: Option<SslAcceptorBuilder>,
+        auth_token: Option<String>,
+        state_events: StateEventBus,
+        mgr_tx: MgrSender,
+        audit_log: AuditLog,
         control: Arc<(Mutex<ServerStartup>, Condvar)>,
     ) {
         thread::spawn(move || {
             let &(ref lock, ref cvar) = &*control;
             let sys = actix::System::new("sup-http-gateway");
 
-            let bind = server::new(move || {
-                let app_state = AppState::new(gateway_state.clone());
-                App::with_state(app_state).configure(routes)
-            }).bind(listen_addr.to_string());
+            let server = server::new(move || {
+                let app_state = AppState::new(
+                    gateway_state.clone(),
+                    auth_token.clone(),
+                    state_events.clone(),
+                    mgr_tx.clone(),
+                    audit_log.clone(),
+                );
+                App::with_state(app_state)
+                    .middleware(Authentication)
+                    .configure(routes)
+            });
+            let bind = match tls {
+                Some(builder) => server.bind_ssl(listen_addr.to_string(), builder),
+                None => server.bind(listen_addr.to_string()),
+            };
 
             // We need to create this scope on purpose here because if we don't, the lock never
             // releases, and the supervisor will wait forever on cvar. Creating this artifical
@@ -195,23 +431,125 @@ impl<S: 'static> Predicate<S> for RedactHTTP {
     }
 }
 
+/// Requires a bearer token matching the Supervisor's, when it has one, for every request other
+/// than GET or HEAD requests for the API documentation and service health checks. Health checks
+/// stay open so that load balancers can probe services without credentials. Requests which
+/// change the Supervisor's state are refused outright when it has no token.
+struct Authentication;
+
+impl Middleware<AppState> for Authentication {
+    fn start(&self, req: &HttpRequest<AppState>) -> actix_web::Result<Started> {
+        let token = match req.state().auth_token {
+            Some(ref token) => token,
+            None if is_read(req.method()) => return Ok(Started::Done),
+            None => {
+                let body = CommandBody {
+                    error: Some(format!(
+                        "Control endpoints are disabled unless --http-auth-token or {} is set",
+                        GATEWAY_AUTH_TOKEN_ENVVAR
+                    )),
+                    ..CommandBody::default()
+                };
+                return Ok(Started::Response(HttpResponse::Forbidden().json(body)));
+            }
+        };
+        if is_public(req.method(), req.path()) {
+            return Ok(Started::Done);
+        }
+        let authorization = req
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok());
+        if is_authorized(token, authorization) {
+            Ok(Started::Done)
+        } else {
+            Ok(Started::Response(
+                HttpResponse::Unauthorized()
+                    .header(header::WWW_AUTHENTICATE, "Bearer")
+                    .finish(),
+            ))
+        }
+    }
+}
+
+fn is_read(method: &Method) -> bool {
+    *method == Method::GET || *method == Method::HEAD
+}
+
+/// Returns true for requests which may be made without a token: reads of the API documentation
+/// and of a service's health check.
+fn is_public(method: &Method, path: &str) -> bool {
+    if !is_read(method) {
+        return false;
+    }
+    if path == "/" {
+        return true;
+    }
+    // Matches `/services/{svc}/{group}/health` and `/services/{svc}/{group}/{org}/health`
+    let segments: Vec<&str> = path.split('/').collect();
+    match segments.len() {
+        5 | 6 => {
+            let last = segments.len() - 1;
+            segments[0].is_empty()
+                && segments[1] == "services"
+                && segments[2..last].iter().all(|segment| !segment.is_empty())
+                && segments[last] == "health"
+        }
+        _ => false,
+    }
+}
+
+fn is_authorized(token: &str, authorization: Option<&str>) -> bool {
+    let mut parts = authorization.unwrap_or("").splitn(2, ' ');
+    match (parts.next(), parts.next()) {
+        (Some(scheme), Some(credentials)) if scheme.eq_ignore_ascii_case("bearer") => {
+            fixed_time_eq(token.as_bytes(), credentials.trim().as_bytes())
+        }
+        _ => false,
+    }
+}
+
 fn routes(app: App<AppState>) -> App<AppState> {
     app.resource("/", |r| r.get().f(doc))
-        .resource("/services", |r| r.get().f(services))
-        .resource("/services/{svc}/{group}", |r| {
-            r.get().f(service_without_org)
+        .resource("/services", |r| {
+            r.get().f(services);
+            r.post().f(service_load);
+        }).resource("/services/{svc}/{group}", |r| {
+            r.get().f(service_without_org);
+            r.delete().f(service_unload);
         }).resource("/services/{svc}/{group}/config", |r| {
-            r.get().f(config_without_org)
+            r.get().f(config_without_org);
+            r.put().f(service_config_set);
         }).resource("/services/{svc}/{group}/health", |r| {
             r.get().f(health_without_org)
+        }).resource("/services/{svc}/{group}/logs", |r| {
+            r.get().f(service_logs)
+        }).resource("/services/{svc}/{group}/hooks/{hook}/output", |r| {
+            r.get().f(hook_output)
+        }).resource("/services/{svc}/{group}/start", |r| {
+            r.post().f(service_start)
+        }).resource("/services/{svc}/{group}/stop", |r| {
+            r.post().f(service_stop)
         }).resource("/services/{svc}/{group}/{org}", |r| {
-            r.get().f(service_with_org)
+            r.get().f(service_with_org);
+            r.delete().f(service_unload);
         }).resource("/services/{svc}/{group}/{org}/config", |r| {
-            r.get().f(config_with_org)
+            r.get().f(config_with_org);
+            r.put().f(service_config_set);
         }).resource("/services/{svc}/{group}/{org}/health", |r| {
             r.get().f(health_with_org)
+        }).resource("/services/{svc}/{group}/{org}/logs", |r| {
+            r.get().f(service_logs)
+        }).resource("/services/{svc}/{group}/{org}/hooks/{hook}/output", |r| {
+            r.get().f(hook_output)
+        }).resource("/services/{svc}/{group}/{org}/start", |r| {
+            r.post().f(service_start)
+        }).resource("/services/{svc}/{group}/{org}/stop", |r| {
+            r.post().f(service_stop)
         }).resource("/butterfly", |r| r.get().filter(RedactHTTP).f(butterfly))
         .resource("/census", |r| r.get().filter(RedactHTTP).f(census))
+        .resource("/events", |r| r.get().f(events))
+        .resource("/metrics", |r| r.get().f(prometheus_metrics))
 }
 
 fn json_response(data: String) -> HttpResponse {
@@ -241,6 +579,23 @@ fn census(req: &HttpRequest<AppState>) -> HttpResponse {
     json_response(data.to_string())
 }
 
+fn events(req: &HttpRequest<AppState>) -> HttpResponse {
+    let stream = req
+        .state()
+        .state_events
+        .subscribe(feat::is_enabled(feat::RedactHTTP))
+        .map_err(|_| actix_web::error::ErrorInternalServerError("State event stream closed"));
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .header(header::CACHE_CONTROL, "no-cache")
+        .streaming(stream)
+}
+
+fn prometheus_metrics(_req: &HttpRequest<AppState>) -> HttpResponse {
+    let (content_type, body) = metrics::render();
+    HttpResponse::Ok().content_type(content_type).body(body)
+}
+
 fn services(req: &HttpRequest<AppState>) -> HttpResponse {
     let data = &req
         .state()
@@ -384,8 +739,377 @@ fn service(
 fn doc(_req: &HttpRequest<AppState>) -> HttpResponse {
     HttpResponse::Ok().content_type("text/html").body(APIDOCS)
 }
+
+fn service_logs(req: &HttpRequest<AppState>) -> HttpResponse {
+    let service_group = match path_service_group(req) {
+        Some(sg) => sg,
+        None => return HttpResponse::BadRequest().finish(),
+    };
+    let service = match loaded_service(req, &service_group) {
+        Some(service) => service,
+        None => return HttpResponse::NotFound().finish(),
+    };
+    if let Some(conflict) = log_capture_conflict(&service, &service_group) {
+        return conflict;
+    }
+    // The service's own output is that of its run hook.
+    let paths = (
+        hooks::stdout_log_path::<RunHook>(&service_group),
+        hooks::stderr_log_path::<RunHook>(&service_group),
+    );
+    log_response(req, paths)
+}
+
+fn hook_output(req: &HttpRequest<AppState>) -> HttpResponse {
+    let service_group = match path_service_group(req) {
+        Some(sg) => sg,
+        None => return HttpResponse::BadRequest().finish(),
+    };
+    let service = match loaded_service(req, &service_group) {
+        Some(service) => service,
+        None => return HttpResponse::NotFound().finish(),
+    };
+    let hook = req.match_info().get("hook").unwrap_or_default();
+    if hook == RunHook::file_name() {
+        if let Some(conflict) = log_capture_conflict(&service, &service_group) {
+            return conflict;
+        }
+    }
+    match hooks::log_paths(&service_group, hook) {
+        Some(paths) => log_response(req, paths),
+        None => HttpResponse::NotFound().finish(),
+    }
+}
+
+fn log_response(req: &HttpRequest<AppState>, (stdout, stderr): (PathBuf, PathBuf)) -> HttpResponse {
+    let query = match Query::<LogQuery>::extract(req) {
+        Ok(query) => query.into_inner(),
+        Err(_) => return HttpResponse::BadRequest().finish(),
+    };
+    let path = match query.stream {
+        LogStream::Stdout => stdout,
+        LogStream::Stderr => stderr,
+    };
+    let (tail, offset) = match read_tail(&path, query.lines) {
+        Ok(tail) => tail,
+        // A log which doesn't exist yet may still be followed until it does.
+        Err(ref err) if err.kind() == io::ErrorKind::NotFound && query.follow => (Bytes::new(), 0),
+        Err(ref err) if err.kind() == io::ErrorKind::NotFound => {
+            return HttpResponse::NotFound().finish()
+        }
+        Err(err) => {
+            error!("Unable to read log {}, {}", path.display(), err);
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+    if !query.follow {
+        return HttpResponse::Ok()
+            .content_type("text/plain; charset=utf-8")
+            .body(tail);
+    }
+    let mut follower = LogFollower::new(path, offset);
+    let interval = Duration::from_millis(LOG_FOLLOW_INTERVAL_MS);
+    let appended = Interval::new(Instant::now() + interval, interval)
+        .map_err(actix_web::error::ErrorInternalServerError)
+        .and_then(move |_| {
+            follower
+                .read_new()
+                .map_err(actix_web::error::ErrorInternalServerError)
+        }).filter_map(|chunk| chunk);
+    HttpResponse::Ok()
+        .content_type("text/plain; charset=utf-8")
+        .header(header::CACHE_CONTROL, "no-cache")
+        .streaming(stream::once(Ok(tail)).chain(appended))
+}
+
+fn service_load(req: &HttpRequest<AppState>) -> FutureResponse<HttpResponse> {
+    let body = req.json::<LoadBody>();
+    let req = req.clone();
+    body.from_err()
+        .and_then(move |body| match body.into_msg() {
+            Ok(msg) => {
+                let entry = AuditEntry::new(
+                    &peer_addr(&req),
+                    AUDIT_CREDENTIAL,
+                    &SrvMessage::from(msg.clone()),
+                );
+                dispatch(&req, entry, move |state, ctl_req| {
+                    Manager::service_load(state, ctl_req, msg.clone())
+                })
+            }
+            Err(err) => respond(err_response(err)),
+        }).responder()
+}
+
+fn service_unload(req: &HttpRequest<AppState>) -> FutureResponse<HttpResponse> {
+    service_command(req, "SvcUnload", |state, ctl_req, ident| {
+        let mut msg = protocol::ctl::SvcUnload::default();
+        msg.ident = Some(ident.into());
+        Manager::service_unload(state, ctl_req, msg)
+    })
+}
+
+fn service_start(req: &HttpRequest<AppState>) -> FutureResponse<HttpResponse> {
+    service_command(req, "SvcStart", |state, ctl_req, ident| {
+        let mut msg = protocol::ctl::SvcStart::default();
+        msg.ident = Some(ident.into());
+        Manager::service_start(state, ctl_req, msg)
+    })
+}
+
+fn service_stop(req: &HttpRequest<AppState>) -> FutureResponse<HttpResponse> {
+    service_command(req, "SvcStop", |state, ctl_req, ident| {
+        let mut msg = protocol::ctl::SvcStop::default();
+        msg.ident = Some(ident.into());
+        Manager::service_stop(state, ctl_req, msg)
+    })
+}
+
+fn service_config_set(req: &HttpRequest<AppState>) -> FutureResponse<HttpResponse> {
+    let service_group = match path_service_group(req) {
+        Some(sg) => sg,
+        None => return respond(HttpResponse::BadRequest().finish()),
+    };
+    let version = match Query::<ConfigQuery>::extract(req) {
+        Ok(query) => query.into_inner().version,
+        Err(_) => {
+            let err = net::err(
+                ErrCode::BadPayload,
+                "A configuration version is required, e.g. ?version=2",
+            );
+            return respond(err_response(err));
+        }
+    };
+    let body = req.body().limit(protocol::butterfly::MAX_SVC_CFG_SIZE);
+    let req = req.clone();
+    body.from_err()
+        .and_then(move |cfg: Bytes| {
+            let mut set_cfg = protocol::ctl::SvcSetCfg::default();
+            set_cfg.service_group = Some(service_group.into());
+            set_cfg.cfg = Some(cfg.to_vec());
+            set_cfg.version = Some(version);
+            let mut validate_cfg = protocol::ctl::SvcValidateCfg::default();
+            validate_cfg.service_group = set_cfg.service_group.clone();
+            validate_cfg.cfg = set_cfg.cfg.clone();
+            let entry = AuditEntry::new(
+                &peer_addr(&req),
+                AUDIT_CREDENTIAL,
+                &SrvMessage::from(set_cfg.clone()),
+            );
+            dispatch(&req, entry, move |state, ctl_req| {
+                // Validation only replies to say that it passed, so it is given a bare request
+                // and only its failure is passed on to the client.
+                Manager::service_cfg_validate(
+                    state,
+                    &mut CtlRequest::default(),
+                    validate_cfg.clone(),
+                )?;
+                Manager::service_cfg_set(state, ctl_req, set_cfg.clone())
+            })
+        }).responder()
+}
 // End route handlers
 
+/// Run a command against the service loaded into the service group named by the request's path.
+fn service_command(
+    req: &HttpRequest<AppState>,
+    message_id: &str,
+    run: fn(&ManagerState, &mut CtlRequest, PackageIdent) -> NetResult<()>,
+) -> FutureResponse<HttpResponse> {
+    let service_group = match path_service_group(req) {
+        Some(sg) => sg,
+        None => return respond(HttpResponse::BadRequest().finish()),
+    };
+    let entry = AuditEntry::for_service_group(
+        &peer_addr(req),
+        AUDIT_CREDENTIAL,
+        message_id,
+        &service_group,
+    );
+    dispatch(req, entry, move |state, ctl_req| {
+        let ident =
+            Manager::ident_for_service_group(&state.cfg, &service_group).ok_or_else(|| {
+                net::err(
+                    ErrCode::NotFound,
+                    format!("Service not loaded, {}", service_group),
+                )
+            })?;
+        run(state, ctl_req, ident)
+    })
+}
+
+/// Hand a command to the Manager's main thread, just as the CtlGateway does for its clients, and
+/// answer with the replies it sends back.
+fn dispatch<F>(
+    req: &HttpRequest<AppState>,
+    entry: AuditEntry,
+    fun: F,
+) -> FutureResponse<HttpResponse>
+where
+    F: Fn(&ManagerState, &mut CtlRequest) -> NetResult<()> + Send + 'static,
+{
+    let (tx, rx) = mpsc::unbounded();
+    let cmd = CtlCommand::new(Some(tx), Some(SrvTxn::default()), fun)
+        .audited(req.state().audit_log.clone(), entry);
+    if let Err(err) = req.state().mgr_tx.unbounded_send(cmd) {
+        warn!("Unable to dispatch command to the Manager, {}", err);
+        return respond(HttpResponse::ServiceUnavailable().finish());
+    }
+    // The Manager drops its sender once the command has run, ending the stream of replies.
+    rx.collect()
+        .map(command_response)
+        .map_err(|()| actix_web::error::ErrorInternalServerError("Command reply channel failed"))
+        .responder()
+}
+
+/// Turn the replies to a command into a response. Any error amongst the replies determines the
+/// response's status. A command which the Manager dropped without completing, as it does when
+/// shutting down, leaves the service unavailable.
+fn command_response(replies: Vec<SrvMessage>) -> HttpResponse {
+    let mut status = StatusCode::SERVICE_UNAVAILABLE;
+    let mut body = CommandBody::default();
+    for reply in replies {
+        match reply.try_ok() {
+            Ok(()) => {
+                if reply.message_id() == "ConsoleLine" {
+                    if let Ok(line) = reply.parse::<protocol::ctl::ConsoleLine>() {
+                        let line = ctl_gateway::strip_ansi_codes(&line.line);
+                        body.output.push(line.trim_right_matches('\n').to_string());
+                    }
+                }
+                if reply.is_complete() {
+                    status = StatusCode::ACCEPTED;
+                }
+            }
+            Err(err) => {
+                status = err_status(&err);
+                body.error = Some(err.msg);
+            }
+        }
+    }
+    HttpResponse::build(status).json(body)
+}
+
+/// Answer a request to one of the control endpoints without dispatching a command.
+fn respond(response: HttpResponse) -> FutureResponse<HttpResponse> {
+    Box::new(future::ok::<_, actix_web::Error>(response))
+}
+
+fn err_response(err: NetErr) -> HttpResponse {
+    let body = CommandBody {
+        error: Some(err.msg.clone()),
+        ..CommandBody::default()
+    };
+    HttpResponse::build(err_status(&err)).json(body)
+}
+
+fn err_status(err: &NetErr) -> StatusCode {
+    match ErrCode::from_i32(err.code).unwrap_or_default() {
+        ErrCode::NotFound => StatusCode::NOT_FOUND,
+        ErrCode::Conflict => StatusCode::CONFLICT,
+        ErrCode::Unauthorized => StatusCode::FORBIDDEN,
+        ErrCode::NotSupported
+        | ErrCode::BadPayload
+        | ErrCode::InvalidPayload
+        | ErrCode::UpdateClient => StatusCode::BAD_REQUEST,
+        ErrCode::EntityTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+        ErrCode::Internal | ErrCode::Io => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// Read the last `lines` lines of the file at `path`, or all of it, along with the file's size
+/// when it was read. The file is read backwards from its end a block at a time until enough
+/// lines have been found, so that tailing a large log doesn't read all of it.
+fn read_tail(path: &StdPath, lines: Option<usize>) -> io::Result<(Bytes, u64)> {
+    let mut file = File::open(path)?;
+    let size = file.seek(SeekFrom::End(0))?;
+    let lines = match lines {
+        Some(lines) => lines,
+        None => {
+            let mut contents = Vec::with_capacity(size as usize);
+            file.seek(SeekFrom::Start(0))?;
+            file.take(size).read_to_end(&mut contents)?;
+            return Ok((Bytes::from(contents), size));
+        }
+    };
+    // The bytes from `offset` to `size`, read so far.
+    let mut contents = vec![];
+    let mut offset = size;
+    loop {
+        let start = tail_start(&contents, lines);
+        if start > 0 || offset == 0 {
+            return Ok((Bytes::from(contents).slice_from(start), size));
+        }
+        let block = cmp::min(offset, TAIL_BLOCK_SIZE);
+        offset -= block;
+        let mut chunk = Vec::with_capacity((block as usize) + contents.len());
+        file.seek(SeekFrom::Start(offset))?;
+        (&mut file).take(block).read_to_end(&mut chunk)?;
+        chunk.extend_from_slice(&contents);
+        contents = chunk;
+    }
+}
+
+/// The index of the first byte of the last `lines` lines of `contents`.
+fn tail_start(contents: &[u8], lines: usize) -> usize {
+    if lines == 0 {
+        return contents.len();
+    }
+    // A trailing newline ends the last line rather than starting another.
+    let end = if contents.ends_with(b"\n") {
+        contents.len() - 1
+    } else {
+        contents.len()
+    };
+    let mut seen = 0;
+    for (i, byte) in contents[..end].iter().enumerate().rev() {
+        if *byte == b'\n' {
+            seen += 1;
+            if seen == lines {
+                return i + 1;
+            }
+        }
+    }
+    0
+}
+
+/// The service group named by the request's path.
+fn path_service_group(req: &HttpRequest<AppState>) -> Option<ServiceGroup> {
+    let info = req.match_info();
+    ServiceGroup::new(None, info.get("svc")?, info.get("group")?, info.get("org")).ok()
+}
+
+fn peer_addr(req: &HttpRequest<AppState>) -> SocketAddr {
+    req.peer_addr()
+        .unwrap_or_else(|| SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::new(0, 0, 0, 0), 0)))
+}
+
+/// The loaded service in the given service group, as served by the `/services` endpoints.
+// Without log capture the Launcher never writes the run hook's output to its logs.
+fn log_capture_conflict(service: &Json, service_group: &ServiceGroup) -> Option<HttpResponse> {
+    if service["process"]["log_capture"] != false {
+        return None;
+    }
+    let body = CommandBody {
+        error: Some(format!(
+            "Log capture is disabled for {}, load it with --log-capture to read its logs",
+            service_group
+        )),
+        ..CommandBody::default()
+    };
+    Some(HttpResponse::Conflict().json(body))
+}
+
+fn loaded_service(req: &HttpRequest<AppState>, service_group: &ServiceGroup) -> Option<Json> {
+    let data = &req
+        .state()
+        .gateway_state
+        .read()
+        .expect("GatewayState lock is poisoned")
+        .services_data;
+    service_from_services(service_group, &data)
+}
+
 fn service_from_services(service_group: &ServiceGroup, services_json: &str) -> Option<Json> {
     match serde_json::from_str(services_json) {
         Ok(Json::Array(services)) => services
@@ -397,15 +1121,31 @@ fn service_from_services(service_group: &ServiceGroup, services_json: &str) -> O
 
 #[cfg(test)]
 mod tests {
-    use std::{fs::File, io::Read, path::PathBuf, sync::Mutex};
+    use std::{
+        fs::{File, OpenOptions},
+        io::{Read, Write},
+        path::PathBuf,
+        str::FromStr,
+        sync::Mutex,
+    };
 
+    use actix_web::http::{Method, StatusCode};
     use butterfly::{
         member::Member,
         server::{Server, ServerProxy, Suitability},
         trace::Trace,
     };
+    use hcore::service::ServiceGroup;
+    use protocol;
+    use protocol::codec::{SrvMessage, SrvTxn};
+    use protocol::net::{self, ErrCode};
     use serde_json;
+    use tempfile::TempDir;
 
+    use super::{
+        command_response, is_authorized, is_public, is_read, log_capture_conflict, read_tail,
+        tail_start, LoadBody, LogFollower,
+    };
     use test_helpers::*;
 
     fn validate_sample_file_against_schema(name: &str, schema: &str) {
@@ -423,6 +1163,167 @@ mod tests {
         assert_valid(&json, schema);
     }
 
+    #[test]
+    fn only_docs_and_health_checks_are_public() {
+        assert!(is_public(&Method::GET, "/"));
+        assert!(is_public(&Method::GET, "/services/redis/default/health"));
+        assert!(is_public(&Method::HEAD, "/services/redis/default/health"));
+        assert!(is_public(
+            &Method::GET,
+            "/services/redis/default/acme/health"
+        ));
+        assert!(!is_public(&Method::GET, "/census"));
+        assert!(!is_public(&Method::GET, "/butterfly"));
+        assert!(!is_public(&Method::GET, "/services"));
+        assert!(!is_public(&Method::GET, "/services/redis/default/config"));
+        assert!(!is_public(&Method::GET, "/services/redis/health"));
+        assert!(!is_public(&Method::GET, "/services/redis//health"));
+        assert!(!is_public(&Method::GET, "/butterfly/a/b/health"));
+        assert!(!is_public(
+            &Method::GET,
+            "/services/redis/default/acme/extra/health"
+        ));
+        assert!(!is_public(&Method::POST, "/services/redis/default/health"));
+        assert!(!is_public(&Method::DELETE, "/"));
+    }
+
+    #[test]
+    fn bearer_token_authorization() {
+        assert!(is_authorized("sekrit", Some("Bearer sekrit")));
+        assert!(is_authorized("sekrit", Some("bearer sekrit")));
+        assert!(!is_authorized("sekrit", Some("Bearer guess")));
+        assert!(!is_authorized("sekrit", Some("Basic sekrit")));
+        assert!(!is_authorized("sekrit", Some("sekrit")));
+        assert!(!is_authorized("sekrit", None));
+    }
+
+    #[test]
+    fn only_reads_are_allowed_without_a_token() {
+        assert!(is_read(&Method::GET));
+        assert!(is_read(&Method::HEAD));
+        assert!(!is_read(&Method::POST));
+        assert!(!is_read(&Method::PUT));
+        assert!(!is_read(&Method::DELETE));
+    }
+
+    fn reply<T>(msg: T, complete: bool) -> SrvMessage
+    where
+        SrvMessage: From<T>,
+    {
+        let mut reply = SrvMessage::from(msg);
+        reply.reply_for(SrvTxn::default(), complete);
+        reply
+    }
+
+    #[test]
+    fn command_replies_determine_response_status() {
+        let mut line = protocol::ctl::ConsoleLine::default();
+        line.line = "Unloading core/redis\n".to_string();
+        let accepted = command_response(vec![reply(line, false), reply(net::ok(), true)]);
+        assert_eq!(accepted.status(), StatusCode::ACCEPTED);
+
+        let not_found = net::err(ErrCode::NotFound, "Service not loaded, redis.default");
+        assert_eq!(
+            command_response(vec![reply(not_found, true)]).status(),
+            StatusCode::NOT_FOUND
+        );
+        let too_large = net::err(ErrCode::EntityTooLarge, "Configuration too large.");
+        assert_eq!(
+            command_response(vec![reply(too_large, true)]).status(),
+            StatusCode::PAYLOAD_TOO_LARGE
+        );
+        assert_eq!(
+            command_response(vec![]).status(),
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+    }
+
+    #[test]
+    fn run_hook_output_requires_log_capture() {
+        let service_group = ServiceGroup::from_str("redis.default").unwrap();
+        let captured = json!({"process": {"log_capture": true}});
+        assert!(log_capture_conflict(&captured, &service_group).is_none());
+        let uncaptured = json!({"process": {"log_capture": false}});
+        assert_eq!(
+            log_capture_conflict(&uncaptured, &service_group)
+                .unwrap()
+                .status(),
+            StatusCode::CONFLICT
+        );
+    }
+
+    #[test]
+    fn load_body_into_msg() {
+        let body: LoadBody = serde_json::from_str(
+            r#"{"ident": "core/redis", "topology": "leader", "binds": ["db:postgres.default"]}"#,
+        ).unwrap();
+        let msg = body.into_msg().unwrap();
+        assert_eq!(msg.ident.unwrap().name, "redis");
+        assert_eq!(msg.topology, Some(protocol::types::Topology::Leader as i32));
+        assert_eq!(msg.binds.unwrap().binds[0].name, "db");
+        assert_eq!(msg.specified_binds, Some(true));
+
+        let body: LoadBody =
+            serde_json::from_str(r#"{"ident": "core/redis", "strategy": "sometimes"}"#).unwrap();
+        assert!(body.into_msg().is_err());
+        assert!(serde_json::from_str::<LoadBody>(r#"{"ident": "core/redis", "x": 1}"#).is_err());
+    }
+
+    #[test]
+    fn tail_of_log() {
+        let log = b"one\ntwo\nthree\n";
+        assert_eq!(&log[tail_start(log, 2)..], b"two\nthree\n");
+        assert_eq!(&log[tail_start(log, 3)..], &log[..]);
+        assert_eq!(&log[tail_start(log, 10)..], &log[..]);
+        assert_eq!(&log[tail_start(log, 0)..], b"");
+        assert_eq!(&b"one\ntwo"[tail_start(b"one\ntwo", 1)..], b"two");
+    }
+
+    #[test]
+    fn tail_of_log_spanning_many_blocks() {
+        let tmp = TempDir::new().expect("create temp dir");
+        let path = tmp.path().join("run.stdout.log");
+        let log: String = (0..5000).map(|i| format!("line {}\n", i)).collect();
+        File::create(&path)
+            .unwrap()
+            .write_all(log.as_bytes())
+            .unwrap();
+
+        let (tail, size) = read_tail(&path, Some(2)).unwrap();
+        assert_eq!(&tail[..], &b"line 4998\nline 4999\n"[..]);
+        assert_eq!(size, log.len() as u64);
+        let (tail, _) = read_tail(&path, Some(4000)).unwrap();
+        assert!(tail.starts_with(b"line 1000\n"));
+        assert!(tail.ends_with(b"line 4999\n"));
+        let (tail, _) = read_tail(&path, Some(10000)).unwrap();
+        assert_eq!(&tail[..], log.as_bytes());
+        let (tail, _) = read_tail(&path, None).unwrap();
+        assert_eq!(&tail[..], log.as_bytes());
+    }
+
+    #[test]
+    fn follow_appended_log_output() {
+        let tmp = TempDir::new().expect("create temp dir");
+        let path = tmp.path().join("run.stdout.log");
+        let mut follower = LogFollower::new(path.clone(), 0);
+        assert_eq!(follower.read_new().unwrap(), None);
+
+        let mut log = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .unwrap();
+        log.write_all(b"one\n").unwrap();
+        assert_eq!(follower.read_new().unwrap().unwrap(), &b"one\n"[..]);
+        assert_eq!(follower.read_new().unwrap(), None);
+        log.write_all(b"two\n").unwrap();
+        assert_eq!(follower.read_new().unwrap().unwrap(), &b"two\n"[..]);
+
+        // A hook's log is recreated when it runs again.
+        File::create(&path).unwrap().write_all(b"1\n").unwrap();
+        assert_eq!(follower.read_new().unwrap().unwrap(), &b"1\n"[..]);
+    }
+
     #[test]
     fn sample_census_file_is_valid() {
         validate_sample_file_against_schema(
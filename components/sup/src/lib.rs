@@ -45,6 +45,7 @@ extern crate ansi_term;
 #[macro_use]
 extern crate bitflags;
 extern crate byteorder;
+extern crate bytes;
 #[cfg(target_os = "linux")]
 extern crate caps;
 #[macro_use]
@@ -73,6 +74,9 @@ extern crate libc;
 #[macro_use]
 extern crate log;
 extern crate notify;
+extern crate openssl;
+#[macro_use]
+extern crate prometheus;
 extern crate prost;
 extern crate protobuf;
 extern crate rand;
@@ -119,6 +123,8 @@ pub mod error;
 pub mod fs;
 pub mod http_gateway;
 pub mod manager;
+pub mod metrics;
+pub mod state_events;
 mod sys;
 pub mod templating;
 #[cfg(test)]
@@ -15,6 +15,7 @@
 extern crate ansi_term;
 extern crate clap;
 extern crate env_logger;
+extern crate habitat_butterfly as butterfly;
 extern crate habitat_common as common;
 #[macro_use]
 extern crate habitat_core as hcore;
@@ -33,6 +34,7 @@ extern crate url;
 #[cfg(test)]
 extern crate tempfile;
 
+use std::collections::BTreeMap;
 use std::env;
 use std::io::{self, Write};
 use std::net::{SocketAddr, ToSocketAddrs};
@@ -52,12 +54,14 @@ use hcore::url::{bldr_url_from_env, default_bldr_url};
 use launcher_client::{LauncherCli, ERR_NO_RETRY_EXCODE};
 use protocol::{
     ctl::ServiceBindList,
-    types::{ApplicationEnvironment, BindingMode, ServiceBind, Topology, UpdateStrategy},
+    tls::TlsConfig,
+    types::{ApplicationEnvironment, BindingMode, ServiceBind, Signal, Topology, UpdateStrategy},
 };
 
 use sup::cli::cli;
 use sup::command;
 use sup::config::{GossipListenAddr, GOSSIP_DEFAULT_PORT};
+use sup::ctl_gateway::audit::{AuditFilter, AuditLog, Outcome};
 use sup::error::{Error, Result, SupError};
 use sup::feat;
 use sup::http_gateway;
@@ -126,6 +130,7 @@ fn start() -> Result<()> {
         }
     };
     match app_matches.subcommand() {
+        ("audit", Some(m)) => sub_audit(m),
         ("bash", Some(_)) => sub_bash(),
         ("run", Some(m)) => {
             let launcher = launcher.ok_or(sup_error!(Error::NoLauncher))?;
@@ -137,6 +142,24 @@ fn start() -> Result<()> {
     }
 }
 
+fn sub_audit(m: &ArgMatches) -> Result<()> {
+    let filter = AuditFilter {
+        service_group: m.value_of("SERVICE_GROUP").map(str::to_string),
+        message: m.value_of("MESSAGE").map(str::to_string),
+        credential: m.value_of("CREDENTIAL").map(str::to_string),
+        outcome: match m.value_of("OUTCOME") {
+            Some(outcome) => Some(Outcome::from_str(outcome)?),
+            None => None,
+        },
+        since: m.value_of("SINCE").map(str::to_string),
+    };
+    let audit_log = AuditLog::new(ManagerConfig::default().sup_root());
+    for entry in audit_log.read(&filter)? {
+        println!("{}", entry);
+    }
+    Ok(())
+}
+
 fn sub_bash() -> Result<()> {
     command::shell::bash()
 }
@@ -209,10 +232,12 @@ fn mgrcfg_from_matches(m: &ArgMatches) -> Result<ManagerConfig> {
         update_url: bldr_url(m),
         update_channel: channel(m),
         http_disable: m.is_present("HTTP_DISABLE"),
+        http_auth_token: http_auth_token(m),
         organization: m.value_of("ORGANIZATION").map(|org| org.to_string()),
         gossip_permanent: m.is_present("PERMANENT_PEER"),
         ring_key: get_ring_key(m)?,
         gossip_peers: get_peers(m)?,
+        tags: get_tags(m)?,
         ..Default::default()
     };
     if let Some(addr_str) = m.value_of("LISTEN_GOSSIP") {
@@ -221,9 +246,24 @@ fn mgrcfg_from_matches(m: &ArgMatches) -> Result<ManagerConfig> {
     if let Some(addr_str) = m.value_of("LISTEN_HTTP") {
         cfg.http_listen = http_gateway::ListenAddr::from_str(addr_str)?;
     }
+    if let Some(cert) = m.value_of("HTTP_SERVER_CERTIFICATE") {
+        // Clap ensures the key accompanies the certificate
+        cfg.http_tls = Some(http_gateway::TlsConfig::new(
+            cert,
+            m.value_of("HTTP_SERVER_KEY").unwrap(),
+        ));
+    }
     if let Some(addr_str) = m.value_of("LISTEN_CTL") {
         cfg.ctl_listen = SocketAddr::from_str(addr_str)?;
     }
+    if let Some(cert) = m.value_of("CTL_SERVER_CERTIFICATE") {
+        // Clap ensures the key and CA certificate accompany the certificate
+        cfg.ctl_tls = Some(TlsConfig::new(
+            cert,
+            m.value_of("CTL_SERVER_KEY").unwrap(),
+            m.value_of("CTL_CLIENT_CA_CERTIFICATE").unwrap(),
+        ));
+    }
     if let Some(watch_peer_file) = m.value_of("PEER_WATCH_FILE") {
         cfg.watch_peer_file = Some(String::from(watch_peer_file));
     }
@@ -236,6 +276,22 @@ fn mgrcfg_from_matches(m: &ArgMatches) -> Result<ManagerConfig> {
 // Various CLI Parsing Functions
 ////////////////////////////////////////////////////////////////////////
 
+fn get_tags(matches: &ArgMatches) -> Result<BTreeMap<String, String>> {
+    let mut tags = BTreeMap::new();
+    if let Some(values) = matches.values_of("TAG") {
+        for tag in values {
+            // Clap ensures each tag is of the form KEY=VALUE
+            let mut parts = tag.splitn(2, '=');
+            let key = parts.next().unwrap_or("");
+            let value = parts.next().unwrap_or("");
+            tags.insert(key.to_string(), value.to_string());
+        }
+    }
+    // Tags are gossiped in every SWIM message about the Supervisor, so are kept small.
+    butterfly::member::check_tags(&tags).map_err(|err| sup_error!(Error::InvalidTags(err)))?;
+    Ok(tags)
+}
+
 fn get_peers(matches: &ArgMatches) -> Result<Vec<SocketAddr>> {
     // TODO fn: Clean this up--using a for loop doesn't feel good however an iterator was
     // causing a lot of developer/compiler type confusion
@@ -275,6 +331,9 @@ fn get_ring_key(m: &ArgMatches) -> Result<Option<SymKey>> {
         None => match henv::var(RING_KEY_ENVVAR) {
             Ok(val) => {
                 let (key, _) = SymKey::write_file_from_str(&val, &default_cache_key_path(None))?;
+                // The ring key may have been rotated since the environment was set, in which
+                // case the newest revision in the cache is the one to hold.
+                let key = SymKey::get_latest_pair_for(&key.name, &default_cache_key_path(None))?;
                 Ok(Some(key))
             }
             Err(_) => match henv::var(RING_ENVVAR) {
@@ -288,6 +347,15 @@ fn get_ring_key(m: &ArgMatches) -> Result<Option<SymKey>> {
     }
 }
 
+/// Resolve the HTTPGateway's bearer token from CLI args or the environment. An empty token is
+/// treated as no token.
+fn http_auth_token(m: &ArgMatches) -> Option<String> {
+    m.value_of("HTTP_AUTH_TOKEN")
+        .map(|token| token.to_string())
+        .or_else(|| henv::var(http_gateway::GATEWAY_AUTH_TOKEN_ENVVAR).ok())
+        .and_then(|token| if token.is_empty() { None } else { Some(token) })
+}
+
 /// Resolve a Builder URL. Taken from CLI args, the environment, or
 /// (failing those) a default value.
 fn bldr_url(m: &ArgMatches) -> String {
@@ -478,6 +546,44 @@ fn update_svc_load_from_input(m: &ArgMatches, msg: &mut protocol::ctl::SvcLoad)
     msg.binding_mode = get_binding_mode_from_input(m).map(|v| v as i32);
     msg.topology = get_topology_from_input(m).map(|v| v as i32);
     msg.update_strategy = get_strategy_from_input(m).map(|v| v as i32);
+    msg.health_check_interval = m
+        .value_of("HEALTH_CHECK_INTERVAL")
+        .and_then(|v| v.parse().ok());
+    msg.health_check_timeout = m
+        .value_of("HEALTH_CHECK_TIMEOUT")
+        .and_then(|v| v.parse().ok());
+    msg.health_check_failure_threshold = m
+        .value_of("HEALTH_CHECK_FAILURE_THRESHOLD")
+        .and_then(|v| v.parse().ok());
+    msg.health_check_success_threshold = m
+        .value_of("HEALTH_CHECK_SUCCESS_THRESHOLD")
+        .and_then(|v| v.parse().ok());
+    msg.restart_after_critical = m
+        .value_of("RESTART_AFTER_CRITICAL")
+        .and_then(|v| v.parse().ok());
+    msg.restart_backoff = m.value_of("RESTART_BACKOFF").and_then(|v| v.parse().ok());
+    msg.restart_max = m.value_of("RESTART_MAX").and_then(|v| v.parse().ok());
+    msg.restart_window = m.value_of("RESTART_WINDOW").and_then(|v| v.parse().ok());
+    msg.canary_size = m.value_of("CANARY_SIZE").map(str::to_string);
+    msg.canary_soak = m.value_of("CANARY_SOAK").and_then(|v| v.parse().ok());
+    if let Some(windows) = m.values_of("UPDATE_WINDOW") {
+        msg.update_windows = windows.map(str::to_string).collect();
+    }
+    msg.update_version = m.value_of("UPDATE_VERSION").map(str::to_string);
+    if m.is_present("LOG_CAPTURE") {
+        msg.log_capture = Some(true);
+    }
+    msg.log_max_size = m.value_of("LOG_MAX_SIZE").and_then(|v| v.parse().ok());
+    msg.log_max_age = m.value_of("LOG_MAX_AGE").and_then(|v| v.parse().ok());
+    msg.log_retain = m.value_of("LOG_RETAIN").and_then(|v| v.parse().ok());
+    msg.cpu_limit = m.value_of("CPU_LIMIT").and_then(|v| v.parse().ok());
+    msg.memory_limit = m.value_of("MEMORY_LIMIT").and_then(|v| v.parse().ok());
+    msg.pids_limit = m.value_of("PIDS_LIMIT").and_then(|v| v.parse().ok());
+    msg.shutdown_signal = m
+        .value_of("SHUTDOWN_SIGNAL")
+        .and_then(|v| Signal::from_str(v).ok())
+        .map(|v| v as i32);
+    msg.shutdown_timeout = m.value_of("SHUTDOWN_TIMEOUT").and_then(|v| v.parse().ok());
     Ok(())
 }
 
@@ -587,6 +693,61 @@ mod test {
             assert_eq!(config.ctl_listen, expected_addr);
         }
 
+        #[test]
+        fn ctl_tls_should_be_set() {
+            use std::fs::File;
+            use tempfile::TempDir;
+
+            let tmp = TempDir::new().expect("create temp dir");
+            let cert = tmp.path().join("ctl.crt");
+            let key = tmp.path().join("ctl.key");
+            let ca = tmp.path().join("ca.crt");
+            for path in &[&cert, &key, &ca] {
+                File::create(path).expect("create tls file");
+            }
+            let cmd = format!(
+                "hab-sup run --ctl-server-certificate {} --ctl-server-key {} \
+                 --ctl-client-ca-certificate {}",
+                cert.display(),
+                key.display(),
+                ca.display()
+            );
+            let config = config_from_cmd_str(&cmd);
+            assert_eq!(config.ctl_tls, Some(TlsConfig::new(cert, key, ca)));
+
+            let config = config_from_cmd_str("hab-sup run");
+            assert_eq!(config.ctl_tls, None);
+        }
+
+        #[test]
+        fn http_tls_should_be_set() {
+            use std::fs::File;
+            use tempfile::TempDir;
+
+            let tmp = TempDir::new().expect("create temp dir");
+            let cert = tmp.path().join("http.crt");
+            let key = tmp.path().join("http.key");
+            for path in &[&cert, &key] {
+                File::create(path).expect("create tls file");
+            }
+            let cmd = format!(
+                "hab-sup run --http-server-certificate {} --http-server-key {}",
+                cert.display(),
+                key.display()
+            );
+            let config = config_from_cmd_str(&cmd);
+            assert_eq!(config.http_tls, Some(http_gateway::TlsConfig::new(cert, key)));
+
+            let config = config_from_cmd_str("hab-sup run");
+            assert_eq!(config.http_tls, None);
+        }
+
+        #[test]
+        fn http_auth_token_should_be_set() {
+            let config = config_from_cmd_str("hab-sup run --http-auth-token sekrit");
+            assert_eq!(config.http_auth_token, Some("sekrit".to_string()));
+        }
+
         #[test]
         fn organization_should_be_set() {
             let config = config_from_cmd_str("hab-sup run --org foobar");
@@ -0,0 +1,290 @@
+// Copyright (c) 2018 Chef Software Inc. and/or applicable contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! One-off commands run in a service's environment by `hab svc exec`.
+//!
+//! The Supervisor runs the command as it does the service's hooks, in the cgroup of the
+//! service's process. Its output is read from its pipes and streamed back to the client as the
+//! command writes it. If the client hangs up first, the command and anything it started are
+//! killed.
+
+#[cfg(windows)]
+use hcore::os::process::windows_child::Child;
+use std::io::{BufRead, BufReader, Read};
+#[cfg(not(windows))]
+use std::process::Child;
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::Duration;
+
+use protocol;
+use protocol::net::{self, ErrCode};
+
+use ctl_gateway::CtlRequest;
+use util::exec;
+
+static LOGKEY: &'static str = "EX";
+/// How long to wait for output before checking that the client is still there, and, once the
+/// command has exited, for the last of its output.
+const POLL_INTERVAL_MS: u64 = 100;
+
+enum Event {
+    /// A line of the command's output.
+    Line(String),
+    /// The command exited, with its exit code if it has one.
+    Exited(Option<i32>),
+}
+
+/// Stream the output of a command started by `exec::run_command` back to the client until the
+/// command exits, then complete the request: successfully if the command exited with a status
+/// of 0, otherwise with an error giving its exit status.
+pub fn stream_output(mut child: Child, mut req: CtlRequest) {
+    let pid = child.id();
+    let (tx, rx) = mpsc::channel();
+    if let Some(stdout) = child.stdout.take() {
+        forward(format!("exec-{}-out", pid), stdout, tx.clone());
+    }
+    if let Some(stderr) = child.stderr.take() {
+        forward(format!("exec-{}-err", pid), stderr, tx.clone());
+    }
+    let waiter = thread::Builder::new()
+        .name(format!("exec-{}-wait", pid))
+        .spawn(move || {
+            let code = child.wait().ok().and_then(|status| status.code());
+            let _ = tx.send(Event::Exited(code));
+        });
+    if let Err(err) = waiter {
+        outputln!("Unable to wait for pid {}, {}", pid, err);
+        kill(pid);
+        req.reply_complete(net::err(ErrCode::Internal, err.to_string()));
+        return;
+    }
+    let streamer = thread::Builder::new()
+        .name(format!("exec-{}", pid))
+        .spawn(move || {
+            let mut exited = false;
+            let mut exit_code = None;
+            loop {
+                if !exited && req.is_abandoned() {
+                    outputln!("Client hung up, killing pid {}", pid);
+                    kill(pid);
+                    break;
+                }
+                match rx.recv_timeout(Duration::from_millis(POLL_INTERVAL_MS)) {
+                    Ok(Event::Line(line)) => {
+                        let mut msg = protocol::ctl::ConsoleLine::default();
+                        msg.line = line;
+                        req.reply_partial(msg);
+                    }
+                    Ok(Event::Exited(code)) => {
+                        exited = true;
+                        exit_code = code;
+                    }
+                    // Don't wait on anything the command left running holding its pipes open.
+                    Err(RecvTimeoutError::Timeout) if exited => break,
+                    Err(RecvTimeoutError::Timeout) => (),
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+            }
+            match exit_code {
+                Some(0) => req.reply_complete(net::ok()),
+                Some(code) => req.reply_complete(net::err(
+                    ErrCode::Internal,
+                    format!("Command exited with status {}", code),
+                )),
+                None => req.reply_complete(net::err(
+                    ErrCode::Internal,
+                    "Command exited without a status code",
+                )),
+            }
+        });
+    if let Err(err) = streamer {
+        outputln!("Unable to stream output of pid {}, {}", pid, err);
+        kill(pid);
+    }
+}
+
+/// Send each line read from one of the command's pipes to `tx`, until the pipe is closed.
+fn forward<R>(name: String, pipe: R, tx: Sender<Event>)
+where
+    R: Read + Send + 'static,
+{
+    let spawned = thread::Builder::new()
+        .name(name)
+        .spawn(move || read_lines(pipe, &tx));
+    if let Err(err) = spawned {
+        outputln!("Unable to read command output, {}", err);
+    }
+}
+
+fn read_lines<R>(pipe: R, tx: &Sender<Event>)
+where
+    R: Read,
+{
+    let mut reader = BufReader::new(pipe);
+    let mut buf = vec![];
+    loop {
+        buf.clear();
+        match reader.read_until(b'\n', &mut buf) {
+            Ok(0) => break,
+            Ok(_) => {
+                let mut line = String::from_utf8_lossy(&buf).into_owned();
+                if !line.ends_with('\n') {
+                    line.push('\n');
+                }
+                if tx.send(Event::Line(line)).is_err() {
+                    break;
+                }
+            }
+            Err(err) => {
+                debug!("Unable to read command output, {}", err);
+                break;
+            }
+        }
+    }
+}
+
+fn kill(pid: u32) {
+    if let Err(err) = exec::kill_process_group(pid) {
+        outputln!("Unable to kill pid {}, {}", pid, err);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Cursor;
+    #[cfg(unix)]
+    use std::os::unix::process::CommandExt;
+    #[cfg(unix)]
+    use std::process::{Command, Stdio};
+    #[cfg(unix)]
+    use std::time::Instant;
+
+    #[cfg(unix)]
+    use futures::sync::mpsc as futures_mpsc;
+    #[cfg(unix)]
+    use futures::Stream;
+    #[cfg(unix)]
+    use hcore::os::process::{self, Pid};
+    #[cfg(unix)]
+    use libc;
+    #[cfg(unix)]
+    use protocol::codec::{SrvMessage, SrvTxn};
+
+    use super::*;
+
+    /// Run a shell script as `exec::run_command` would run a command: in a process group of its
+    /// own, with its output piped.
+    #[cfg(unix)]
+    fn spawn(script: &str) -> Child {
+        Command::new("sh")
+            .arg("-c")
+            .arg(script)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .before_exec(|| {
+                unsafe { libc::setpgid(0, 0) };
+                Ok(())
+            })
+            .spawn()
+            .unwrap()
+    }
+
+    #[cfg(unix)]
+    fn request() -> (CtlRequest, futures_mpsc::UnboundedReceiver<SrvMessage>) {
+        let (tx, rx) = futures_mpsc::unbounded();
+        (CtlRequest::new(Some(tx), Some(SrvTxn::default())), rx)
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn streams_output_and_forwards_exit_status() {
+        let (req, rx) = request();
+        stream_output(spawn("echo one; echo two >&2; exit 3"), req);
+
+        let mut lines = vec![];
+        let mut complete = None;
+        for msg in rx.wait() {
+            let msg = msg.unwrap();
+            if msg.is_complete() {
+                complete = Some(msg);
+                break;
+            }
+            let line = msg.parse::<protocol::ctl::ConsoleLine>().unwrap().line;
+            lines.push(line);
+        }
+        lines.sort();
+        assert_eq!(lines, vec!["one\n", "two\n"]);
+        let complete = complete.unwrap();
+        assert_eq!(complete.message_id(), "NetErr");
+        let err = complete.parse::<protocol::net::NetErr>().unwrap();
+        assert_eq!(err.msg, "Command exited with status 3");
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn completes_successfully_when_the_command_succeeds() {
+        let (req, rx) = request();
+        stream_output(spawn("true"), req);
+
+        let complete = rx
+            .wait()
+            .map(|msg| msg.unwrap())
+            .find(|msg| msg.is_complete())
+            .unwrap();
+        assert_eq!(complete.message_id(), "NetOk");
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn kills_the_command_when_the_client_hangs_up() {
+        let (req, rx) = request();
+        let child = spawn("echo started; sleep 30 & wait");
+        let pid = child.id();
+        stream_output(child, req);
+
+        let mut rx = rx.wait();
+        let first = rx.next().unwrap().unwrap();
+        assert_eq!(
+            first.parse::<protocol::ctl::ConsoleLine>().unwrap().line,
+            "started\n"
+        );
+        drop(rx);
+
+        let started = Instant::now();
+        while process::is_alive(pid as Pid) {
+            assert!(
+                started.elapsed() < Duration::from_secs(5),
+                "command still running after the client hung up"
+            );
+            thread::sleep(Duration::from_millis(POLL_INTERVAL_MS));
+        }
+    }
+
+    #[test]
+    fn read_lines_yields_each_line() {
+        let (tx, rx) = mpsc::channel();
+        read_lines(Cursor::new("one\ntwo\nthree"), &tx);
+        drop(tx);
+        let lines = rx
+            .iter()
+            .filter_map(|event| match event {
+                Event::Line(line) => Some(line),
+                Event::Exited(_) => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(lines, vec!["one\n", "two\n", "three\n"]);
+    }
+}
@@ -16,6 +16,7 @@ pub mod service;
 #[macro_use]
 mod debug;
 mod events;
+mod exec;
 mod file_watcher;
 mod peer_watcher;
 mod periodic;
@@ -26,7 +27,7 @@ mod sys;
 mod user_config_watcher;
 
 use std;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt;
 use std::fs::{self, File, OpenOptions};
 use std::io::{BufRead, BufReader, Read, Write};
@@ -42,13 +43,14 @@ use std::time::Duration;
 
 use butterfly;
 use butterfly::member::Member;
+use butterfly::rumor::{RingKey, Tombstone};
 use butterfly::server::{timing::Timing, ServerProxy, Suitability};
 use butterfly::trace::Trace;
 use common::command::package::install::InstallSource;
 use common::ui::UIWriter;
 use futures::prelude::*;
 use futures::sync::mpsc;
-use hcore::crypto::SymKey;
+use hcore::crypto::{default_cache_key_path, PairType, SymKey};
 use hcore::env;
 use hcore::fs::FS_ROOT_PATH;
 use hcore::os::process::{self, Pid, Signal};
@@ -59,6 +61,7 @@ use hcore::service::ServiceGroup;
 use launcher_client::{LauncherCli, LAUNCHER_LOCK_CLEAN_ENV, LAUNCHER_PID_ENV};
 use protocol;
 use protocol::net::{self, ErrCode, NetResult};
+use protocol::tls::TlsConfig;
 use serde;
 use serde_json;
 use time::{self, Duration as TimeDuration, Timespec};
@@ -67,7 +70,9 @@ use toml;
 
 use self::peer_watcher::PeerWatcher;
 use self::self_updater::{SelfUpdater, SUP_PKG_IDENT};
-use self::service::{health::HealthCheck, DesiredState, IntoServiceSpec, Pkg, ProcessState};
+use self::service::{
+    health::HealthCheck, DesiredState, IntoServiceSpec, Pkg, ProcessState, ResourceUsage,
+};
 pub use self::service::{
     CompositeSpec, ConfigRendering, Service, ServiceBind, ServiceProxy, ServiceSpec, Spec,
     Topology, UpdateStrategy,
@@ -79,10 +84,14 @@ use self::user_config_watcher::UserConfigWatcher;
 use super::feat;
 use census::{CensusRing, CensusRingProxy};
 use config::GossipListenAddr;
+use ctl_gateway::audit::AuditLog;
+use ctl_gateway::auth::Credentials;
 use ctl_gateway::{self, CtlRequest};
 use error::{Error, Result, SupError};
 use http_gateway;
 use manager::service::spec::DesiredState as SpecDesiredState;
+use metrics;
+use state_events::{ServiceSnapshot, StateEventBus, StateTracker};
 use util;
 use ShutdownReason;
 use VERSION;
@@ -123,6 +132,13 @@ impl FsCfg {
             sup_root: sup_root,
         }
     }
+
+    /// The file recording the package which failed its smoke test in
+    /// the given service group, so it isn't retried once the
+    /// Supervisor restarts.
+    pub fn failed_pkg_file(&self, service_group: &ServiceGroup) -> PathBuf {
+        self.data_path.join(format!("{}.failed", service_group))
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -134,13 +150,21 @@ pub struct ManagerConfig {
     pub update_channel: String,
     pub gossip_listen: GossipListenAddr,
     pub ctl_listen: SocketAddr,
+    /// Secures CtlGateway connections from non-loopback addresses with mutual TLS.
+    pub ctl_tls: Option<TlsConfig>,
     pub http_listen: http_gateway::ListenAddr,
     pub http_disable: bool,
+    /// Serves the HTTPGateway over HTTPS rather than HTTP.
+    pub http_tls: Option<http_gateway::TlsConfig>,
+    /// Bearer token required by the HTTPGateway for all but health checks.
+    pub http_auth_token: Option<String>,
     pub gossip_peers: Vec<SocketAddr>,
     pub gossip_permanent: bool,
     pub ring_key: Option<SymKey>,
     pub organization: Option<String>,
     pub watch_peer_file: Option<String>,
+    /// Key/value tags describing this Supervisor, gossiped to the rest of the ring.
+    pub tags: BTreeMap<String, String>,
 }
 
 impl ManagerConfig {
@@ -159,13 +183,17 @@ impl Default for ManagerConfig {
             update_channel: "".to_string(),
             gossip_listen: GossipListenAddr::default(),
             ctl_listen: protocol::ctl::default_addr(),
+            ctl_tls: None,
             http_listen: http_gateway::ListenAddr::default(),
             http_disable: false,
+            http_tls: None,
+            http_auth_token: None,
             gossip_peers: vec![],
             gossip_permanent: false,
             ring_key: None,
             organization: None,
             watch_peer_file: None,
+            tags: BTreeMap::new(),
         }
     }
 }
@@ -178,6 +206,8 @@ pub struct ManagerState {
     pub cfg: ManagerConfig,
     pub services: Arc<RwLock<HashMap<PackageIdent, Service>>>,
     pub gateway_state: Arc<RwLock<GatewayState>>,
+    pub launcher: LauncherCli,
+    pub butterfly: butterfly::Server,
 }
 
 #[derive(Debug, Default)]
@@ -194,7 +224,6 @@ pub struct Manager {
     census_ring: CensusRing,
     events_group: Option<ServiceGroup>,
     fs_cfg: Arc<FsCfg>,
-    launcher: LauncherCli,
     updater: ServiceUpdater,
     peer_watcher: Option<PeerWatcher>,
     spec_watcher: SpecWatcher,
@@ -204,6 +233,8 @@ pub struct Manager {
     service_states: HashMap<PackageIdent, Timespec>,
     sys: Arc<Sys>,
     http_disable: bool,
+    state_events: StateEventBus,
+    state_tracker: StateTracker,
 }
 
 impl Manager {
@@ -234,18 +265,104 @@ impl Manager {
         let specs = match package.pkg_type()? {
             PackageType::Standalone => {
                 let mut spec = ServiceSpec::default();
-                opts.into_spec(&mut spec);
+                opts.into_spec(&mut spec)?;
                 vec![spec]
             }
             PackageType::Composite => opts.into_composite_spec(
                 package.ident().name.clone(),
                 package.pkg_services()?,
                 package.bind_map()?,
-            ),
+            )?,
         };
         Ok(specs)
     }
 
+    pub fn service_signal(
+        mgr: &ManagerState,
+        req: &mut CtlRequest,
+        opts: protocol::ctl::SvcSignal,
+    ) -> NetResult<()> {
+        let ident: PackageIdent = opts.ident.ok_or(err_update_client())?.into();
+        let signal = opts
+            .signal
+            .and_then(protocol::types::Signal::from_i32)
+            .ok_or(err_update_client())?;
+        let specs = match Self::existing_specs_for_ident(&mgr.cfg, &ident)? {
+            Some(Spec::Service(spec)) => vec![spec],
+            Some(Spec::Composite(_, service_specs)) => service_specs,
+            None => {
+                return Err(net::err(
+                    ErrCode::NotFound,
+                    format!("Service not loaded, {}", &ident),
+                ));
+            }
+        };
+        let services = mgr.services.read().expect("Services lock is poisoned!");
+        for spec in specs {
+            match services.get(&spec.ident) {
+                Some(service) if service.process_state() == ProcessState::Up => {
+                    service.signal(&mgr.launcher, signal)?;
+                    req.info(format!("Sent SIG{} to {}", signal, &spec.ident))?;
+                }
+                _ => {
+                    return Err(net::err(
+                        ErrCode::Conflict,
+                        format!("Service not running, {}", &spec.ident),
+                    ));
+                }
+            }
+        }
+        req.reply_complete(net::ok());
+        Ok(())
+    }
+
+    /// Run a one-off command in a running service's environment. The request is completed once
+    /// the command exits, with its output streamed back until then.
+    pub fn service_exec(
+        mgr: &ManagerState,
+        req: &mut CtlRequest,
+        opts: protocol::ctl::SvcExec,
+    ) -> NetResult<()> {
+        let ident: PackageIdent = opts.ident.ok_or(err_update_client())?.into();
+        let command = match opts.command {
+            Some(ref command) if !command.is_empty() => command.clone(),
+            _ => {
+                return Err(net::err(ErrCode::InvalidPayload, "No command given to run"));
+            }
+        };
+        let spec = match Self::existing_specs_for_ident(&mgr.cfg, &ident)? {
+            Some(Spec::Service(spec)) => spec,
+            Some(Spec::Composite(..)) => {
+                return Err(net::err(
+                    ErrCode::NotSupported,
+                    format!(
+                        "Commands must be run in one of the services of composite {}",
+                        &ident
+                    ),
+                ));
+            }
+            None => {
+                return Err(net::err(
+                    ErrCode::NotFound,
+                    format!("Service not loaded, {}", &ident),
+                ));
+            }
+        };
+        let services = mgr.services.read().expect("Services lock is poisoned!");
+        let service = match services.get(&spec.ident) {
+            Some(service) if service.process_state() == ProcessState::Up => service,
+            _ => {
+                return Err(net::err(
+                    ErrCode::Conflict,
+                    format!("Service not running, {}", &spec.ident),
+                ));
+            }
+        };
+        let child = service.exec(&command, &opts.args)?;
+        exec::stream_output(child, req.clone());
+        Ok(())
+    }
+
     pub fn service_status(
         mgr: &ManagerState,
         req: &mut CtlRequest,
@@ -356,7 +473,9 @@ impl Manager {
             cfg.ctl_listen,
             cfg.http_listen,
         );
+        sys.tags = cfg.tags.clone();
         let member = Self::load_member(&mut sys, &fs_cfg)?;
+        let (ring_key, newer_ring_key) = Self::load_ring_keys(cfg.ring_key, &fs_cfg.data_path);
         let services = Arc::new(RwLock::new(HashMap::new()));
         let gateway_state = Arc::new(RwLock::new(GatewayState::default()));
         let server = butterfly::Server::new(
@@ -364,11 +483,14 @@ impl Manager {
             sys.gossip_listen(),
             member,
             Trace::default(),
-            cfg.ring_key,
+            ring_key,
             None,
             Some(&fs_cfg.data_path),
             Box::new(SuitabilityLookup(services.clone())),
         )?;
+        if let Some(key) = newer_ring_key {
+            server.add_ring_key(key);
+        }
         outputln!("Supervisor Member-ID {}", sys.member_id);
         for peer_addr in &cfg.gossip_peers {
             let mut peer = Member::default();
@@ -388,13 +510,14 @@ impl Manager {
                 cfg: cfg_static,
                 services: services,
                 gateway_state: gateway_state,
+                launcher: launcher,
+                butterfly: server.clone(),
             }),
             self_updater: self_updater,
             updater: ServiceUpdater::new(server.clone()),
             census_ring: CensusRing::new(sys.member_id.clone()),
             butterfly: server,
             events_group: cfg.eventsrv_group,
-            launcher: launcher,
             peer_watcher: peer_watcher,
             spec_watcher: SpecWatcher::run(&fs_cfg.specs_path)?,
             user_config_watcher: UserConfigWatcher::new(),
@@ -403,9 +526,51 @@ impl Manager {
             service_states: HashMap::new(),
             sys: Arc::new(sys),
             http_disable: cfg.http_disable,
+            state_events: StateEventBus::default(),
+            state_tracker: StateTracker::default(),
         })
     }
 
+    /// Returns the revision of the ring key to encrypt with, and the newer revision given, if
+    /// any. The newest revision in the key cache may have been received while the ring key was
+    /// being rotated, before the rest of the ring held it. A restarted Supervisor keeps
+    /// encrypting with the revision it was encrypting with before it stopped until they do.
+    fn load_ring_keys(
+        ring_key: Option<SymKey>,
+        data_path: &Path,
+    ) -> (Option<SymKey>, Option<SymKey>) {
+        let newest = match ring_key {
+            Some(key) => key,
+            None => return (None, None),
+        };
+        let active = match butterfly::Server::active_ring_key_rev(data_path) {
+            Some(rev) => format!("{}-{}", newest.name, rev),
+            None => return (Some(newest), None),
+        };
+        if active >= newest.name_with_rev() {
+            return (Some(newest), None);
+        }
+        match SymKey::get_pair_for(&active, &default_cache_key_path(None)) {
+            Ok(key) => {
+                outputln!(
+                    "Encrypting with ring key {} until the rest of the ring holds {}",
+                    active,
+                    newest.name_with_rev()
+                );
+                (Some(key), Some(newest))
+            }
+            Err(err) => {
+                warn!(
+                    "Failed to load ring key {}, encrypting with {}, {}",
+                    active,
+                    newest.name_with_rev(),
+                    err
+                );
+                (Some(newest), None)
+            }
+        }
+    }
+
     /// Load the initial Butterly Member which is used in initializing the Butterfly server. This
     /// will load the member-id for the initial Member from disk if a previous manager has been
     /// run.
@@ -443,6 +608,7 @@ impl Manager {
         }
         sys.member_id = member.id.to_string();
         member.persistent = sys.permanent;
+        member.tags = sys.tags.clone();
         Ok(member)
     }
 
@@ -502,6 +668,47 @@ impl Manager {
         }
     }
 
+    /// The service groups of the services currently loaded for the given `PackageIdent`.
+    pub fn service_groups_for_ident(
+        cfg: &ManagerConfig,
+        ident: &PackageIdent,
+    ) -> Vec<ServiceGroup> {
+        let specs = match Self::existing_specs_for_ident(cfg, ident) {
+            Ok(Some(Spec::Service(spec))) => vec![spec],
+            Ok(Some(Spec::Composite(_, specs))) => specs,
+            Ok(None) | Err(_) => vec![],
+        };
+        specs
+            .iter()
+            .filter_map(|spec| {
+                ServiceGroup::new(
+                    spec.application_environment.as_ref(),
+                    &spec.ident.name,
+                    &spec.group,
+                    cfg.organization.as_ref().map(String::as_str),
+                ).ok()
+            }).collect()
+    }
+
+    /// The `PackageIdent` of the service loaded into the given service group, if there is one.
+    pub fn ident_for_service_group(
+        cfg: &ManagerConfig,
+        service_group: &ServiceGroup,
+    ) -> Option<PackageIdent> {
+        if service_group.org().is_some()
+            && service_group.org() != cfg.organization.as_ref().map(String::as_str)
+        {
+            return None;
+        }
+        let spec_files = SpecWatcher::spec_files(Self::specs_path(cfg.sup_root())).ok()?;
+        spec_files
+            .iter()
+            .filter_map(|file| ServiceSpec::from_file(file).ok())
+            .find(|spec| {
+                spec.ident.name == service_group.service() && spec.group == service_group.group()
+            }).map(|spec| spec.ident)
+    }
+
     pub fn save_spec_for(cfg: &ManagerConfig, spec: &ServiceSpec) -> Result<()> {
         spec.to_file(Self::spec_path_for(cfg, spec))
     }
@@ -671,8 +878,23 @@ impl Manager {
         let http_listen_addr = self.sys.http_listen();
         let ctl_listen_addr = self.sys.ctl_listen();
         let ctl_secret_key = ctl_gateway::readgen_secret_key(&self.fs_cfg.sup_root)?;
+        let ctl_credentials = Credentials::load(&self.fs_cfg.sup_root, ctl_secret_key)?;
+        let audit_log = AuditLog::new(&self.fs_cfg.sup_root);
+        let ctl_acceptor = match self.state.cfg.ctl_tls {
+            Some(ref tls) => {
+                outputln!("Requiring TLS for remote ctl-gateway clients");
+                Some(tls.acceptor()?)
+            }
+            None => None,
+        };
         outputln!("Starting ctl-gateway on {}", &ctl_listen_addr);
-        ctl_gateway::server::run(ctl_listen_addr, ctl_secret_key, ctl_tx);
+        ctl_gateway::server::run(
+            ctl_listen_addr,
+            ctl_credentials,
+            ctl_acceptor,
+            audit_log.clone(),
+            ctl_tx.clone(),
+        );
         debug!("ctl-gateway started");
 
         if self.http_disable {
@@ -686,10 +908,28 @@ impl Manager {
                 Condvar::new(),
             ));
 
-            outputln!("Starting http-gateway on {}", &http_listen_addr);
+            let http_acceptor = match self.state.cfg.http_tls {
+                Some(ref tls) => Some(tls.acceptor()?),
+                None => None,
+            };
+            let http_auth_token = self.state.cfg.http_auth_token.clone();
+            if http_auth_token.is_some() {
+                outputln!("Requiring a bearer token for http-gateway requests but health checks");
+            }
+
+            outputln!(
+                "Starting http-gateway on {}{}",
+                &http_listen_addr,
+                if http_acceptor.is_some() { " with TLS" } else { "" }
+            );
             http_gateway::Server::run(
                 http_listen_addr.clone(),
                 self.state.gateway_state.clone(),
+                http_acceptor,
+                http_auth_token,
+                self.state_events.clone(),
+                ctl_tx,
+                audit_log,
                 pair.clone(),
             );
 
@@ -761,7 +1001,7 @@ impl Manager {
             }
 
             let next_check = time::get_time() + TimeDuration::milliseconds(1000);
-            if self.launcher.is_stopping() {
+            if self.state.launcher.is_stopping() {
                 self.shutdown(ShutdownReason::LauncherStopping);
                 return Ok(());
             }
@@ -834,10 +1074,13 @@ impl Manager {
                 .expect("Services lock is poisoned!")
                 .values_mut()
             {
-                if service.tick(&self.census_ring, &self.launcher) {
+                if service.tick(&self.census_ring, &self.state.launcher) {
                     self.gossip_latest_service_rumor(&service);
                 }
             }
+            self.adopt_ring_keys();
+            self.update_metrics();
+            self.publish_state_events();
             let time_to_wait = ((next_check - time::get_time()).num_milliseconds()).max(100);
             core.turn(Some(Duration::from_millis(time_to_wait as u64)));
         }
@@ -959,7 +1202,7 @@ impl Manager {
         );
         let mut client = match butterfly::client::Client::new(
             mgr.cfg.gossip_listen.local_addr(),
-            mgr.cfg.ring_key.clone(),
+            mgr.butterfly.ring_key(),
         ) {
             Ok(client) => client,
             Err(err) => {
@@ -976,6 +1219,25 @@ impl Manager {
         }
     }
 
+    pub fn service_group_forget(
+        mgr: &ManagerState,
+        req: &mut CtlRequest,
+        opts: protocol::ctl::SvcGroupForget,
+    ) -> NetResult<()> {
+        let service_group: ServiceGroup = opts.service_group.ok_or(err_update_client())?.into();
+        if mgr.butterfly.is_advertised(&service_group) {
+            return Err(net::err(
+                ErrCode::Conflict,
+                format!("Service group is still running, {}", service_group),
+            ));
+        }
+        outputln!("Forgetting service group {}", service_group);
+        let tombstone = Tombstone::new(mgr.butterfly.member_id(), service_group);
+        mgr.butterfly.insert_tombstone(tombstone);
+        req.reply_complete(net::ok());
+        Ok(())
+    }
+
     pub fn service_file_put(
         mgr: &ManagerState,
         req: &mut CtlRequest,
@@ -997,7 +1259,7 @@ impl Manager {
         );
         let mut client = match butterfly::client::Client::new(
             mgr.cfg.gossip_listen.local_addr(),
-            mgr.cfg.ring_key.clone(),
+            mgr.butterfly.ring_key(),
         ) {
             Ok(client) => client,
             Err(err) => {
@@ -1080,7 +1342,7 @@ impl Manager {
 
                 match spec {
                     Spec::Service(mut service_spec) => {
-                        opts.into_spec(&mut service_spec);
+                        opts.into_spec(&mut service_spec)?;
 
                         // Only install if we don't have something
                         // locally; otherwise you could potentially
@@ -1111,7 +1373,7 @@ impl Manager {
                                 };
 
                             for mut service_spec in existing_service_specs.iter_mut() {
-                                opts.update_composite(&mut bind_map, &mut service_spec);
+                                opts.update_composite(&mut bind_map, &mut service_spec)?;
                                 Self::save_spec_for(&mgr.cfg, service_spec)?;
                                 req.info(format!(
                                     "The {} service was successfully loaded",
@@ -1352,7 +1614,7 @@ impl Manager {
         let member_id = opts.member_id.ok_or(err_update_client())?;
         let mut client = match butterfly::client::Client::new(
             mgr.cfg.gossip_listen.local_addr(),
-            mgr.cfg.ring_key.clone(),
+            mgr.butterfly.ring_key(),
         ) {
             Ok(client) => client,
             Err(err) => {
@@ -1370,6 +1632,50 @@ impl Manager {
         }
     }
 
+    /// Generate a new revision of the ring key and distribute it to the rest of the ring. The key
+    /// is generated here rather than by the client, so its secret is never sent over the ctl
+    /// connection.
+    pub fn ring_key_rotate(
+        mgr: &ManagerState,
+        req: &mut CtlRequest,
+        _opts: protocol::ctl::RingKeyRotate,
+    ) -> NetResult<()> {
+        let current = match mgr.butterfly.ring_key() {
+            Some(key) => key,
+            None => {
+                return Err(net::err(
+                    ErrCode::InvalidPayload,
+                    "Ring is not encrypted, there is no ring key to rotate.",
+                ))
+            }
+        };
+        let cache = default_cache_key_path(None);
+        let key = SymKey::generate_pair_for_ring(&current.name)
+            .and_then(|key| key.to_pair_files(&cache).map(|_| key))
+            .map_err(|err| net::err(ErrCode::Internal, err.to_string()))?;
+        let name_with_rev = key.name_with_rev();
+        let content = SymKey::get_secret_key_path(&name_with_rev, &cache)
+            .map_err(|err| net::err(ErrCode::Internal, err.to_string()))
+            .and_then(|path| {
+                fs::read_to_string(path).map_err(|err| net::err(ErrCode::Internal, err.to_string()))
+            })?;
+        if !mgr.butterfly.add_ring_key(key) {
+            return Err(net::err(
+                ErrCode::Conflict,
+                format!("Ring key {} is already known.", name_with_rev),
+            ));
+        }
+        outputln!("Rotating ring key to {}", name_with_rev);
+        req.info(format!("Rotating ring key to {}", name_with_rev))?;
+        mgr.butterfly.insert_ring_key(RingKey::new(
+            mgr.butterfly.member_id(),
+            name_with_rev,
+            content,
+        ));
+        req.reply_complete(net::ok());
+        Ok(())
+    }
+
     fn check_for_updated_supervisor(&mut self) -> Option<PackageInstall> {
         if let Some(ref mut updater) = self.self_updater {
             return updater.updated();
@@ -1393,7 +1699,7 @@ impl Manager {
         {
             if self
                 .updater
-                .check_for_updated_package(service, &self.census_ring, &self.launcher)
+                .check_for_updated_package(service, &self.census_ring, &self.state.launcher)
             {
                 self.gossip_latest_service_rumor(&service);
             }
@@ -1456,6 +1762,95 @@ impl Manager {
         }
     }
 
+    /// Write every newer revision of our ring key gossiped by the rest of the ring to the key
+    /// cache, and start accepting payloads encrypted with it.
+    fn adopt_ring_keys(&self) {
+        let name = match self.butterfly.ring_key() {
+            Some(key) => key.name,
+            None => return,
+        };
+        let newest = match self.butterfly.ring_key_revs().into_iter().next() {
+            Some(name_with_rev) => name_with_rev,
+            None => return,
+        };
+        let mut adopt = Vec::new();
+        self.butterfly
+            .ring_key_store
+            .with_rumors("ring_key", |ring_key| {
+                if ring_key.name_with_rev > newest
+                    && ring_key.name_with_rev.starts_with(&format!("{}-", name))
+                {
+                    adopt.push(ring_key.clone());
+                }
+            });
+        for ring_key in adopt {
+            match SymKey::write_file_from_str(&ring_key.content, &default_cache_key_path(None)) {
+                Ok((key, PairType::Secret)) => {
+                    if self.butterfly.add_ring_key(key) {
+                        outputln!("Received ring key {}", ring_key.name_with_rev);
+                    }
+                }
+                Ok((_, PairType::Public)) => {
+                    warn!(
+                        "Ignoring ring key {}, it is not a secret key",
+                        ring_key.name_with_rev
+                    )
+                }
+                Err(err) => warn!(
+                    "Failed to write ring key {}, {}",
+                    ring_key.name_with_rev, err
+                ),
+            }
+        }
+    }
+
+    fn update_metrics(&self) {
+        metrics::update_services(
+            self.state
+                .services
+                .read()
+                .expect("Services lock is poisoned!")
+                .values(),
+        );
+        metrics::update_butterfly(&self.butterfly);
+    }
+
+    /// Publish an event for every change to the services, the ring's members and the census'
+    /// leaders since the previous turn of the main loop.
+    fn publish_state_events(&mut self) {
+        let services = self
+            .state
+            .services
+            .read()
+            .expect("Services lock is poisoned!")
+            .values()
+            .map(|service| {
+                let snapshot = ServiceSnapshot {
+                    pkg: service.pkg.ident.clone(),
+                    up: service.process_state() == ProcessState::Up,
+                    health_check: service.health_check(),
+                    cfg_incarnation: service.cfg.gossip_incarnation(),
+                };
+                (service.service_group.clone(), snapshot)
+            }).collect();
+        let leaders = self
+            .census_ring
+            .groups()
+            .into_iter()
+            .filter_map(|group| {
+                group
+                    .leader_id
+                    .clone()
+                    .map(|leader| (group.service_group.clone(), leader))
+            }).collect();
+        let events = self.state_tracker.update(
+            services,
+            self.butterfly.member_list.health_by_id(),
+            leaders,
+        );
+        self.state_events.publish(events);
+    }
+
     fn persist_state(&self) {
         debug!("Updating census state");
         self.persist_census_state();
@@ -1550,7 +1945,7 @@ impl Manager {
             _ => false,
         };
         if term {
-            service.stop(&self.launcher, cause);
+            service.stop(&self.state.launcher, cause);
         }
         if let Err(_) = self.user_config_watcher.remove(service) {
             debug!(
@@ -1732,6 +2127,10 @@ pub struct ProcessStatus {
     pub elapsed: TimeDuration,
     pub pid: Option<u32>,
     pub state: ProcessState,
+    #[serde(default)]
+    pub crash_looping: bool,
+    #[serde(default)]
+    pub usage: Option<ResourceUsage>,
 }
 
 impl fmt::Display for ProcessStatus {
@@ -1754,6 +2153,8 @@ pub struct ServiceStatus {
     pub service_group: ServiceGroup,
     pub composite: Option<String>,
     pub desired_state: DesiredState,
+    #[serde(default)]
+    pub update_status: Option<String>,
 }
 
 impl fmt::Display for ServiceStatus {
@@ -1970,6 +2371,18 @@ impl From<ProcessStatus> for protocol::types::ProcessStatus {
         if let Some(pid) = other.pid {
             proto.pid = Some(pid);
         }
+        proto.crash_looping = Some(other.crash_looping);
+        proto.usage = other.usage.map(Into::into);
+        proto
+    }
+}
+
+impl From<ResourceUsage> for protocol::types::ResourceUsage {
+    fn from(other: ResourceUsage) -> Self {
+        let mut proto = protocol::types::ResourceUsage::default();
+        proto.cpu_usec = Some(other.cpu_usec);
+        proto.memory_bytes = Some(other.memory_bytes);
+        proto.pids = Some(other.pids);
         proto
     }
 }
@@ -1979,6 +2392,13 @@ impl From<service::ServiceBind> for protocol::types::ServiceBind {
         let mut proto = protocol::types::ServiceBind::default();
         proto.name = bind.name;
         proto.service_group = bind.service_group.into();
+        proto.tags = bind
+            .tags
+            .into_iter()
+            .map(|(key, value)| match value {
+                Some(value) => format!("{}={}", key, value),
+                None => key,
+            }).collect();
         proto
     }
 }
@@ -1993,20 +2413,11 @@ impl From<ServiceStatus> for protocol::types::ServiceStatus {
             proto.composite = Some(composite);
         }
         proto.desired_state = Some(other.desired_state.into());
+        proto.update_status = other.update_status;
         proto
     }
 }
 
-impl Into<service::ServiceBind> for protocol::types::ServiceBind {
-    fn into(self) -> service::ServiceBind {
-        service::ServiceBind {
-            name: self.name,
-            service_group: self.service_group.into(),
-            service_name: self.service_name,
-        }
-    }
-}
-
 impl From<SpecDesiredState> for i32 {
     fn from(other: SpecDesiredState) -> Self {
         match other {
@@ -118,6 +118,11 @@ pub struct Cfg {
 }
 
 impl Cfg {
+    /// The incarnation of the census group's service config most recently applied.
+    pub fn gossip_incarnation(&self) -> u64 {
+        self.gossip_incarnation
+    }
+
     pub fn new<P>(package: &P, config_from: Option<&PathBuf>) -> Result<Cfg>
     where
         P: PackageConfigPaths,
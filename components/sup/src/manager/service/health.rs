@@ -13,6 +13,12 @@
 // limitations under the License.
 
 use std::fmt;
+use std::result;
+use std::str::FromStr;
+
+use error::{Error, SupError};
+
+static LOGKEY: &'static str = "HC";
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
 pub enum HealthCheck {
@@ -52,6 +58,93 @@ impl fmt::Display for HealthCheck {
     }
 }
 
+impl FromStr for HealthCheck {
+    type Err = SupError;
+
+    fn from_str(value: &str) -> result::Result<Self, Self::Err> {
+        match value.to_uppercase().as_ref() {
+            "OK" => Ok(HealthCheck::Ok),
+            "WARNING" => Ok(HealthCheck::Warning),
+            "CRITICAL" => Ok(HealthCheck::Critical),
+            "UNKNOWN" => Ok(HealthCheck::Unknown),
+            _ => Err(sup_error!(Error::BadHealthCheck(value.to_string()))),
+        }
+    }
+}
+
+impl HealthCheck {
+    fn is_ok(&self) -> bool {
+        *self == HealthCheck::Ok
+    }
+}
+
+/// Tracks consecutive health check results for a service, only changing the reported status
+/// once a differing result has been seen enough times in a row.
+///
+/// This keeps a single flaky probe from flapping the health gossiped for the service.
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthCheckThresholds {
+    /// Consecutive non-`Ok` results required to move away from a healthy status.
+    failure_threshold: u32,
+    /// Consecutive `Ok` results required to return to a healthy status.
+    success_threshold: u32,
+    status: HealthCheck,
+    pending: HealthCheck,
+    consecutive: u32,
+}
+
+impl HealthCheckThresholds {
+    pub fn new(failure_threshold: u32, success_threshold: u32) -> Self {
+        HealthCheckThresholds {
+            failure_threshold: failure_threshold.max(1),
+            success_threshold: success_threshold.max(1),
+            status: HealthCheck::default(),
+            pending: HealthCheck::default(),
+            consecutive: 0,
+        }
+    }
+
+    pub fn failure_threshold(&self) -> u32 {
+        self.failure_threshold
+    }
+
+    pub fn success_threshold(&self) -> u32 {
+        self.success_threshold
+    }
+
+    /// The currently reported health status.
+    pub fn status(&self) -> HealthCheck {
+        self.status
+    }
+
+    /// Record the result of a health check, returning the resulting health status.
+    ///
+    /// The first result is taken as-is since there is no earlier status to protect.
+    pub fn record(&mut self, result: HealthCheck) -> HealthCheck {
+        if self.status == HealthCheck::Unknown || result == self.status {
+            self.status = result;
+            self.consecutive = 0;
+            return self.status;
+        }
+        if result == self.pending {
+            self.consecutive += 1;
+        } else {
+            self.pending = result;
+            self.consecutive = 1;
+        }
+        let threshold = if result.is_ok() {
+            self.success_threshold
+        } else {
+            self.failure_threshold
+        };
+        if self.consecutive >= threshold {
+            self.status = result;
+            self.consecutive = 0;
+        }
+        self.status
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum SmokeCheck {
     Ok,
@@ -75,3 +168,55 @@ impl fmt::Display for SmokeCheck {
         write!(f, "{}", msg)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn health_check_round_trips_through_a_string() {
+        for check in &[
+            HealthCheck::Ok,
+            HealthCheck::Warning,
+            HealthCheck::Critical,
+            HealthCheck::Unknown,
+        ] {
+            assert_eq!(HealthCheck::from_str(&check.to_string()).unwrap(), *check);
+        }
+        assert!(HealthCheck::from_str("dope").is_err());
+    }
+
+    #[test]
+    fn health_check_thresholds_take_first_result() {
+        let mut thresholds = HealthCheckThresholds::new(3, 2);
+        assert_eq!(thresholds.record(HealthCheck::Critical), HealthCheck::Critical);
+    }
+
+    #[test]
+    fn health_check_thresholds_require_consecutive_failures() {
+        let mut thresholds = HealthCheckThresholds::new(3, 1);
+        thresholds.record(HealthCheck::Ok);
+        assert_eq!(thresholds.record(HealthCheck::Critical), HealthCheck::Ok);
+        assert_eq!(thresholds.record(HealthCheck::Critical), HealthCheck::Ok);
+        assert_eq!(thresholds.record(HealthCheck::Critical), HealthCheck::Critical);
+    }
+
+    #[test]
+    fn health_check_thresholds_reset_on_interrupted_streak() {
+        let mut thresholds = HealthCheckThresholds::new(2, 1);
+        thresholds.record(HealthCheck::Ok);
+        assert_eq!(thresholds.record(HealthCheck::Critical), HealthCheck::Ok);
+        assert_eq!(thresholds.record(HealthCheck::Ok), HealthCheck::Ok);
+        assert_eq!(thresholds.record(HealthCheck::Critical), HealthCheck::Ok);
+        assert_eq!(thresholds.record(HealthCheck::Warning), HealthCheck::Ok);
+        assert_eq!(thresholds.record(HealthCheck::Warning), HealthCheck::Warning);
+    }
+
+    #[test]
+    fn health_check_thresholds_require_consecutive_successes() {
+        let mut thresholds = HealthCheckThresholds::new(1, 2);
+        thresholds.record(HealthCheck::Critical);
+        assert_eq!(thresholds.record(HealthCheck::Ok), HealthCheck::Critical);
+        assert_eq!(thresholds.record(HealthCheck::Ok), HealthCheck::Ok);
+    }
+}
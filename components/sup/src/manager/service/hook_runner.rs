@@ -0,0 +1,375 @@
+// Copyright (c) 2018 Chef Software Inc. and/or applicable contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Executes the hooks which don't drive a service's lifecycle
+//! (`health_check`, `file_updated`, `reconfigure` and `smoke_test`) on a
+//! dedicated worker thread.
+//!
+//! Running these inline from `Service::tick` meant a single slow or
+//! hung hook stalled every service on the Supervisor, along with
+//! census processing and gossip persistence. Jobs are instead handed
+//! to a worker which runs each hook with a timeout and sends the
+//! result back to be collected on a later tick.
+
+use std::collections::HashSet;
+use std::sync::mpsc::{channel, Receiver, Sender, TryRecvError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use hcore::service::ServiceGroup;
+
+use super::health::{HealthCheck, SmokeCheck};
+use super::hooks::{FileUpdatedHook, HealthCheckHook, Hook, ReconfigureHook, SmokeTestHook};
+use super::Pkg;
+
+static LOGKEY: &'static str = "HR";
+
+lazy_static! {
+    /// How long a hook run by the worker may take before it is terminated.
+    pub static ref DEFAULT_HOOK_TIMEOUT: Duration = { Duration::from_millis(30_000) };
+}
+
+/// A hook to be run by the worker.
+#[derive(Debug)]
+pub enum HookJob {
+    HealthCheck(Arc<HealthCheckHook>),
+    FileUpdated(Arc<FileUpdatedHook>),
+    Reconfigure(Arc<ReconfigureHook>),
+    SmokeTest(Arc<SmokeTestHook>),
+}
+
+impl HookJob {
+    fn name(&self) -> &'static str {
+        match *self {
+            HookJob::HealthCheck(_) => HealthCheckHook::file_name(),
+            HookJob::FileUpdated(_) => FileUpdatedHook::file_name(),
+            HookJob::Reconfigure(_) => ReconfigureHook::file_name(),
+            HookJob::SmokeTest(_) => SmokeTestHook::file_name(),
+        }
+    }
+}
+
+/// The outcome of a hook run by the worker.
+#[derive(Debug)]
+pub enum HookResult {
+    HealthCheck(HealthCheck),
+    /// The health_check hook didn't exit within its timeout.
+    HealthCheckTimedOut,
+    FileUpdated(bool),
+    Reconfigure,
+    SmokeTest(SmokeCheck),
+}
+
+impl HookResult {
+    fn name(&self) -> &'static str {
+        match *self {
+            HookResult::HealthCheck(_) | HookResult::HealthCheckTimedOut => {
+                HealthCheckHook::file_name()
+            }
+            HookResult::FileUpdated(_) => FileUpdatedHook::file_name(),
+            HookResult::Reconfigure => ReconfigureHook::file_name(),
+            HookResult::SmokeTest(_) => SmokeTestHook::file_name(),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Request {
+    job: HookJob,
+    pkg: Pkg,
+    svc_encrypted_password: Option<String>,
+    timeout: Duration,
+}
+
+#[derive(Debug)]
+struct Channels {
+    tx: Sender<Request>,
+    rx: Receiver<HookResult>,
+}
+
+/// Hands hooks off to a service's worker thread and collects their results.
+///
+/// The channel ends live behind a `Mutex` only so that `Service` remains
+/// `Sync`; every access goes through `&mut self` and never contends.
+#[derive(Debug)]
+pub struct HookRunner {
+    service_group: ServiceGroup,
+    channels: Mutex<Channels>,
+    /// Names of the hooks which have been submitted but whose results
+    /// haven't been collected yet.
+    in_flight: HashSet<&'static str>,
+}
+
+impl HookRunner {
+    pub fn new(service_group: ServiceGroup) -> Self {
+        let channels = Self::init(&service_group);
+        HookRunner {
+            service_group: service_group,
+            channels: Mutex::new(channels),
+            in_flight: HashSet::new(),
+        }
+    }
+
+    /// Spawn a new hook worker thread.
+    fn init(service_group: &ServiceGroup) -> Channels {
+        let (job_tx, job_rx) = channel();
+        let (result_tx, result_rx) = channel();
+        let worker_group = service_group.clone();
+        thread::Builder::new()
+            .name(format!("hook-runner-{}", service_group))
+            .spawn(move || Self::run(worker_group, job_rx, result_tx))
+            .expect("Unable to start hook-runner thread");
+        Channels {
+            tx: job_tx,
+            rx: result_rx,
+        }
+    }
+
+    fn run(service_group: ServiceGroup, rx: Receiver<Request>, tx: Sender<HookResult>) {
+        // The loop ends once the owning `Service` has been dropped.
+        while let Ok(req) = rx.recv() {
+            let password = req.svc_encrypted_password.as_ref();
+            let result = match req.job {
+                HookJob::HealthCheck(hook) => {
+                    match hook.run_with_timeout(&service_group, &req.pkg, password, req.timeout) {
+                        Some(check) => HookResult::HealthCheck(check),
+                        None => HookResult::HealthCheckTimedOut,
+                    }
+                }
+                HookJob::FileUpdated(hook) => HookResult::FileUpdated(
+                    hook.run_with_timeout(&service_group, &req.pkg, password, req.timeout)
+                        .unwrap_or_default(),
+                ),
+                HookJob::Reconfigure(hook) => {
+                    hook.run_with_timeout(&service_group, &req.pkg, password, req.timeout);
+                    HookResult::Reconfigure
+                }
+                HookJob::SmokeTest(hook) => HookResult::SmokeTest(
+                    hook.run_with_timeout(&service_group, &req.pkg, password, req.timeout)
+                        .unwrap_or_default(),
+                ),
+            };
+            if tx.send(result).is_err() {
+                break;
+            }
+        }
+        debug!("{}, hook runner shutting down", service_group);
+    }
+
+    /// Queue a hook to be run on the worker thread.
+    ///
+    /// A hook which is still running from an earlier submission is not
+    /// queued again; returns `false` in that case.
+    pub fn submit(
+        &mut self,
+        job: HookJob,
+        pkg: &Pkg,
+        svc_encrypted_password: Option<&String>,
+        timeout: Duration,
+    ) -> bool {
+        let name = job.name();
+        if self.in_flight.contains(name) {
+            debug!(
+                "{}, {} hook is still running, not queueing it again",
+                self.service_group, name
+            );
+            return false;
+        }
+        let req = Request {
+            job: job,
+            pkg: pkg.clone(),
+            svc_encrypted_password: svc_encrypted_password.cloned(),
+            timeout: timeout,
+        };
+        let sent = {
+            let channels = self
+                .channels
+                .get_mut()
+                .expect("HookRunner channels lock is poisoned");
+            channels.tx.send(req)
+        };
+        match sent {
+            Ok(()) => {
+                self.in_flight.insert(name);
+                true
+            }
+            Err(_) => {
+                outputln!(preamble self.service_group,
+                          "Hook runner has died, restarting; {} hook was not run", name);
+                self.restart();
+                false
+            }
+        }
+    }
+
+    /// Collect the results of every hook which has finished since the last call.
+    pub fn results(&mut self) -> Vec<HookResult> {
+        let mut results = Vec::new();
+        let mut disconnected = false;
+        {
+            let channels = self
+                .channels
+                .get_mut()
+                .expect("HookRunner channels lock is poisoned");
+            loop {
+                match channels.rx.try_recv() {
+                    Ok(result) => results.push(result),
+                    Err(TryRecvError::Empty) => break,
+                    Err(TryRecvError::Disconnected) => {
+                        disconnected = true;
+                        break;
+                    }
+                }
+            }
+        }
+        for result in results.iter() {
+            self.in_flight.remove(result.name());
+        }
+        if disconnected {
+            outputln!(preamble self.service_group, "Hook runner has died, restarting");
+            self.restart();
+        }
+        results
+    }
+
+    fn restart(&mut self) {
+        self.in_flight.clear();
+        *self
+            .channels
+            .get_mut()
+            .expect("HookRunner channels lock is poisoned") = Self::init(&self.service_group);
+    }
+}
+
+#[cfg(test)]
+#[cfg(not(windows))]
+mod tests {
+    use std::fs::{self, File};
+    use std::io::Write;
+    use std::path::{Path, PathBuf};
+    use std::time::Instant;
+
+    use hcore::package::{PackageIdent, PackageInstall};
+    use hcore::util::posix_perm;
+    use tempfile::TempDir;
+
+    use super::super::hooks::HOOK_PERMISSIONS;
+    use super::*;
+
+    fn service_group() -> ServiceGroup {
+        ServiceGroup::new(None, "test_service", "test_group", None)
+            .expect("couldn't create ServiceGroup")
+    }
+
+    fn pkg(service_group: &ServiceGroup) -> Pkg {
+        let ident = PackageIdent::new(
+            "testing",
+            &service_group.service(),
+            Some("1.0.0"),
+            Some("20170712000000"),
+        );
+        let install = PackageInstall::new_from_parts(
+            ident,
+            PathBuf::from("/tmp"),
+            PathBuf::from("/tmp"),
+            PathBuf::from("/tmp"),
+        );
+        Pkg::from_install(install).expect("couldn't create package")
+    }
+
+    /// Load a health_check hook whose compiled script is `content`.
+    fn health_check_hook(root: &Path, content: &str) -> Arc<HealthCheckHook> {
+        let templates = root.join("templates");
+        let hooks = root.join("hooks");
+        fs::create_dir_all(&templates).expect("couldn't create templates dir");
+        fs::create_dir_all(&hooks).expect("couldn't create hooks dir");
+        File::create(templates.join(HealthCheckHook::file_name()))
+            .expect("couldn't create hook template");
+        let hook = HealthCheckHook::load(&service_group(), &hooks, &templates)
+            .expect("couldn't load hook");
+        let path = hooks.join(HealthCheckHook::file_name());
+        File::create(&path)
+            .and_then(|mut file| file.write_all(content.as_bytes()))
+            .expect("couldn't write hook");
+        posix_perm::set_permissions(&path, HOOK_PERMISSIONS).expect("couldn't set permissions");
+        Arc::new(hook)
+    }
+
+    fn wait_for_results(runner: &mut HookRunner) -> Vec<HookResult> {
+        let deadline = Instant::now() + Duration::from_secs(30);
+        while Instant::now() < deadline {
+            let results = runner.results();
+            if !results.is_empty() {
+                return results;
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+        panic!("hook runner never returned a result");
+    }
+
+    #[test]
+    fn a_hook_which_times_out_is_killed_along_with_its_children() {
+        let tmp_dir = TempDir::new().expect("create temp dir");
+        let survived = tmp_dir.path().join("survived");
+        let hook = health_check_hook(
+            tmp_dir.path(),
+            &format!(
+                "#!/bin/sh\n(sleep 1; touch {}) &\nsleep 60 | cat\n",
+                survived.display()
+            ),
+        );
+        let service_group = service_group();
+        let mut runner = HookRunner::new(service_group.clone());
+
+        let started = Instant::now();
+        assert!(runner.submit(
+            HookJob::HealthCheck(hook),
+            &pkg(&service_group),
+            None,
+            Duration::from_millis(200),
+        ));
+        let results = wait_for_results(&mut runner);
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert_eq!(results.len(), 1);
+        match results[0] {
+            HookResult::HealthCheckTimedOut => (),
+            ref other => panic!("expected the health check to time out, got {:?}", other),
+        }
+
+        // Give the backgrounded subshell long enough to have finished, had it not been killed.
+        thread::sleep(Duration::from_millis(1500) - started.elapsed());
+        assert!(
+            !survived.exists(),
+            "a process started by the hook outlived it"
+        );
+    }
+
+    #[test]
+    fn a_hook_is_not_queued_again_while_it_is_timing_out() {
+        let tmp_dir = TempDir::new().expect("create temp dir");
+        let hook = health_check_hook(tmp_dir.path(), "#!/bin/sh\nsleep 60\n");
+        let service_group = service_group();
+        let pkg = pkg(&service_group);
+        let mut runner = HookRunner::new(service_group);
+
+        let timeout = Duration::from_millis(200);
+        assert!(runner.submit(HookJob::HealthCheck(hook.clone()), &pkg, None, timeout));
+        assert!(!runner.submit(HookJob::HealthCheck(hook.clone()), &pkg, None, timeout));
+
+        assert_eq!(wait_for_results(&mut runner).len(), 1);
+        assert!(runner.submit(HookJob::HealthCheck(hook), &pkg, None, timeout));
+        assert_eq!(wait_for_results(&mut runner).len(), 1);
+    }
+}
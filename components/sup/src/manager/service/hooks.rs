@@ -23,6 +23,10 @@ use std::path::{Path, PathBuf};
 #[cfg(not(windows))]
 use std::process::{Child, ExitStatus};
 use std::result;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
 
 use hcore::service::ServiceGroup;
 use hcore::{self, crypto};
@@ -31,6 +35,7 @@ use serde::{Serialize, Serializer};
 use super::{health, Pkg};
 use error::{Result, SupError};
 use fs;
+use metrics;
 use templating::{RenderContext, TemplateRenderer};
 use util::exec;
 
@@ -42,14 +47,42 @@ pub fn stdout_log_path<T>(service_group: &ServiceGroup) -> PathBuf
 where
     T: Hook,
 {
-    fs::svc_logs_path(service_group.service()).join(format!("{}.stdout.log", T::file_name()))
+    log_path(service_group, T::file_name(), "stdout")
 }
 
 pub fn stderr_log_path<T>(service_group: &ServiceGroup) -> PathBuf
 where
     T: Hook,
 {
-    fs::svc_logs_path(service_group.service()).join(format!("{}.stderr.log", T::file_name()))
+    log_path(service_group, T::file_name(), "stderr")
+}
+
+/// The paths of the stdout and stderr logs of the hook with the given file name, or `None` if
+/// there is no hook by that name.
+pub fn log_paths(service_group: &ServiceGroup, hook: &str) -> Option<(PathBuf, PathBuf)> {
+    let hooks = [
+        FileUpdatedHook::file_name(),
+        HealthCheckHook::file_name(),
+        InitHook::file_name(),
+        RunHook::file_name(),
+        PostRunHook::file_name(),
+        ReloadHook::file_name(),
+        ReconfigureHook::file_name(),
+        SmokeTestHook::file_name(),
+        SuitabilityHook::file_name(),
+        PostStopHook::file_name(),
+    ];
+    if !hooks.contains(&hook) {
+        return None;
+    }
+    Some((
+        log_path(service_group, hook, "stdout"),
+        log_path(service_group, hook, "stderr"),
+    ))
+}
+
+fn log_path(service_group: &ServiceGroup, hook: &str, stream: &str) -> PathBuf {
+    fs::svc_logs_path(service_group.service()).join(format!("{}.{}.log", hook, stream))
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -142,6 +175,7 @@ pub trait Hook: fmt::Debug + Sized {
     where
         T: ToString,
     {
+        let _timer = metrics::HookTimer::start(service_group, Self::file_name());
         let mut child = match exec::run(self.path(), &pkg, svc_encrypted_password) {
             Ok(child) => child,
             Err(err) => {
@@ -162,6 +196,101 @@ pub trait Hook: fmt::Debug + Sized {
         }
     }
 
+    /// Run a compiled hook, terminating it if it hasn't exited once `timeout` has elapsed.
+    ///
+    /// The hook's output is streamed from a helper thread so that a hook which never closes
+    /// its pipes can't prevent the timeout from being enforced. Returns `None` if the hook timed
+    /// out; one which failed to run yields the default `ExitValue`.
+    fn run_with_timeout<T>(
+        &self,
+        service_group: &ServiceGroup,
+        pkg: &Pkg,
+        svc_encrypted_password: Option<T>,
+        timeout: Duration,
+    ) -> Option<Self::ExitValue>
+    where
+        T: ToString,
+    {
+        let _timer = metrics::HookTimer::start(service_group, Self::file_name());
+        let mut child = match exec::run(self.path(), &pkg, svc_encrypted_password) {
+            Ok(child) => child,
+            Err(err) => {
+                outputln!(preamble service_group,
+                    "Hook failed to run, {}, {}", Self::file_name(), err);
+                return Some(Self::ExitValue::default());
+            }
+        };
+        let stdout = child.stdout.take();
+        let stderr = child.stderr.take();
+        let stdout_log = self.stdout_log_path().to_path_buf();
+        let stderr_log = self.stderr_log_path().to_path_buf();
+        let preamble_str = format!("{} hook[{}]:", service_group, Self::file_name());
+        let streamer = thread::Builder::new()
+            .name(format!("{}-{}-output", service_group, Self::file_name()))
+            .spawn(move || {
+                HookOutput::new(&stdout_log, &stderr_log).stream_pipes(
+                    &preamble_str,
+                    stdout,
+                    stderr,
+                )
+            });
+        let streamer = match streamer {
+            Ok(handle) => handle,
+            Err(err) => {
+                outputln!(preamble service_group,
+                    "Hook failed to run, {}, {}", Self::file_name(), err);
+                let _ = child.kill();
+                let _ = child.wait();
+                return Some(Self::ExitValue::default());
+            }
+        };
+        let pid = child.id();
+        let (tx, rx) = mpsc::channel();
+        let waiter = thread::Builder::new()
+            .name(format!("{}-{}-wait", service_group, Self::file_name()))
+            .spawn(move || {
+                let _ = tx.send(child.wait());
+            });
+        if let Err(err) = waiter {
+            outputln!(preamble service_group,
+                "Hook failed to run, {}, {}", Self::file_name(), err);
+            let _ = exec::kill_process_group(pid);
+            return Some(Self::ExitValue::default());
+        }
+        match rx.recv_timeout(timeout) {
+            Ok(Ok(status)) => {
+                let _ = streamer.join();
+                let hook_output = HookOutput::new(self.stdout_log_path(), self.stderr_log_path());
+                Some(self.handle_exit(service_group, &hook_output, &status))
+            }
+            Ok(Err(err)) => {
+                outputln!(preamble service_group,
+                    "Hook failed to run, {}, {}", Self::file_name(), err);
+                Some(Self::ExitValue::default())
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                outputln!(preamble service_group,
+                    "Hook timed out after {}s, {}, terminating",
+                    timeout.as_secs(), Self::file_name());
+                // The hook runs in its own process group, so anything it started (the rest of
+                // a pipeline, or a command it backgrounded) goes down along with it.
+                if let Err(err) = exec::kill_process_group(pid) {
+                    outputln!(preamble service_group,
+                        "Unable to terminate hook, {}, {}", Self::file_name(), err);
+                }
+                // Wait for the hook to be reaped but not for the output thread; a process which
+                // escaped the group may still hold the pipes open.
+                let _ = rx.recv();
+                None
+            }
+            Err(RecvTimeoutError::Disconnected) => {
+                outputln!(preamble service_group,
+                    "Hook failed to run, {}, lost track of its process", Self::file_name());
+                Some(Self::ExitValue::default())
+            }
+        }
+    }
+
     fn handle_exit<'a>(
         &self,
         group: &ServiceGroup,
@@ -819,15 +948,15 @@ where
 
 #[derive(Debug, Default, Serialize)]
 pub struct HookTable {
-    pub health_check: Option<HealthCheckHook>,
+    pub health_check: Option<Arc<HealthCheckHook>>,
     pub init: Option<InitHook>,
-    pub file_updated: Option<FileUpdatedHook>,
+    pub file_updated: Option<Arc<FileUpdatedHook>>,
     pub reload: Option<ReloadHook>,
-    pub reconfigure: Option<ReconfigureHook>,
+    pub reconfigure: Option<Arc<ReconfigureHook>>,
     pub suitability: Option<SuitabilityHook>,
     pub run: Option<RunHook>,
     pub post_run: Option<PostRunHook>,
-    pub smoke_test: Option<SmokeTestHook>,
+    pub smoke_test: Option<Arc<SmokeTestHook>>,
     pub post_stop: Option<PostStopHook>,
 }
 
@@ -841,15 +970,19 @@ impl HookTable {
         let mut table = HookTable::default();
         if let Some(meta) = std::fs::metadata(templates.as_ref()).ok() {
             if meta.is_dir() {
-                table.file_updated = FileUpdatedHook::load(service_group, &hooks_path, &templates);
-                table.health_check = HealthCheckHook::load(service_group, &hooks_path, &templates);
+                table.file_updated =
+                    FileUpdatedHook::load(service_group, &hooks_path, &templates).map(Arc::new);
+                table.health_check =
+                    HealthCheckHook::load(service_group, &hooks_path, &templates).map(Arc::new);
                 table.suitability = SuitabilityHook::load(service_group, &hooks_path, &templates);
                 table.init = InitHook::load(service_group, &hooks_path, &templates);
                 table.reload = ReloadHook::load(service_group, &hooks_path, &templates);
-                table.reconfigure = ReconfigureHook::load(service_group, &hooks_path, &templates);
+                table.reconfigure =
+                    ReconfigureHook::load(service_group, &hooks_path, &templates).map(Arc::new);
                 table.run = RunHook::load(service_group, &hooks_path, &templates);
                 table.post_run = PostRunHook::load(service_group, &hooks_path, &templates);
-                table.smoke_test = SmokeTestHook::load(service_group, &hooks_path, &templates);
+                table.smoke_test =
+                    SmokeTestHook::load(service_group, &hooks_path, &templates).map(Arc::new);
                 table.post_stop = PostStopHook::load(service_group, &hooks_path, &templates);
             }
         }
@@ -870,10 +1003,10 @@ impl HookTable {
         debug!("{:?}", self);
         let mut changed = false;
         if let Some(ref hook) = self.file_updated {
-            changed = self.compile_one(hook, service_group, ctx) || changed;
+            changed = self.compile_one(hook.as_ref(), service_group, ctx) || changed;
         }
         if let Some(ref hook) = self.health_check {
-            changed = self.compile_one(hook, service_group, ctx) || changed;
+            changed = self.compile_one(hook.as_ref(), service_group, ctx) || changed;
         }
         if let Some(ref hook) = self.init {
             changed = self.compile_one(hook, service_group, ctx) || changed;
@@ -882,7 +1015,7 @@ impl HookTable {
             changed = self.compile_one(hook, service_group, ctx) || changed;
         }
         if let Some(ref hook) = self.reconfigure {
-            changed = self.compile_one(hook, service_group, ctx) || changed;
+            changed = self.compile_one(hook.as_ref(), service_group, ctx) || changed;
         }
         if let Some(ref hook) = self.suitability {
             changed = self.compile_one(hook, service_group, ctx) || changed;
@@ -894,7 +1027,7 @@ impl HookTable {
             changed = self.compile_one(hook, service_group, ctx) || changed;
         }
         if let Some(ref hook) = self.smoke_test {
-            changed = self.compile_one(hook, service_group, ctx) || changed;
+            changed = self.compile_one(hook.as_ref(), service_group, ctx) || changed;
         }
         if let Some(ref hook) = self.post_stop {
             changed = self.compile_one(hook, service_group, ctx) || changed;
@@ -987,13 +1120,25 @@ impl<'a> HookOutput<'a> {
     }
 
     fn stream_output<H: Hook>(&mut self, service_group: &ServiceGroup, process: &mut Child) {
+        let preamble_str = self.stream_preamble::<H>(service_group);
+        self.stream_pipes(
+            &preamble_str,
+            process.stdout.as_mut(),
+            process.stderr.as_mut(),
+        );
+    }
+
+    fn stream_pipes<O, E>(&mut self, preamble_str: &str, stdout: Option<O>, stderr: Option<E>)
+    where
+        O: Read,
+        E: Read,
+    {
         let mut stdout_log =
             File::create(&self.stdout_log_file).expect("couldn't create log output file");
         let mut stderr_log =
             File::create(&self.stderr_log_file).expect("couldn't create log output file");
 
-        let preamble_str = self.stream_preamble::<H>(service_group);
-        if let Some(ref mut stdout) = process.stdout {
+        if let Some(stdout) = stdout {
             for line in BufReader::new(stdout).lines() {
                 if let Some(ref l) = line.ok() {
                     outputln!(preamble preamble_str, l);
@@ -1003,7 +1148,7 @@ impl<'a> HookOutput<'a> {
                 }
             }
         }
-        if let Some(ref mut stderr) = process.stderr {
+        if let Some(stderr) = stderr {
             for line in BufReader::new(stderr).lines() {
                 if let Some(ref l) = line.ok() {
                     outputln!(preamble preamble_str, l);
@@ -1543,4 +1688,15 @@ echo "The message is Hello"
 
         fs::remove_dir_all(tmp_dir).expect("remove temp dir");
     }
+
+    #[test]
+    fn log_paths_of_known_hooks() {
+        let service_group = ServiceGroup::new(None, "dummy", "service", None)
+            .expect("couldn't create ServiceGroup");
+        let (stdout, stderr) = log_paths(&service_group, "health_check").unwrap();
+        assert_eq!(stdout, stdout_log_path::<HealthCheckHook>(&service_group));
+        assert_eq!(stderr, stderr_log_path::<HealthCheckHook>(&service_group));
+        assert!(log_paths(&service_group, "post-stop").is_some());
+        assert!(log_paths(&service_group, "../../../etc/passwd").is_none());
+    }
 }
@@ -16,8 +16,10 @@ mod composite_spec;
 pub mod config;
 mod dir;
 pub mod health;
+mod hook_runner;
 pub mod hooks;
 mod package;
+mod restart_policy;
 pub mod spec;
 mod supervisor;
 
@@ -26,7 +28,10 @@ use std::collections::HashSet;
 use std::fmt;
 use std::fs::File;
 use std::io::prelude::*;
+use std::mem;
 use std::path::{Path, PathBuf};
+#[cfg(not(windows))]
+use std::process::Child;
 use std::result;
 use std::sync::{Arc, RwLock};
 use std::time::{Duration, Instant};
@@ -35,11 +40,16 @@ use butterfly::rumor::service::Service as ServiceRumor;
 use hcore;
 use hcore::crypto::hash;
 use hcore::fs::FS_ROOT_PATH;
+#[cfg(windows)]
+use hcore::os::process::windows_child::Child;
 use hcore::package::metadata::Bind;
 use hcore::package::{PackageIdent, PackageInstall};
 use hcore::service::ServiceGroup;
-use launcher_client::LauncherCli;
-pub use protocol::types::{BindingMode, ProcessState, Topology, UpdateStrategy};
+use launcher_client::{LauncherCli, OutputLogs, ResourceLimits};
+pub use protocol::types::{
+    BindingMode, CanarySize, MaintenanceWindow, ProcessState, Signal, Topology, UpdateStrategy,
+    VersionConstraint,
+};
 use serde::ser::SerializeStruct;
 use serde::{Serialize, Serializer};
 use time::Timespec;
@@ -48,10 +58,13 @@ pub use self::composite_spec::CompositeSpec;
 use self::config::CfgRenderer;
 pub use self::config::{Cfg, UserConfigPath};
 use self::dir::SvcDir;
-pub use self::health::{HealthCheck, SmokeCheck};
-use self::hooks::{Hook, HookTable};
+pub use self::health::{HealthCheck, HealthCheckThresholds, SmokeCheck};
+use self::hook_runner::{HookJob, HookResult, HookRunner, DEFAULT_HOOK_TIMEOUT};
+use self::hooks::{Hook, HookTable, RunHook};
+use self::restart_policy::RestartPolicy;
 pub use self::package::{Env, Pkg, PkgProxy};
 pub use self::spec::{BindMap, DesiredState, IntoServiceSpec, ServiceBind, ServiceSpec, Spec};
+pub use self::supervisor::ResourceUsage;
 use self::supervisor::Supervisor;
 use super::ShutdownReason;
 use super::Sys;
@@ -66,10 +79,6 @@ static LOGKEY: &'static str = "SR";
 #[cfg(not(windows))]
 pub const GOSSIP_FILE_PERMISSIONS: u32 = 0o640;
 
-lazy_static! {
-    static ref HEALTH_CHECK_INTERVAL: Duration = { Duration::from_millis(30_000) };
-}
-
 /// When evaluating whether a particular service group can satisfy a
 /// bind of the Service, there are several states it can be
 /// in. Depending on which point in the lifecycle of the Service we
@@ -79,7 +88,7 @@ enum BindStatus<'a> {
     /// The bound group is not present in the census
     NotPresent,
     /// The bound group is present in the census, but has no active
-    /// members.
+    /// members whose tags satisfy the bind.
     Empty,
     /// The bound group is present in the census, has active members,
     /// but does not satisfy the contract of the bind; the set of
@@ -107,14 +116,48 @@ pub struct Service {
     pub sys: Arc<Sys>,
     pub initialized: bool,
     pub user_config_updated: bool,
+    /// The package which most recently failed its smoke test after an
+    /// update, if any. Gossiped so the rest of the group can react.
+    pub failed_pkg: Option<PackageIdent>,
+    /// Progress of the service's update strategy, if an update is
+    /// under way. Maintained by the `ServiceUpdater`.
+    pub update_status: Option<String>,
+    #[serde(skip_serializing)]
+    pub canary_size: CanarySize,
+    #[serde(skip_serializing)]
+    pub canary_soak: Duration,
+    #[serde(skip_serializing)]
+    pub update_windows: Vec<MaintenanceWindow>,
+    #[serde(skip_serializing)]
+    pub update_version: Option<VersionConstraint>,
 
     #[serde(skip_serializing)]
     config_renderer: CfgRenderer,
     health_check: HealthCheck,
+    /// Whether the health status has changed since the service was
+    /// last gossiped.
+    #[serde(skip_serializing)]
+    health_check_changed: bool,
+    #[serde(skip_serializing)]
+    health_check_interval: Duration,
+    #[serde(skip_serializing)]
+    health_check_timeout: Duration,
+    #[serde(skip_serializing)]
+    health_check_thresholds: HealthCheckThresholds,
+    #[serde(skip_serializing)]
+    restart_policy: RestartPolicy,
     last_election_status: ElectionStatus,
     needs_reload: bool,
     needs_reconfiguration: bool,
     smoke_check: SmokeCheck,
+    /// The package the service ran before its last update, kept so the
+    /// update can be rolled back if the new package fails its smoke test.
+    #[serde(skip_serializing)]
+    previous_pkg: Option<PackageIdent>,
+    /// Whether the smoke test has yet to be run against a process
+    /// started from a freshly updated package.
+    #[serde(skip_serializing)]
+    needs_smoke_test: bool,
     /// The mapping of bind name to a service group, specified by the
     /// user when the service definition was loaded into the Supervisor.
     binds: Vec<ServiceBind>,
@@ -141,6 +184,10 @@ pub struct Service {
     #[serde(skip_serializing)]
     unsatisfied_binds: HashSet<ServiceBind>,
     hooks: HookTable,
+    /// Runs the health_check, file_updated and reconfigure hooks off
+    /// of the main loop.
+    #[serde(skip_serializing)]
+    hook_runner: HookRunner,
     config_from: Option<PathBuf>,
     #[serde(skip_serializing)]
     last_health_check: Option<Instant>,
@@ -179,6 +226,7 @@ impl Service {
         )?;
         let config_root = Self::config_root(&pkg, spec.config_from.as_ref());
         let hooks_root = Self::hooks_root(&pkg, spec.config_from.as_ref());
+        let failed_pkg = load_failed_pkg(&manager_fs_cfg.failed_pkg_file(&service_group));
         Ok(Service {
             sys: sys,
             cfg: Cfg::new(&pkg, spec.config_from.as_ref())?,
@@ -187,21 +235,60 @@ impl Service {
             channel: spec.channel,
             desired_state: spec.desired_state,
             health_check: HealthCheck::default(),
+            health_check_changed: false,
+            health_check_interval: Duration::from_secs(spec.health_check_interval),
+            health_check_timeout: Duration::from_secs(spec.health_check_timeout),
+            health_check_thresholds: HealthCheckThresholds::new(
+                spec.health_check_failure_threshold,
+                spec.health_check_success_threshold,
+            ),
+            restart_policy: RestartPolicy::new(
+                spec.restart_after_critical,
+                Duration::from_secs(spec.restart_backoff),
+                spec.restart_max,
+                Duration::from_secs(spec.restart_window),
+            ),
             hooks: HookTable::load(
                 &service_group,
                 &hooks_root,
                 fs::svc_hooks_path(&service_group.service()),
             ),
+            hook_runner: HookRunner::new(service_group.clone()),
             initialized: false,
             last_election_status: ElectionStatus::None,
             needs_reload: false,
             needs_reconfiguration: false,
             user_config_updated: false,
             manager_fs_cfg: manager_fs_cfg,
-            supervisor: Supervisor::new(&service_group),
+            supervisor: Supervisor::new(
+                &service_group,
+                spec.log_capture,
+                OutputLogs {
+                    stdout: hooks::stdout_log_path::<RunHook>(&service_group),
+                    stderr: hooks::stderr_log_path::<RunHook>(&service_group),
+                    max_size: spec.log_max_size,
+                    max_age: spec.log_max_age,
+                    retain: spec.log_retain,
+                },
+                ResourceLimits {
+                    cpu: spec.cpu_limit,
+                    memory: spec.memory_limit,
+                    pids: spec.pids_limit,
+                },
+                spec.shutdown_signal,
+                spec.shutdown_timeout,
+            ),
             pkg: pkg,
             service_group: service_group,
             smoke_check: SmokeCheck::default(),
+            previous_pkg: None,
+            needs_smoke_test: false,
+            failed_pkg: failed_pkg,
+            update_status: None,
+            canary_size: spec.canary_size,
+            canary_soak: Duration::from_secs(spec.canary_soak),
+            update_windows: spec.update_windows,
+            update_version: spec.update_version,
             binds: spec.binds,
             all_pkg_binds: all_pkg_binds,
             unsatisfied_binds: HashSet::new(),
@@ -310,10 +397,34 @@ impl Service {
         }
     }
 
+    /// Send a signal to the service's process, if it's running.
+    pub fn signal(&self, launcher: &LauncherCli, signal: Signal) -> Result<()> {
+        self.supervisor.signal(launcher, signal)
+    }
+
+    /// Run a one-off command in the service's environment. Its output is read from the returned
+    /// child's pipes.
+    pub fn exec(&self, command: &str, args: &[String]) -> Result<Child> {
+        self.supervisor.exec(
+            &self.pkg,
+            self.svc_encrypted_password.as_ref(),
+            command,
+            args,
+        )
+    }
+
     pub fn last_state_change(&self) -> Timespec {
         self.supervisor.state_entered
     }
 
+    pub fn process_state(&self) -> ProcessState {
+        self.supervisor.state
+    }
+
+    pub fn health_check(&self) -> HealthCheck {
+        self.health_check
+    }
+
     /// Performs updates and executes hooks.
     ///
     /// Returns `true` if the service was updated.
@@ -340,7 +451,9 @@ impl Service {
             self.validate_binds(census_ring);
         }
 
-        let svc_updated = self.update_templates(census_ring);
+        let rolled_back = self.collect_hook_results(launcher);
+
+        let svc_updated = self.update_templates(census_ring) || rolled_back;
         if self.update_service_files(census_ring) {
             self.file_updated();
         }
@@ -393,7 +506,9 @@ impl Service {
                 }
             }
         }
-        svc_updated
+        // Peers watch gossiped health while soaking an update
+        let health_changed = mem::replace(&mut self.health_check_changed, false);
+        svc_updated || health_changed
     }
 
     pub fn to_spec(&self) -> ServiceSpec {
@@ -415,6 +530,27 @@ impl Service {
         if let Some(ref password) = self.svc_encrypted_password {
             spec.svc_encrypted_password = Some(password.clone())
         }
+        spec.health_check_interval = self.health_check_interval.as_secs();
+        spec.health_check_timeout = self.health_check_timeout.as_secs();
+        spec.health_check_failure_threshold = self.health_check_thresholds.failure_threshold();
+        spec.health_check_success_threshold = self.health_check_thresholds.success_threshold();
+        spec.restart_after_critical = self.restart_policy.after_critical();
+        spec.restart_backoff = self.restart_policy.backoff().as_secs();
+        spec.restart_max = self.restart_policy.max_restarts();
+        spec.restart_window = self.restart_policy.window().as_secs();
+        spec.canary_size = self.canary_size;
+        spec.canary_soak = self.canary_soak.as_secs();
+        spec.update_windows = self.update_windows.clone();
+        spec.update_version = self.update_version.clone();
+        spec.log_capture = self.supervisor.log_capture;
+        spec.log_max_size = self.supervisor.output_logs.max_size;
+        spec.log_max_age = self.supervisor.output_logs.max_age;
+        spec.log_retain = self.supervisor.output_logs.retain;
+        spec.cpu_limit = self.supervisor.limits.cpu;
+        spec.memory_limit = self.supervisor.limits.memory;
+        spec.pids_limit = self.supervisor.limits.pids;
+        spec.shutdown_signal = self.supervisor.shutdown_signal;
+        spec.shutdown_timeout = self.supervisor.shutdown_timeout;
         spec
     }
 
@@ -501,10 +637,14 @@ impl Service {
         match census_ring.census_group_for(&service_bind.service_group) {
             None => BindStatus::NotPresent,
             Some(group) => {
-                if group.active_members().is_empty() {
+                if !group
+                    .active_members()
+                    .iter()
+                    .any(|m| service_bind.matches_tags(&m.tags, &self.sys.tags))
+                {
                     BindStatus::Empty
                 } else {
-                    match self.unsatisfied_bind_exports(group, &service_bind.name) {
+                    match self.unsatisfied_bind_exports(group, service_bind) {
                         Ok(unsatisfied) => {
                             if unsatisfied.is_empty() {
                                 BindStatus::Satisfied
@@ -528,14 +668,15 @@ impl Service {
     /// Can return `Error::NoSuchBind` if there's not a bind with the
     /// given name.
     /// Can return `Error::NoActiveMembers` if there are no active members
-    /// of the group.
+    /// of the group matching the bind's tags.
     fn unsatisfied_bind_exports<'a>(
         &'a self,
         group: &'a CensusGroup,
-        bind_name: &'a str,
+        service_bind: &'a ServiceBind,
     ) -> Result<HashSet<&'a String>> {
-        let exports = self.exports_required_for_bind(bind_name)?;
-        let group_exports = group.group_exports()?;
+        let exports = self.exports_required_for_bind(&service_bind.name)?;
+        let group_exports = group
+            .group_exports(|member| service_bind.matches_tags(&member.tags, &self.sys.tags))?;
 
         let diff: HashSet<&String> = exports
             .difference(&group_exports)
@@ -614,7 +755,45 @@ impl Service {
     }
 
     /// Replace the package of the running service and restart its system process.
+    ///
+    /// The smoke test is run against the restarted process, and the update is rolled back if it
+    /// fails.
     pub fn update_package(&mut self, package: PackageInstall, launcher: &LauncherCli) {
+        let previous = self.pkg.ident.clone();
+        if self.swap_package(package, launcher) {
+            self.previous_pkg = Some(previous);
+            self.needs_smoke_test = true;
+            self.smoke_check = SmokeCheck::Pending;
+        }
+    }
+
+    /// Return the service to the package it ran before its last update.
+    ///
+    /// Returns `true` if the service's package was replaced.
+    fn roll_back(&mut self, launcher: &LauncherCli) -> bool {
+        let ident = match self.previous_pkg.take() {
+            Some(ident) => ident,
+            None => return false,
+        };
+        let fs_root_path = Path::new(&*FS_ROOT_PATH);
+        match PackageInstall::load(&ident, Some(fs_root_path)) {
+            Ok(package) => {
+                outputln!(preamble self.service_group, "Rolling back to {}", ident);
+                self.swap_package(package, launcher)
+            }
+            Err(err) => {
+                outputln!(preamble self.service_group,
+                          "Unable to roll back to {}, {}", ident, err);
+                false
+            }
+        }
+    }
+
+    /// Swap in the given package and stop the running process so it is restarted from the new
+    /// package on the next tick.
+    ///
+    /// Returns `true` if the package was replaced.
+    fn swap_package(&mut self, package: PackageInstall, launcher: &LauncherCli) -> bool {
         match Pkg::from_install(package) {
             Ok(pkg) => {
                 outputln!(preamble self.service_group,
@@ -624,7 +803,7 @@ impl Service {
                     Err(e) => {
                         outputln!(preamble self.service_group,
                                   "Failed to load config templates after updating package, {}", e);
-                        return;
+                        return false;
                     }
                 }
                 self.hooks = HookTable::load(
@@ -637,7 +816,7 @@ impl Service {
             Err(err) => {
                 outputln!(preamble self.service_group,
                           "Unexpected error while updating package, {}", err);
-                return;
+                return false;
             }
         }
         if let Err(err) = self.supervisor.stop(launcher, ShutdownReason::PkgUpdating) {
@@ -656,6 +835,7 @@ impl Service {
         }
 
         self.initialized = false;
+        true
     }
 
     pub fn to_rumor(&self, incarnation: u64) -> ServiceRumor {
@@ -676,6 +856,8 @@ impl Service {
             exported.as_ref(),
         );
         rumor.incarnation = incarnation;
+        rumor.failed_pkg = self.failed_pkg.as_ref().map(|ident| ident.to_string());
+        rumor.health_check = Some(self.health_check.to_string());
         rumor
     }
 
@@ -695,14 +877,15 @@ impl Service {
         }
     }
 
-    /// Run reconfigure hook if present.
+    /// Queue the reconfigure hook to run if present.
     fn reconfigure(&mut self) {
         self.needs_reconfiguration = false;
         if let Some(ref hook) = self.hooks.reconfigure {
-            hook.run(
-                &self.service_group,
+            self.hook_runner.submit(
+                HookJob::Reconfigure(hook.clone()),
                 &self.pkg,
                 self.svc_encrypted_password.as_ref(),
+                *DEFAULT_HOOK_TIMEOUT,
             );
         }
     }
@@ -828,6 +1011,11 @@ impl Service {
     }
 
     fn execute_hooks(&mut self, launcher: &LauncherCli) {
+        // The Launcher doesn't wait for a stopped service to exit, so its next process isn't
+        // started until it has.
+        if self.supervisor.is_stopping() {
+            return;
+        }
         if !self.initialized {
             if self.check_process() {
                 outputln!("Reattached to {}", self.service_group);
@@ -838,16 +1026,19 @@ impl Service {
             if self.initialized {
                 self.start(launcher);
                 self.post_run();
+                if self.needs_smoke_test {
+                    self.smoke_test();
+                }
             }
         } else {
             self.check_process();
             match self.last_health_check {
                 Some(last_check) => {
-                    if Instant::now().duration_since(last_check) >= *HEALTH_CHECK_INTERVAL {
-                        self.run_health_check_hook();
+                    if Instant::now().duration_since(last_check) >= self.health_check_interval {
+                        self.run_health_check_hook(launcher);
                     }
                 }
-                None => self.run_health_check_hook(),
+                None => self.run_health_check_hook(launcher),
             }
 
             // NOTE: if you need reconfiguration and you DON'T have a
@@ -862,18 +1053,18 @@ impl Service {
         }
     }
 
-    /// Run file_updated hook if present.
-    fn file_updated(&self) -> bool {
+    /// Queue the file_updated hook to run if present.
+    fn file_updated(&mut self) {
         if self.initialized {
             if let Some(ref hook) = self.hooks.file_updated {
-                return hook.run(
-                    &self.service_group,
+                self.hook_runner.submit(
+                    HookJob::FileUpdated(hook.clone()),
                     &self.pkg,
                     self.svc_encrypted_password.as_ref(),
+                    *DEFAULT_HOOK_TIMEOUT,
                 );
             }
         }
-        false
     }
 
     /// Write service files from gossip data to disk under
@@ -913,21 +1104,164 @@ impl Service {
         )
     }
 
-    fn run_health_check_hook(&mut self) {
-        let check_result = if let Some(ref hook) = self.hooks.health_check {
-            hook.run(
-                &self.service_group,
+    /// Queue the health_check hook to run if present. Services without
+    /// one are checked inline against the state of their process.
+    fn run_health_check_hook(&mut self, launcher: &LauncherCli) {
+        self.last_health_check = Some(Instant::now());
+        if let Some(ref hook) = self.hooks.health_check {
+            self.hook_runner.submit(
+                HookJob::HealthCheck(hook.clone()),
                 &self.pkg,
                 self.svc_encrypted_password.as_ref(),
-            )
-        } else {
-            match self.supervisor.status() {
-                (true, _) => HealthCheck::Ok,
-                (false, _) => HealthCheck::Critical,
-            }
+                self.health_check_timeout,
+            );
+            return;
+        }
+        let check_result = match self.supervisor.status() {
+            (true, _) => HealthCheck::Ok,
+            (false, _) => HealthCheck::Critical,
         };
-        self.last_health_check = Some(Instant::now());
-        self.cache_health_check(check_result);
+        self.set_health_check(check_result, launcher);
+    }
+
+    /// Record a health check result, only changing the service's health
+    /// once the configured thresholds have been crossed, and restart the
+    /// service if its restart policy calls for it.
+    fn set_health_check(&mut self, check_result: HealthCheck, launcher: &LauncherCli) {
+        self.record_health_check(check_result);
+        self.apply_restart_policy(check_result, launcher);
+    }
+
+    /// A health check which hangs reports an unknown status, but counts
+    /// as a critical one towards the restart policy.
+    fn health_check_timed_out(&mut self, launcher: &LauncherCli) {
+        self.record_health_check(HealthCheck::Unknown);
+        self.apply_restart_policy(HealthCheck::Critical, launcher);
+    }
+
+    fn record_health_check(&mut self, check_result: HealthCheck) {
+        let status = self.health_check_thresholds.record(check_result);
+        if status != self.health_check {
+            outputln!(preamble self.service_group, "Health check status changed to {}", status);
+            self.health_check_changed = true;
+        }
+        self.health_check = status;
+        self.cache_health_check(status);
+    }
+
+    fn apply_restart_policy(&mut self, check_result: HealthCheck, launcher: &LauncherCli) {
+        if self.restart_policy.record(check_result, Instant::now()) {
+            outputln!(preamble self.service_group,
+                      "Restarting after {} consecutive critical health checks",
+                      self.restart_policy.after_critical());
+            if let Some(err) = self
+                .supervisor
+                .restart(
+                    &self.pkg,
+                    &self.service_group,
+                    launcher,
+                    self.svc_encrypted_password.as_ref(),
+                ).err()
+            {
+                outputln!(preamble self.service_group, "Service restart failed: {}", err);
+            }
+        }
+        let crash_looping = self.restart_policy.is_crash_looping();
+        if crash_looping && !self.supervisor.crash_looping {
+            outputln!(preamble self.service_group,
+                      "Service is crash-looping, {} restarts within {}s; withholding further \
+                      restarts",
+                      self.restart_policy.max_restarts(),
+                      self.restart_policy.window().as_secs());
+        }
+        self.supervisor.crash_looping = crash_looping;
+    }
+
+    /// Queue the smoke_test hook to run against a process started from a
+    /// freshly updated package. Without one, the update is accepted as-is.
+    fn smoke_test(&mut self) {
+        self.needs_smoke_test = false;
+        if let Some(ref hook) = self.hooks.smoke_test {
+            self.hook_runner.submit(
+                HookJob::SmokeTest(hook.clone()),
+                &self.pkg,
+                self.svc_encrypted_password.as_ref(),
+                *DEFAULT_HOOK_TIMEOUT,
+            );
+            return;
+        }
+        self.previous_pkg = None;
+        self.smoke_check = SmokeCheck::Ok;
+    }
+
+    /// Accept or roll back the last package update based on its smoke test.
+    ///
+    /// Returns `true` if the service was rolled back.
+    fn handle_smoke_check(&mut self, check: SmokeCheck, launcher: &LauncherCli) -> bool {
+        match check {
+            SmokeCheck::Ok => {
+                outputln!(preamble self.service_group, "Smoke test passed for {}", self.pkg.ident);
+                self.smoke_check = SmokeCheck::Ok;
+                self.previous_pkg = None;
+                self.set_failed_pkg(None);
+                false
+            }
+            SmokeCheck::Failed(_) | SmokeCheck::Pending => {
+                outputln!(preamble self.service_group, "Smoke test failed for {}", self.pkg.ident);
+                // A smoke test which couldn't be run to completion counts as a failure
+                self.smoke_check = match check {
+                    SmokeCheck::Pending => SmokeCheck::Failed(-1),
+                    check => check,
+                };
+                self.abort_update(launcher)
+            }
+        }
+    }
+
+    /// Mark the service's current package as failed and roll back to the package it ran before
+    /// its last update.
+    ///
+    /// Returns `false` if there is no update to abort.
+    pub fn abort_update(&mut self, launcher: &LauncherCli) -> bool {
+        if self.previous_pkg.is_none() {
+            return false;
+        }
+        self.needs_smoke_test = false;
+        let failed_pkg = self.pkg.ident.clone();
+        self.set_failed_pkg(Some(failed_pkg));
+        self.roll_back(launcher);
+        true
+    }
+
+    /// Record the package which failed its smoke test, saving it to the
+    /// Supervisor's data directory so it isn't retried after a restart.
+    fn set_failed_pkg(&mut self, failed_pkg: Option<PackageIdent>) {
+        let path = self.manager_fs_cfg.failed_pkg_file(&self.service_group);
+        if let Err(err) = save_failed_pkg(&path, failed_pkg.as_ref()) {
+            outputln!(preamble self.service_group,
+                      "Unable to record the failed package in {}, {}", path.display(), err);
+        }
+        self.failed_pkg = failed_pkg;
+    }
+
+    /// Apply the results of any hooks which have finished on the hook runner.
+    ///
+    /// Returns `true` if the service needs to be gossiped as a result.
+    fn collect_hook_results(&mut self, launcher: &LauncherCli) -> bool {
+        let mut updated = false;
+        for result in self.hook_runner.results() {
+            match result {
+                HookResult::HealthCheck(check_result) => {
+                    self.set_health_check(check_result, launcher)
+                }
+                HookResult::HealthCheckTimedOut => self.health_check_timed_out(launcher),
+                HookResult::SmokeTest(check) => {
+                    updated = self.handle_smoke_check(check, launcher) || updated
+                }
+                HookResult::FileUpdated(_) | HookResult::Reconfigure => (),
+            }
+        }
+        updated
     }
 
     // Returns `false` if the write fails.
@@ -1064,9 +1398,9 @@ impl<'a> Serialize for ServiceProxy<'a> {
         S: Serializer,
     {
         let num_fields: usize = if *&self.config_rendering == ConfigRendering::Full {
-            28
+            30
         } else {
-            27
+            29
         };
 
         let s = &self.service;
@@ -1084,6 +1418,7 @@ impl<'a> Serialize for ServiceProxy<'a> {
         strukt.serialize_field("composite", &s.composite)?;
         strukt.serialize_field("config_from", &s.config_from)?;
         strukt.serialize_field("desired_state", &s.desired_state)?;
+        strukt.serialize_field("failed_pkg", &s.failed_pkg)?;
         strukt.serialize_field("health_check", &s.health_check)?;
         strukt.serialize_field("hooks", &s.hooks)?;
         strukt.serialize_field("initialized", &s.initialized)?;
@@ -1104,12 +1439,32 @@ impl<'a> Serialize for ServiceProxy<'a> {
         strukt.serialize_field("svc_encrypted_password", &s.svc_encrypted_password)?;
         strukt.serialize_field("sys", &s.sys)?;
         strukt.serialize_field("topology", &s.topology)?;
+        strukt.serialize_field("update_status", &s.update_status)?;
         strukt.serialize_field("update_strategy", &s.update_strategy)?;
         strukt.serialize_field("user_config_updated", &s.user_config_updated)?;
         strukt.end()
     }
 }
 
+/// Reads the package saved by `save_failed_pkg`, if any.
+fn load_failed_pkg(path: &Path) -> Option<PackageIdent> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| content.trim().parse().ok())
+}
+
+/// Saves the package which failed its smoke test, or removes the file
+/// once there is none.
+fn save_failed_pkg(path: &Path, failed_pkg: Option<&PackageIdent>) -> std::io::Result<()> {
+    match failed_pkg {
+        Some(ident) => {
+            File::create(path).and_then(|mut file| file.write_all(ident.to_string().as_bytes()))
+        }
+        None if path.exists() => std::fs::remove_file(path),
+        None => Ok(()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1120,6 +1475,7 @@ mod tests {
 
     use hcore::package::{ident::PackageIdent, PackageInstall};
     use serde_json;
+    use tempfile::TempDir;
 
     use self::{
         manager::{sys::Sys, FsCfg},
@@ -1174,4 +1530,22 @@ mod tests {
             .expect("Expected to convert proxies_without_config to JSON but failed");
         assert_valid(&json_without_config, "http_gateway_services_schema.json");
     }
+
+    #[test]
+    fn failed_pkg_survives_a_restart() {
+        let tmp_dir = TempDir::new().expect("create temp dir");
+        let fs_cfg = FsCfg::new(tmp_dir.path());
+        std::fs::create_dir_all(&fs_cfg.data_path).expect("create data dir");
+        let service_group = ServiceGroup::from_str("redis.default").unwrap();
+        let path = fs_cfg.failed_pkg_file(&service_group);
+        assert_eq!(load_failed_pkg(&path), None);
+
+        let failed = PackageIdent::from_str("core/redis/4.0.10/20180801003001").unwrap();
+        save_failed_pkg(&path, Some(&failed)).expect("save failed package");
+        assert_eq!(load_failed_pkg(&path), Some(failed));
+
+        save_failed_pkg(&path, None).expect("clear failed package");
+        assert!(!path.exists());
+        assert_eq!(load_failed_pkg(&path), None);
+    }
 }
@@ -14,6 +14,7 @@
 
 use std::collections::HashMap;
 use std::env;
+use std::fs::read_to_string;
 use std::ops::Deref;
 use std::path::PathBuf;
 use std::result;
@@ -21,6 +22,7 @@ use std::result;
 use hcore::os::users;
 use hcore::package::{PackageIdent, PackageInstall};
 use hcore::util::{deserialize_using_from_str, serialize_using_to_string};
+use launcher_client::ResourceLimits;
 use serde::ser::SerializeStruct;
 use serde::{Serialize, Serializer};
 
@@ -32,6 +34,7 @@ const DEFAULT_USER: &'static str = "hab";
 const DEFAULT_GROUP: &'static str = "hab";
 
 const PATH_KEY: &'static str = "PATH";
+const SVC_LIMITS_FILE: &'static str = "SVC_LIMITS";
 static LOGKEY: &'static str = "PK";
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -145,6 +148,14 @@ impl Pkg {
         };
         Ok(pkg)
     }
+
+    /// The resource limits declared by the package's `pkg_svc_limits`, if any.
+    pub fn svc_limits(&self) -> ResourceLimits {
+        match read_to_string(self.path.join(SVC_LIMITS_FILE)) {
+            Ok(body) => parse_svc_limits(&body),
+            Err(_) => ResourceLimits::default(),
+        }
+    }
 }
 
 /// This is a proxy struct to represent the data about a Pkg that we actually want to be
@@ -241,6 +252,32 @@ fn get_pkg_user_and_group(pkg_install: &PackageInstall) -> Result<Option<(String
     }
 }
 
+/// Parses the `key=value` lines of a package's `SVC_LIMITS` metadata file, ignoring any it
+/// doesn't understand.
+fn parse_svc_limits(body: &str) -> ResourceLimits {
+    let mut limits = ResourceLimits::default();
+    for line in body.lines() {
+        let mut parts = line.splitn(2, '=');
+        let (key, value) = match (parts.next(), parts.next()) {
+            (Some(key), Some(value)) => (key.trim(), value.trim()),
+            _ => continue,
+        };
+        let valid = match key {
+            "cpu" => value.parse().map(|v| limits.cpu = v).is_ok(),
+            "memory" => value.parse().map(|v| limits.memory = v).is_ok(),
+            "pids" => value.parse().map(|v| limits.pids = v).is_ok(),
+            _ => {
+                outputln!("Ignoring unknown resource limit '{}' in package", key);
+                continue;
+            }
+        };
+        if !valid {
+            outputln!("Ignoring invalid {} limit '{}' in package", key, value);
+        }
+    }
+    limits
+}
+
 /// checks to see if hab/hab exists, if not, fall back to
 /// current user/group. If that fails, then return an error.
 fn default_user_and_group() -> Result<(String, String)> {
@@ -266,3 +303,22 @@ fn default_user_and_group() -> Result<(String, String)> {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parses_svc_limits() {
+        let limits = parse_svc_limits("pids=64\ncpu=500\nmemory=lots\nswap=0\n");
+
+        assert_eq!(
+            limits,
+            ResourceLimits {
+                cpu: 500,
+                memory: 0,
+                pids: 64,
+            }
+        );
+    }
+}
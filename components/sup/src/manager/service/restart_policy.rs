@@ -0,0 +1,219 @@
+// Copyright (c) 2018 Chef Software Inc. and/or applicable contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Decides when a service should be restarted in response to failing
+//! health checks.
+//!
+//! A service is restarted once it has reported `Critical` for a
+//! configured number of consecutive checks. Successive restarts are
+//! spaced out with an exponential backoff, and once the maximum number
+//! of restarts within a window has been reached no further restarts are
+//! attempted until the oldest of them falls outside of the window. A
+//! service in that state is considered to be crash-looping.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use super::health::HealthCheck;
+
+#[derive(Debug)]
+pub struct RestartPolicy {
+    /// Consecutive `Critical` health checks which trigger a restart; `0`
+    /// disables the policy.
+    after_critical: u32,
+    /// Delay enforced after the first restart, doubled for each further
+    /// restart within the window. A service reporting `Ok` may be
+    /// restarted again without waiting out the delay.
+    backoff: Duration,
+    /// Maximum number of restarts permitted within `window`; `0` places
+    /// no limit on restarts.
+    max_restarts: u32,
+    window: Duration,
+    consecutive_critical: u32,
+    /// Restarts which happened within the current window, oldest first.
+    restarts: VecDeque<Instant>,
+    next_restart: Option<Instant>,
+    crash_looping: bool,
+}
+
+impl RestartPolicy {
+    pub fn new(after_critical: u32, backoff: Duration, max_restarts: u32, window: Duration) -> Self {
+        RestartPolicy {
+            after_critical: after_critical,
+            backoff: backoff,
+            max_restarts: max_restarts,
+            window: window,
+            consecutive_critical: 0,
+            restarts: VecDeque::new(),
+            next_restart: None,
+            crash_looping: false,
+        }
+    }
+
+    pub fn after_critical(&self) -> u32 {
+        self.after_critical
+    }
+
+    pub fn backoff(&self) -> Duration {
+        self.backoff
+    }
+
+    pub fn max_restarts(&self) -> u32 {
+        self.max_restarts
+    }
+
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.after_critical > 0
+    }
+
+    /// Whether the maximum number of restarts within the window has been reached.
+    pub fn is_crash_looping(&self) -> bool {
+        self.crash_looping
+    }
+
+    /// Record a health check result, returning `true` if the service should be restarted now.
+    pub fn record(&mut self, check: HealthCheck, now: Instant) -> bool {
+        if !self.is_enabled() {
+            return false;
+        }
+        self.expire_restarts(now);
+        match check {
+            HealthCheck::Critical => self.consecutive_critical += 1,
+            HealthCheck::Ok => {
+                self.consecutive_critical = 0;
+                self.next_restart = None;
+                return false;
+            }
+            HealthCheck::Warning | HealthCheck::Unknown => {
+                self.consecutive_critical = 0;
+                return false;
+            }
+        }
+        if self.consecutive_critical < self.after_critical || self.crash_looping {
+            return false;
+        }
+        if let Some(next_restart) = self.next_restart {
+            if now < next_restart {
+                return false;
+            }
+        }
+        self.consecutive_critical = 0;
+        self.restarts.push_back(now);
+        self.next_restart = Some(now + self.next_backoff());
+        self.crash_looping = self.limit_reached();
+        true
+    }
+
+    /// The backoff doubles with each restart in the current window, never exceeding the window
+    /// itself.
+    fn next_backoff(&self) -> Duration {
+        let exponent = (self.restarts.len() as u32).saturating_sub(1).min(16);
+        let backoff = self
+            .backoff
+            .checked_mul(1 << exponent)
+            .unwrap_or(self.window);
+        backoff.min(self.window)
+    }
+
+    fn limit_reached(&self) -> bool {
+        self.max_restarts > 0 && self.restarts.len() >= self.max_restarts as usize
+    }
+
+    fn expire_restarts(&mut self, now: Instant) {
+        while let Some(oldest) = self.restarts.front().cloned() {
+            if now.duration_since(oldest) < self.window {
+                break;
+            }
+            self.restarts.pop_front();
+        }
+        self.crash_looping = self.limit_reached();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn policy() -> RestartPolicy {
+        RestartPolicy::new(2, Duration::from_secs(10), 3, Duration::from_secs(600))
+    }
+
+    #[test]
+    fn disabled_policy_never_restarts() {
+        let mut policy =
+            RestartPolicy::new(0, Duration::from_secs(10), 3, Duration::from_secs(600));
+        let now = Instant::now();
+        for _ in 0..10 {
+            assert!(!policy.record(HealthCheck::Critical, now));
+        }
+    }
+
+    #[test]
+    fn restarts_after_consecutive_critical_checks() {
+        let mut policy = policy();
+        let now = Instant::now();
+        assert!(!policy.record(HealthCheck::Critical, now));
+        assert!(policy.record(HealthCheck::Critical, now));
+    }
+
+    #[test]
+    fn non_critical_checks_reset_the_count() {
+        let mut policy = policy();
+        let now = Instant::now();
+        assert!(!policy.record(HealthCheck::Critical, now));
+        assert!(!policy.record(HealthCheck::Warning, now));
+        assert!(!policy.record(HealthCheck::Critical, now));
+        assert!(policy.record(HealthCheck::Critical, now));
+    }
+
+    #[test]
+    fn restarts_back_off_exponentially() {
+        let mut policy = policy();
+        let start = Instant::now();
+        policy.record(HealthCheck::Critical, start);
+        assert!(policy.record(HealthCheck::Critical, start));
+
+        let soon = start + Duration::from_secs(5);
+        policy.record(HealthCheck::Critical, soon);
+        assert!(!policy.record(HealthCheck::Critical, soon));
+
+        let later = start + Duration::from_secs(10);
+        assert!(policy.record(HealthCheck::Critical, later));
+
+        // The second restart doubles the backoff to 20 seconds
+        let after_second = later + Duration::from_secs(15);
+        policy.record(HealthCheck::Critical, after_second);
+        assert!(!policy.record(HealthCheck::Critical, after_second));
+        let after_backoff = later + Duration::from_secs(20);
+        assert!(policy.record(HealthCheck::Critical, after_backoff));
+    }
+
+    #[test]
+    fn trips_circuit_breaker_within_window() {
+        let mut policy = RestartPolicy::new(1, Duration::from_secs(1), 2, Duration::from_secs(60));
+        let start = Instant::now();
+        assert!(policy.record(HealthCheck::Critical, start));
+        assert!(!policy.is_crash_looping());
+        assert!(policy.record(HealthCheck::Critical, start + Duration::from_secs(5)));
+        assert!(policy.is_crash_looping());
+        assert!(!policy.record(HealthCheck::Critical, start + Duration::from_secs(30)));
+
+        // Once the first restart ages out of the window another is permitted
+        assert!(policy.record(HealthCheck::Critical, start + Duration::from_secs(61)));
+    }
+}
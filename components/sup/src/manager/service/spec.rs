@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt;
 use std::fs::{self, File};
 use std::io::{BufReader, Read, Write};
@@ -28,17 +28,31 @@ use hcore::service::{ApplicationEnvironment, ServiceGroup};
 use hcore::url::DEFAULT_BLDR_URL;
 use hcore::util::{deserialize_using_from_str, serialize_using_to_string};
 use protocol;
+use protocol::types::{parse_bind_tag, split_bind_tags};
 use rand::distributions::Alphanumeric;
 use rand::{thread_rng, Rng};
 use serde::{self, Deserialize};
 use toml;
 
 use super::composite_spec::CompositeSpec;
-use super::{BindingMode, Topology, UpdateStrategy};
+use super::{
+    BindingMode, CanarySize, MaintenanceWindow, Signal, Topology, UpdateStrategy, VersionConstraint,
+};
 use error::{Error, Result, SupError};
 
 static LOGKEY: &'static str = "SS";
 static DEFAULT_GROUP: &'static str = "default";
+const DEFAULT_HEALTH_CHECK_INTERVAL: u64 = 30;
+const DEFAULT_HEALTH_CHECK_TIMEOUT: u64 = 30;
+const DEFAULT_HEALTH_CHECK_THRESHOLD: u32 = 1;
+const DEFAULT_RESTART_BACKOFF: u64 = 10;
+const DEFAULT_RESTART_MAX: u32 = 5;
+const DEFAULT_RESTART_WINDOW: u64 = 600;
+const DEFAULT_CANARY_SOAK: u64 = 300;
+const DEFAULT_LOG_MAX_SIZE: u64 = 10 * 1024 * 1024;
+const DEFAULT_LOG_MAX_AGE: u64 = 24 * 60 * 60;
+const DEFAULT_LOG_RETAIN: u32 = 5;
+const DEFAULT_SHUTDOWN_TIMEOUT: u32 = 8;
 const SPEC_FILE_EXT: &'static str = "spec";
 
 pub type BindMap = HashMap<PackageIdent, Vec<BindMapping>>;
@@ -108,7 +122,7 @@ where
 }
 
 pub trait IntoServiceSpec {
-    fn into_spec(&self, spec: &mut ServiceSpec);
+    fn into_spec(&self, spec: &mut ServiceSpec) -> Result<()>;
 
     /// All specs in a composite currently share a lot of the same
     /// information. Here, we create a "base spec" that we can clone and
@@ -118,13 +132,13 @@ pub trait IntoServiceSpec {
         composite_name: String,
         services: Vec<PackageIdent>,
         bind_map: BindMap,
-    ) -> Vec<ServiceSpec>;
+    ) -> Result<Vec<ServiceSpec>>;
 
-    fn update_composite(&self, bind_map: &mut BindMap, spec: &mut ServiceSpec);
+    fn update_composite(&self, bind_map: &mut BindMap, spec: &mut ServiceSpec) -> Result<()>;
 }
 
 impl IntoServiceSpec for protocol::ctl::SvcLoad {
-    fn into_spec(&self, spec: &mut ServiceSpec) {
+    fn into_spec(&self, spec: &mut ServiceSpec) -> Result<()> {
         spec.ident = self.ident.clone().unwrap().into();
         spec.group = self.group.clone().unwrap_or(DEFAULT_GROUP.to_string());
         if let Some(ref app_env) = self.application_environment {
@@ -143,7 +157,12 @@ impl IntoServiceSpec for protocol::ctl::SvcLoad {
             spec.update_strategy = UpdateStrategy::from_i32(update_strategy).unwrap_or_default();
         }
         if let Some(ref list) = self.binds {
-            let binds: Vec<ServiceBind> = list.binds.clone().into_iter().map(Into::into).collect();
+            let binds = list
+                .binds
+                .iter()
+                .cloned()
+                .map(ServiceBind::from_proto)
+                .collect::<Result<Vec<ServiceBind>>>()?;
             let (_, standard) = binds.into_iter().partition(|ref bind| bind.is_composite());
             spec.binds = standard;
         }
@@ -156,7 +175,83 @@ impl IntoServiceSpec for protocol::ctl::SvcLoad {
         if let Some(ref svc_encrypted_password) = self.svc_encrypted_password {
             spec.svc_encrypted_password = Some(svc_encrypted_password.to_string());
         }
+        if let Some(interval) = self.health_check_interval {
+            spec.health_check_interval = interval;
+        }
+        if let Some(timeout) = self.health_check_timeout {
+            spec.health_check_timeout = timeout;
+        }
+        if let Some(threshold) = self.health_check_failure_threshold {
+            spec.health_check_failure_threshold = threshold;
+        }
+        if let Some(threshold) = self.health_check_success_threshold {
+            spec.health_check_success_threshold = threshold;
+        }
+        if let Some(after_critical) = self.restart_after_critical {
+            spec.restart_after_critical = after_critical;
+        }
+        if let Some(backoff) = self.restart_backoff {
+            spec.restart_backoff = backoff;
+        }
+        if let Some(max) = self.restart_max {
+            spec.restart_max = max;
+        }
+        if let Some(window) = self.restart_window {
+            spec.restart_window = window;
+        }
+        if let Some(ref size) = self.canary_size {
+            spec.canary_size = CanarySize::from_str(size)?;
+        }
+        if let Some(soak) = self.canary_soak {
+            spec.canary_soak = soak;
+        }
+        if !self.update_windows.is_empty() {
+            spec.update_windows = self
+                .update_windows
+                .iter()
+                .filter_map(|window| match MaintenanceWindow::from_str(window) {
+                    Ok(window) => Some(window),
+                    Err(err) => {
+                        outputln!("Ignoring update window, {}", err);
+                        None
+                    }
+                }).collect();
+        }
+        if let Some(ref version) = self.update_version {
+            match VersionConstraint::from_str(version) {
+                Ok(version) => spec.update_version = Some(version),
+                Err(err) => outputln!("Ignoring update version, {}", err),
+            }
+        }
+        if let Some(capture) = self.log_capture {
+            spec.log_capture = capture;
+        }
+        if let Some(max_size) = self.log_max_size {
+            spec.log_max_size = max_size;
+        }
+        if let Some(max_age) = self.log_max_age {
+            spec.log_max_age = max_age;
+        }
+        if let Some(retain) = self.log_retain {
+            spec.log_retain = retain;
+        }
+        if let Some(cpu) = self.cpu_limit {
+            spec.cpu_limit = cpu;
+        }
+        if let Some(memory) = self.memory_limit {
+            spec.memory_limit = memory;
+        }
+        if let Some(pids) = self.pids_limit {
+            spec.pids_limit = pids;
+        }
+        if let Some(signal) = self.shutdown_signal {
+            spec.shutdown_signal = Signal::from_i32(signal).unwrap_or_default();
+        }
+        if let Some(timeout) = self.shutdown_timeout {
+            spec.shutdown_timeout = timeout;
+        }
         spec.composite = None;
+        Ok(())
     }
 
     /// All specs in a composite currently share a lot of the same
@@ -178,10 +273,10 @@ impl IntoServiceSpec for protocol::ctl::SvcLoad {
         composite_name: String,
         services: Vec<PackageIdent>,
         mut bind_map: BindMap,
-    ) -> Vec<ServiceSpec> {
+    ) -> Result<Vec<ServiceSpec>> {
         // All the service specs will be customized copies of this.
         let mut base_spec = ServiceSpec::default();
-        self.into_spec(&mut base_spec);
+        self.into_spec(&mut base_spec)?;
         base_spec.composite = Some(composite_name);
         // TODO (CM): Not dealing with service passwords for now, since
         // that's a Windows-only feature, and we don't currently build
@@ -198,8 +293,12 @@ impl IntoServiceSpec for protocol::ctl::SvcLoad {
         // pull them all out.
         let composite_binds_from_cli = match self.binds {
             Some(ref list) => {
-                let binds: Vec<ServiceBind> =
-                    list.binds.clone().into_iter().map(Into::into).collect();
+                let binds = list
+                    .binds
+                    .iter()
+                    .cloned()
+                    .map(ServiceBind::from_proto)
+                    .collect::<Result<Vec<ServiceBind>>>()?;
                 let (composite_binds, _standard_binds) =
                     binds.into_iter().partition(|ref bind| bind.is_composite());
                 composite_binds
@@ -214,10 +313,10 @@ impl IntoServiceSpec for protocol::ctl::SvcLoad {
             set_composite_binds(&mut spec, &mut bind_map, &composite_binds_from_cli);
             specs.push(spec);
         }
-        specs
+        Ok(specs)
     }
 
-    fn update_composite(&self, bind_map: &mut BindMap, spec: &mut ServiceSpec) {
+    fn update_composite(&self, bind_map: &mut BindMap, spec: &mut ServiceSpec) -> Result<()> {
         // We only want to update fields that were set by SvcLoad
         spec.group = self.group.clone().unwrap_or_default();
         if let Some(ref app_env) = self.application_environment {
@@ -235,17 +334,93 @@ impl IntoServiceSpec for protocol::ctl::SvcLoad {
         if let Some(update_strategy) = self.update_strategy {
             spec.update_strategy = UpdateStrategy::from_i32(update_strategy).unwrap_or_default();
         }
+        if let Some(interval) = self.health_check_interval {
+            spec.health_check_interval = interval;
+        }
+        if let Some(timeout) = self.health_check_timeout {
+            spec.health_check_timeout = timeout;
+        }
+        if let Some(threshold) = self.health_check_failure_threshold {
+            spec.health_check_failure_threshold = threshold;
+        }
+        if let Some(threshold) = self.health_check_success_threshold {
+            spec.health_check_success_threshold = threshold;
+        }
+        if let Some(after_critical) = self.restart_after_critical {
+            spec.restart_after_critical = after_critical;
+        }
+        if let Some(backoff) = self.restart_backoff {
+            spec.restart_backoff = backoff;
+        }
+        if let Some(max) = self.restart_max {
+            spec.restart_max = max;
+        }
+        if let Some(window) = self.restart_window {
+            spec.restart_window = window;
+        }
+        if let Some(ref size) = self.canary_size {
+            spec.canary_size = CanarySize::from_str(size)?;
+        }
+        if let Some(soak) = self.canary_soak {
+            spec.canary_soak = soak;
+        }
+        if !self.update_windows.is_empty() {
+            spec.update_windows = self
+                .update_windows
+                .iter()
+                .filter_map(|window| match MaintenanceWindow::from_str(window) {
+                    Ok(window) => Some(window),
+                    Err(err) => {
+                        outputln!("Ignoring update window, {}", err);
+                        None
+                    }
+                }).collect();
+        }
+        if let Some(ref version) = self.update_version {
+            match VersionConstraint::from_str(version) {
+                Ok(version) => spec.update_version = Some(version),
+                Err(err) => outputln!("Ignoring update version, {}", err),
+            }
+        }
+        if let Some(capture) = self.log_capture {
+            spec.log_capture = capture;
+        }
+        if let Some(max_size) = self.log_max_size {
+            spec.log_max_size = max_size;
+        }
+        if let Some(max_age) = self.log_max_age {
+            spec.log_max_age = max_age;
+        }
+        if let Some(retain) = self.log_retain {
+            spec.log_retain = retain;
+        }
+        if let Some(cpu) = self.cpu_limit {
+            spec.cpu_limit = cpu;
+        }
+        if let Some(memory) = self.memory_limit {
+            spec.memory_limit = memory;
+        }
+        if let Some(pids) = self.pids_limit {
+            spec.pids_limit = pids;
+        }
+        if let Some(signal) = self.shutdown_signal {
+            spec.shutdown_signal = Signal::from_i32(signal).unwrap_or_default();
+        }
+        if let Some(timeout) = self.shutdown_timeout {
+            spec.shutdown_timeout = timeout;
+        }
         if let Some(ref list) = self.binds {
-            let binds: Vec<ServiceBind> = list
+            let binds = list
                 .binds
                 .iter()
-                .map(Clone::clone)
-                .map(Into::into)
-                .collect();
+                .cloned()
+                .map(ServiceBind::from_proto)
+                .collect::<Result<Vec<ServiceBind>>>()?;
             let (composite, standard) = binds.into_iter().partition(|ref bind| bind.is_composite());
             spec.binds = standard;
             set_composite_binds(spec, bind_map, &composite);
         }
+        Ok(())
     }
 }
 
@@ -278,6 +453,61 @@ pub struct ServiceSpec {
     pub svc_encrypted_password: Option<String>,
     // The name of the composite this service is a part of
     pub composite: Option<String>,
+    /// Seconds between runs of the service's health check.
+    pub health_check_interval: u64,
+    /// Seconds a health_check hook may run before it is terminated.
+    pub health_check_timeout: u64,
+    /// Consecutive failed health checks before the service is reported unhealthy.
+    pub health_check_failure_threshold: u32,
+    /// Consecutive passing health checks before the service is reported healthy again.
+    pub health_check_success_threshold: u32,
+    /// Consecutive critical health checks after which the service is restarted; `0` disables
+    /// automatic restarts.
+    pub restart_after_critical: u32,
+    /// Seconds to wait after an automatic restart before another, doubled for each further
+    /// restart within the window.
+    pub restart_backoff: u64,
+    /// Automatic restarts permitted within the window before the service is considered to be
+    /// crash-looping.
+    pub restart_max: u32,
+    /// Seconds over which automatic restarts are counted.
+    pub restart_window: u64,
+    /// Members which take a new package first under the canary update strategy.
+    #[serde(
+        deserialize_with = "deserialize_using_from_str",
+        serialize_with = "serialize_using_to_string"
+    )]
+    pub canary_size: CanarySize,
+    /// Seconds the canaries must run a new package healthily before the rest of the group
+    /// updates.
+    pub canary_soak: u64,
+    /// Cron-style schedules, in UTC, of the minutes during which the service may be updated. An
+    /// empty list allows updates at any time.
+    pub update_windows: Vec<MaintenanceWindow>,
+    /// Restricts the versions the service may be updated to.
+    pub update_version: Option<VersionConstraint>,
+    /// Whether the Launcher also writes the service's output to log files in its logs directory.
+    pub log_capture: bool,
+    /// Bytes a captured log file may grow to before it is rotated; `0` disables size-based
+    /// rotation.
+    pub log_max_size: u64,
+    /// Seconds a captured log file is written to before it is rotated; `0` disables time-based
+    /// rotation.
+    pub log_max_age: u64,
+    /// Rotated log files kept for each of the service's output streams.
+    pub log_retain: u32,
+    /// CPU time the service may use, in thousandths of a CPU. This and the other limits override
+    /// those declared by the service's package; `0` leaves the resource to the package's limit,
+    /// if any.
+    pub cpu_limit: u32,
+    /// Bytes of memory the service may use.
+    pub memory_limit: u64,
+    /// Number of processes the service may run.
+    pub pids_limit: u64,
+    /// Signal sent to the service to ask it to stop.
+    pub shutdown_signal: Signal,
+    /// Seconds the service is given to stop after the shutdown signal before it is killed.
+    pub shutdown_timeout: u32,
 }
 
 impl ServiceSpec {
@@ -291,6 +521,7 @@ impl ServiceSpec {
         if self.ident == PackageIdent::default() {
             return Err(sup_error!(Error::MissingRequiredIdent));
         }
+        self.validate_health_check()?;
         toml::to_string(self).map_err(|err| sup_error!(Error::ServiceSpecRender(err)))
     }
 
@@ -344,6 +575,26 @@ impl ServiceSpec {
         format!("{}.{}", &self.ident.name, SPEC_FILE_EXT)
     }
 
+    /// Every health check setting must be greater than 0; a zero interval would run the
+    /// health check continuously and a zero threshold is met before anything has been checked.
+    fn validate_health_check(&self) -> Result<()> {
+        let zero = if self.health_check_interval == 0 {
+            Some("health_check_interval")
+        } else if self.health_check_timeout == 0 {
+            Some("health_check_timeout")
+        } else if self.health_check_failure_threshold == 0 {
+            Some("health_check_failure_threshold")
+        } else if self.health_check_success_threshold == 0 {
+            Some("health_check_success_threshold")
+        } else {
+            None
+        };
+        match zero {
+            Some(setting) => Err(sup_error!(Error::InvalidHealthCheckSetting(setting))),
+            None => Ok(()),
+        }
+    }
+
     pub fn validate(&self, package: &PackageInstall) -> Result<()> {
         self.validate_binds(package)?;
         Ok(())
@@ -409,6 +660,27 @@ impl Default for ServiceSpec {
             desired_state: DesiredState::default(),
             svc_encrypted_password: None,
             composite: None,
+            health_check_interval: DEFAULT_HEALTH_CHECK_INTERVAL,
+            health_check_timeout: DEFAULT_HEALTH_CHECK_TIMEOUT,
+            health_check_failure_threshold: DEFAULT_HEALTH_CHECK_THRESHOLD,
+            health_check_success_threshold: DEFAULT_HEALTH_CHECK_THRESHOLD,
+            restart_after_critical: 0,
+            restart_backoff: DEFAULT_RESTART_BACKOFF,
+            restart_max: DEFAULT_RESTART_MAX,
+            restart_window: DEFAULT_RESTART_WINDOW,
+            canary_size: CanarySize::default(),
+            canary_soak: DEFAULT_CANARY_SOAK,
+            update_windows: Vec::new(),
+            update_version: None,
+            log_capture: false,
+            log_max_size: DEFAULT_LOG_MAX_SIZE,
+            log_max_age: DEFAULT_LOG_MAX_AGE,
+            log_retain: DEFAULT_LOG_RETAIN,
+            cpu_limit: 0,
+            memory_limit: 0,
+            pids_limit: 0,
+            shutdown_signal: Signal::Term,
+            shutdown_timeout: DEFAULT_SHUTDOWN_TIMEOUT,
         }
     }
 }
@@ -422,6 +694,7 @@ impl FromStr for ServiceSpec {
         if spec.ident == PackageIdent::default() {
             return Err(sup_error!(Error::MissingRequiredIdent));
         }
+        spec.validate_health_check()?;
         Ok(spec)
     }
 }
@@ -432,33 +705,77 @@ pub struct ServiceBind {
     pub service_group: ServiceGroup,
     /// Only set if this is a bind targeting a composite service
     pub service_name: Option<String>,
+    /// Restricts the bind to members of the service group whose Supervisor tags match. A tag
+    /// without a value matches members whose value for it is the same as this Supervisor's.
+    pub tags: BTreeMap<String, Option<String>>,
 }
 
 impl ServiceBind {
+    /// Converts a bind received over the ctl gateway, failing if any entry of its tag filter is
+    /// malformed.
+    pub fn from_proto(bind: protocol::types::ServiceBind) -> Result<Self> {
+        let mut tags = BTreeMap::new();
+        for tag in bind.tags.iter() {
+            let (key, value) = parse_bind_tag(tag).ok_or_else(|| {
+                sup_error!(Error::InvalidBinding(format!(
+                    "{}:{}[{}]",
+                    bind.name,
+                    bind.service_group,
+                    bind.tags.join(",")
+                )))
+            })?;
+            tags.insert(key, value);
+        }
+        Ok(ServiceBind {
+            name: bind.name,
+            service_group: bind.service_group.into(),
+            service_name: bind.service_name,
+            tags: tags,
+        })
+    }
+
     pub fn is_composite(&self) -> bool {
         self.service_name.is_some()
     }
+
+    /// Whether a member with the given tags satisfies this bind's tag filter, when this
+    /// Supervisor has `local_tags`.
+    pub fn matches_tags(
+        &self,
+        member_tags: &BTreeMap<String, String>,
+        local_tags: &BTreeMap<String, String>,
+    ) -> bool {
+        self.tags.iter().all(|(key, value)| match *value {
+            Some(ref value) => member_tags.get(key) == Some(value),
+            None => member_tags.get(key) == local_tags.get(key),
+        })
+    }
 }
 
 impl FromStr for ServiceBind {
     type Err = SupError;
 
     fn from_str(bind_str: &str) -> result::Result<Self, Self::Err> {
-        let values: Vec<&str> = bind_str.split(':').collect();
+        let invalid = || sup_error!(Error::InvalidBinding(bind_str.to_string()));
+        let (target, tags) = split_bind_tags(bind_str).ok_or_else(&invalid)?;
+        let tags: BTreeMap<String, Option<String>> = tags.into_iter().collect();
+        let values: Vec<&str> = target.split(':').collect();
         if !(values.len() == 3 || values.len() == 2) {
-            return Err(sup_error!(Error::InvalidBinding(bind_str.to_string())));
+            return Err(invalid());
         }
         let bind = if values.len() == 3 {
             ServiceBind {
                 name: values[1].to_string(),
                 service_group: ServiceGroup::from_str(values[2])?,
                 service_name: Some(values[0].to_string()),
+                tags: tags,
             }
         } else {
             ServiceBind {
                 name: values[0].to_string(),
                 service_group: ServiceGroup::from_str(values[1])?,
                 service_name: None,
+                tags: tags,
             }
         };
         Ok(bind)
@@ -473,7 +790,18 @@ impl fmt::Display for ServiceBind {
         //
         // Display is what governs how this is rendered in a spec
         // file, so everything should look the same.
-        write!(f, "{}:{}", self.name, self.service_group)
+        write!(f, "{}:{}", self.name, self.service_group)?;
+        if !self.tags.is_empty() {
+            let tags: Vec<String> = self
+                .tags
+                .iter()
+                .map(|(key, value)| match *value {
+                    Some(ref value) => format!("{}={}", key, value),
+                    None => key.to_string(),
+                }).collect();
+            write!(f, "[{}]", tags.join(","))?;
+        }
+        Ok(())
     }
 }
 
@@ -542,6 +870,7 @@ fn set_composite_binds(spec: &mut ServiceSpec, bind_map: &mut BindMap, binds: &V
                 name: bind_mapping.bind_name.clone(),
                 service_group: group,
                 service_name: Some(bind_mapping.bind_name.clone()),
+                tags: BTreeMap::new(),
             };
             final_binds.insert(bind.name.clone(), bind);
         }
@@ -610,6 +939,13 @@ mod test {
             update_strategy = "rolling"
             binds = ["cache:redis.cache@acmecorp", "db:postgres.app@acmecorp"]
             config_from = "/only/for/development"
+            health_check_interval = 10
+            health_check_failure_threshold = 3
+            canary_size = "2"
+            update_windows = ["0-59 2 * * *"]
+            update_version = "1.2.x"
+            log_capture = true
+            memory_limit = 1048576
 
             extra_stuff = "should be ignored"
             "#;
@@ -638,6 +974,25 @@ mod test {
             spec.config_from,
             Some(PathBuf::from("/only/for/development"))
         );
+        assert_eq!(spec.health_check_interval, 10);
+        assert_eq!(spec.health_check_timeout, DEFAULT_HEALTH_CHECK_TIMEOUT);
+        assert_eq!(spec.health_check_failure_threshold, 3);
+        assert_eq!(spec.health_check_success_threshold, 1);
+        assert_eq!(spec.canary_size, CanarySize::Count(2));
+        assert_eq!(spec.canary_soak, DEFAULT_CANARY_SOAK);
+        assert_eq!(
+            spec.update_windows,
+            vec![MaintenanceWindow::from_str("0-59 2 * * *").unwrap()]
+        );
+        assert_eq!(
+            spec.update_version,
+            Some(VersionConstraint::from_str("1.2.x").unwrap())
+        );
+        assert!(spec.log_capture);
+        assert_eq!(spec.log_max_size, DEFAULT_LOG_MAX_SIZE);
+        assert_eq!(spec.log_retain, DEFAULT_LOG_RETAIN);
+        assert_eq!(spec.memory_limit, 1048576);
+        assert_eq!(spec.cpu_limit, 0);
     }
 
     #[test]
@@ -653,6 +1008,38 @@ mod test {
         }
     }
 
+    #[test]
+    fn service_spec_from_str_zero_health_check_interval() {
+        let toml = r#"
+            ident = "origin/name/1.2.3/20170223130020"
+            health_check_interval = 0
+            "#;
+
+        match ServiceSpec::from_str(toml) {
+            Err(e) => match e.err {
+                InvalidHealthCheckSetting("health_check_interval") => assert!(true),
+                e => panic!("Unexpected error returned: {:?}", e),
+            },
+            Ok(_) => panic!("Spec TOML should fail to parse"),
+        }
+    }
+
+    #[test]
+    fn service_spec_from_str_zero_health_check_threshold() {
+        let toml = r#"
+            ident = "origin/name/1.2.3/20170223130020"
+            health_check_failure_threshold = 0
+            "#;
+
+        match ServiceSpec::from_str(toml) {
+            Err(e) => match e.err {
+                InvalidHealthCheckSetting("health_check_failure_threshold") => assert!(true),
+                e => panic!("Unexpected error returned: {:?}", e),
+            },
+            Ok(_) => panic!("Spec TOML should fail to parse"),
+        }
+    }
+
     #[test]
     fn service_spec_from_str_invalid_topology() {
         let toml = r#"
@@ -707,6 +1094,27 @@ mod test {
             desired_state: DesiredState::Down,
             svc_encrypted_password: None,
             composite: None,
+            health_check_interval: 10,
+            health_check_timeout: 5,
+            health_check_failure_threshold: 3,
+            health_check_success_threshold: 2,
+            restart_after_critical: 3,
+            restart_backoff: 15,
+            restart_max: 4,
+            restart_window: 300,
+            canary_size: CanarySize::Percent(25),
+            canary_soak: 600,
+            update_windows: vec![MaintenanceWindow::from_str("* 2-4 * * 6").unwrap()],
+            update_version: Some(VersionConstraint::from_str("1.2.x").unwrap()),
+            log_capture: true,
+            log_max_size: 1024,
+            log_max_age: 3600,
+            log_retain: 2,
+            cpu_limit: 500,
+            memory_limit: 268435456,
+            pids_limit: 64,
+            shutdown_signal: Signal::Quit,
+            shutdown_timeout: 30,
         };
         let toml = spec.to_toml_string().unwrap();
 
@@ -722,6 +1130,27 @@ mod test {
         assert!(toml.contains(r#"desired_state = "down""#));
         assert!(toml.contains(r#"config_from = "/only/for/development""#));
         assert!(toml.contains(r#"binding_mode = "relaxed""#));
+        assert!(toml.contains(r#"health_check_interval = 10"#));
+        assert!(toml.contains(r#"health_check_timeout = 5"#));
+        assert!(toml.contains(r#"health_check_failure_threshold = 3"#));
+        assert!(toml.contains(r#"health_check_success_threshold = 2"#));
+        assert!(toml.contains(r#"restart_after_critical = 3"#));
+        assert!(toml.contains(r#"restart_backoff = 15"#));
+        assert!(toml.contains(r#"restart_max = 4"#));
+        assert!(toml.contains(r#"restart_window = 300"#));
+        assert!(toml.contains(r#"canary_size = "25%""#));
+        assert!(toml.contains(r#"canary_soak = 600"#));
+        assert!(toml.contains(r#"update_windows = ["* 2-4 * * 6"]"#));
+        assert!(toml.contains(r#"update_version = "1.2.x""#));
+        assert!(toml.contains(r#"log_capture = true"#));
+        assert!(toml.contains(r#"log_max_size = 1024"#));
+        assert!(toml.contains(r#"log_max_age = 3600"#));
+        assert!(toml.contains(r#"log_retain = 2"#));
+        assert!(toml.contains(r#"cpu_limit = 500"#));
+        assert!(toml.contains(r#"memory_limit = 268435456"#));
+        assert!(toml.contains(r#"pids_limit = 64"#));
+        assert!(toml.contains(r#"shutdown_signal = "quit""#));
+        assert!(toml.contains(r#"shutdown_timeout = 30"#));
     }
 
     #[test]
@@ -857,6 +1286,27 @@ mod test {
             desired_state: DesiredState::Down,
             svc_encrypted_password: None,
             composite: None,
+            health_check_interval: 10,
+            health_check_timeout: 5,
+            health_check_failure_threshold: 3,
+            health_check_success_threshold: 2,
+            restart_after_critical: 3,
+            restart_backoff: 15,
+            restart_max: 4,
+            restart_window: 300,
+            canary_size: CanarySize::Percent(25),
+            canary_soak: 600,
+            update_windows: vec![MaintenanceWindow::from_str("* 2-4 * * 6").unwrap()],
+            update_version: Some(VersionConstraint::from_str("1.2.x").unwrap()),
+            log_capture: true,
+            log_max_size: 1024,
+            log_max_age: 3600,
+            log_retain: 2,
+            cpu_limit: 500,
+            memory_limit: 268435456,
+            pids_limit: 64,
+            shutdown_signal: Signal::Quit,
+            shutdown_timeout: 30,
         };
         spec.to_file(&path).unwrap();
         let toml = string_from_file(path);
@@ -873,6 +1323,27 @@ mod test {
         assert!(toml.contains(r#"desired_state = "down""#));
         assert!(toml.contains(r#"config_from = "/only/for/development""#));
         assert!(toml.contains(r#"binding_mode = "relaxed""#));
+        assert!(toml.contains(r#"health_check_interval = 10"#));
+        assert!(toml.contains(r#"health_check_timeout = 5"#));
+        assert!(toml.contains(r#"health_check_failure_threshold = 3"#));
+        assert!(toml.contains(r#"health_check_success_threshold = 2"#));
+        assert!(toml.contains(r#"restart_after_critical = 3"#));
+        assert!(toml.contains(r#"restart_backoff = 15"#));
+        assert!(toml.contains(r#"restart_max = 4"#));
+        assert!(toml.contains(r#"restart_window = 300"#));
+        assert!(toml.contains(r#"canary_size = "25%""#));
+        assert!(toml.contains(r#"canary_soak = 600"#));
+        assert!(toml.contains(r#"update_windows = ["* 2-4 * * 6"]"#));
+        assert!(toml.contains(r#"update_version = "1.2.x""#));
+        assert!(toml.contains(r#"log_capture = true"#));
+        assert!(toml.contains(r#"log_max_size = 1024"#));
+        assert!(toml.contains(r#"log_max_age = 3600"#));
+        assert!(toml.contains(r#"log_retain = 2"#));
+        assert!(toml.contains(r#"cpu_limit = 500"#));
+        assert!(toml.contains(r#"memory_limit = 268435456"#));
+        assert!(toml.contains(r#"pids_limit = 64"#));
+        assert!(toml.contains(r#"shutdown_signal = "quit""#));
+        assert!(toml.contains(r#"shutdown_timeout = 30"#));
     }
 
     #[test]
@@ -964,12 +1435,93 @@ mod test {
         }
     }
 
+    #[test]
+    fn service_bind_from_str_with_tags() {
+        let bind = ServiceBind::from_str("name:service.group[zone, rack=12]").unwrap();
+
+        assert_eq!(bind.name, String::from("name"));
+        assert_eq!(
+            bind.service_group,
+            ServiceGroup::from_str("service.group").unwrap()
+        );
+        assert_eq!(bind.tags.get("zone"), Some(&None));
+        assert_eq!(bind.tags.get("rack"), Some(&Some("12".to_string())));
+        assert_eq!("name:service.group[rack=12,zone]", bind.to_string());
+    }
+
+    #[test]
+    fn service_bind_from_str_invalid_tags() {
+        for bind_str in &[
+            "name:service.group[]",
+            "name:service.group[=12]",
+            "name:service.group[zone",
+        ] {
+            match ServiceBind::from_str(bind_str) {
+                Err(e) => match e.err {
+                    InvalidBinding(val) => assert_eq!(*bind_str, val),
+                    wrong => panic!("Unexpected error returned: {:?}", wrong),
+                },
+                Ok(_) => panic!("String should fail to parse"),
+            }
+        }
+    }
+
+    #[test]
+    fn svc_load_with_an_invalid_canary_size_is_rejected() {
+        let mut opts = protocol::ctl::SvcLoad::default();
+        opts.ident = Some(PackageIdent::from_str("core/redis").unwrap().into());
+        opts.canary_size = Some(String::from("101%"));
+        assert!(opts.into_spec(&mut ServiceSpec::default()).is_err());
+
+        opts.canary_size = Some(String::from("25%"));
+        let mut spec = ServiceSpec::default();
+        opts.into_spec(&mut spec).unwrap();
+        assert_eq!(spec.canary_size, CanarySize::Percent(25));
+    }
+
+    #[test]
+    fn service_bind_from_proto_rejects_invalid_tags() {
+        let mut proto = protocol::types::ServiceBind::default();
+        proto.name = String::from("name");
+        proto.service_group = ServiceGroup::from_str("service.group").unwrap().into();
+        proto.tags = vec![String::from("zone"), String::from("rack=12")];
+        let bind = ServiceBind::from_proto(proto.clone()).unwrap();
+        assert_eq!(bind.tags.get("rack"), Some(&Some("12".to_string())));
+
+        proto.tags.push(String::from("=12"));
+        match ServiceBind::from_proto(proto) {
+            Err(e) => match e.err {
+                InvalidBinding(val) => assert_eq!("name:service.group[zone,rack=12,=12]", val),
+                wrong => panic!("Unexpected error returned: {:?}", wrong),
+            },
+            Ok(_) => panic!("Bind should fail to convert"),
+        }
+    }
+
+    #[test]
+    fn service_bind_matches_tags() {
+        let bind = ServiceBind::from_str("name:service.group[zone,rack=12]").unwrap();
+        let mut local = BTreeMap::new();
+        local.insert("zone".to_string(), "us-east-1a".to_string());
+        let mut member = local.clone();
+        member.insert("rack".to_string(), "12".to_string());
+
+        assert!(bind.matches_tags(&member, &local));
+        member.insert("zone".to_string(), "us-east-1b".to_string());
+        assert!(!bind.matches_tags(&member, &local));
+        assert!(!bind.matches_tags(&BTreeMap::new(), &local));
+        assert!(ServiceBind::from_str("name:service.group")
+            .unwrap()
+            .matches_tags(&BTreeMap::new(), &local));
+    }
+
     #[test]
     fn service_bind_to_string() {
         let bind = ServiceBind {
             name: String::from("name"),
             service_group: ServiceGroup::from_str("service.group").unwrap(),
             service_name: None,
+            tags: BTreeMap::new(),
         };
 
         assert_eq!("name:service.group", bind.to_string());
@@ -1003,6 +1555,7 @@ mod test {
                 name: String::from("name"),
                 service_group: ServiceGroup::from_str("service.group").unwrap(),
                 service_name: None,
+                tags: BTreeMap::new(),
             },
         };
         let toml = toml::to_string(&data).unwrap();
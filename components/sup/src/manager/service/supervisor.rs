@@ -22,24 +22,32 @@ use std::fs::File;
 use std::io::prelude::*;
 use std::io::BufReader;
 use std::path::{Path, PathBuf};
+#[cfg(not(windows))]
+use std::process::Child;
 use std::result;
 
+#[cfg(windows)]
+use hcore::os::process::windows_child::Child;
 use hcore::os::process::{self, Pid};
 #[cfg(unix)]
 use hcore::os::users;
 use hcore::service::ServiceGroup;
-use launcher_client::LauncherCli;
+use launcher_client::{self, LauncherCli, OutputLogs, ResourceLimits, ShutdownConfig};
 use serde::ser::SerializeStruct;
 use serde::{Serialize, Serializer};
 use time::{self, Timespec};
 
 use super::ProcessState;
 use super::ShutdownReason;
+use super::Signal;
 use error::{Error, Result};
 use fs;
 use manager::service::Pkg;
+use metrics;
 #[cfg(unix)]
 use sys::abilities;
+use sys::cgroup;
+use util::exec;
 
 static LOGKEY: &'static str = "SV";
 
@@ -60,22 +68,67 @@ struct UserInfo {
     gid: Option<u32>,
 }
 
+/// Resources in use by a service's processes, as accounted by its cgroup.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct ResourceUsage {
+    /// CPU time used, in microseconds.
+    pub cpu_usec: u64,
+    /// Bytes of memory in use.
+    pub memory_bytes: u64,
+    /// Number of processes running.
+    pub pids: u64,
+}
+
 #[derive(Debug)]
 pub struct Supervisor {
     pub preamble: String,
     pub state: ProcessState,
     pub state_entered: Timespec,
+    /// Set while the service's restart policy is withholding restarts
+    /// because it has restarted the service too often.
+    pub crash_looping: bool,
+    /// Whether the Launcher captures the service's output to the files
+    /// in `output_logs`.
+    pub log_capture: bool,
+    pub output_logs: OutputLogs,
+    /// Resource limits for the service, overriding those declared by its package.
+    pub limits: ResourceLimits,
+    /// Signal sent to the service to ask it to stop.
+    pub shutdown_signal: Signal,
+    /// Seconds the service is given to stop before it is killed.
+    pub shutdown_timeout: u32,
     pid: Option<Pid>,
+    /// The pid of a process which exited while the service was up, or which was stopped to
+    /// restart the service. Starting the service again counts as a restart.
+    exited_pid: Option<Pid>,
+    /// The pid of a process which has been asked to stop but may not have exited yet. The
+    /// service isn't started again until it has.
+    stopping_pid: Option<Pid>,
     pid_file: PathBuf,
 }
 
 impl Supervisor {
-    pub fn new(service_group: &ServiceGroup) -> Supervisor {
+    pub fn new(
+        service_group: &ServiceGroup,
+        log_capture: bool,
+        output_logs: OutputLogs,
+        limits: ResourceLimits,
+        shutdown_signal: Signal,
+        shutdown_timeout: u32,
+    ) -> Supervisor {
         Supervisor {
             preamble: service_group.to_string(),
             state: ProcessState::Down,
             state_entered: time::get_time(),
+            crash_looping: false,
+            log_capture: log_capture,
+            output_logs: output_logs,
+            limits: limits,
+            shutdown_signal: shutdown_signal,
+            shutdown_timeout: shutdown_timeout,
             pid: None,
+            exited_pid: None,
+            stopping_pid: None,
             pid_file: fs::svc_pid_file(service_group.service()),
         }
     }
@@ -100,6 +153,9 @@ impl Supervisor {
             }
         }
         debug!("Could not find a live process with pid {:?}", self.pid);
+        if self.state == ProcessState::Up {
+            self.exited_pid = pid;
+        }
         self.change_state(ProcessState::Down);
         self.cleanup_pidfile();
         self.pid = None;
@@ -213,13 +269,68 @@ impl Supervisor {
             service_group_id, // Linux preferred
             svc_password,     // Windows optional
             (*pkg.env).clone(),
+            if self.log_capture {
+                Some(&self.output_logs)
+            } else {
+                None
+            },
+            &self.resource_limits(pkg),
+            &self.shutdown_config(),
         )?;
         self.pid = Some(pid);
         self.create_pidfile()?;
         self.change_state(ProcessState::Up);
+        if self.exited_pid.take().is_some() {
+            metrics::service_restarted(group);
+        }
         Ok(())
     }
 
+    /// Run a one-off command in the service's environment: as the same user and group, with the
+    /// same environment, and in the same working directory and cgroup as its running process.
+    /// The command's output is read from the returned child's pipes.
+    pub fn exec<T>(
+        &self,
+        pkg: &Pkg,
+        svc_password: Option<T>,
+        command: &str,
+        args: &[String],
+    ) -> Result<Child>
+    where
+        T: ToString,
+    {
+        outputln!(preamble self.preamble, "Running {} {}", command, args.join(" "));
+        exec::run_command(command, args, pkg, svc_password, self.pid)
+    }
+
+    /// The service's resource limits: those given for it, falling back to those declared by its
+    /// package.
+    fn resource_limits(&self, pkg: &Pkg) -> ResourceLimits {
+        let mut limits = pkg.svc_limits();
+        if self.limits.cpu > 0 {
+            limits.cpu = self.limits.cpu;
+        }
+        if self.limits.memory > 0 {
+            limits.memory = self.limits.memory;
+        }
+        if self.limits.pids > 0 {
+            limits.pids = self.limits.pids;
+        }
+        limits
+    }
+
+    fn shutdown_config(&self) -> ShutdownConfig {
+        ShutdownConfig {
+            signal: launcher_signal(self.shutdown_signal),
+            timeout: self.shutdown_timeout,
+        }
+    }
+
+    /// Resources in use by the running service, if it's running in its own cgroup.
+    pub fn usage(&self) -> Option<ResourceUsage> {
+        self.pid.and_then(cgroup::usage)
+    }
+
     pub fn status(&self) -> (bool, String) {
         let status = format!(
             "{}: {} for {}",
@@ -235,6 +346,7 @@ impl Supervisor {
     }
 
     pub fn stop(&mut self, launcher: &LauncherCli, cause: ShutdownReason) -> Result<()> {
+        self.exited_pid = None;
         if self.pid.is_none() {
             return Ok(());
         }
@@ -243,13 +355,37 @@ impl Supervisor {
             // we'll avoid this knowing that launcher will gratuitously kill off
             // all services as part of its shutdown routine
         } else {
-            launcher.terminate(self.pid.unwrap())?;
+            launcher.terminate(self.pid.unwrap(), &self.shutdown_config())?;
+            self.stopping_pid = self.pid.take();
         }
         self.cleanup_pidfile();
         self.change_state(ProcessState::Down);
         Ok(())
     }
 
+    /// Whether a process asked to stop by `stop` is still running.
+    pub fn is_stopping(&mut self) -> bool {
+        match self.stopping_pid {
+            Some(pid) if process::is_alive(pid) => true,
+            _ => {
+                self.stopping_pid = None;
+                false
+            }
+        }
+    }
+
+    /// Send a signal to the running service. Does nothing if the service isn't running.
+    pub fn signal(&self, launcher: &LauncherCli, signal: Signal) -> Result<()> {
+        match self.pid {
+            Some(pid) => launcher
+                .signal(pid, launcher_signal(signal))
+                .map_err(|err| sup_error!(Error::Launcher(err))),
+            None => Ok(()),
+        }
+    }
+
+    /// Restart the service: it is asked to stop, and started again by `start` once `is_stopping`
+    /// shows it has exited. Starts the service at once if it isn't running.
     pub fn restart<T>(
         &mut self,
         pkg: &Pkg,
@@ -261,19 +397,15 @@ impl Supervisor {
         T: ToString,
     {
         match self.pid {
-            Some(pid) => match launcher.restart(pid) {
-                Ok(pid) => {
-                    self.pid = Some(pid);
-                    self.create_pidfile()?;
-                    self.change_state(ProcessState::Up);
-                    Ok(())
-                }
-                Err(err) => {
-                    self.cleanup_pidfile();
-                    self.change_state(ProcessState::Down);
-                    Err(sup_error!(Error::Launcher(err)))
-                }
-            },
+            Some(pid) => {
+                launcher.terminate(pid, &self.shutdown_config())?;
+                self.stopping_pid = self.pid.take();
+                // Starting the service again counts as a restart.
+                self.exited_pid = Some(pid);
+                self.cleanup_pidfile();
+                self.change_state(ProcessState::Down);
+                Ok(())
+            }
             None => self.start(pkg, group, launcher, svc_password),
         }
     }
@@ -322,14 +454,29 @@ impl Serialize for Supervisor {
     where
         S: Serializer,
     {
-        let mut strukt = serializer.serialize_struct("supervisor", 5)?;
+        let mut strukt = serializer.serialize_struct("supervisor", 6)?;
         strukt.serialize_field("pid", &self.pid)?;
         strukt.serialize_field("state", &self.state)?;
         strukt.serialize_field("state_entered", &self.state_entered.sec)?;
+        strukt.serialize_field("crash_looping", &self.crash_looping)?;
+        strukt.serialize_field("log_capture", &self.log_capture)?;
+        strukt.serialize_field("usage", &self.usage())?;
         strukt.end()
     }
 }
 
+fn launcher_signal(signal: Signal) -> launcher_client::Signal {
+    match signal {
+        Signal::Term => launcher_client::Signal::TERM,
+        Signal::Int => launcher_client::Signal::INT,
+        Signal::Quit => launcher_client::Signal::QUIT,
+        Signal::Hup => launcher_client::Signal::HUP,
+        Signal::Usr1 => launcher_client::Signal::USR1,
+        Signal::Usr2 => launcher_client::Signal::USR2,
+        Signal::Kill => launcher_client::Signal::KILL,
+    }
+}
+
 fn read_pid<T>(pid_file: T) -> Result<Pid>
 where
     T: AsRef<Path>,
@@ -15,17 +15,23 @@
 use std::collections::HashMap;
 use std::sync::mpsc::{sync_channel, Receiver, SyncSender, TryRecvError};
 use std::thread;
+use std::time::{Duration, Instant};
 
 use butterfly;
+use common::command::package::install::InstallSource;
 use common::ui::UI;
 use env;
 use hcore::package::{PackageIdent, PackageInstall, PackageTarget};
 use hcore::service::ServiceGroup;
 use launcher_client::LauncherCli;
+use time;
 
-use census::CensusRing;
+use census::{CensusGroup, CensusMember, CensusRing, MemberId};
+use error;
 use manager::periodic::Periodic;
-use manager::service::{Service, Topology, UpdateStrategy};
+use manager::service::{
+    CanarySize, HealthCheck, Service, Topology, UpdateStrategy, VersionConstraint,
+};
 use util;
 
 static LOGKEY: &'static str = "SU";
@@ -39,6 +45,7 @@ type UpdaterStateList = HashMap<ServiceGroup, UpdaterState>;
 enum UpdaterState {
     AtOnce(Receiver<PackageInstall>),
     Rolling(RollingState),
+    Canary(CanaryState),
 }
 
 enum RollingState {
@@ -60,6 +67,50 @@ enum FollowerState {
     Waiting,
     /// Currently updating
     Updating(Receiver<PackageInstall>),
+    /// A member of the group failed the smoke test for the given
+    /// package, so the rollout of that package has been stopped
+    Halted(PackageIdent),
+}
+
+/// Current package update state of a member of a group using the
+/// canary update strategy.
+///
+/// The canaries are the first members of the group, ordered by member
+/// ID, and are the only members which poll for new packages. The rest
+/// of the group follows once every canary has run the new package
+/// healthily for the soak period.
+enum CanaryState {
+    /// Waiting for the group to appear in the census
+    AwaitingCensus,
+    /// A canary, polling for a newer package
+    Polling(Receiver<PackageInstall>),
+    /// Not a canary, waiting for the canaries to take a newer package
+    Watching,
+    /// The canaries are running the given package, which must stay
+    /// healthy until the soak period which began at the given time is
+    /// over
+    Soaking(PackageIdent, Instant),
+    /// The given package survived its soak period and is being
+    /// installed
+    Updating(PackageIdent, Receiver<PackageInstall>),
+    /// The given package failed on a canary and won't be rolled out
+    /// any further
+    Aborted(PackageIdent),
+}
+
+impl CanaryState {
+    /// A description of the update in progress, if there is one.
+    fn status(&self, soak: Duration) -> Option<String> {
+        match *self {
+            CanaryState::Soaking(ref ident, since) => {
+                let left = soak.checked_sub(since.elapsed()).unwrap_or_default();
+                Some(format!("canary: soaking {}, {}s left", ident, left.as_secs()))
+            }
+            CanaryState::Updating(ref ident, _) => Some(format!("canary: updating to {}", ident)),
+            CanaryState::Aborted(ref ident) => Some(format!("canary: aborted {}", ident)),
+            CanaryState::AwaitingCensus | CanaryState::Polling(_) | CanaryState::Watching => None,
+        }
+    }
 }
 
 /// The ServiceUpdater is in charge of updating a Service when a more recent version of a package
@@ -67,6 +118,9 @@ enum FollowerState {
 /// To use an update strategy, the supervisor must be configured to watch a depot for new versions.
 pub struct ServiceUpdater {
     states: UpdaterStateList,
+    /// Packages found outside of their service's maintenance windows,
+    /// held back until the next window opens.
+    queued: HashMap<ServiceGroup, PackageInstall>,
     butterfly: butterfly::Server,
 }
 
@@ -74,6 +128,7 @@ impl ServiceUpdater {
     pub fn new(butterfly: butterfly::Server) -> Self {
         ServiceUpdater {
             states: UpdaterStateList::default(),
+            queued: HashMap::new(),
             butterfly: butterfly,
         }
     }
@@ -99,6 +154,12 @@ impl ServiceUpdater {
                     .or_insert(UpdaterState::Rolling(RollingState::AwaitingElection));
                 true
             }
+            UpdateStrategy::Canary => {
+                self.states
+                    .entry(service.service_group.clone())
+                    .or_insert(UpdaterState::Canary(CanaryState::AwaitingCensus));
+                true
+            }
         }
     }
 
@@ -113,10 +174,39 @@ impl ServiceUpdater {
         service: &mut Service,
         census_ring: &CensusRing,
         launcher: &LauncherCli,
+    ) -> bool {
+        let updated = self.check_state(service, census_ring, launcher);
+        service.update_status = self.update_status(service);
+        updated
+    }
+
+    /// A description of the update in progress for the given service, if there is one.
+    fn update_status(&self, service: &Service) -> Option<String> {
+        if let Some(package) = self.queued.get(&service.service_group) {
+            return Some(format!("queued: {}", package.ident()));
+        }
+        match self.states.get(&service.service_group) {
+            Some(&UpdaterState::Rolling(RollingState::Follower(FollowerState::Halted(
+                ref ident,
+            )))) => Some(format!("rolling: halted {}", ident)),
+            Some(&UpdaterState::Canary(ref state)) => state.status(service.canary_soak),
+            _ => None,
+        }
+    }
+
+    fn check_state(
+        &mut self,
+        service: &mut Service,
+        census_ring: &CensusRing,
+        launcher: &LauncherCli,
     ) -> bool {
         let mut updated = false;
         match self.states.get_mut(&service.service_group) {
-            Some(&mut UpdaterState::AtOnce(ref mut rx)) => match rx.try_recv() {
+            Some(&mut UpdaterState::AtOnce(ref mut rx)) => match receive(
+                &mut self.queued,
+                service,
+                rx,
+            ) {
                 Ok(package) => {
                     service.update_package(package, launcher);
                     return true;
@@ -180,31 +270,45 @@ impl ServiceUpdater {
             }
             Some(&mut UpdaterState::Rolling(RollingState::Leader(ref mut state))) => {
                 match *state {
-                    LeaderState::Polling(ref mut rx) => match rx.try_recv() {
-                        Ok(package) => {
-                            debug!("Rolling Update, polling found a new package");
-                            service.update_package(package, launcher);
-                            updated = true;
-                        }
-                        Err(TryRecvError::Empty) => return false,
-                        Err(TryRecvError::Disconnected) => {
-                            debug!("Service Updater worker has died; restarting...");
-                            *rx = Worker::new(service).start(&service.service_group, None);
+                    LeaderState::Polling(ref mut rx) => {
+                        match receive(&mut self.queued, service, rx) {
+                            Ok(package) => {
+                                debug!("Rolling Update, polling found a new package");
+                                service.update_package(package, launcher);
+                                updated = true;
+                            }
+                            Err(TryRecvError::Empty) => return false,
+                            Err(TryRecvError::Disconnected) => {
+                                debug!("Service Updater worker has died; restarting...");
+                                *rx = Worker::new(service).start(&service.service_group, None);
+                            }
                         }
-                    },
+                    }
                     LeaderState::Waiting => {
                         match census_ring.census_group_for(&service.service_group) {
-                            Some(census_group) => {
-                                if census_group.members().iter().any(|cm| {
-                                    cm.pkg.as_ref().unwrap()
-                                        != census_group.me().unwrap().pkg.as_ref().unwrap()
-                                }) {
+                            Some(census_group) => match rollout_progress(census_group) {
+                                RolloutProgress::InProgress => {
                                     debug!("Update leader still waiting for followers...");
                                     return false;
                                 }
-                                let rx = Worker::new(service).start(&service.service_group, None);
-                                *state = LeaderState::Polling(rx);
-                            }
+                                RolloutProgress::Complete => {
+                                    let rx =
+                                        Worker::new(service).start(&service.service_group, None);
+                                    *state = LeaderState::Polling(rx);
+                                }
+                                RolloutProgress::Failed(ident, member_id) => {
+                                    outputln!(preamble service.service_group,
+                                              "Rolling update halted; {} failed its smoke test on {}",
+                                              ident, member_id);
+                                    // Go back to polling for a fix, but never offer the failed
+                                    // package to the followers again
+                                    let mut worker = Worker::new(service);
+                                    worker.failed = Some(ident);
+                                    *state = LeaderState::Polling(
+                                        worker.start(&service.service_group, None),
+                                    );
+                                }
+                            },
                             None => panic!(
                                 "Expected census list to have service group '{}'!",
                                 &*service.service_group
@@ -230,6 +334,16 @@ impl ServiceUpdater {
                                         debug!("We're not in an update");
                                         return false;
                                     }
+                                    if let Some(failed) = census_group.members().iter().find(|cm| {
+                                        cm.failed_pkg.is_some() && cm.failed_pkg == leader.pkg
+                                    }) {
+                                        outputln!(preamble service.service_group,
+                                                  "Rolling update halted; {} failed its smoke test on {}",
+                                                  leader.pkg.as_ref().unwrap(), failed.member_id);
+                                        *state =
+                                            FollowerState::Halted(leader.pkg.clone().unwrap());
+                                        return false;
+                                    }
                                     if leader.pkg != peer.pkg {
                                         debug!("We're in an update but it's not our turn");
                                         return false;
@@ -250,7 +364,7 @@ impl ServiceUpdater {
                     FollowerState::Updating(ref mut rx) => match census_ring
                         .census_group_for(&service.service_group)
                     {
-                        Some(census_group) => match rx.try_recv() {
+                        Some(census_group) => match receive(&mut self.queued, service, rx) {
                             Ok(package) => {
                                 service.update_package(package, launcher);
                                 updated = true
@@ -267,19 +381,322 @@ impl ServiceUpdater {
                             &*service.service_group
                         ),
                     },
+                    FollowerState::Halted(ref ident) => {
+                        match census_ring
+                            .census_group_for(&service.service_group)
+                            .and_then(|census_group| census_group.update_leader())
+                        {
+                            Some(leader) if leader.pkg.as_ref() != Some(ident) => {
+                                debug!("Update leader has moved on from {}; resuming", ident);
+                            }
+                            _ => return false,
+                        }
+                    }
+                }
+                if let FollowerState::Halted(_) = *state {
+                    *state = FollowerState::Waiting;
+                    return false;
                 }
                 if updated {
                     *state = FollowerState::Waiting;
                 }
             }
+            Some(&mut UpdaterState::Canary(ref mut state)) => {
+                updated =
+                    Self::check_canary(state, &mut self.queued, service, census_ring, launcher);
+            }
             None => {}
         }
         updated
     }
+
+    fn check_canary(
+        state: &mut CanaryState,
+        queued: &mut HashMap<ServiceGroup, PackageInstall>,
+        service: &mut Service,
+        census_ring: &CensusRing,
+        launcher: &LauncherCli,
+    ) -> bool {
+        let census_group = match census_ring.census_group_for(&service.service_group) {
+            Some(census_group) => census_group,
+            None => return false,
+        };
+        let me = match census_group.me() {
+            Some(me) => me,
+            None => return false,
+        };
+        let canaries = canaries(census_group, service.canary_size);
+        let is_canary = canaries.iter().any(|cm| cm.member_id == me.member_id);
+        let mut updated = false;
+        let next = match *state {
+            CanaryState::AwaitingCensus => Some(if is_canary {
+                debug!("We're a canary");
+                CanaryState::Polling(Worker::new(service).start(&service.service_group, None))
+            } else {
+                debug!("We're not a canary");
+                CanaryState::Watching
+            }),
+            CanaryState::Polling(ref mut rx) => {
+                if !is_canary {
+                    debug!("We're no longer a canary");
+                    Some(CanaryState::Watching)
+                } else {
+                    match receive(queued, service, rx) {
+                        Ok(package) => {
+                            let ident = package.ident().clone();
+                            if census_group
+                                .members()
+                                .iter()
+                                .any(|cm| cm.failed_pkg.as_ref() == Some(&ident))
+                            {
+                                outputln!(preamble service.service_group,
+                                          "Not updating to {}; it has already failed on a canary",
+                                          ident);
+                                Some(CanaryState::Aborted(ident))
+                            } else {
+                                service.update_package(package, launcher);
+                                updated = true;
+                                Some(CanaryState::Soaking(ident, Instant::now()))
+                            }
+                        }
+                        Err(TryRecvError::Empty) => None,
+                        Err(TryRecvError::Disconnected) => {
+                            debug!("Service Updater worker has died; restarting...");
+                            *rx = Worker::new(service).start(&service.service_group, None);
+                            None
+                        }
+                    }
+                }
+            }
+            CanaryState::Watching => {
+                if is_canary {
+                    debug!("We've become a canary");
+                    Some(CanaryState::Polling(
+                        Worker::new(service).start(&service.service_group, None),
+                    ))
+                } else {
+                    match canary_package(&canaries) {
+                        Some(ident) if service.pkg.ident < ident => {
+                            outputln!(preamble service.service_group,
+                                      "Canaries have updated to {}; soaking for {}s",
+                                      ident, service.canary_soak.as_secs());
+                            Some(CanaryState::Soaking(ident, Instant::now()))
+                        }
+                        _ => None,
+                    }
+                }
+            }
+            CanaryState::Soaking(ref ident, since) => {
+                match soak_progress(&canaries, ident, since.elapsed(), service.canary_soak) {
+                    SoakProgress::Failed(member_id) => {
+                        outputln!(preamble service.service_group,
+                                  "Canary update to {} aborted; it failed on {}",
+                                  ident, member_id);
+                        if service.pkg.ident == *ident {
+                            updated = service.abort_update(launcher);
+                        }
+                        Some(CanaryState::Aborted(ident.clone()))
+                    }
+                    SoakProgress::Diverged => {
+                        if is_canary {
+                            debug!("Waiting for the other canaries to take {}", ident);
+                            None
+                        } else {
+                            debug!("Canaries are no longer running {}", ident);
+                            Some(CanaryState::Watching)
+                        }
+                    }
+                    SoakProgress::Soaking => None,
+                    SoakProgress::Survived if is_canary => {
+                        outputln!(preamble service.service_group,
+                                  "{} survived its soak period", ident);
+                        Some(CanaryState::Polling(
+                            Worker::new(service).start(&service.service_group, None),
+                        ))
+                    }
+                    SoakProgress::Survived => {
+                        outputln!(preamble service.service_group,
+                                  "{} survived its soak period on the canaries", ident);
+                        let rx =
+                            Worker::new(service).start(&service.service_group, Some(ident.clone()));
+                        Some(CanaryState::Updating(ident.clone(), rx))
+                    }
+                }
+            }
+            CanaryState::Updating(ref ident, ref mut rx) => match receive(queued, service, rx) {
+                Ok(package) => {
+                    service.update_package(package, launcher);
+                    updated = true;
+                    Some(CanaryState::Watching)
+                }
+                Err(TryRecvError::Empty) => None,
+                Err(TryRecvError::Disconnected) => {
+                    debug!("Service Updater worker has died; restarting...");
+                    *rx = Worker::new(service).start(&service.service_group, Some(ident.clone()));
+                    None
+                }
+            },
+            CanaryState::Aborted(ref ident) => {
+                if is_canary {
+                    // Keep polling for a fix, but never take the failed package again
+                    let mut worker = Worker::new(service);
+                    worker.failed = Some(ident.clone());
+                    Some(CanaryState::Polling(worker.start(&service.service_group, None)))
+                } else if canary_package(&canaries).map_or(false, |pkg| pkg > *ident) {
+                    Some(CanaryState::Watching)
+                } else {
+                    None
+                }
+            }
+        };
+        if let Some(next) = next {
+            *state = next;
+        }
+        updated
+    }
+}
+
+/// How far a rolling update of the update leader's package has got
+/// through the rest of the group.
+#[derive(Debug, PartialEq)]
+enum RolloutProgress {
+    /// Some followers aren't running the leader's package yet
+    InProgress,
+    /// Every follower is running the leader's package
+    Complete,
+    /// The leader's package failed its smoke test on the given
+    /// follower, which rolled back; the followers which haven't taken
+    /// it yet never will
+    Failed(PackageIdent, MemberId),
+}
+
+fn rollout_progress(census_group: &CensusGroup) -> RolloutProgress {
+    let pkg = census_group.me().and_then(|me| me.pkg.as_ref());
+    let members = census_group.members();
+    if let Some(failed) = members
+        .iter()
+        .find(|cm| cm.failed_pkg.is_some() && cm.failed_pkg.as_ref() == pkg)
+    {
+        return RolloutProgress::Failed(
+            failed.failed_pkg.clone().unwrap(),
+            failed.member_id.clone(),
+        );
+    }
+    if members.iter().any(|cm| cm.pkg.as_ref() != pkg) {
+        RolloutProgress::InProgress
+    } else {
+        RolloutProgress::Complete
+    }
+}
+
+/// Receive a package found by an update worker, holding it back
+/// until the service's next maintenance window if necessary.
+fn receive(
+    queued: &mut HashMap<ServiceGroup, PackageInstall>,
+    service: &Service,
+    rx: &Receiver<PackageInstall>,
+) -> Result<PackageInstall, TryRecvError> {
+    let open = in_update_window(service);
+    if let Some(package) = queued.remove(&service.service_group) {
+        if open {
+            outputln!(preamble service.service_group,
+                      "Maintenance window is open; applying queued update to {}",
+                      package.ident());
+            return Ok(package);
+        }
+        queued.insert(service.service_group.clone(), package);
+        return Err(TryRecvError::Empty);
+    }
+    let package = rx.try_recv()?;
+    if open {
+        return Ok(package);
+    }
+    outputln!(preamble service.service_group,
+              "Queueing update to {} until the next maintenance window",
+              package.ident());
+    queued.insert(service.service_group.clone(), package);
+    Err(TryRecvError::Empty)
+}
+
+/// Whether the service may be updated now. A service without any
+/// maintenance windows may be updated at any time.
+fn in_update_window(service: &Service) -> bool {
+    if service.update_windows.is_empty() {
+        return true;
+    }
+    let now = time::now_utc();
+    service.update_windows.iter().any(|window| {
+        window.contains(
+            now.tm_min as u32,
+            now.tm_hour as u32,
+            now.tm_mday as u32,
+            now.tm_mon as u32 + 1,
+            now.tm_wday as u32,
+        )
+    })
+}
+
+/// The members of the group which take new packages first under the
+/// canary update strategy.
+fn canaries(census_group: &CensusGroup, size: CanarySize) -> Vec<&CensusMember> {
+    let mut members = census_group.active_members();
+    let count = size.members(members.len());
+    members.truncate(count);
+    members
+}
+
+/// How far the canaries have got through soaking a package.
+#[derive(Debug, PartialEq)]
+enum SoakProgress {
+    /// The package failed its smoke test, or its health check is
+    /// critical, on the given canary
+    Failed(MemberId),
+    /// Not every canary is running the package
+    Diverged,
+    /// The soak period isn't over yet
+    Soaking,
+    /// The package stayed healthy on every canary for the whole soak
+    /// period
+    Survived,
+}
+
+fn soak_progress(
+    canaries: &[&CensusMember],
+    ident: &PackageIdent,
+    soaked: Duration,
+    soak: Duration,
+) -> SoakProgress {
+    if let Some(canary) = canaries.iter().find(|cm| {
+        cm.failed_pkg.as_ref() == Some(ident)
+            || (cm.pkg.as_ref() == Some(ident) && cm.health_check == HealthCheck::Critical)
+    }) {
+        SoakProgress::Failed(canary.member_id.clone())
+    } else if canary_package(canaries).as_ref() != Some(ident) {
+        SoakProgress::Diverged
+    } else if soaked < soak {
+        SoakProgress::Soaking
+    } else {
+        SoakProgress::Survived
+    }
+}
+
+/// The package every canary is running, if they agree on one.
+fn canary_package(canaries: &[&CensusMember]) -> Option<PackageIdent> {
+    let pkg = canaries.first().and_then(|cm| cm.pkg.clone());
+    if canaries.iter().all(|cm| cm.pkg == pkg) {
+        pkg
+    } else {
+        None
+    }
 }
 
 struct Worker {
     current: PackageIdent,
+    /// A package which failed its smoke test on this Supervisor and
+    /// must not be installed again.
+    failed: Option<PackageIdent>,
+    /// Restricts the versions the service may be updated to.
+    version: Option<VersionConstraint>,
     spec_ident: PackageIdent,
     builder_url: String,
     channel: String,
@@ -339,12 +756,46 @@ impl Worker {
     fn new(service: &Service) -> Self {
         Worker {
             current: service.pkg.ident.clone(),
+            failed: service.failed_pkg.clone(),
+            version: service.update_version.clone(),
             spec_ident: service.spec_ident.clone(),
             builder_url: service.bldr_url.clone(),
             channel: service.channel.clone(),
         }
     }
 
+    /// Whether the given package satisfies the service's version
+    /// constraint, if it has one.
+    fn satisfies_version(&self, ident: &PackageIdent) -> bool {
+        match (self.version.as_ref(), ident.version.as_ref()) {
+            (None, _) => true,
+            (Some(constraint), Some(version)) => constraint.matches(version),
+            (Some(_), None) => false,
+        }
+    }
+
+    /// What to install when polling for a newer package.
+    ///
+    /// Without a version constraint this is the latest release of the
+    /// service's package. With one, Builder is asked for the newest
+    /// matching release, so that a newer release outside the
+    /// constraint can't hide the ones within it.
+    fn latest_source(&self) -> error::Result<Option<InstallSource>> {
+        let target = *PackageTarget::active_target();
+        match self.version {
+            None => Ok(Some((self.spec_ident.clone(), target).into())),
+            Some(ref constraint) => {
+                let latest = util::pkg::latest_matching(
+                    &self.builder_url,
+                    &self.spec_ident,
+                    &self.channel,
+                    constraint,
+                )?;
+                Ok(latest.map(|ident| (ident, target).into()))
+            }
+        }
+    }
+
     /// Start a new update worker.
     ///
     /// Passing an optional package identifier will make the worker perform a run-once update to
@@ -414,10 +865,22 @@ impl Worker {
     /// Continually poll for a new version of a package, installing it
     /// when found.
     fn run_poll(&mut self, sender: SyncSender<PackageInstall>) {
-        let install_source = (self.spec_ident.clone(), *PackageTarget::active_target()).into();
         loop {
             let next_time = self.next_period_start();
 
+            let install_source = match self.latest_source() {
+                Ok(Some(install_source)) => install_source,
+                Ok(None) => {
+                    debug!("No package satisfies the version constraint yet");
+                    self.sleep_until(next_time);
+                    continue;
+                }
+                Err(e) => {
+                    warn!("Updater failed to get latest package: {:?}", e);
+                    self.sleep_until(next_time);
+                    continue;
+                }
+            };
             match util::pkg::install(
                 // We don't want anything in here to print
                 &mut UI::with_sinks(),
@@ -426,7 +889,17 @@ impl Worker {
                 &self.channel,
             ) {
                 Ok(maybe_newer_package) => {
-                    if self.current < *maybe_newer_package.ident() {
+                    if self.failed.as_ref() == Some(maybe_newer_package.ident()) {
+                        debug!(
+                            "Package found failed its smoke test; ignoring {}",
+                            maybe_newer_package.ident()
+                        );
+                    } else if !self.satisfies_version(maybe_newer_package.ident()) {
+                        debug!(
+                            "Package found doesn't satisfy the version constraint; ignoring {}",
+                            maybe_newer_package.ident()
+                        );
+                    } else if self.current < *maybe_newer_package.ident() {
                         outputln!(
                             "Updating from {} to {}",
                             self.current,
@@ -448,3 +921,164 @@ impl Worker {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use butterfly::member::{Health, Member, MemberList};
+    use butterfly::rumor::election::Election as ElectionRumor;
+    use butterfly::rumor::election::ElectionUpdate as ElectionUpdateRumor;
+    use butterfly::rumor::service::Service as ServiceRumor;
+    use butterfly::rumor::service::SysInfo;
+    use butterfly::rumor::service_config::ServiceConfig as ServiceConfigRumor;
+    use butterfly::rumor::service_file::ServiceFile as ServiceFileRumor;
+    use butterfly::rumor::RumorStore;
+
+    fn ident(release: &str) -> PackageIdent {
+        PackageIdent::new("core", "redis", Some("4.0.10"), Some(release))
+    }
+
+    /// A census of a group led by `member-a`, in which each member
+    /// runs the given package and may have failed to update to another.
+    fn census_ring(members: &[(&str, PackageIdent, Option<PackageIdent>)]) -> CensusRing {
+        let sg = ServiceGroup::new(None, "redis", "default", None).unwrap();
+        let service_store: RumorStore<ServiceRumor> = RumorStore::default();
+        let member_list = MemberList::new();
+        for &(member_id, ref pkg, ref failed) in members {
+            let mut rumor = ServiceRumor::new(member_id, pkg, sg.clone(), SysInfo::default(), None);
+            rumor.failed_pkg = failed.as_ref().map(|ident| ident.to_string());
+            service_store.insert(rumor);
+            let mut member = Member::default();
+            member.id = member_id.to_string();
+            member_list.insert(member, Health::Alive);
+        }
+        let election_update_store: RumorStore<ElectionUpdateRumor> = RumorStore::default();
+        let mut election_update = ElectionUpdateRumor::new("member-a", &sg, 10);
+        election_update.finish();
+        election_update_store.insert(election_update);
+
+        let mut ring = CensusRing::new("member-a");
+        ring.update_from_rumors(
+            &service_store,
+            &RumorStore::<ElectionRumor>::default(),
+            &election_update_store,
+            &member_list,
+            &RumorStore::<ServiceConfigRumor>::default(),
+            &RumorStore::<ServiceFileRumor>::default(),
+        );
+        ring
+    }
+
+    fn progress(ring: &CensusRing) -> RolloutProgress {
+        let sg = ServiceGroup::new(None, "redis", "default", None).unwrap();
+        rollout_progress(ring.census_group_for(&sg).unwrap())
+    }
+
+    #[test]
+    fn rollout_is_in_progress_until_every_follower_has_updated() {
+        let ring = census_ring(&[
+            ("member-a", ident("20180102000000"), None),
+            ("member-b", ident("20180102000000"), None),
+            ("member-c", ident("20180101000000"), None),
+        ]);
+        assert_eq!(progress(&ring), RolloutProgress::InProgress);
+
+        let ring = census_ring(&[
+            ("member-a", ident("20180102000000"), None),
+            ("member-b", ident("20180102000000"), None),
+            ("member-c", ident("20180102000000"), None),
+        ]);
+        assert_eq!(progress(&ring), RolloutProgress::Complete);
+    }
+
+    #[test]
+    fn rollout_fails_when_a_follower_fails_its_smoke_test() {
+        let failed = Some(ident("20180102000000"));
+        let ring = census_ring(&[
+            ("member-a", ident("20180102000000"), None),
+            ("member-b", ident("20180101000000"), failed),
+            ("member-c", ident("20180101000000"), None),
+        ]);
+        assert_eq!(
+            progress(&ring),
+            RolloutProgress::Failed(ident("20180102000000"), "member-b".to_string())
+        );
+    }
+
+    #[test]
+    fn an_earlier_failure_does_not_hold_up_a_later_rollout() {
+        let failed = Some(ident("20180102000000"));
+        let ring = census_ring(&[
+            ("member-a", ident("20180103000000"), None),
+            ("member-b", ident("20180103000000"), failed),
+        ]);
+        assert_eq!(progress(&ring), RolloutProgress::Complete);
+    }
+
+    fn canary(member_id: &str, pkg: PackageIdent) -> CensusMember {
+        let mut cm = CensusMember::default();
+        cm.member_id = member_id.to_string();
+        cm.pkg = Some(pkg);
+        cm
+    }
+
+    #[test]
+    fn canaries_agree_on_a_package_only_when_they_all_run_it() {
+        let a = canary("member-a", ident("20180102000000"));
+        let b = canary("member-b", ident("20180102000000"));
+        assert_eq!(canary_package(&[&a, &b]), Some(ident("20180102000000")));
+
+        let b = canary("member-b", ident("20180101000000"));
+        assert_eq!(canary_package(&[&a, &b]), None);
+    }
+
+    #[test]
+    fn a_package_is_promoted_once_it_survives_the_soak_period() {
+        let soak = Duration::from_secs(60);
+        let pkg = ident("20180102000000");
+        let a = canary("member-a", pkg.clone());
+        let b = canary("member-b", pkg.clone());
+
+        assert_eq!(
+            soak_progress(&[&a, &b], &pkg, Duration::from_secs(30), soak),
+            SoakProgress::Soaking
+        );
+        assert_eq!(
+            soak_progress(&[&a, &b], &pkg, soak, soak),
+            SoakProgress::Survived
+        );
+    }
+
+    #[test]
+    fn soaking_waits_while_the_canaries_run_different_packages() {
+        let pkg = ident("20180102000000");
+        let a = canary("member-a", pkg.clone());
+        let b = canary("member-b", ident("20180101000000"));
+        let soak = Duration::from_secs(60);
+        assert_eq!(
+            soak_progress(&[&a, &b], &pkg, soak, soak),
+            SoakProgress::Diverged
+        );
+    }
+
+    #[test]
+    fn a_canary_update_is_aborted_when_a_canary_fails() {
+        let soak = Duration::from_secs(60);
+        let pkg = ident("20180102000000");
+        let a = canary("member-a", pkg.clone());
+        let mut b = canary("member-b", ident("20180101000000"));
+        b.failed_pkg = Some(pkg.clone());
+        assert_eq!(
+            soak_progress(&[&a, &b], &pkg, Duration::from_secs(0), soak),
+            SoakProgress::Failed("member-b".to_string())
+        );
+
+        let mut b = canary("member-b", pkg.clone());
+        b.health_check = HealthCheck::Critical;
+        assert_eq!(
+            soak_progress(&[&a, &b], &pkg, soak, soak),
+            SoakProgress::Failed("member-b".to_string())
+        );
+    }
+}
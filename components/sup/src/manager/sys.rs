@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::BTreeMap;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::str;
 
@@ -38,6 +39,9 @@ pub struct Sys {
     pub http_gateway_ip: IpAddr,
     pub http_gateway_port: u16,
     pub permanent: bool,
+    /// Key/value tags describing this Supervisor, such as the zone it runs in.
+    #[serde(default)]
+    pub tags: BTreeMap<String, String>,
 }
 
 impl Sys {
@@ -75,6 +79,7 @@ impl Sys {
             http_gateway_ip: http.ip(),
             http_gateway_port: http.port(),
             permanent: permanent,
+            tags: BTreeMap::new(),
         }
     }
 
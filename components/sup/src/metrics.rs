@@ -0,0 +1,219 @@
+// Copyright (c) 2018 Chef Software Inc. and/or applicable contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Prometheus metrics describing the Supervisor, its services and its gossip ring.
+//!
+//! Counters and histograms are updated as events happen, from whichever thread observes them.
+//! Gauges describing the services and the Butterfly server are refreshed by the Manager on every
+//! turn of its main loop. Everything is served in the text exposition format from the
+//! HTTPGateway's `/metrics` endpoint.
+
+use std::time::{Duration, Instant};
+
+use butterfly;
+use hcore::service::ServiceGroup;
+use prometheus::{self, Encoder, HistogramVec, IntCounterVec, IntGauge, IntGaugeVec, TextEncoder};
+use protocol::types::ProcessState;
+
+use manager::service::{HealthCheck, Service};
+
+const HEALTH_CHECKS: &'static [HealthCheck] = &[
+    HealthCheck::Ok,
+    HealthCheck::Warning,
+    HealthCheck::Critical,
+    HealthCheck::Unknown,
+];
+
+lazy_static! {
+    static ref SERVICE_UP: IntGaugeVec = register_int_gauge_vec!(
+        "hab_sup_service_up",
+        "Whether the service's process is running (1) or not (0)",
+        &["service_group"]
+    ).unwrap();
+    static ref SERVICE_RESTARTS: IntCounterVec = register_int_counter_vec!(
+        "hab_sup_service_restarts_total",
+        "Number of times the Supervisor has restarted the service's process",
+        &["service_group"]
+    ).unwrap();
+    static ref SERVICE_HEALTH_CHECK: IntGaugeVec = register_int_gauge_vec!(
+        "hab_sup_service_health_check",
+        "The service's current health check status; 1 for the status it has, 0 for the others",
+        &["service_group", "status"]
+    ).unwrap();
+    static ref HOOK_DURATION: HistogramVec = register_histogram_vec!(
+        "hab_sup_hook_duration_seconds",
+        "Time taken to run each of a service's hooks, including its health_check",
+        &["service_group", "hook"]
+    ).unwrap();
+    static ref SWIM_ROUNDS: IntGauge = register_int_gauge!(
+        "hab_sup_butterfly_swim_rounds",
+        "Number of completed SWIM protocol rounds"
+    ).unwrap();
+    static ref GOSSIP_ROUNDS: IntGauge = register_int_gauge!(
+        "hab_sup_butterfly_gossip_rounds",
+        "Number of completed gossip protocol rounds"
+    ).unwrap();
+    static ref MEMBERS: IntGaugeVec = register_int_gauge_vec!(
+        "hab_sup_butterfly_members",
+        "Number of members of the ring in each state of health",
+        &["health"]
+    ).unwrap();
+    static ref RUMORS: IntGaugeVec = register_int_gauge_vec!(
+        "hab_sup_butterfly_rumors",
+        "Number of rumors held in each rumor store",
+        &["store"]
+    ).unwrap();
+    static ref HOT_RUMORS: IntGauge = register_int_gauge!(
+        "hab_sup_butterfly_hot_rumors",
+        "Number of rumors still being actively gossiped"
+    ).unwrap();
+    static ref PUSH_THREADS: IntGauge = register_int_gauge!(
+        "hab_sup_butterfly_push_threads",
+        "Number of running gossip push worker threads"
+    ).unwrap();
+    static ref PUSH_SOCKETS: IntGauge = register_int_gauge!(
+        "hab_sup_butterfly_push_sockets",
+        "Number of gossip push sockets held open to other members"
+    ).unwrap();
+    static ref CTL_REQUESTS: IntCounterVec = register_int_counter_vec!(
+        "hab_sup_ctl_requests_total",
+        "Number of requests received by the CtlGateway",
+        &["message", "outcome"]
+    ).unwrap();
+}
+
+/// Records the time taken to run a hook when dropped.
+pub struct HookTimer {
+    service_group: String,
+    hook: &'static str,
+    started: Instant,
+}
+
+impl HookTimer {
+    pub fn start(service_group: &ServiceGroup, hook: &'static str) -> Self {
+        HookTimer {
+            service_group: service_group.to_string(),
+            hook: hook,
+            started: Instant::now(),
+        }
+    }
+}
+
+impl Drop for HookTimer {
+    fn drop(&mut self) {
+        HOOK_DURATION
+            .with_label_values(&[&self.service_group, self.hook])
+            .observe(seconds(self.started.elapsed()));
+    }
+}
+
+pub fn service_restarted(service_group: &ServiceGroup) {
+    SERVICE_RESTARTS.with_label_values(&[service_group.as_ref()]).inc();
+}
+
+/// Count a request received by the CtlGateway. `authorized` is false for requests refused
+/// because the client's credential lacks the required role.
+pub fn ctl_request(message_id: &str, authorized: bool) {
+    let outcome = if authorized { "accepted" } else { "refused" };
+    CTL_REQUESTS.with_label_values(&[message_id, outcome]).inc();
+}
+
+/// Refresh the gauges describing the given services. Services which are no longer loaded are
+/// dropped from the gauges.
+pub fn update_services<'a, I>(services: I)
+where
+    I: IntoIterator<Item = &'a Service>,
+{
+    SERVICE_UP.reset();
+    SERVICE_HEALTH_CHECK.reset();
+    for service in services {
+        let service_group = service.service_group.as_ref();
+        let up = service.process_state() == ProcessState::Up;
+        SERVICE_UP.with_label_values(&[service_group]).set(up as i64);
+        let current = service.health_check();
+        for check in HEALTH_CHECKS {
+            SERVICE_HEALTH_CHECK
+                .with_label_values(&[service_group, &check.to_string().to_lowercase()])
+                .set((*check == current) as i64);
+        }
+    }
+}
+
+/// Refresh the gauges describing the given Butterfly server.
+pub fn update_butterfly(server: &butterfly::Server) {
+    SWIM_ROUNDS.set(server.swim_rounds() as i64);
+    GOSSIP_ROUNDS.set(server.gossip_rounds() as i64);
+    MEMBERS.reset();
+    for (health, count) in server.member_list.health_counts() {
+        MEMBERS.with_label_values(&[&health.to_string()]).set(count as i64);
+    }
+    let stores = [
+        ("service", server.service_store.len()),
+        ("service_config", server.service_config_store.len()),
+        ("service_file", server.service_file_store.len()),
+        ("election", server.election_store.len()),
+        ("election_update", server.update_store.len()),
+        ("departure", server.departure_store.len()),
+        ("tombstone", server.tombstone_store.len()),
+    ];
+    for &(store, len) in stores.iter() {
+        RUMORS.with_label_values(&[store]).set(len as i64);
+    }
+    HOT_RUMORS.set(server.hot_rumor_count() as i64);
+    PUSH_THREADS.set(server.push_threads() as i64);
+    PUSH_SOCKETS.set(server.push_sockets() as i64);
+}
+
+/// Render every metric in the Prometheus text exposition format, returning the content type
+/// along with the body.
+pub fn render() -> (String, Vec<u8>) {
+    let encoder = TextEncoder::new();
+    let mut buffer = vec![];
+    if let Err(err) = encoder.encode(&prometheus::gather(), &mut buffer) {
+        error!("Unable to encode metrics, {}", err);
+    }
+    (encoder.format_type().to_string(), buffer)
+}
+
+fn seconds(duration: Duration) -> f64 {
+    duration.as_secs() as f64 + f64::from(duration.subsec_nanos()) / 1_000_000_000.0
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn render_text_exposition_format() {
+        let sg = ServiceGroup::new(None, "redis", "metrics", None).unwrap();
+        service_restarted(&sg);
+        ctl_request("SvcStatus", true);
+        drop(HookTimer::start(&sg, "health_check"));
+
+        let (content_type, body) = render();
+        let body = String::from_utf8(body).unwrap();
+        assert!(content_type.starts_with("text/plain"));
+        assert!(body.contains("# TYPE hab_sup_service_restarts_total counter"));
+        assert!(body.contains("hab_sup_service_restarts_total{service_group=\"redis.metrics\"} 1"));
+        assert!(body.contains(
+            "hab_sup_ctl_requests_total{message=\"SvcStatus\",outcome=\"accepted\"} 1"
+        ));
+        assert!(body.contains("# TYPE hab_sup_hook_duration_seconds histogram"));
+    }
+
+    #[test]
+    fn seconds_from_duration() {
+        assert_eq!(seconds(Duration::from_millis(1500)), 1.5);
+    }
+}
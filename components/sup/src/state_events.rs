@@ -0,0 +1,448 @@
+// Copyright (c) 2018 Chef Software Inc. and/or applicable contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Incremental events describing changes to the state of the Supervisor, its services and its
+//! ring, streamed to clients of the HTTPGateway's `/events` endpoint as Server-Sent Events.
+//!
+//! On every turn of its main loop the Manager hands a snapshot of its services, the ring's
+//! members and the census' leaders to a `StateTracker`, which compares them with the previous
+//! snapshot and returns an event for each change. Those events are published to every client
+//! currently subscribed to the `StateEventBus`. A client which falls too far behind is dropped
+//! rather than buffering events for it without bound.
+
+use std::collections::HashMap;
+use std::mem;
+use std::sync::{Arc, Mutex};
+
+use butterfly::member::Health;
+use bytes::Bytes;
+use futures::sync::mpsc;
+use hcore::package::PackageIdent;
+use hcore::service::ServiceGroup;
+use serde_json;
+use time;
+
+use manager::service::HealthCheck;
+
+/// Number of events buffered for a subscriber before it's dropped for not keeping up.
+const SUBSCRIBER_BUFFER: usize = 256;
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StateEvent {
+    ServiceStarted {
+        service_group: String,
+        pkg: String,
+    },
+    ServiceStopped {
+        service_group: String,
+        pkg: String,
+    },
+    HealthChanged {
+        service_group: String,
+        health_check: String,
+    },
+    MemberHealthChanged {
+        member_id: String,
+        health: String,
+    },
+    LeaderElected {
+        service_group: String,
+        member_id: String,
+    },
+    ConfigApplied {
+        service_group: String,
+        incarnation: u64,
+    },
+    PackageUpdated {
+        service_group: String,
+        from: String,
+        to: String,
+    },
+}
+
+impl StateEvent {
+    /// The name of the event, used as the Server-Sent Event's type.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            StateEvent::ServiceStarted { .. } => "service_started",
+            StateEvent::ServiceStopped { .. } => "service_stopped",
+            StateEvent::HealthChanged { .. } => "health_changed",
+            StateEvent::MemberHealthChanged { .. } => "member_health_changed",
+            StateEvent::LeaderElected { .. } => "leader_elected",
+            StateEvent::ConfigApplied { .. } => "config_applied",
+            StateEvent::PackageUpdated { .. } => "package_updated",
+        }
+    }
+
+    /// Whether the event reveals the ring's membership or the census, which the HTTPGateway
+    /// withholds when the RedactHTTP feature is enabled.
+    fn is_redacted(&self) -> bool {
+        match *self {
+            StateEvent::MemberHealthChanged { .. } | StateEvent::LeaderElected { .. } => true,
+            _ => false,
+        }
+    }
+
+    /// Encode the event as a Server-Sent Event.
+    fn to_sse(&self) -> String {
+        #[derive(Serialize)]
+        struct Envelope<'a> {
+            timestamp: String,
+            #[serde(flatten)]
+            event: &'a StateEvent,
+        }
+
+        let envelope = Envelope {
+            timestamp: time::now_utc().rfc3339().to_string(),
+            event: self,
+        };
+        let data = serde_json::to_string(&envelope).expect("StateEvent is serializable");
+        format!("event: {}\ndata: {}\n\n", self.name(), data)
+    }
+}
+
+#[derive(Debug)]
+struct Subscriber {
+    tx: mpsc::Sender<Bytes>,
+    /// Withholds the events which reveal the ring's membership or the census.
+    redacted: bool,
+}
+
+/// Delivers published events to every subscribed client. Clones share their subscribers.
+#[derive(Clone, Debug, Default)]
+pub struct StateEventBus {
+    subscribers: Arc<Mutex<Vec<Subscriber>>>,
+}
+
+impl StateEventBus {
+    /// Subscribe to every event published from now on, each already encoded as a Server-Sent
+    /// Event. A `redacted` subscriber isn't sent member health or leader election events.
+    /// Dropping the receiver unsubscribes. The stream ends early if the receiver falls more than
+    /// `SUBSCRIBER_BUFFER` events behind.
+    pub fn subscribe(&self, redacted: bool) -> mpsc::Receiver<Bytes> {
+        let (tx, rx) = mpsc::channel(SUBSCRIBER_BUFFER);
+        self.subscribers
+            .lock()
+            .expect("StateEventBus lock is poisoned")
+            .push(Subscriber {
+                tx: tx,
+                redacted: redacted,
+            });
+        rx
+    }
+
+    pub fn publish(&self, events: Vec<StateEvent>) {
+        if events.is_empty() {
+            return;
+        }
+        let mut subscribers = self
+            .subscribers
+            .lock()
+            .expect("StateEventBus lock is poisoned");
+        if subscribers.is_empty() {
+            return;
+        }
+        let events: Vec<(Bytes, bool)> = events
+            .iter()
+            .map(|event| {
+                debug!("Publishing state event, {:?}", event);
+                (Bytes::from(event.to_sse()), event.is_redacted())
+            }).collect();
+        let current = mem::replace(&mut *subscribers, vec![]);
+        *subscribers = current
+            .into_iter()
+            .filter_map(|mut subscriber| {
+                for &(ref sse, redacted) in events.iter() {
+                    if redacted && subscriber.redacted {
+                        continue;
+                    }
+                    if let Err(err) = subscriber.tx.try_send(sse.clone()) {
+                        if err.is_full() {
+                            warn!("Dropping a state event subscriber which isn't keeping up");
+                        }
+                        return None;
+                    }
+                }
+                Some(subscriber)
+            }).collect();
+    }
+}
+
+/// The state of a loaded service which events are raised for.
+#[derive(Clone, Debug)]
+pub struct ServiceSnapshot {
+    pub pkg: PackageIdent,
+    pub up: bool,
+    pub health_check: HealthCheck,
+    pub cfg_incarnation: u64,
+}
+
+/// Remembers the last snapshot of each kind of state so that changes can be turned into events.
+#[derive(Debug, Default)]
+pub struct StateTracker {
+    seeded: bool,
+    services: HashMap<ServiceGroup, ServiceSnapshot>,
+    members: HashMap<String, Health>,
+    leaders: HashMap<ServiceGroup, String>,
+}
+
+impl StateTracker {
+    /// Compare a snapshot of every kind of state with the previous one and return an event for
+    /// each change. The first snapshot only seeds the tracker, since the state the Supervisor
+    /// starts with, such as the members it finds already in the ring, isn't a change.
+    pub fn update(
+        &mut self,
+        services: HashMap<ServiceGroup, ServiceSnapshot>,
+        members: HashMap<String, Health>,
+        leaders: HashMap<ServiceGroup, String>,
+    ) -> Vec<StateEvent> {
+        let mut events = self.services(services);
+        events.extend(self.members(members));
+        events.extend(self.leaders(leaders));
+        if !self.seeded {
+            self.seeded = true;
+            events.clear();
+        }
+        events
+    }
+
+    fn services(&mut self, current: HashMap<ServiceGroup, ServiceSnapshot>) -> Vec<StateEvent> {
+        let mut events = vec![];
+        for (sg, service) in current.iter() {
+            let previous = self.services.get(sg);
+            let was_up = previous.map_or(false, |p| p.up);
+            if service.up && !was_up {
+                events.push(StateEvent::ServiceStarted {
+                    service_group: sg.to_string(),
+                    pkg: service.pkg.to_string(),
+                });
+            } else if !service.up && was_up {
+                events.push(StateEvent::ServiceStopped {
+                    service_group: sg.to_string(),
+                    pkg: service.pkg.to_string(),
+                });
+            }
+            let previous = match previous {
+                Some(previous) => previous,
+                None => continue,
+            };
+            if service.pkg != previous.pkg {
+                events.push(StateEvent::PackageUpdated {
+                    service_group: sg.to_string(),
+                    from: previous.pkg.to_string(),
+                    to: service.pkg.to_string(),
+                });
+            }
+            if service.health_check != previous.health_check {
+                events.push(StateEvent::HealthChanged {
+                    service_group: sg.to_string(),
+                    health_check: service.health_check.to_string(),
+                });
+            }
+            if service.cfg_incarnation > previous.cfg_incarnation {
+                events.push(StateEvent::ConfigApplied {
+                    service_group: sg.to_string(),
+                    incarnation: service.cfg_incarnation,
+                });
+            }
+        }
+        for (sg, previous) in self.services.iter() {
+            if previous.up && !current.contains_key(sg) {
+                events.push(StateEvent::ServiceStopped {
+                    service_group: sg.to_string(),
+                    pkg: previous.pkg.to_string(),
+                });
+            }
+        }
+        self.services = current;
+        events
+    }
+
+    fn members(&mut self, current: HashMap<String, Health>) -> Vec<StateEvent> {
+        let events = current
+            .iter()
+            .filter(|&(id, health)| self.members.get(id) != Some(health))
+            .map(|(id, health)| StateEvent::MemberHealthChanged {
+                member_id: id.to_string(),
+                health: health.to_string(),
+            }).collect();
+        self.members = current;
+        events
+    }
+
+    fn leaders(&mut self, current: HashMap<ServiceGroup, String>) -> Vec<StateEvent> {
+        let events = current
+            .iter()
+            .filter(|&(sg, leader)| self.leaders.get(sg) != Some(leader))
+            .map(|(sg, leader)| StateEvent::LeaderElected {
+                service_group: sg.to_string(),
+                member_id: leader.to_string(),
+            }).collect();
+        self.leaders = current;
+        events
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use futures::Stream;
+
+    use super::*;
+
+    fn snapshot(pkg: &str, up: bool, health_check: HealthCheck, inc: u64) -> ServiceSnapshot {
+        ServiceSnapshot {
+            pkg: PackageIdent::from_str(pkg).unwrap(),
+            up: up,
+            health_check: health_check,
+            cfg_incarnation: inc,
+        }
+    }
+
+    fn services(snapshot: ServiceSnapshot) -> HashMap<ServiceGroup, ServiceSnapshot> {
+        let mut services = HashMap::new();
+        services.insert(ServiceGroup::from_str("redis.default").unwrap(), snapshot);
+        services
+    }
+
+    #[test]
+    fn service_changes_raise_events() {
+        let mut tracker = StateTracker::default();
+        let events = tracker.services(services(snapshot(
+            "core/redis/4.0.10/20180801003001",
+            true,
+            HealthCheck::Unknown,
+            0,
+        )));
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].name(), "service_started");
+
+        let events = tracker.services(services(snapshot(
+            "core/redis/4.0.14/20190319155852",
+            true,
+            HealthCheck::Ok,
+            2,
+        )));
+        let names: Vec<&str> = events.iter().map(|e| e.name()).collect();
+        assert_eq!(names, vec!["package_updated", "health_changed", "config_applied"]);
+
+        let events = tracker.services(HashMap::new());
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].name(), "service_stopped");
+    }
+
+    #[test]
+    fn unchanged_state_raises_no_events() {
+        let mut tracker = StateTracker::default();
+        let mut members = HashMap::new();
+        members.insert("a".to_string(), Health::Alive);
+        assert_eq!(tracker.members(members.clone()).len(), 1);
+        assert!(tracker.members(members.clone()).is_empty());
+        members.insert("a".to_string(), Health::Suspect);
+        assert_eq!(
+            tracker.members(members),
+            vec![StateEvent::MemberHealthChanged {
+                member_id: "a".to_string(),
+                health: "suspect".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn the_first_snapshot_raises_no_events() {
+        let mut tracker = StateTracker::default();
+        let mut members = HashMap::new();
+        members.insert("a".to_string(), Health::Alive);
+        let mut leaders = HashMap::new();
+        let sg = ServiceGroup::from_str("redis.default").unwrap();
+        leaders.insert(sg, "a".to_string());
+        let redis = snapshot("core/redis/4.0.10/20180801003001", true, HealthCheck::Ok, 0);
+        let seeded = tracker.update(services(redis.clone()), members.clone(), leaders.clone());
+        assert!(seeded.is_empty());
+        members.insert("b".to_string(), Health::Alive);
+        assert_eq!(
+            tracker.update(services(redis), members, leaders),
+            vec![StateEvent::MemberHealthChanged {
+                member_id: "b".to_string(),
+                health: "alive".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn published_events_are_encoded_as_sse() {
+        let bus = StateEventBus::default();
+        let rx = bus.subscribe(false);
+        bus.publish(vec![StateEvent::LeaderElected {
+            service_group: "redis.default".to_string(),
+            member_id: "a".to_string(),
+        }]);
+        let sse = rx.wait().next().unwrap().unwrap();
+        let sse = String::from_utf8(sse.to_vec()).unwrap();
+        assert!(sse.starts_with("event: leader_elected\ndata: {"));
+        assert!(sse.contains("\"type\":\"leader_elected\""));
+        assert!(sse.contains("\"service_group\":\"redis.default\""));
+        assert!(sse.ends_with("}\n\n"));
+    }
+
+    #[test]
+    fn dropped_subscribers_are_removed() {
+        let bus = StateEventBus::default();
+        drop(bus.subscribe(false));
+        bus.publish(vec![StateEvent::ConfigApplied {
+            service_group: "redis.default".to_string(),
+            incarnation: 1,
+        }]);
+        assert!(bus.subscribers.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn slow_subscribers_are_removed() {
+        let bus = StateEventBus::default();
+        let _rx = bus.subscribe(false);
+        let events = (0..SUBSCRIBER_BUFFER as u64 + 2)
+            .map(|incarnation| StateEvent::ConfigApplied {
+                service_group: "redis.default".to_string(),
+                incarnation: incarnation,
+            }).collect();
+        bus.publish(events);
+        assert!(bus.subscribers.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn redacted_subscribers_are_not_sent_ring_or_census_events() {
+        let bus = StateEventBus::default();
+        let rx = bus.subscribe(true);
+        bus.publish(vec![
+            StateEvent::MemberHealthChanged {
+                member_id: "b".to_string(),
+                health: "alive".to_string(),
+            },
+            StateEvent::LeaderElected {
+                service_group: "redis.default".to_string(),
+                member_id: "a".to_string(),
+            },
+            StateEvent::ConfigApplied {
+                service_group: "redis.default".to_string(),
+                incarnation: 1,
+            },
+        ]);
+        drop(bus);
+        let received: Vec<Bytes> = rx.wait().map(|sse| sse.unwrap()).collect();
+        assert_eq!(received.len(), 1);
+        assert!(received[0].starts_with(b"event: config_applied\n"));
+    }
+}
@@ -0,0 +1,259 @@
+// Copyright (c) 2018 Chef Software Inc. and/or applicable contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Reads the resource usage of services out of the cgroups the Launcher places them in, and
+//! places one-off commands run in a service's environment in the same cgroups.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::os::unix::io::RawFd;
+use std::path::{Path, PathBuf};
+
+use hcore::os::process::Pid;
+use libc;
+
+use manager::service::ResourceUsage;
+
+const CGROUP_ROOT: &'static str = "/sys/fs/cgroup";
+/// The cgroup the Launcher creates services' cgroups under.
+const CGROUP_PARENT: &'static str = "/habitat/";
+
+/// Resources in use by the service running as the given process, if it is running in its own
+/// cgroup.
+pub fn usage(pid: Pid) -> Option<ResourceUsage> {
+    let membership = fs::read_to_string(format!("/proc/{}/cgroup", pid)).ok()?;
+    usage_in(Path::new(CGROUP_ROOT), &membership)
+}
+
+/// Reads usage from the cgroup hierarchy mounted at `root`, given the contents of a process's
+/// `/proc/<pid>/cgroup` file.
+fn usage_in(root: &Path, membership: &str) -> Option<ResourceUsage> {
+    let unified = root.join("cgroup.controllers").is_file();
+    let mut usage = None;
+    for line in membership.lines() {
+        let mut fields = line.splitn(3, ':');
+        let (controllers, path) = match (fields.next(), fields.next(), fields.next()) {
+            (Some(_), Some(controllers), Some(path)) => (controllers, path),
+            _ => continue,
+        };
+        if !path.starts_with(CGROUP_PARENT) {
+            continue;
+        }
+        let relative = &path[1..];
+        if unified {
+            if !controllers.is_empty() {
+                continue;
+            }
+            let dir = root.join(relative);
+            return Some(ResourceUsage {
+                cpu_usec: read_stat(&dir.join("cpu.stat"), "usage_usec").unwrap_or(0),
+                memory_bytes: read_value(&dir.join("memory.current")).unwrap_or(0),
+                pids: read_value(&dir.join("pids.current")).unwrap_or(0),
+            });
+        }
+        // On v1, each hierarchy is mounted at a directory named for its controllers.
+        let dir = root.join(controllers).join(relative);
+        let usage = usage.get_or_insert_with(ResourceUsage::default);
+        for controller in controllers.split(',') {
+            match controller {
+                "cpuacct" => {
+                    if let Some(nsec) = read_value(&dir.join("cpuacct.usage")) {
+                        usage.cpu_usec = nsec / 1000;
+                    }
+                }
+                "memory" => {
+                    if let Some(bytes) = read_value(&dir.join("memory.usage_in_bytes")) {
+                        usage.memory_bytes = bytes;
+                    }
+                }
+                "pids" => {
+                    if let Some(pids) = read_value(&dir.join("pids.current")) {
+                        usage.pids = pids;
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+    usage
+}
+
+/// Open the `cgroup.procs` files of the cgroups the service running as the given process is in,
+/// so that a newly forked process can join them with `join` before it execs.
+pub fn procs(pid: Pid) -> Vec<File> {
+    let membership = match fs::read_to_string(format!("/proc/{}/cgroup", pid)) {
+        Ok(membership) => membership,
+        Err(_) => return vec![],
+    };
+    dirs_in(Path::new(CGROUP_ROOT), &membership)
+        .into_iter()
+        .filter_map(|dir| {
+            let path = dir.join("cgroup.procs");
+            match OpenOptions::new().write(true).open(&path) {
+                Ok(file) => Some(file),
+                Err(err) => {
+                    debug!("Unable to open {}, {}", path.display(), err);
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+/// Move the calling process into the cgroups whose `cgroup.procs` files are open at the given
+/// descriptors. Called between fork and exec, so sticks to async-signal-safe calls.
+///
+/// Failures are ignored: a command which no longer runs as root may not be allowed to move
+/// itself, and is moved by `add` once it has been spawned instead.
+pub fn join(procs: &[RawFd]) -> io::Result<()> {
+    for fd in procs {
+        // Writing "0" moves the writing process itself.
+        unsafe { libc::write(*fd, b"0".as_ptr() as *const libc::c_void, 1) };
+    }
+    Ok(())
+}
+
+/// Move the process `pid` into the cgroups whose `cgroup.procs` files are given.
+pub fn add(procs: &[File], pid: u32) -> io::Result<()> {
+    for file in procs {
+        (&*file).write_all(pid.to_string().as_bytes())?;
+    }
+    Ok(())
+}
+
+/// The directories of the service cgroups in the hierarchy mounted at `root`, given the contents
+/// of a process's `/proc/<pid>/cgroup` file.
+fn dirs_in(root: &Path, membership: &str) -> Vec<PathBuf> {
+    let unified = root.join("cgroup.controllers").is_file();
+    membership
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, ':');
+            match (fields.next(), fields.next(), fields.next()) {
+                (Some(_), Some(controllers), Some(path)) if path.starts_with(CGROUP_PARENT) => {
+                    match (unified, controllers.is_empty()) {
+                        (true, true) => Some(root.join(&path[1..])),
+                        (false, false) => Some(root.join(controllers).join(&path[1..])),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+fn read_value(path: &Path) -> Option<u64> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+/// Reads the value of `key` from a flat-keyed file of `key value` lines, such as `cpu.stat`.
+fn read_stat(path: &Path, key: &str) -> Option<u64> {
+    fs::read_to_string(path)
+        .ok()?
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            match (fields.next(), fields.next()) {
+                (Some(k), Some(value)) if k == key => value.parse().ok(),
+                _ => None,
+            }
+        })
+        .next()
+}
+
+#[cfg(test)]
+mod test {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn reads_v2_usage() {
+        let root = TempDir::new().unwrap();
+        let dir = root.path().join("habitat").join("redis.default");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(root.path().join("cgroup.controllers"), "cpu memory pids\n").unwrap();
+        fs::write(dir.join("cpu.stat"), "usage_usec 1500\nuser_usec 1000\n").unwrap();
+        fs::write(dir.join("memory.current"), "4096\n").unwrap();
+        fs::write(dir.join("pids.current"), "3\n").unwrap();
+
+        assert_eq!(
+            usage_in(root.path(), "0::/habitat/redis.default\n"),
+            Some(ResourceUsage {
+                cpu_usec: 1500,
+                memory_bytes: 4096,
+                pids: 3,
+            })
+        );
+    }
+
+    #[test]
+    fn reads_v1_usage() {
+        let root = TempDir::new().unwrap();
+        let cpu = root
+            .path()
+            .join("cpu,cpuacct")
+            .join("habitat")
+            .join("redis.default");
+        let memory = root
+            .path()
+            .join("memory")
+            .join("habitat")
+            .join("redis.default");
+        fs::create_dir_all(&cpu).unwrap();
+        fs::create_dir_all(&memory).unwrap();
+        fs::write(cpu.join("cpuacct.usage"), "2000000\n").unwrap();
+        fs::write(memory.join("memory.usage_in_bytes"), "8192\n").unwrap();
+        let membership = "5:cpu,cpuacct:/habitat/redis.default\n\
+                          4:memory:/habitat/redis.default\n\
+                          1:name=systemd:/system.slice/hab-sup.service\n";
+
+        assert_eq!(
+            usage_in(root.path(), membership),
+            Some(ResourceUsage {
+                cpu_usec: 2000,
+                memory_bytes: 8192,
+                pids: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn finds_v1_cgroup_dirs() {
+        let root = TempDir::new().unwrap();
+        let membership = "5:cpu,cpuacct:/habitat/redis.default\n\
+                          4:memory:/habitat/redis.default\n\
+                          1:name=systemd:/system.slice/hab-sup.service\n";
+
+        assert_eq!(
+            dirs_in(root.path(), membership),
+            vec![
+                root.path().join("cpu,cpuacct/habitat/redis.default"),
+                root.path().join("memory/habitat/redis.default"),
+            ]
+        );
+    }
+
+    #[test]
+    fn no_usage_outside_a_service_cgroup() {
+        let root = TempDir::new().unwrap();
+        fs::write(root.path().join("cgroup.controllers"), "cpu memory pids\n").unwrap();
+
+        assert_eq!(
+            usage_in(root.path(), "0::/system.slice/hab-sup.service\n"),
+            None
+        );
+    }
+}
@@ -13,12 +13,17 @@
 // limitations under the License.
 
 use std::ffi::OsStr;
+use std::fs;
+use std::io;
+use std::os::unix::io::AsRawFd;
 use std::os::unix::process::CommandExt;
 use std::process::{Child, Command, Stdio};
 
-use sys::abilities;
+use sys::{abilities, cgroup};
 
 use hcore::os;
+use hcore::os::process::Pid;
+use libc;
 
 use error::{Error, Result};
 use manager::service::Pkg;
@@ -30,10 +35,55 @@ where
     T: ToString,
     S: AsRef<OsStr>,
 {
-    let mut cmd = Command::new(path.as_ref());
+    Ok(command(path.as_ref(), pkg)?.spawn()?)
+}
+
+/// Run a one-off command in a service's environment, as a hook would be. If the service is
+/// running as `service_pid`, the command is run as the same user and group, in the same working
+/// directory and in the same cgroup as that process.
+pub fn run_command<T>(
+    path: &str,
+    args: &[String],
+    pkg: &Pkg,
+    _: Option<T>,
+    service_pid: Option<Pid>,
+) -> Result<Child>
+where
+    T: ToString,
+{
+    let mut cmd = command(path.as_ref(), pkg)?;
+    cmd.args(args);
+    if let Some(pid) = service_pid {
+        run_as_process(&mut cmd, path, pid)?;
+    }
+    let procs = service_pid.map(cgroup::procs).unwrap_or_default();
+    let fds = procs
+        .iter()
+        .map(|file| file.as_raw_fd())
+        .collect::<Vec<_>>();
+    cmd.before_exec(move || cgroup::join(&fds));
+    let child = cmd.spawn()?;
+    // The command may have given up root before it could join the cgroups itself.
+    if let Err(err) = cgroup::add(&procs, child.id()) {
+        warn!("Unable to place {} in the service's cgroup, {}", path, err);
+    }
+    Ok(child)
+}
+
+fn command(path: &OsStr, pkg: &Pkg) -> Result<Command> {
+    let mut cmd = Command::new(path);
     cmd.stdin(Stdio::null())
         .stdout(Stdio::piped())
         .stderr(Stdio::piped());
+    // Start the hook in a process group of its own so that it can be terminated along with
+    // any processes it starts; see `kill_process_group`.
+    cmd.before_exec(|| {
+        if unsafe { libc::setpgid(0, 0) } == 0 {
+            Ok(())
+        } else {
+            Err(io::Error::last_os_error())
+        }
+    });
     for (key, val) in pkg.env.iter() {
         cmd.env(key, val);
     }
@@ -55,10 +105,71 @@ where
     } else {
         debug!(
             "Current user lacks sufficient capabilites to run {:?} as \"{}\"; running as self!",
-            path.as_ref(),
-            &pkg.svc_user
+            path, &pkg.svc_user
         );
     }
+    Ok(cmd)
+}
+
+/// Run `cmd` as the same user and group, and in the same working directory, as the process
+/// `pid`. Refuses to run it at all if the Supervisor can't change to that user and group.
+fn run_as_process(cmd: &mut Command, path: &str, pid: Pid) -> Result<()> {
+    let (uid, gid) = process_ids(pid).ok_or(sup_error!(Error::Permissions(format!(
+        "Unable to read the user and group of pid {}",
+        pid
+    ))))?;
+    if abilities::can_run_services_as_svc_user() {
+        cmd.uid(uid).gid(gid);
+    } else if uid != os::users::get_effective_uid() || gid != os::users::get_effective_gid() {
+        return Err(sup_error!(Error::Permissions(format!(
+            "Unable to run {} as the service's user ({}) and group ({}), the Supervisor lacks \
+             the capabilities to change user",
+            path, uid, gid
+        ))));
+    }
+    if let Ok(dir) = fs::read_link(format!("/proc/{}/cwd", pid)) {
+        cmd.current_dir(dir);
+    }
+    Ok(())
+}
+
+/// The effective user and group IDs of the process `pid`.
+fn process_ids(pid: Pid) -> Option<(u32, u32)> {
+    let status = fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+    ids_in(&status)
+}
+
+/// Reads the effective user and group IDs out of the contents of a `/proc/<pid>/status` file.
+fn ids_in(status: &str) -> Option<(u32, u32)> {
+    // Each line lists the real, effective, saved and filesystem IDs.
+    let effective = |key: &str| -> Option<u32> {
+        status
+            .lines()
+            .find(|line| line.starts_with(key))
+            .and_then(|line| line.split_whitespace().nth(2))
+            .and_then(|id| id.parse().ok())
+    };
+    Some((effective("Uid:")?, effective("Gid:")?))
+}
 
-    Ok(cmd.spawn()?)
+/// Forcibly terminate a process started by `run` along with every process in its group.
+pub fn kill_process_group(pid: u32) -> io::Result<()> {
+    if unsafe { libc::kill(-(pid as libc::pid_t), libc::SIGKILL) } == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn ids_from_process_status() {
+        let status = "Name:\tredis-server\nUmask:\t0022\nState:\tS (sleeping)\n\
+                      Uid:\t0\t42\t42\t42\nGid:\t0\t43\t43\t43\nFDSize:\t64\n";
+        assert_eq!(ids_in(status), Some((42, 43)));
+        assert_eq!(ids_in("Name:\tredis-server\n"), None);
+    }
 }
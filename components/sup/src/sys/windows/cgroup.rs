@@ -0,0 +1,25 @@
+// Copyright (c) 2018 Chef Software Inc. and/or applicable contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Windows-equivalent for reading the resource usage of services.
+
+use hcore::os::process::Pid;
+
+use manager::service::ResourceUsage;
+
+// The Launcher doesn't place services in cgroups, or anything like them, on Windows, so there is
+// no usage to report.
+pub fn usage(_pid: Pid) -> Option<ResourceUsage> {
+    None
+}
@@ -13,8 +13,11 @@
 // limitations under the License.
 
 use std::ffi::OsStr;
+use std::io;
+use std::process::{Command, Stdio};
 
 use hcore::os::process::windows_child::Child;
+use hcore::os::process::Pid;
 
 use error::Result;
 use manager::service::Pkg;
@@ -34,3 +37,52 @@ where
         svc_encrypted_password,
     )?)
 }
+
+/// Run a one-off command in a service's environment, as a hook would be. There are no cgroups
+/// on Windows, so `service_pid` is unused.
+pub fn run_command<T>(
+    path: &str,
+    args: &[String],
+    pkg: &Pkg,
+    svc_encrypted_password: Option<T>,
+    _service_pid: Option<Pid>,
+) -> Result<Child>
+where
+    T: ToString,
+{
+    // Each part of the command is quoted so that PowerShell passes it through literally.
+    let mut ps_cmd = format!("& {}", ps_quote(path));
+    for arg in args {
+        ps_cmd.push(' ');
+        ps_cmd.push_str(&ps_quote(arg));
+    }
+    let args = vec!["-NonInteractive", "-command", ps_cmd.as_str()];
+    Ok(Child::spawn(
+        "pwsh.exe",
+        args,
+        &pkg.env,
+        &pkg.svc_user,
+        svc_encrypted_password,
+    )?)
+}
+
+/// Forcibly terminate a process started by `run` along with every process it has started.
+pub fn kill_process_group(pid: u32) -> io::Result<()> {
+    let status = Command::new("taskkill.exe")
+        .args(&["/F", "/T", "/PID", &pid.to_string()])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::Other,
+            format!("taskkill exited with {}", status),
+        ))
+    }
+}
+
+fn ps_quote(value: &str) -> String {
+    format!("'{}'", value.replace("'", "''"))
+}
@@ -13,5 +13,6 @@
 // limitations under the License.
 
 pub mod abilities;
+pub mod cgroup;
 pub mod exec;
 pub mod users;
@@ -48,7 +48,7 @@
 //! anything else, and so, they _can't_ be used for anything else.
 
 use std::borrow::Cow;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::net::IpAddr;
 use std::path::PathBuf;
 use std::result;
@@ -115,7 +115,7 @@ impl<'a> RenderContext<'a> {
             pkg: Package::from_pkg(pkg),
             cfg: Cow::Borrowed(cfg),
             svc: Svc::new(census_group),
-            bind: Binds::new(bindings, census),
+            bind: Binds::new(bindings, census, &sys.tags),
         }
     }
 
@@ -388,14 +388,17 @@ impl<'a> Serialize for Svc<'a> {
 struct Binds<'a>(HashMap<String, BindGroup<'a>>);
 
 impl<'a> Binds<'a> {
-    fn new<T>(bindings: T, census: &'a CensusRing) -> Self
+    fn new<T>(bindings: T, census: &'a CensusRing, local_tags: &BTreeMap<String, String>) -> Self
     where
         T: Iterator<Item = &'a ServiceBind>,
     {
         let mut map = HashMap::default();
         for bind in bindings {
             if let Some(group) = census.census_group_for(&bind.service_group) {
-                map.insert(bind.name.to_string(), BindGroup::new(group));
+                map.insert(
+                    bind.name.to_string(),
+                    BindGroup::new(group, bind, local_tags),
+                );
             }
         }
         Binds(map)
@@ -410,13 +413,23 @@ struct BindGroup<'a> {
 }
 
 impl<'a> BindGroup<'a> {
-    fn new(group: &'a CensusGroup) -> Self {
+    /// Only members whose tags satisfy the bind's tag filter are included.
+    fn new(
+        group: &'a CensusGroup,
+        bind: &ServiceBind,
+        local_tags: &BTreeMap<String, String>,
+    ) -> Self {
+        let matches = |m: &&CensusMember| bind.matches_tags(&m.tags, local_tags);
+        let leader = group.leader().into_iter().find(&matches);
         BindGroup {
-            first: select_first(group),
-            leader: group.leader().map(|m| SvcMember::from_census_member(m)),
+            first: leader
+                .or_else(|| group.members().into_iter().find(&matches))
+                .map(|m| SvcMember::from_census_member(m)),
+            leader: leader.map(|m| SvcMember::from_census_member(m)),
             members: group
                 .active_members()
-                .iter()
+                .into_iter()
+                .filter(&matches)
                 .map(|m| SvcMember::from_census_member(m))
                 .collect(),
         }
@@ -439,6 +452,7 @@ struct SvcMember<'a> {
     group: Cow<'a, String>,
     org: Cow<'a, Option<String>>,
     persistent: Cow<'a, bool>,
+    tags: Cow<'a, BTreeMap<String, String>>,
     leader: Cow<'a, bool>,
     follower: Cow<'a, bool>,
     update_leader: Cow<'a, bool>,
@@ -468,6 +482,7 @@ impl<'a> SvcMember<'a> {
             group: Cow::Borrowed(&c.group),
             org: Cow::Borrowed(&c.org),
             persistent: Cow::Borrowed(&c.persistent),
+            tags: Cow::Borrowed(&c.tags),
             leader: Cow::Borrowed(&c.leader),
             follower: Cow::Borrowed(&c.follower),
             update_leader: Cow::Borrowed(&c.update_leader),
@@ -528,6 +543,7 @@ impl<'a> Serialize for SvcMember<'a> {
         // it's a boolean ("permanent", because this is actually the
         // permanent peer status of this member)
         map.serialize_entry("persistent", &self.persistent)?;
+        map.serialize_entry("tags", &self.tags)?;
         // TODO (CM): add an "is_leader" field to make it clear it's a boolean
         map.serialize_entry("leader", &self.leader)?;
         // TODO (CM): is_follower
@@ -571,8 +587,8 @@ impl<'a> Serialize for SvcMember<'a> {
 ////////////////////////////////////////////////////////////////////////
 
 /// Helper for pulling the leader or first member from a census
-/// group. This is used to populate the `.first` field in `bind` and
-/// `svc`.
+/// group. This is used to populate the `.first` field in `svc`; `bind`
+/// applies the same rule to the members matching the bind's tags.
 ///
 /// IMPORTANT
 ///
@@ -689,6 +705,7 @@ two = 2
             group: Cow::Owned("default".into()),
             org: Cow::Owned(None),
             persistent: Cow::Owned(true),
+            tags: Cow::Owned(BTreeMap::new()),
             leader: Cow::Owned(false),
             follower: Cow::Owned(false),
             update_leader: Cow::Owned(false),
@@ -12,15 +12,18 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::cmp::Ordering;
 use std::path::Path;
 
+use api_client;
 use common;
 use common::command::package::install::{InstallMode, InstallSource, LocalPackageUsage};
 use common::ui::UIWriter;
 use hcore::env as henv;
 use hcore::fs::{self, FS_ROOT_PATH};
-use hcore::package::{PackageIdent, PackageInstall};
+use hcore::package::{PackageIdent, PackageInstall, PackageTarget};
 use hcore::AUTH_TOKEN_ENVVAR;
+use protocol::types::VersionConstraint;
 
 use error::{Result, SupError};
 use {PRODUCT, VERSION};
@@ -64,6 +67,38 @@ where
     ).map_err(SupError::from)
 }
 
+/// Find the latest release in `channel` of the newest version of a package which satisfies
+/// `constraint`, without installing it.
+///
+/// Returns `None` if no release of a matching version has been promoted to the channel.
+pub fn latest_matching(
+    url: &str,
+    ident: &PackageIdent,
+    channel: &str,
+    constraint: &VersionConstraint,
+) -> Result<Option<PackageIdent>> {
+    let fs_root_path = Path::new(&*FS_ROOT_PATH);
+    let auth_token = henv::var(AUTH_TOKEN_ENVVAR).ok();
+    let token = auth_token.as_ref().map(String::as_str);
+    let client = api_client::Client::new(url, PRODUCT, VERSION, Some(fs_root_path))?;
+    let (origin, name) = (ident.origin.as_str(), ident.name.as_str());
+    let mut candidates: Vec<PackageIdent> = client
+        .package_versions(ident, token)?
+        .into_iter()
+        .filter(|version| constraint.matches(version))
+        .map(|version| PackageIdent::new(origin, name, Some(version.as_str()), None))
+        .collect();
+    // Newest version first
+    candidates.sort_by(|a, b| b.partial_cmp(a).unwrap_or(Ordering::Equal));
+    for candidate in candidates {
+        match client.show_package(&candidate, PackageTarget::active_target(), channel, token) {
+            Ok(latest) => return Ok(Some(latest)),
+            Err(err) => debug!("No release of {} in {}, {}", candidate, channel, err),
+        }
+    }
+    Ok(None)
+}
+
 /// Given an InstallSource, install a new package only if an existing
 /// one that can satisfy the package identifier is not already
 /// present.